@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use bbs::ciphersuites::{BbsCiphersuite, Bls12381Sha256, Bls12381Shake256};
+use bbs::curve::{Backend, PairingCurve};
+use bbs::generators::make_generators;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::{BbsError, KeyPair, Proof, PublicKey, Scalar, SecretKey, Signature};
+
+// Python wrappers over the reference implementation. Keys, signatures, proofs and generators are
+// bytes in their octet encodings, messages are lists of bytes mapped to scalars with
+// map_message_to_scalar_as_hash. suite is "sha256" or "shake256", errors raise ValueError
+
+enum Ciphersuite {
+    SHA256,
+    SHAKE256,
+}
+
+fn ciphersuite(suite: &str) -> PyResult<Ciphersuite> {
+    match suite.to_lowercase().as_str() {
+        "sha256" | "bls12-381-sha-256" => Ok(Ciphersuite::SHA256),
+        "shake256" | "bls12-381-shake-256" => Ok(Ciphersuite::SHAKE256),
+        _ => Err(PyValueError::new_err(format!("unknown ciphersuite {:?}", suite))),
+    }
+}
+
+// Returns (secret_key, public_key)
+#[pyfunction]
+fn keygen<'py>(py: Python<'py>, ikm: &[u8], key_info: Option<&[u8]>) -> PyResult<(&'py PyBytes, &'py PyBytes)> {
+    let key_pair = KeyPair::from_ikm(ikm, key_info).map_err(bbs_error)?;
+
+    Ok((
+        PyBytes::new(py, &key_pair.secret_key.to_bytes()),
+        PyBytes::new(py, &key_pair.public_key.to_bytes()),
+    ))
+}
+
+#[pyfunction]
+fn sk_to_pk<'py>(py: Python<'py>, secret_key: &[u8]) -> PyResult<&'py PyBytes> {
+    let sk = secret_key_from(secret_key)?;
+    Ok(PyBytes::new(py, &sk.public_key().to_bytes()))
+}
+
+#[pyfunction]
+fn sign<'py>(
+    py: Python<'py>,
+    suite: &str,
+    secret_key: &[u8],
+    messages: Vec<&[u8]>,
+    header: Option<&[u8]>,
+) -> PyResult<&'py PyBytes> {
+    let sign_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => sign_with::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => sign_with::<Bls12381Shake256>,
+    };
+    Ok(PyBytes::new(py, &sign_fn(secret_key, header, &messages)?))
+}
+
+#[pyfunction]
+fn verify(suite: &str, public_key: &[u8], signature: &[u8], messages: Vec<&[u8]>, header: Option<&[u8]>) -> PyResult<bool> {
+    let verify_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => verify_with::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => verify_with::<Bls12381Shake256>,
+    };
+    verify_fn(public_key, signature, header, &messages)
+}
+
+// disclosed_indexes are zero based and ascending
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn proof_gen<'py>(
+    py: Python<'py>,
+    suite: &str,
+    public_key: &[u8],
+    signature: &[u8],
+    messages: Vec<&[u8]>,
+    disclosed_indexes: Vec<usize>,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+) -> PyResult<&'py PyBytes> {
+    let proof_gen_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => proof_gen_with::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => proof_gen_with::<Bls12381Shake256>,
+    };
+    let proof = proof_gen_fn(public_key, signature, header, ph, &messages, &disclosed_indexes)?;
+    Ok(PyBytes::new(py, &proof))
+}
+
+// disclosed_messages maps the zero based index a message was signed at to the message
+#[pyfunction]
+fn proof_verify(
+    suite: &str,
+    public_key: &[u8],
+    proof: &[u8],
+    disclosed_messages: BTreeMap<usize, &[u8]>,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+) -> PyResult<bool> {
+    let proof_verify_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => proof_verify_with::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => proof_verify_with::<Bls12381Shake256>,
+    };
+    proof_verify_fn(public_key, proof, header, ph, &disclosed_messages)
+}
+
+// Returns (base_point, message_generators), seed defaults to the ciphersuite's generator_seed
+#[pyfunction]
+fn create_generators<'py>(
+    py: Python<'py>,
+    suite: &str,
+    count: usize,
+    seed: Option<&[u8]>,
+) -> PyResult<(&'py PyBytes, Vec<&'py PyBytes>)> {
+    let make_generators_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => make_generators::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => make_generators::<Bls12381Shake256>,
+    };
+    let generators = make_generators_fn(seed, count);
+
+    Ok((
+        PyBytes::new(py, &Backend::g1_to_bytes(&generators.g1_base_point)),
+        generators
+            .message_generators
+            .iter()
+            .map(|g| PyBytes::new(py, &Backend::g1_to_bytes(g)))
+            .collect(),
+    ))
+}
+
+#[pymodule]
+fn bbs_python(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(sk_to_pk, m)?)?;
+    m.add_function(wrap_pyfunction!(sign, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(proof_gen, m)?)?;
+    m.add_function(wrap_pyfunction!(proof_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(create_generators, m)?)?;
+    Ok(())
+}
+
+fn sign_with<'a, X>(secret_key: &[u8], header: Option<&[u8]>, messages: &[&[u8]]) -> PyResult<Vec<u8>>
+where
+    X: BbsCiphersuite<'a>
+{
+    let sk = secret_key_from(secret_key)?;
+    let messages = message_scalars::<X>(messages)?;
+    let signature = Signature::sign::<X>(&messages, &sk, header).map_err(bbs_error)?;
+    Ok(signature.to_bytes().to_vec())
+}
+
+fn verify_with<'a, X>(public_key: &[u8], signature: &[u8], header: Option<&[u8]>, messages: &[&[u8]]) -> PyResult<bool>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let signature = signature_from(signature)?;
+    let messages = message_scalars::<X>(messages)?;
+    Ok(signature.verify::<X>(&pk, &messages, header))
+}
+
+fn proof_gen_with<'a, X>(
+    public_key: &[u8],
+    signature: &[u8],
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    messages: &[&[u8]],
+    disclosed_indexes: &[usize],
+) -> PyResult<Vec<u8>>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let signature = signature_from(signature)?;
+    let messages = message_scalars::<X>(messages)?;
+    let proof = Proof::generate::<X>(&signature, &pk, header, ph, &messages, disclosed_indexes)
+        .map_err(bbs_error)?;
+    Ok(proof.to_bytes())
+}
+
+fn proof_verify_with<'a, X>(
+    public_key: &[u8],
+    proof: &[u8],
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    disclosed_messages: &BTreeMap<usize, &[u8]>,
+) -> PyResult<bool>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let proof = Proof::from_bytes(proof).map_err(bbs_error)?;
+    let mut disclosed = BTreeMap::new();
+    for (&i, msg) in disclosed_messages {
+        disclosed.insert(i, message_scalar::<X>(msg)?);
+    }
+    Ok(proof.verify::<X>(&pk, header, ph, &disclosed))
+}
+
+fn error(msg: &str) -> PyErr {
+    PyValueError::new_err(msg.to_string())
+}
+
+fn bbs_error(e: BbsError) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn message_scalar<'a, X>(msg: &[u8]) -> PyResult<Scalar>
+where
+    X: BbsCiphersuite<'a>
+{
+    map_message_to_scalar_as_hash::<X>(msg, None).map_err(bbs_error)
+}
+
+fn message_scalars<'a, X>(messages: &[&[u8]]) -> PyResult<Vec<Scalar>>
+where
+    X: BbsCiphersuite<'a>
+{
+    messages.iter().map(|msg| message_scalar::<X>(msg)).collect()
+}
+
+fn secret_key_from(bytes: &[u8]) -> PyResult<SecretKey> {
+    let bytes = bytes.try_into().map_err(|_| error("secret key must be 32 bytes"))?;
+    SecretKey::from_bytes(bytes).map_err(bbs_error)
+}
+
+fn public_key_from(bytes: &[u8]) -> PyResult<PublicKey> {
+    let bytes = bytes.try_into().map_err(|_| error("public key must be 96 bytes"))?;
+    PublicKey::from_bytes(bytes).map_err(bbs_error)
+}
+
+fn signature_from(bytes: &[u8]) -> PyResult<Signature> {
+    Signature::from_bytes(bytes).map_err(bbs_error)
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+use structopt::StructOpt;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+use bbs::ciphersuites::{BbsCiphersuite, Bls12381Sha256, Bls12381Shake256};
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::{BbsError, KeyPair, Proof, PublicKey, Scalar, SecretKey, Signature};
+
+mod proto {
+    tonic::include_proto!("bbs.v1");
+}
+
+use proto::bbs_server::{Bbs, BbsServer};
+use proto::{
+    Ciphersuite, KeyGenRequest, KeyGenResponse, ProofGenRequest, ProofGenResponse, ProofVerifyRequest, SignRequest,
+    SignResponse, VerifyRequest, VerifyResponse,
+};
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "bbs-service")]
+struct Opt {
+    /// Address the gRPC service listens on
+    #[structopt(long, default_value = "127.0.0.1:50051")]
+    addr: SocketAddr,
+}
+
+// The operations of the library over the messages of proto/bbs.proto. Invalid encodings and
+// unknown ciphersuites are INVALID_ARGUMENT with the Display of the BbsError, signatures and proofs
+// that do not verify are valid = false. The operations run on the blocking pool of the runtime
+#[derive(Default)]
+struct BbsService;
+
+#[tonic::async_trait]
+impl Bbs for BbsService {
+    async fn key_gen(&self, request: Request<KeyGenRequest>) -> Result<Response<KeyGenResponse>, Status> {
+        let request = request.into_inner();
+        let response = blocking(move || {
+            let key_pair = if request.ikm.is_empty() {
+                KeyPair::generate()
+            } else {
+                KeyPair::from_ikm(&request.ikm, non_empty(&request.key_info)).map_err(error)?
+            };
+            Ok(KeyGenResponse {
+                secret_key: key_pair.secret_key.to_bytes().to_vec(),
+                public_key: key_pair.public_key.to_bytes().to_vec(),
+            })
+        })
+        .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn sign(&self, request: Request<SignRequest>) -> Result<Response<SignResponse>, Status> {
+        let request = request.into_inner();
+        let sign_fn = match ciphersuite(request.ciphersuite)? {
+            Ciphersuite::Bls12381Sha256 => sign_with::<Bls12381Sha256>,
+            Ciphersuite::Bls12381Shake256 => sign_with::<Bls12381Shake256>,
+            Ciphersuite::Unspecified => unreachable!("rejected by ciphersuite"),
+        };
+        let signature = blocking(move || sign_fn(&request)).await?;
+        Ok(Response::new(SignResponse { signature }))
+    }
+
+    async fn verify(&self, request: Request<VerifyRequest>) -> Result<Response<VerifyResponse>, Status> {
+        let request = request.into_inner();
+        let verify_fn = match ciphersuite(request.ciphersuite)? {
+            Ciphersuite::Bls12381Sha256 => verify_with::<Bls12381Sha256>,
+            Ciphersuite::Bls12381Shake256 => verify_with::<Bls12381Shake256>,
+            Ciphersuite::Unspecified => unreachable!("rejected by ciphersuite"),
+        };
+        let valid = blocking(move || verify_fn(&request)).await?;
+        Ok(Response::new(VerifyResponse { valid }))
+    }
+
+    async fn proof_gen(&self, request: Request<ProofGenRequest>) -> Result<Response<ProofGenResponse>, Status> {
+        let request = request.into_inner();
+        let proof_gen_fn = match ciphersuite(request.ciphersuite)? {
+            Ciphersuite::Bls12381Sha256 => proof_gen_with::<Bls12381Sha256>,
+            Ciphersuite::Bls12381Shake256 => proof_gen_with::<Bls12381Shake256>,
+            Ciphersuite::Unspecified => unreachable!("rejected by ciphersuite"),
+        };
+        let proof = blocking(move || proof_gen_fn(&request)).await?;
+        Ok(Response::new(ProofGenResponse { proof }))
+    }
+
+    async fn proof_verify(&self, request: Request<ProofVerifyRequest>) -> Result<Response<VerifyResponse>, Status> {
+        let request = request.into_inner();
+        let proof_verify_fn = match ciphersuite(request.ciphersuite)? {
+            Ciphersuite::Bls12381Sha256 => proof_verify_with::<Bls12381Sha256>,
+            Ciphersuite::Bls12381Shake256 => proof_verify_with::<Bls12381Shake256>,
+            Ciphersuite::Unspecified => unreachable!("rejected by ciphersuite"),
+        };
+        let valid = blocking(move || proof_verify_fn(&request)).await?;
+        Ok(Response::new(VerifyResponse { valid }))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let opt = Opt::from_args();
+    eprintln!("bbs-service listening on {}", opt.addr);
+    Server::builder().add_service(BbsServer::new(BbsService::default())).serve(opt.addr).await?;
+    Ok(())
+}
+
+// Pairings and hashing to the curve take milliseconds, on a runtime thread they would hold up every
+// other call scheduled on it
+async fn blocking<T, F>(operation: F) -> Result<T, Status>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Status> + Send + 'static
+{
+    tokio::task::spawn_blocking(operation).await.map_err(|e| Status::internal(e.to_string()))?
+}
+
+fn sign_with<'a, X>(request: &SignRequest) -> Result<Vec<u8>, Status>
+where
+    X: BbsCiphersuite<'a>
+{
+    let sk = secret_key_from(&request.secret_key)?;
+    let messages = message_scalars::<X>(&request.messages)?;
+    let signature = Signature::sign::<X>(&messages, &sk, Some(&request.header)).map_err(error)?;
+    Ok(signature.to_bytes().to_vec())
+}
+
+fn verify_with<'a, X>(request: &VerifyRequest) -> Result<bool, Status>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(&request.public_key)?;
+    let signature = Signature::from_bytes(&request.signature).map_err(error)?;
+    let messages = message_scalars::<X>(&request.messages)?;
+    Ok(signature.verify::<X>(&pk, &messages, Some(&request.header)))
+}
+
+fn proof_gen_with<'a, X>(request: &ProofGenRequest) -> Result<Vec<u8>, Status>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(&request.public_key)?;
+    let signature = Signature::from_bytes(&request.signature).map_err(error)?;
+    let messages = message_scalars::<X>(&request.messages)?;
+    let disclosed_indexes: Vec<usize> = request.disclosed_indexes.iter().map(|&i| i as usize).collect();
+    let proof = Proof::generate::<X>(
+        &signature,
+        &pk,
+        Some(&request.header),
+        Some(&request.presentation_header),
+        &messages,
+        &disclosed_indexes,
+    )
+    .map_err(error)?;
+    Ok(proof.to_bytes())
+}
+
+fn proof_verify_with<'a, X>(request: &ProofVerifyRequest) -> Result<bool, Status>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(&request.public_key)?;
+    let proof = Proof::from_bytes(&request.proof).map_err(error)?;
+    let mut disclosed_messages = BTreeMap::new();
+    for (&i, msg) in &request.disclosed_messages {
+        disclosed_messages.insert(i as usize, message_scalar::<X>(msg)?);
+    }
+    Ok(proof.verify::<X>(&pk, Some(&request.header), Some(&request.presentation_header), &disclosed_messages))
+}
+
+fn ciphersuite(value: i32) -> Result<Ciphersuite, Status> {
+    match Ciphersuite::from_i32(value) {
+        Some(Ciphersuite::Unspecified) | None => Err(Status::invalid_argument("unknown ciphersuite")),
+        Some(suite) => Ok(suite),
+    }
+}
+
+fn message_scalar<'a, X>(msg: &[u8]) -> Result<Scalar, Status>
+where
+    X: BbsCiphersuite<'a>
+{
+    map_message_to_scalar_as_hash::<X>(msg, None).map_err(error)
+}
+
+fn message_scalars<'a, X>(messages: &[Vec<u8>]) -> Result<Vec<Scalar>, Status>
+where
+    X: BbsCiphersuite<'a>
+{
+    messages.iter().map(|msg| message_scalar::<X>(msg)).collect()
+}
+
+fn non_empty(octets: &[u8]) -> Option<&[u8]> {
+    (!octets.is_empty()).then(|| octets)
+}
+
+fn error(e: BbsError) -> Status {
+    Status::invalid_argument(e.to_string())
+}
+
+fn secret_key_from(bytes: &[u8]) -> Result<SecretKey, Status> {
+    let bytes = bytes.try_into().map_err(|_| Status::invalid_argument("secret key must be 32 bytes"))?;
+    SecretKey::from_bytes(bytes).map_err(error)
+}
+
+fn public_key_from(bytes: &[u8]) -> Result<PublicKey, Status> {
+    let bytes = bytes.try_into().map_err(|_| Status::invalid_argument("public key must be 96 bytes"))?;
+    PublicKey::from_bytes(bytes).map_err(error)
+}
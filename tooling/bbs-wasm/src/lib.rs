@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use js_sys::{Array, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use bbs::ciphersuites::{BbsCiphersuite, Bls12381Sha256, Bls12381Shake256};
+use bbs::curve::{Backend, PairingCurve};
+use bbs::generators::make_generators;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::{BbsError, Proof, PublicKey, Scalar, SecretKey, Signature};
+
+// Bindings over the same code that generates the spec fixtures. Keys, signatures, proofs and
+// generators cross the boundary as their octet encodings, messages as arrays of Uint8Array that
+// are mapped to scalars with map_message_to_scalar_as_hash. suite is "sha256" or "shake256"
+
+enum Ciphersuite {
+    SHA256,
+    SHAKE256,
+}
+
+fn ciphersuite(suite: &str) -> Result<Ciphersuite, JsValue> {
+    match suite.to_lowercase().as_str() {
+        "sha256" | "bls12-381-sha-256" => Ok(Ciphersuite::SHA256),
+        "shake256" | "bls12-381-shake-256" => Ok(Ciphersuite::SHAKE256),
+        _ => Err(JsValue::from_str("unknown ciphersuite")),
+    }
+}
+
+#[wasm_bindgen]
+pub struct KeyPair {
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl KeyPair {
+    #[wasm_bindgen(getter, js_name = secretKey)]
+    pub fn secret_key(&self) -> Vec<u8> {
+        self.secret_key.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+}
+
+#[wasm_bindgen]
+pub fn keygen(ikm: &[u8], key_info: Option<Vec<u8>>) -> Result<KeyPair, JsValue> {
+    let key_pair = bbs::KeyPair::from_ikm(ikm, key_info.as_deref()).map_err(error)?;
+
+    Ok(KeyPair {
+        secret_key: key_pair.secret_key.to_bytes().to_vec(),
+        public_key: key_pair.public_key.to_bytes().to_vec(),
+    })
+}
+
+#[wasm_bindgen]
+pub fn sign(suite: &str, secret_key: &[u8], header: Option<Vec<u8>>, messages: Array) -> Result<Vec<u8>, JsValue> {
+    let sign_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => sign_with::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => sign_with::<Bls12381Shake256>,
+    };
+    sign_fn(secret_key, header.as_deref(), &octets(&messages))
+}
+
+#[wasm_bindgen]
+pub fn verify(
+    suite: &str,
+    public_key: &[u8],
+    signature: &[u8],
+    header: Option<Vec<u8>>,
+    messages: Array,
+) -> Result<bool, JsValue> {
+    let verify_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => verify_with::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => verify_with::<Bls12381Shake256>,
+    };
+    verify_fn(public_key, signature, header.as_deref(), &octets(&messages))
+}
+
+// disclosed_indexes are zero based and ascending
+#[wasm_bindgen(js_name = proofGen)]
+pub fn proof_gen(
+    suite: &str,
+    public_key: &[u8],
+    signature: &[u8],
+    header: Option<Vec<u8>>,
+    ph: Option<Vec<u8>>,
+    messages: Array,
+    disclosed_indexes: Vec<u32>,
+) -> Result<Vec<u8>, JsValue> {
+    let proof_gen_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => proof_gen_with::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => proof_gen_with::<Bls12381Shake256>,
+    };
+    let disclosed_indexes: Vec<usize> = disclosed_indexes.into_iter().map(|i| i as usize).collect();
+    proof_gen_fn(public_key, signature, header.as_deref(), ph.as_deref(), &octets(&messages), &disclosed_indexes)
+}
+
+// disclosed_messages[i] is the message signed at disclosed_indexes[i]
+#[wasm_bindgen(js_name = proofVerify)]
+pub fn proof_verify(
+    suite: &str,
+    public_key: &[u8],
+    proof: &[u8],
+    header: Option<Vec<u8>>,
+    ph: Option<Vec<u8>>,
+    disclosed_messages: Array,
+    disclosed_indexes: Vec<u32>,
+) -> Result<bool, JsValue> {
+    let disclosed_messages = octets(&disclosed_messages);
+    if disclosed_messages.len() != disclosed_indexes.len() {
+        return Err(JsValue::from_str("one disclosed message is required per disclosed index"));
+    }
+
+    let proof_verify_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => proof_verify_with::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => proof_verify_with::<Bls12381Shake256>,
+    };
+    let disclosed: Vec<(usize, Vec<u8>)> = disclosed_indexes
+        .into_iter()
+        .map(|i| i as usize)
+        .zip(disclosed_messages)
+        .collect();
+    proof_verify_fn(public_key, proof, header.as_deref(), ph.as_deref(), &disclosed)
+}
+
+// The base point P1 followed by count message generators, 48 octets each
+#[wasm_bindgen(js_name = createGenerators)]
+pub fn create_generators(suite: &str, count: usize) -> Result<Vec<u8>, JsValue> {
+    let make_generators_fn = match ciphersuite(suite)? {
+        Ciphersuite::SHA256 => make_generators::<Bls12381Sha256>,
+        Ciphersuite::SHAKE256 => make_generators::<Bls12381Shake256>,
+    };
+    let generators = make_generators_fn(None, count);
+
+    let mut bytes = Backend::g1_to_bytes(&generators.g1_base_point).to_vec();
+    for g in &generators.message_generators {
+        bytes.extend_from_slice(&Backend::g1_to_bytes(g));
+    }
+    Ok(bytes)
+}
+
+fn sign_with<'a, X>(secret_key: &[u8], header: Option<&[u8]>, messages: &[Vec<u8>]) -> Result<Vec<u8>, JsValue>
+where
+    X: BbsCiphersuite<'a>
+{
+    let sk = secret_key_from(secret_key)?;
+    let messages = message_scalars::<X>(messages)?;
+    let signature = Signature::sign::<X>(&messages, &sk, header).map_err(error)?;
+    Ok(signature.to_bytes().to_vec())
+}
+
+fn verify_with<'a, X>(
+    public_key: &[u8],
+    signature: &[u8],
+    header: Option<&[u8]>,
+    messages: &[Vec<u8>],
+) -> Result<bool, JsValue>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let signature = signature_from(signature)?;
+    let messages = message_scalars::<X>(messages)?;
+    Ok(signature.verify::<X>(&pk, &messages, header))
+}
+
+fn proof_gen_with<'a, X>(
+    public_key: &[u8],
+    signature: &[u8],
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    messages: &[Vec<u8>],
+    disclosed_indexes: &[usize],
+) -> Result<Vec<u8>, JsValue>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let signature = signature_from(signature)?;
+    let messages = message_scalars::<X>(messages)?;
+    let proof = Proof::generate::<X>(&signature, &pk, header, ph, &messages, disclosed_indexes)
+        .map_err(error)?;
+    Ok(proof.to_bytes())
+}
+
+fn proof_verify_with<'a, X>(
+    public_key: &[u8],
+    proof: &[u8],
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    disclosed: &[(usize, Vec<u8>)],
+) -> Result<bool, JsValue>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let proof = Proof::from_bytes(proof).map_err(error)?;
+    let mut disclosed_messages = BTreeMap::new();
+    for (i, msg) in disclosed {
+        disclosed_messages.insert(*i, message_scalar::<X>(msg)?);
+    }
+    Ok(proof.verify::<X>(&pk, header, ph, &disclosed_messages))
+}
+
+fn octets(array: &Array) -> Vec<Vec<u8>> {
+    array.iter().map(|v| Uint8Array::new(&v).to_vec()).collect()
+}
+
+fn message_scalar<'a, X>(msg: &[u8]) -> Result<Scalar, JsValue>
+where
+    X: BbsCiphersuite<'a>
+{
+    map_message_to_scalar_as_hash::<X>(msg, None).map_err(error)
+}
+
+fn message_scalars<'a, X>(messages: &[Vec<u8>]) -> Result<Vec<Scalar>, JsValue>
+where
+    X: BbsCiphersuite<'a>
+{
+    messages.iter().map(|msg| message_scalar::<X>(msg)).collect()
+}
+
+// Errors cross the boundary as the Display of the BbsError
+fn error(e: BbsError) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+fn secret_key_from(bytes: &[u8]) -> Result<SecretKey, JsValue> {
+    let bytes = bytes.try_into().map_err(|_| "secret key must be 32 bytes")?;
+    SecretKey::from_bytes(bytes).map_err(error)
+}
+
+fn public_key_from(bytes: &[u8]) -> Result<PublicKey, JsValue> {
+    let bytes = bytes.try_into().map_err(|_| "public key must be 96 bytes")?;
+    PublicKey::from_bytes(bytes).map_err(error)
+}
+
+fn signature_from(bytes: &[u8]) -> Result<Signature, JsValue> {
+    Signature::from_bytes(bytes).map_err(error)
+}
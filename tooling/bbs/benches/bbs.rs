@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use bbs::ciphersuites::{BbsCiphersuite, Bls12381Sha256, Bls12381Shake256};
+use bbs::curve::{Backend, PairingCurve};
+use bbs::generators::make_generators;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::{KeyPair, Proof, Scalar, Signature};
+
+const MESSAGE_COUNTS: [usize; 4] = [1, 10, 100, 1000];
+const IKM: &[u8] = b"bbs-signature benchmark input key material";
+
+fn messages<'a, X>(count: usize) -> Vec<Scalar>
+where
+    X: BbsCiphersuite<'a>
+{
+    (0..count)
+        .map(|i| map_message_to_scalar_as_hash::<X>(format!("message {}", i).as_bytes(), None).unwrap())
+        .collect()
+}
+
+fn bench_suite<'a, X>(c: &mut Criterion, name: &str)
+where
+    X: BbsCiphersuite<'a>
+{
+    let header: &[u8] = b"benchmark header";
+    let ph: &[u8] = b"benchmark presentation header";
+
+    c.bench_function(&format!("{}/keygen", name), |b| {
+        b.iter(|| KeyPair::from_ikm(IKM, None))
+    });
+
+    let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+
+    let mut group = c.benchmark_group(name);
+    for count in MESSAGE_COUNTS {
+        let msgs = messages::<X>(count);
+        let signature = Signature::sign::<X>(&msgs, &key_pair.secret_key, Some(header)).unwrap();
+
+        // Every other message disclosed
+        let disclosed_indexes: Vec<usize> = (0..count).step_by(2).collect();
+        let disclosed: BTreeMap<usize, Scalar> = disclosed_indexes.iter().map(|&i| (i, msgs[i])).collect();
+        let proof = Proof::generate::<X>(&signature, &key_pair.public_key, Some(header), Some(ph), &msgs, &disclosed_indexes)
+            .unwrap();
+
+        group.bench_with_input(BenchmarkId::new("generators", count), &count, |b, &count| {
+            b.iter(|| make_generators::<X>(None, count + 2))
+        });
+        group.bench_with_input(BenchmarkId::new("sign", count), &msgs, |b, msgs| {
+            b.iter(|| Signature::sign::<X>(msgs, &key_pair.secret_key, Some(header)))
+        });
+        group.bench_with_input(BenchmarkId::new("verify", count), &msgs, |b, msgs| {
+            b.iter(|| signature.verify::<X>(&key_pair.public_key, msgs, Some(header)))
+        });
+        group.bench_with_input(BenchmarkId::new("proofgen", count), &msgs, |b, msgs| {
+            b.iter(|| {
+                Proof::generate::<X>(&signature, &key_pair.public_key, Some(header), Some(ph), msgs, &disclosed_indexes)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("proofverify", count), &disclosed, |b, disclosed| {
+            b.iter(|| proof.verify::<X>(&key_pair.public_key, Some(header), Some(ph), disclosed))
+        });
+    }
+    group.finish();
+}
+
+// The pairing check of Verify, e(A, W + P2 * e) == e(B, P2), as two full pairings compared in GT and
+// as the single multi Miller loop and final exponentiation Verify and ProofVerify use
+fn bench_pairing_check(c: &mut Criterion) {
+    let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+    let msgs = messages::<Bls12381Sha256>(1);
+    let signature = Signature::sign::<Bls12381Sha256>(&msgs, &key_pair.secret_key, None).unwrap();
+    let octets = signature.to_bytes();
+
+    // A and e decoded from the signature, B = A * (SK + e)
+    let a = Backend::g1_from_bytes(octets[..48].try_into().unwrap()).unwrap();
+    let e = bbs::encoding::scalar_from_bytes(octets[48..80].try_into().unwrap()).unwrap();
+    let sk = bbs::encoding::scalar_from_bytes(&key_pair.secret_key.to_bytes()).unwrap();
+    let b = a * (sk + e);
+    let w_e = Backend::g2_generator() * (sk + e);
+    let p2 = Backend::g2_generator();
+
+    let mut group = c.benchmark_group("pairing-check");
+    group.bench_function("two-pairings", |bench| {
+        bench.iter(|| Backend::pairing(&a, &w_e) == Backend::pairing(&b, &p2))
+    });
+    group.bench_function("multi-miller-loop", |bench| {
+        bench.iter(|| Backend::pairing_product_is_identity(&[(a, w_e), (-b, p2)]))
+    });
+    group.finish();
+}
+
+fn bench_msm(c: &mut Criterion) {
+    let mut group = c.benchmark_group("msm");
+    for count in MESSAGE_COUNTS[1..].iter().copied() {
+        let points = make_generators::<Bls12381Sha256>(None, count).message_generators;
+        let scalars = messages::<Bls12381Sha256>(count);
+
+        group.bench_with_input(BenchmarkId::new("naive", count), &count, |bench, _| {
+            bench.iter(|| points.iter().zip(&scalars).fold(Backend::g1_identity(), |acc, (p, s)| acc + p * s))
+        });
+        group.bench_with_input(BenchmarkId::new("pippenger", count), &count, |bench, _| {
+            bench.iter(|| Backend::g1_sum_of_products_vartime(&points, &scalars))
+        });
+    }
+    group.finish();
+}
+
+// The cost of the tables against the time they save on each Sign
+fn bench_precompute(c: &mut Criterion) {
+    let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+
+    let mut group = c.benchmark_group("precompute");
+    for count in MESSAGE_COUNTS[1..3].iter().copied() {
+        let msgs = messages::<Bls12381Sha256>(count);
+        group.bench_with_input(BenchmarkId::new("tables", count), &count, |b, &count| {
+            b.iter(|| make_generators::<Bls12381Sha256>(None, count + 2).precompute(count + 2))
+        });
+
+        let precomputed = make_generators::<Bls12381Sha256>(None, count + 2).precompute(count + 2);
+        group.bench_with_input(BenchmarkId::new("sign", count), &msgs, |b, msgs| {
+            b.iter(|| Signature::sign::<Bls12381Sha256>(msgs, &key_pair.secret_key, None))
+        });
+        group.bench_with_input(BenchmarkId::new("sign-precomputed", count), &msgs, |b, msgs| {
+            b.iter(|| Signature::sign_precomputed::<Bls12381Sha256>(msgs, &key_pair.secret_key, None, &precomputed))
+        });
+    }
+    group.finish();
+}
+
+fn benches(c: &mut Criterion) {
+    bench_suite::<Bls12381Shake256>(c, "BLS12-381-SHAKE-256");
+    bench_suite::<Bls12381Sha256>(c, "BLS12-381-SHA-256");
+    bench_pairing_check(c);
+    bench_msm(c);
+    bench_precompute(c);
+}
+
+criterion_group! {
+    name = bbs_benches;
+    config = Criterion::default().sample_size(10);
+    targets = benches
+}
+criterion_main!(bbs_benches);
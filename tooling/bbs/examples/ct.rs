@@ -0,0 +1,44 @@
+use std::env;
+use std::process;
+
+use bbs::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+use bbs::ct::{test_proof_gen, test_sign, TimingReport, T_THRESHOLD};
+
+// Number of timed runs per operation and ciphersuite, overridden by the first argument
+const DEFAULT_SAMPLES: usize = 10_000;
+
+fn main() {
+    let samples = env::args()
+        .nth(1)
+        .map(|s| s.parse().expect("samples must be a number"))
+        .unwrap_or(DEFAULT_SAMPLES);
+
+    let reports = [
+        ("BLS12-381-SHA-256", test_sign::<Bls12381Sha256>(samples)),
+        ("BLS12-381-SHA-256", test_proof_gen::<Bls12381Sha256>(samples)),
+        ("BLS12-381-SHAKE-256", test_sign::<Bls12381Shake256>(samples)),
+        ("BLS12-381-SHAKE-256", test_proof_gen::<Bls12381Shake256>(samples)),
+    ];
+
+    let mut leaks = false;
+    for (suite, report) in &reports {
+        print_report(suite, report);
+        leaks |= report.leaks();
+    }
+
+    if leaks {
+        eprintln!("timing depends on secret inputs (|t| > {})", T_THRESHOLD);
+        process::exit(1);
+    }
+}
+
+fn print_report(suite: &str, report: &TimingReport) {
+    println!(
+        "{} {}: {} samples, max |t| = {:.2}{}",
+        suite,
+        report.operation,
+        report.samples,
+        report.max_t,
+        if report.leaks() { " LEAK" } else { "" }
+    );
+}
@@ -0,0 +1,49 @@
+use std::env;
+use std::process;
+
+use bbs::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+use bbs::props::{
+    check_deterministic_sign, check_octets_round_trip, check_os2ip_i2osp, check_proof, check_sign_verify, check_tampered_signature,
+    PropertyReport,
+};
+
+// Number of random cases per property and ciphersuite, overridden by the first argument
+const DEFAULT_CASES: u32 = 256;
+
+fn main() {
+    let cases = env::args()
+        .nth(1)
+        .map(|s| s.parse().expect("cases must be a number"))
+        .unwrap_or(DEFAULT_CASES);
+
+    let reports = [
+        ("BLS12-381-SHA-256", check_sign_verify::<Bls12381Sha256>(cases)),
+        ("BLS12-381-SHA-256", check_deterministic_sign::<Bls12381Sha256>(cases)),
+        ("BLS12-381-SHA-256", check_tampered_signature::<Bls12381Sha256>(cases)),
+        ("BLS12-381-SHA-256", check_proof::<Bls12381Sha256>(cases)),
+        ("BLS12-381-SHA-256", check_octets_round_trip::<Bls12381Sha256>(cases)),
+        ("BLS12-381-SHAKE-256", check_sign_verify::<Bls12381Shake256>(cases)),
+        ("BLS12-381-SHAKE-256", check_deterministic_sign::<Bls12381Shake256>(cases)),
+        ("BLS12-381-SHAKE-256", check_tampered_signature::<Bls12381Shake256>(cases)),
+        ("BLS12-381-SHAKE-256", check_proof::<Bls12381Shake256>(cases)),
+        ("BLS12-381-SHAKE-256", check_octets_round_trip::<Bls12381Shake256>(cases)),
+        ("encoding", check_os2ip_i2osp(cases)),
+    ];
+
+    let mut failed = false;
+    for (suite, report) in &reports {
+        print_report(suite, report);
+        failed |= !report.passed();
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn print_report(suite: &str, report: &PropertyReport) {
+    match &report.failure {
+        None => println!("{} {}: {} cases passed", suite, report.property, report.cases),
+        Some(failure) => println!("{} {}: FAILED {}", suite, report.property, failure),
+    }
+}
@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use bbs::Proof;
+
+// As octets_to_signature, for proofs of any number of undisclosed messages
+fuzz_target!(|data: &[u8]| {
+    if let Ok(proof) = Proof::from_bytes(data) {
+        assert_eq!(proof.to_bytes(), data);
+    }
+});
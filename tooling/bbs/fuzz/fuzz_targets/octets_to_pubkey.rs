@@ -0,0 +1,17 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use bbs::PublicKey;
+
+// A public key that decodes must encode back to the same octets. validate() runs KeyValidate on
+// it, as every verification does
+fuzz_target!(|data: &[u8]| {
+    let data: &[u8; 96] = match data.try_into() {
+        Ok(data) => data,
+        Err(_) => return,
+    };
+    if let Ok(pk) = PublicKey::from_bytes(data) {
+        assert_eq!(&pk.to_bytes(), data);
+        let _ = pk.validate();
+    }
+});
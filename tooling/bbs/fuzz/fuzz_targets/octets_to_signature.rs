@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use bbs::Signature;
+
+// Any octets either fail to decode or decode to a signature that encodes back to the same octets
+fuzz_target!(|data: &[u8]| {
+    if let Ok(signature) = Signature::from_bytes(data) {
+        assert_eq!(signature.to_bytes().as_slice(), data);
+    }
+});
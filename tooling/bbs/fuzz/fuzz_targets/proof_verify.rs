@@ -0,0 +1,36 @@
+#![no_main]
+use std::collections::BTreeMap;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use bbs::ciphersuites::Bls12381Sha256;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::{KeyPair, Proof};
+
+// Disclosed indexes are kept small, an index beyond the proof's message count is rejected before
+// any generator is created
+#[derive(Debug, Arbitrary)]
+struct Input {
+    header: Vec<u8>,
+    ph: Vec<u8>,
+    disclosed: Vec<(u8, Vec<u8>)>,
+    proof: Vec<u8>,
+}
+
+// ProofVerify of attacker controlled proofs, disclosed messages and headers against a valid public
+// key, so that decoded proofs reach the pairing checks. It must return false, never panic or hang
+fuzz_target!(|input: Input| {
+    let key_pair = KeyPair::from_ikm(b"bbs fuzzing key pair ikm of 32 octets", None).unwrap();
+    let proof = match Proof::from_bytes(&input.proof) {
+        Ok(proof) => proof,
+        Err(_) => return,
+    };
+    let disclosed: BTreeMap<_, _> = input
+        .disclosed
+        .iter()
+        .map(|(i, msg)| (*i as usize, map_message_to_scalar_as_hash::<Bls12381Sha256>(msg, None).unwrap()))
+        .collect();
+
+    let _ = proof.verify::<Bls12381Sha256>(&key_pair.public_key, Some(input.header.as_slice()), Some(input.ph.as_slice()), &disclosed);
+});
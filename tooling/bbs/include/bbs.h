@@ -0,0 +1,164 @@
+#ifndef BBS_H
+#define BBS_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The ciphersuite ids an operation runs with, any other id is rejected with BBS_ERROR_INVALID_ARGUMENT.
+ * Passed as a plain integer, a C enum value outside the Rust enum's variants would be undefined
+ * behaviour on the Rust side
+ */
+#define BBS_CIPHERSUITE_BLS12381_SHA256 0
+
+#define BBS_CIPHERSUITE_BLS12381_SHAKE256 1
+
+/**
+ * Result codes, BBS_ERROR_OK on success. The C counterpart of crate::BbsError: codes 1 to 9 name
+ * the argument that was rejected, each other crate::BbsError variant has its own code from 10 on.
+ * BBS_ERROR_PANIC is not a crate::BbsError, codes added after it continue from 23
+ */
+typedef enum BbsError {
+  BBS_ERROR_OK = 0,
+  /**
+   * A null pointer with a non zero length or a null output pointer
+   */
+  BBS_ERROR_INVALID_ARGUMENT = 1,
+  BBS_ERROR_INVALID_SECRET_KEY = 2,
+  BBS_ERROR_INVALID_PUBLIC_KEY = 3,
+  BBS_ERROR_INVALID_SIGNATURE = 4,
+  BBS_ERROR_INVALID_PROOF = 5,
+  BBS_ERROR_INVALID_MESSAGE = 6,
+  /**
+   * Disclosed indexes out of range or not in ascending order
+   */
+  BBS_ERROR_INVALID_DISCLOSED_INDEXES = 7,
+  BBS_ERROR_SIGNING_FAILED = 8,
+  /**
+   * The signature or proof is well formed but does not verify
+   */
+  BBS_ERROR_VERIFICATION_FAILED = 9,
+  BBS_ERROR_INVALID_LENGTH = 10,
+  BBS_ERROR_INVALID_POINT = 11,
+  BBS_ERROR_INVALID_SCALAR = 12,
+  BBS_ERROR_INVALID_COMMITMENT = 13,
+  /**
+   * r1 = 0 while generating the proof
+   */
+  BBS_ERROR_PROOF_GENERATION_FAILED = 14,
+  /**
+   * More messages than the default max_messages of limits::Limits
+   */
+  BBS_ERROR_TOO_MANY_MESSAGES = 15,
+  BBS_ERROR_CIPHERSUITE_MISMATCH = 16,
+  BBS_ERROR_INVALID_GENERATOR = 17,
+  BBS_ERROR_SERIALIZATION = 18,
+  BBS_ERROR_IO = 19,
+  BBS_ERROR_PKCS11 = 20,
+  BBS_ERROR_INVALID_KEY_ID = 21,
+  /**
+   * The library panicked, a bug to report rather than a rejected input
+   */
+  BBS_ERROR_PANIC = 22,
+  BBS_ERROR_INVALID_LABEL = 23,
+} BbsError;
+
+/**
+ * Borrowed input bytes, data may be null when len is 0
+ */
+typedef struct ByteSlice {
+  const uint8_t *data;
+  size_t len;
+} ByteSlice;
+
+/**
+ * Bytes allocated by the library, released with bbs_byte_buffer_free
+ */
+typedef struct ByteBuffer {
+  uint8_t *data;
+  size_t len;
+} ByteBuffer;
+
+/**
+ * Signs the messages, writing the 112 octet signature to signature_out
+ *
+ * # Safety
+ *
+ * Every ByteSlice must reference len readable bytes, messages must reference messages_len
+ * ByteSlices and signature_out 112 writable bytes
+ */
+BbsError bbs_sign(uint32_t suite,
+                  struct ByteSlice secret_key,
+                  struct ByteSlice header,
+                  const struct ByteSlice *messages,
+                  size_t messages_len,
+                  uint8_t *signature_out);
+
+/**
+ * Verifies the 112 octet signature, BBS_ERROR_VERIFICATION_FAILED when it does not verify
+ *
+ * # Safety
+ *
+ * Every ByteSlice must reference len readable bytes and messages must reference messages_len ByteSlices
+ */
+BbsError bbs_verify(uint32_t suite,
+                    struct ByteSlice public_key,
+                    struct ByteSlice signature,
+                    struct ByteSlice header,
+                    const struct ByteSlice *messages,
+                    size_t messages_len);
+
+/**
+ * Generates a proof disclosing the messages at the zero based, ascending disclosed_indexes. On
+ * success proof_out holds the proof, to be released with bbs_byte_buffer_free
+ *
+ * # Safety
+ *
+ * Every ByteSlice must reference len readable bytes, messages must reference messages_len
+ * ByteSlices, disclosed_indexes disclosed_indexes_len indexes and proof_out a writable ByteBuffer
+ */
+BbsError bbs_proof_gen(uint32_t suite,
+                       struct ByteSlice public_key,
+                       struct ByteSlice signature,
+                       struct ByteSlice header,
+                       struct ByteSlice ph,
+                       const struct ByteSlice *messages,
+                       size_t messages_len,
+                       const size_t *disclosed_indexes,
+                       size_t disclosed_indexes_len,
+                       struct ByteBuffer *proof_out);
+
+/**
+ * Verifies a proof, disclosed_messages[i] is the message signed at disclosed_indexes[i]. The
+ * indexes must be ascending without repeats, BBS_ERROR_INVALID_DISCLOSED_INDEXES otherwise, and
+ * BBS_ERROR_VERIFICATION_FAILED when the proof does not verify
+ *
+ * # Safety
+ *
+ * Every ByteSlice must reference len readable bytes, disclosed_messages must reference
+ * disclosed_len ByteSlices and disclosed_indexes disclosed_len indexes
+ */
+BbsError bbs_proof_verify(uint32_t suite,
+                          struct ByteSlice public_key,
+                          struct ByteSlice proof,
+                          struct ByteSlice header,
+                          struct ByteSlice ph,
+                          const struct ByteSlice *disclosed_messages,
+                          const size_t *disclosed_indexes,
+                          size_t disclosed_len);
+
+/**
+ * Releases a buffer returned by the library
+ *
+ * # Safety
+ *
+ * buffer must have been returned by the library and not been released before
+ */
+void bbs_byte_buffer_free(struct ByteBuffer buffer);
+
+#endif /* BBS_H */
@@ -0,0 +1,695 @@
+// Revocation with a pairing based accumulator (Nguyen, with the proofs of Vitto and Biryukov) whose
+// elements are credential identifiers signed as hidden BBS messages. The revocation manager holds
+// alpha and publishes V = P * (y_1 + alpha) * ... * (y_n + alpha) and Q~ = P~ * alpha. An allow list
+// accumulates the valid identifiers and revokes by removing them, the holder proving membership; a
+// deny list accumulates the revoked ones, the holder proving non-membership. Both proofs randomize the
+// witness C to C' = C * r, with C_bar = C' * alpha checked by e(C', Q~) == e(C_bar, P~), and prove the
+// opening of C_bar with the blinding factor of y in the BBS proof, whose challenge they share through
+// the presentation header, so y stays hidden. Witnesses are issued by the manager and must be reissued
+// after every update of V
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1, G2};
+use crate::encoding::{g1_from_octets, scalar_from_bytes, scalar_to_bytes};
+use crate::error::BbsError;
+use crate::generators::create_generators;
+use crate::key_pair::PublicKey;
+use crate::predicate::{linked_ph, opens_hidden_message};
+use crate::proof::Proof;
+use crate::secret::SecretScalar;
+use crate::signature::Signature;
+
+pub struct AccumulatorSecretKey(SecretScalar);
+
+// Q~ = P~ * alpha
+pub struct AccumulatorPublicKey(pub(crate) G2);
+
+// V, published by the manager after every update
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Accumulator(pub(crate) G1);
+
+// C = V * 1 / (y + alpha)
+pub struct MembershipWitness(pub(crate) G1);
+
+// C = (V - P * d) * 1 / (y + alpha), d = (y_1 - y) * ... * (y_n - y) being nonzero for y not in V
+pub struct NonMembershipWitness {
+    pub(crate) c: G1,
+    pub(crate) d: Scalar,
+}
+
+pub struct MembershipProof {
+    pub(crate) c_prime: G1,
+    pub(crate) c_bar: G1,
+    // T = V * r~ - C' * y~, y~ being the blinding factor of y in the BBS proof
+    pub(crate) t: G1,
+    pub(crate) r_hat: Scalar,
+}
+
+pub struct NonMembershipProof {
+    pub(crate) c_prime: G1,
+    pub(crate) c_bar: G1,
+    // E = P * u with u = d * r, nonzero when E is not the identity
+    pub(crate) e: G1,
+    // T_1 = V * r~ - C' * y~ and T_2 = P * u~
+    pub(crate) t_1: G1,
+    pub(crate) t_2: G1,
+    pub(crate) r_hat: Scalar,
+    pub(crate) u_hat: Scalar,
+}
+
+// P, created from its own seed like the blind generators. P~ is the generator of G2
+pub fn accumulator_generator<'a, X>() -> G1
+where
+    X: BbsCiphersuite<'a>
+{
+    create_generators::<X>(&[X::ID, b"ACCUMULATOR_GENERATOR_SEED"].concat(), 1)[0]
+}
+
+impl AccumulatorSecretKey {
+    #[cfg(feature = "std")]
+    pub fn generate() -> Self {
+        Self::generate_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn generate_with_rng<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + CryptoRng
+    {
+        AccumulatorSecretKey(SecretScalar(Scalar::random(rng)))
+    }
+
+    pub fn public_key(&self) -> AccumulatorPublicKey {
+        AccumulatorPublicKey(Backend::g2_generator() * self.0 .0)
+    }
+
+    // 1 / (y + alpha), an error for the one y whose sum with alpha is 0
+    fn inverse(&self, y: &Scalar) -> Result<Scalar, BbsError> {
+        Option::from((y + self.0 .0).invert()).ok_or(BbsError::InvalidScalar)
+    }
+}
+
+impl AccumulatorPublicKey {
+    pub fn to_bytes(&self) -> [u8; 96] {
+        Backend::g2_to_bytes(&self.0)
+    }
+
+    pub fn from_bytes(bytes: &[u8; 96]) -> Result<Self, BbsError> {
+        let q = Backend::g2_from_bytes(bytes).ok_or(BbsError::InvalidPoint)?;
+        if Backend::g2_is_identity(&q) {
+            return Err(BbsError::InvalidPoint);
+        }
+        Ok(AccumulatorPublicKey(q))
+    }
+}
+
+impl Accumulator {
+    // Run by the manager, V over elements, P when there are none
+    pub fn new<'a, X>(sk: &AccumulatorSecretKey, elements: &[Scalar]) -> Self
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let exponent = elements.iter().fold(Scalar::one(), |product, y| product * (y + sk.0 .0));
+        Accumulator(accumulator_generator::<X>() * exponent)
+    }
+
+    // Run by the manager, V * (y + alpha)
+    pub fn add(&self, sk: &AccumulatorSecretKey, y: &Scalar) -> Self {
+        Accumulator(self.0 * (y + sk.0 .0))
+    }
+
+    // Run by the manager, V * 1 / (y + alpha). y must be an element
+    pub fn remove(&self, sk: &AccumulatorSecretKey, y: &Scalar) -> Result<Self, BbsError> {
+        Ok(Accumulator(self.0 * sk.inverse(y)?))
+    }
+
+    // Run by the manager for the holder of the credential identified by y, an element of V
+    pub fn membership_witness(&self, sk: &AccumulatorSecretKey, y: &Scalar) -> Result<MembershipWitness, BbsError> {
+        Ok(MembershipWitness(self.0 * sk.inverse(y)?))
+    }
+
+    // Run by the manager for y, which must not be one of elements, the elements of V. An error when it is
+    pub fn non_membership_witness<'a, X>(
+        &self,
+        sk: &AccumulatorSecretKey,
+        elements: &[Scalar],
+        y: &Scalar,
+    ) -> Result<NonMembershipWitness, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let d = elements.iter().fold(Scalar::one(), |product, y_i| product * (y_i - y));
+        if bool::from(d.is_zero()) {
+            return Err(BbsError::InvalidScalar);
+        }
+        let c = (self.0 - accumulator_generator::<X>() * d) * sk.inverse(y)?;
+        Ok(NonMembershipWitness { c, d })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 48] {
+        Backend::g1_to_bytes(&self.0)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        g1_from_octets(bytes).map(Accumulator)
+    }
+}
+
+impl MembershipWitness {
+    // e(C, P~ * y + Q~) == e(V, P~), as the holder checks a witness it is given
+    pub fn verify(&self, accumulator: &Accumulator, pk: &AccumulatorPublicKey, y: &Scalar) -> bool {
+        let p2 = Backend::g2_generator();
+        Backend::pairing_product_is_identity(&[(self.0, p2 * y + pk.0), (-accumulator.0, p2)])
+    }
+
+    pub fn to_bytes(&self) -> [u8; 48] {
+        Backend::g1_to_bytes(&self.0)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        g1_from_octets(bytes).map(MembershipWitness)
+    }
+}
+
+impl NonMembershipWitness {
+    // e(C, P~ * y + Q~) * e(P * d, P~) == e(V, P~) with d nonzero
+    pub fn verify<'a, X>(&self, accumulator: &Accumulator, pk: &AccumulatorPublicKey, y: &Scalar) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let p2 = Backend::g2_generator();
+        !bool::from(self.d.is_zero())
+            && Backend::pairing_product_is_identity(&[
+                (self.c, p2 * y + pk.0),
+                (accumulator_generator::<X>() * self.d - accumulator.0, p2),
+            ])
+    }
+
+    // C || d
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&Backend::g1_to_bytes(&self.c)[..], &scalar_to_bytes(&self.d)].concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        if bytes.len() != 48 + 32 {
+            return Err(BbsError::InvalidLength("a non-membership witness is a point and a scalar"));
+        }
+        let (c, d) = bytes.split_at(48);
+        Ok(NonMembershipWitness {
+            c: g1_from_octets(c)?,
+            d: scalar_from_bytes(d.try_into().expect("32 octets"))?,
+        })
+    }
+}
+
+// Generates a BBS proof together with a proof that messages[index], which must not be disclosed, is
+// an element of accumulator. The proof is verified with verify_membership and the same ph
+#[allow(clippy::too_many_arguments)]
+pub fn prove_membership<'a, X, R>(
+    signature: &Signature,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    messages: &[Scalar],
+    disclosed_indexes: &[usize],
+    index: usize,
+    accumulator: &Accumulator,
+    witness: &MembershipWitness,
+    rng: &mut R,
+) -> Result<(Proof, MembershipProof), BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    if index >= messages.len() || disclosed_indexes.contains(&index) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+    let y = messages[index];
+
+    let r = SecretScalar(nonzero_random(&mut *rng));
+    let r_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let y_tilde = SecretScalar(Scalar::random(&mut *rng));
+
+    // C_bar = C' * alpha = V * r - C' * y
+    let c_prime = witness.0 * r.0;
+    let c_bar = accumulator.0 * r.0 - c_prime * y;
+    let t = accumulator.0 * r_tilde.0 - c_prime * y_tilde.0;
+
+    let proof = Proof::generate_with_blindings::<X, _>(
+        signature,
+        pk,
+        header,
+        Some(&linked_ph(ph, &c_prime, &c_bar, &[t])),
+        messages,
+        disclosed_indexes,
+        &BTreeMap::from([(index, y_tilde.0)]),
+        rng,
+    )?;
+
+    let membership = MembershipProof {
+        c_prime,
+        c_bar,
+        t,
+        r_hat: proof.challenge() * r.0 + r_tilde.0,
+    };
+    Ok((proof, membership))
+}
+
+// Verifies the BBS proof and that its hidden message at index, the zero based index the message was
+// signed at, is an element of accumulator
+#[allow(clippy::too_many_arguments)]
+pub fn verify_membership<'a, X>(
+    proof: &Proof,
+    membership: &MembershipProof,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    disclosed_messages: &BTreeMap<usize, Scalar>,
+    index: usize,
+    accumulator: &Accumulator,
+    accumulator_pk: &AccumulatorPublicKey,
+) -> bool
+where
+    X: BbsCiphersuite<'a>
+{
+    if disclosed_messages.contains_key(&index) || !randomized_witness_is_valid(&membership.c_prime, &membership.c_bar, accumulator_pk) {
+        return false;
+    }
+    let linked_ph = linked_ph(ph, &membership.c_prime, &membership.c_bar, &[membership.t]);
+    if !proof.verify::<X>(pk, header, Some(&linked_ph), disclosed_messages) {
+        return false;
+    }
+
+    // V * r^ - C' * y^ == T + C_bar * c
+    opens_hidden_message(
+        proof,
+        disclosed_messages,
+        index,
+        (-membership.c_prime, accumulator.0),
+        &membership.c_bar,
+        &membership.t,
+        &membership.r_hat,
+    )
+}
+
+// Generates a BBS proof together with a proof that messages[index], which must not be disclosed, is
+// not an element of accumulator. The proof is verified with verify_non_membership and the same ph
+#[allow(clippy::too_many_arguments)]
+pub fn prove_non_membership<'a, X, R>(
+    signature: &Signature,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    messages: &[Scalar],
+    disclosed_indexes: &[usize],
+    index: usize,
+    accumulator: &Accumulator,
+    witness: &NonMembershipWitness,
+    rng: &mut R,
+) -> Result<(Proof, NonMembershipProof), BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    if index >= messages.len() || disclosed_indexes.contains(&index) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+    let y = messages[index];
+    let p = accumulator_generator::<X>();
+
+    let r = SecretScalar(nonzero_random(&mut *rng));
+    let u = SecretScalar(witness.d * r.0);
+    let r_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let u_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let y_tilde = SecretScalar(Scalar::random(&mut *rng));
+
+    // C_bar = C' * alpha = V * r - E - C' * y
+    let c_prime = witness.c * r.0;
+    let e = p * u.0;
+    let c_bar = accumulator.0 * r.0 - e - c_prime * y;
+    let t_1 = accumulator.0 * r_tilde.0 - c_prime * y_tilde.0;
+    let t_2 = p * u_tilde.0;
+
+    let proof = Proof::generate_with_blindings::<X, _>(
+        signature,
+        pk,
+        header,
+        Some(&linked_ph(ph, &c_prime, &c_bar, &[e, t_1, t_2])),
+        messages,
+        disclosed_indexes,
+        &BTreeMap::from([(index, y_tilde.0)]),
+        rng,
+    )?;
+
+    let c = proof.challenge();
+    let non_membership = NonMembershipProof {
+        c_prime,
+        c_bar,
+        e,
+        t_1,
+        t_2,
+        r_hat: c * r.0 + r_tilde.0,
+        u_hat: c * u.0 + u_tilde.0,
+    };
+    Ok((proof, non_membership))
+}
+
+// Verifies the BBS proof and that its hidden message at index is not an element of accumulator
+#[allow(clippy::too_many_arguments)]
+pub fn verify_non_membership<'a, X>(
+    proof: &Proof,
+    non_membership: &NonMembershipProof,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    disclosed_messages: &BTreeMap<usize, Scalar>,
+    index: usize,
+    accumulator: &Accumulator,
+    accumulator_pk: &AccumulatorPublicKey,
+) -> bool
+where
+    X: BbsCiphersuite<'a>
+{
+    let NonMembershipProof { c_prime, c_bar, e, t_1, t_2, r_hat, u_hat } = non_membership;
+    if disclosed_messages.contains_key(&index) || Backend::g1_is_identity(e) {
+        return false;
+    }
+    if !randomized_witness_is_valid(c_prime, c_bar, accumulator_pk) {
+        return false;
+    }
+    let linked_ph = linked_ph(ph, c_prime, c_bar, &[*e, *t_1, *t_2]);
+    if !proof.verify::<X>(pk, header, Some(&linked_ph), disclosed_messages) {
+        return false;
+    }
+
+    // P * u^ == T_2 + E * c, and V * r^ - C' * y^ == T_1 + (C_bar + E) * c
+    accumulator_generator::<X>() * u_hat == t_2 + e * proof.challenge()
+        && opens_hidden_message(proof, disclosed_messages, index, (-c_prime, accumulator.0), &(c_bar + e), t_1, r_hat)
+}
+
+impl MembershipProof {
+    // C' || C_bar || T || r^
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3 * 48 + 32);
+        for p in [&self.c_prime, &self.c_bar, &self.t] {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(p));
+        }
+        bytes.extend_from_slice(&scalar_to_bytes(&self.r_hat));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        if bytes.len() != 3 * 48 + 32 {
+            return Err(BbsError::InvalidLength("a membership proof is 3 points and a scalar"));
+        }
+        let (points, scalars) = points_and_scalars(bytes, 3)?;
+        Ok(MembershipProof {
+            c_prime: points[0],
+            c_bar: points[1],
+            t: points[2],
+            r_hat: scalars[0],
+        })
+    }
+}
+
+impl NonMembershipProof {
+    // C' || C_bar || E || T_1 || T_2 || r^ || u^
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(5 * 48 + 2 * 32);
+        for p in [&self.c_prime, &self.c_bar, &self.e, &self.t_1, &self.t_2] {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(p));
+        }
+        for s in [&self.r_hat, &self.u_hat] {
+            bytes.extend_from_slice(&scalar_to_bytes(s));
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        if bytes.len() != 5 * 48 + 2 * 32 {
+            return Err(BbsError::InvalidLength("a non-membership proof is 5 points and 2 scalars"));
+        }
+        let (points, scalars) = points_and_scalars(bytes, 5)?;
+        Ok(NonMembershipProof {
+            c_prime: points[0],
+            c_bar: points[1],
+            e: points[2],
+            t_1: points[3],
+            t_2: points[4],
+            r_hat: scalars[0],
+            u_hat: scalars[1],
+        })
+    }
+}
+
+// e(C', Q~) == e(C_bar, P~) with C' not the identity, C_bar = C' * alpha
+fn randomized_witness_is_valid(c_prime: &G1, c_bar: &G1, pk: &AccumulatorPublicKey) -> bool {
+    !Backend::g1_is_identity(c_prime) && Backend::pairing_product_is_identity(&[(*c_prime, pk.0), (-c_bar, Backend::g2_generator())])
+}
+
+fn nonzero_random<R>(rng: &mut R) -> Scalar
+where
+    R: RngCore + CryptoRng
+{
+    loop {
+        let r = Scalar::random(&mut *rng);
+        if !bool::from(r.is_zero()) {
+            return r;
+        }
+    }
+}
+
+// count points followed by scalars
+fn points_and_scalars(bytes: &[u8], count: usize) -> Result<(Vec<G1>, Vec<Scalar>), BbsError> {
+    let (points, scalars) = bytes.split_at(48 * count);
+    let points = points.chunks(48).map(g1_from_octets).collect::<Result<_, _>>()?;
+    let scalars = scalars
+        .chunks(32)
+        .map(|s| scalar_from_bytes(s.try_into().expect("32 octet chunk")))
+        .collect::<Result<_, _>>()?;
+    Ok((points, scalars))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+    const INDEX: usize = 1;
+
+    // Messages whose message at INDEX is the credential identifier y, disclosing 0 and 2
+    fn credential<'a, X>(y: Scalar) -> (KeyPair, Vec<Scalar>, Signature, BTreeMap<usize, Scalar>)
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let messages = alloc::vec![Scalar::from(1u64), y, Scalar::from(3u64)];
+        let signature = Signature::sign::<X>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+        let disclosed = BTreeMap::from([(0, messages[0]), (2, messages[2])]);
+        (key_pair, messages, signature, disclosed)
+    }
+
+    fn elements() -> Vec<Scalar> {
+        (100..105u64).map(Scalar::from).collect()
+    }
+
+    fn membership<'a, X>()
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let sk = AccumulatorSecretKey::generate_with_rng(rng);
+        let accumulator_pk = sk.public_key();
+        let elements = elements();
+        let accumulator = Accumulator::new::<X>(&sk, &elements);
+        let y = elements[2];
+
+        let witness = accumulator.membership_witness(&sk, &y).unwrap();
+        assert!(witness.verify(&accumulator, &accumulator_pk, &y));
+        assert!(!witness.verify(&accumulator, &accumulator_pk, &elements[3]));
+
+        let (key_pair, messages, signature, disclosed) = credential::<X>(y);
+        let (proof, membership) = prove_membership::<X, _>(
+            &signature,
+            &key_pair.public_key,
+            Some(b"header"),
+            Some(b"ph"),
+            &messages,
+            &[0, 2],
+            INDEX,
+            &accumulator,
+            &witness,
+            rng,
+        )
+        .unwrap();
+        let verify = |membership: &MembershipProof, ph: &[u8], accumulator: &Accumulator| {
+            verify_membership::<X>(
+                &proof,
+                membership,
+                &key_pair.public_key,
+                Some(b"header"),
+                Some(ph),
+                &disclosed,
+                INDEX,
+                accumulator,
+                &accumulator_pk,
+            )
+        };
+        assert!(verify(&membership, b"ph", &accumulator));
+        assert!(verify(&MembershipProof::from_bytes(&membership.to_bytes()).unwrap(), b"ph", &accumulator));
+
+        // Another presentation header, or y revoked by its removal from V
+        assert!(!verify(&membership, b"another ph", &accumulator));
+        let revoked = accumulator.remove(&sk, &y).unwrap();
+        assert!(!verify(&membership, b"ph", &revoked));
+        assert!(!witness.verify(&revoked, &accumulator_pk, &y));
+    }
+
+    #[test]
+    fn membership_proof_verifies_until_revoked() {
+        membership::<Bls12381Sha256>();
+        membership::<Bls12381Shake256>();
+    }
+
+    #[test]
+    fn membership_proof_of_a_non_member_fails() {
+        let rng = &mut StdRng::seed_from_u64(1);
+        let sk = AccumulatorSecretKey::generate_with_rng(rng);
+        let accumulator = Accumulator::new::<Bls12381Sha256>(&sk, &elements());
+        // A witness for an element, presented with a credential whose identifier is not one
+        let witness = accumulator.membership_witness(&sk, &elements()[0]).unwrap();
+        let (key_pair, messages, signature, disclosed) = credential::<Bls12381Sha256>(Scalar::from(7u64));
+        let (proof, membership) = prove_membership::<Bls12381Sha256, _>(
+            &signature,
+            &key_pair.public_key,
+            None,
+            None,
+            &messages,
+            &[0, 2],
+            INDEX,
+            &accumulator,
+            &witness,
+            rng,
+        )
+        .unwrap();
+        assert!(!verify_membership::<Bls12381Sha256>(
+            &proof,
+            &membership,
+            &key_pair.public_key,
+            None,
+            None,
+            &disclosed,
+            INDEX,
+            &accumulator,
+            &sk.public_key()
+        ));
+
+        // The identifier can not be disclosed
+        let result = prove_membership::<Bls12381Sha256, _>(
+            &signature,
+            &key_pair.public_key,
+            None,
+            None,
+            &messages,
+            &[0, INDEX],
+            INDEX,
+            &accumulator,
+            &witness,
+            rng,
+        );
+        assert!(matches!(result, Err(BbsError::InvalidDisclosedIndexes)));
+    }
+
+    #[test]
+    fn non_membership_proof_verifies_until_the_element_is_added() {
+        let rng = &mut StdRng::seed_from_u64(2);
+        let sk = AccumulatorSecretKey::generate_with_rng(rng);
+        let accumulator_pk = sk.public_key();
+        let elements = elements();
+        let accumulator = Accumulator::new::<Bls12381Sha256>(&sk, &elements);
+        let y = Scalar::from(7u64);
+
+        // No witness for an element of the deny list
+        let result = accumulator.non_membership_witness::<Bls12381Sha256>(&sk, &elements, &elements[1]);
+        assert!(matches!(result, Err(BbsError::InvalidScalar)));
+
+        let witness = accumulator.non_membership_witness::<Bls12381Sha256>(&sk, &elements, &y).unwrap();
+        assert!(witness.verify::<Bls12381Sha256>(&accumulator, &accumulator_pk, &y));
+        let decoded = NonMembershipWitness::from_bytes(&witness.to_bytes()).unwrap();
+        assert!(decoded.verify::<Bls12381Sha256>(&accumulator, &accumulator_pk, &y));
+
+        let (key_pair, messages, signature, disclosed) = credential::<Bls12381Sha256>(y);
+        let (proof, non_membership) = prove_non_membership::<Bls12381Sha256, _>(
+            &signature,
+            &key_pair.public_key,
+            Some(b"header"),
+            Some(b"ph"),
+            &messages,
+            &[0, 2],
+            INDEX,
+            &accumulator,
+            &witness,
+            rng,
+        )
+        .unwrap();
+        let verify = |non_membership: &NonMembershipProof, accumulator: &Accumulator| {
+            verify_non_membership::<Bls12381Sha256>(
+                &proof,
+                non_membership,
+                &key_pair.public_key,
+                Some(b"header"),
+                Some(b"ph"),
+                &disclosed,
+                INDEX,
+                accumulator,
+                &accumulator_pk,
+            )
+        };
+        assert!(verify(&non_membership, &accumulator));
+        assert!(verify(&NonMembershipProof::from_bytes(&non_membership.to_bytes()).unwrap(), &accumulator));
+
+        // Revoked by adding y to the deny list
+        let denied = accumulator.add(&sk, &y);
+        assert!(!verify(&non_membership, &denied));
+        assert!(!witness.verify::<Bls12381Sha256>(&denied, &accumulator_pk, &y));
+    }
+
+    #[test]
+    fn add_and_remove_match_new() {
+        let sk = AccumulatorSecretKey::generate_with_rng(&mut StdRng::seed_from_u64(3));
+        let elements = elements();
+        let accumulator = Accumulator::new::<Bls12381Sha256>(&sk, &elements[..4]);
+        assert_eq!(accumulator.add(&sk, &elements[4]), Accumulator::new::<Bls12381Sha256>(&sk, &elements));
+        assert_eq!(accumulator.remove(&sk, &elements[0]).unwrap(), Accumulator::new::<Bls12381Sha256>(&sk, &elements[1..4]));
+        assert_eq!(Accumulator::new::<Bls12381Sha256>(&sk, &[]).0, accumulator_generator::<Bls12381Sha256>());
+    }
+
+    #[test]
+    fn from_bytes_rejects_malformed_octets() {
+        let sk = AccumulatorSecretKey::generate_with_rng(&mut StdRng::seed_from_u64(4));
+        let accumulator = Accumulator::new::<Bls12381Sha256>(&sk, &elements());
+        assert_eq!(Accumulator::from_bytes(&accumulator.to_bytes()).unwrap(), accumulator);
+        let pk = sk.public_key();
+        assert_eq!(AccumulatorPublicKey::from_bytes(&pk.to_bytes()).unwrap().to_bytes(), pk.to_bytes());
+
+        let mut identity = [0u8; 48];
+        identity[0] = 0xc0;
+        assert!(matches!(Accumulator::from_bytes(&identity), Err(BbsError::InvalidPoint)));
+        assert!(matches!(MembershipWitness::from_bytes(&identity), Err(BbsError::InvalidPoint)));
+        let mut identity = [0u8; 96];
+        identity[0] = 0xc0;
+        assert!(matches!(AccumulatorPublicKey::from_bytes(&identity), Err(BbsError::InvalidPoint)));
+
+        assert!(matches!(NonMembershipWitness::from_bytes(&[0; 79]), Err(BbsError::InvalidLength(_))));
+        assert!(matches!(MembershipProof::from_bytes(&[0; 3 * 48 + 31]), Err(BbsError::InvalidLength(_))));
+        assert!(matches!(NonMembershipProof::from_bytes(&[0; 5 * 48 + 2 * 32 + 1]), Err(BbsError::InvalidLength(_))));
+    }
+}
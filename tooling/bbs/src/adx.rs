@@ -0,0 +1,361 @@
+// Multi-scalar multiplication over field arithmetic of its own, built with ADX and BMI2 and selected
+// at runtime by curve.rs when the CPU has them, see cpu.rs. The bls12_381_plus arithmetic is compiled
+// once for the target features of the build and is not inlined across the crate boundary, so building
+// a copy of a caller with the features enabled leaves it on the baseline instructions. Here the 381
+// bit Montgomery multiplication is written with the _mulx_u64 and _addcarryx_u64 intrinsics and
+// inlined into the bucket method, which only needs additions of points: the complete formulas of
+// Renes, Costello and Batina, Algorithm 7 of https://eprint.iacr.org/2015/1060, in projective
+// coordinates on y^2 = x^3 + 4. Points cross from and to bls12_381_plus in their uncompressed encoding
+use alloc::vec;
+use alloc::vec::Vec;
+use core::arch::x86_64::{_addcarryx_u64, _mulx_u64};
+
+use bls12_381_plus::{G1Affine, G1Projective};
+
+use crate::curve::{pippenger_window, window_value};
+
+// p, little endian 64 bit limbs
+const MODULUS: [u64; 6] = [
+    0xb9fe_ffff_ffff_aaab,
+    0x1eab_fffe_b153_ffff,
+    0x6730_d2a0_f6b0_f624,
+    0x6477_4b84_f385_12bf,
+    0x4b1b_a7b6_434b_acd7,
+    0x1a01_11ea_397f_e69a,
+];
+
+// -p^-1 mod 2^64
+const INV: u64 = 0x89f3_fffc_fffc_fffd;
+
+// 2^384 mod p, 1 in Montgomery form
+const R: Fp = Fp([
+    0x7609_0000_0002_fffd,
+    0xebf4_000b_c40c_0002,
+    0x5f48_9857_53c7_58ba,
+    0x77ce_5853_7052_5745,
+    0x5c07_1a97_a256_ec6d,
+    0x15f6_5ec3_fa80_e493,
+]);
+
+// 2^768 mod p, to move into Montgomery form
+const R2: Fp = Fp([
+    0xf4df_1f34_1c34_1746,
+    0x0a76_e6a6_09d1_04f1,
+    0x8de5_476c_4c95_b6d5,
+    0x67eb_88a9_939d_83c0,
+    0x9a79_3e85_b519_952d,
+    0x1198_8fe5_92ca_e3aa,
+]);
+
+// An element of F_p in Montgomery form, less than p
+#[derive(Clone, Copy, PartialEq)]
+struct Fp([u64; 6]);
+
+// Projective x, y, z with x = X / Z and y = Y / Z, the identity is (0, 1, 0)
+#[derive(Clone, Copy)]
+struct Point {
+    x: Fp,
+    y: Fp,
+    z: Fp,
+}
+
+const ZERO: Fp = Fp([0; 6]);
+const IDENTITY: Point = Point { x: ZERO, y: R, z: ZERO };
+
+// a + b * c + carry as (low, high)
+#[inline(always)]
+unsafe fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let mut hi = 0;
+    let lo = _mulx_u64(b, c, &mut hi);
+    let mut sum = 0;
+    let k = _addcarryx_u64(0, lo, a, &mut sum);
+    _addcarryx_u64(k, hi, 0, &mut hi);
+    let mut out = 0;
+    let k = _addcarryx_u64(0, sum, carry, &mut out);
+    _addcarryx_u64(k, hi, 0, &mut hi);
+    (out, hi)
+}
+
+// Subtracts p when a >= p, a < 2p
+#[inline(always)]
+unsafe fn reduce(a: [u64; 6]) -> Fp {
+    let mut d = [0u64; 6];
+    let mut borrow = 0u8;
+    for i in 0..6 {
+        let (t, b1) = a[i].overflowing_sub(MODULUS[i]);
+        let (t, b2) = t.overflowing_sub(borrow as u64);
+        d[i] = t;
+        borrow = (b1 | b2) as u8;
+    }
+    if borrow == 0 {
+        Fp(d)
+    } else {
+        Fp(a)
+    }
+}
+
+impl Fp {
+    // CIOS Montgomery multiplication a * b / 2^384 mod p. p < 2^382, so the intermediate sums fit in
+    // seven limbs and the result is less than 2p before the final subtraction
+    #[inline(always)]
+    unsafe fn mul(&self, rhs: &Fp) -> Fp {
+        let (a, b) = (&self.0, &rhs.0);
+        let mut t = [0u64; 7];
+        for i in 0..6 {
+            let mut carry = 0;
+            for j in 0..6 {
+                let (lo, hi) = mac(t[j], a[j], b[i], carry);
+                t[j] = lo;
+                carry = hi;
+            }
+            t[6] = carry;
+
+            let m = t[0].wrapping_mul(INV);
+            let (_, mut carry) = mac(t[0], m, MODULUS[0], 0);
+            for j in 1..6 {
+                let (lo, hi) = mac(t[j], m, MODULUS[j], carry);
+                t[j - 1] = lo;
+                carry = hi;
+            }
+            let mut top = 0;
+            _addcarryx_u64(0, t[6], carry, &mut top);
+            t[5] = top;
+        }
+        reduce([t[0], t[1], t[2], t[3], t[4], t[5]])
+    }
+
+    #[inline(always)]
+    unsafe fn add(&self, rhs: &Fp) -> Fp {
+        let mut s = [0u64; 6];
+        let mut carry = 0u8;
+        for i in 0..6 {
+            carry = _addcarryx_u64(carry, self.0[i], rhs.0[i], &mut s[i]);
+        }
+        reduce(s)
+    }
+
+    #[inline(always)]
+    unsafe fn sub(&self, rhs: &Fp) -> Fp {
+        let mut d = [0u64; 6];
+        let mut borrow = false;
+        for i in 0..6 {
+            let (t, b1) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (t, b2) = t.overflowing_sub(borrow as u64);
+            d[i] = t;
+            borrow = b1 | b2;
+        }
+        if borrow {
+            let mut carry = 0u8;
+            for i in 0..6 {
+                carry = _addcarryx_u64(carry, d[i], MODULUS[i], &mut d[i]);
+            }
+        }
+        Fp(d)
+    }
+
+    // 3 * b = 12
+    #[inline(always)]
+    unsafe fn mul_by_3b(&self) -> Fp {
+        let x2 = self.add(self);
+        let x4 = x2.add(&x2);
+        let x8 = x4.add(&x4);
+        x8.add(&x4)
+    }
+
+    // The 48 octet big endian integer, less than p as the uncompressed encoding guarantees
+    #[inline(always)]
+    unsafe fn from_be_bytes(bytes: &[u8]) -> Fp {
+        let mut limbs = [0u64; 6];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let start = 40 - 8 * i;
+            *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().expect("8 octets"));
+        }
+        Fp(limbs).mul(&R2)
+    }
+
+    #[inline(always)]
+    unsafe fn to_be_bytes(self, out: &mut [u8]) {
+        let limbs = self.mul(&Fp([1, 0, 0, 0, 0, 0])).0;
+        for (i, limb) in limbs.iter().enumerate() {
+            let start = 40 - 8 * i;
+            out[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+        }
+    }
+
+    // self^(p - 2), variable time in the public exponent only
+    #[inline(always)]
+    unsafe fn invert(&self) -> Fp {
+        let mut exponent = MODULUS;
+        exponent[0] -= 2;
+        let mut acc = R;
+        for i in (0..6).rev() {
+            for bit in (0..64).rev() {
+                acc = acc.mul(&acc);
+                if (exponent[i] >> bit) & 1 == 1 {
+                    acc = acc.mul(self);
+                }
+            }
+        }
+        acc
+    }
+}
+
+impl Point {
+    // Algorithm 7 of https://eprint.iacr.org/2015/1060, complete so it also doubles and handles the
+    // identity
+    #[inline(always)]
+    unsafe fn add(&self, rhs: &Point) -> Point {
+        let t0 = self.x.mul(&rhs.x);
+        let t1 = self.y.mul(&rhs.y);
+        let t2 = self.z.mul(&rhs.z);
+        let t3 = self.x.add(&self.y);
+        let t4 = rhs.x.add(&rhs.y);
+        let t3 = t3.mul(&t4);
+        let t4 = t0.add(&t1);
+        let t3 = t3.sub(&t4);
+        let t4 = self.y.add(&self.z);
+        let x3 = rhs.y.add(&rhs.z);
+        let t4 = t4.mul(&x3);
+        let x3 = t1.add(&t2);
+        let t4 = t4.sub(&x3);
+        let x3 = self.x.add(&self.z);
+        let y3 = rhs.x.add(&rhs.z);
+        let x3 = x3.mul(&y3);
+        let y3 = t0.add(&t2);
+        let y3 = x3.sub(&y3);
+        let x3 = t0.add(&t0);
+        let t0 = x3.add(&t0);
+        let t2 = t2.mul_by_3b();
+        let z3 = t1.add(&t2);
+        let t1 = t1.sub(&t2);
+        let y3 = y3.mul_by_3b();
+        let x3 = t4.mul(&y3);
+        let t2 = t3.mul(&t1);
+        let x3 = t2.sub(&x3);
+        let y3 = y3.mul(&t0);
+        let t1 = t1.mul(&z3);
+        let y3 = t1.add(&y3);
+        let t0 = t0.mul(&t3);
+        let z3 = z3.mul(&t4);
+        let z3 = z3.add(&t0);
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    // The uncompressed encoding x || y, the infinity flag alone for the identity
+    #[inline(always)]
+    unsafe fn from_uncompressed(bytes: &[u8; 96]) -> Point {
+        if bytes[0] & 0x40 != 0 {
+            return IDENTITY;
+        }
+        let mut x = [0u8; 48];
+        x.copy_from_slice(&bytes[..48]);
+        x[0] &= 0x1f;
+        Point { x: Fp::from_be_bytes(&x), y: Fp::from_be_bytes(&bytes[48..]), z: R }
+    }
+
+    #[inline(always)]
+    unsafe fn to_uncompressed(self) -> [u8; 96] {
+        let mut bytes = [0u8; 96];
+        if self.z == ZERO {
+            bytes[0] = 0x40;
+            return bytes;
+        }
+        let z_inv = self.z.invert();
+        self.x.mul(&z_inv).to_be_bytes(&mut bytes[..48]);
+        self.y.mul(&z_inv).to_be_bytes(&mut bytes[48..]);
+        bytes
+    }
+}
+
+// Pippenger's bucket method as curve::pippenger, on points in their uncompressed encoding and
+// little endian scalars
+//
+// Safety: the CPU must have ADX and BMI2
+#[target_feature(enable = "adx,bmi2")]
+pub(crate) unsafe fn pippenger(points: &[G1Affine], scalars: &[[u8; 32]]) -> G1Projective {
+    let n = points.len().min(scalars.len());
+    let points: Vec<Point> = points[..n].iter().map(|p| Point::from_uncompressed(&p.to_uncompressed())).collect();
+    let c = pippenger_window(n);
+    let windows = (255 + c - 1) / c;
+
+    let mut acc = IDENTITY;
+    for w in (0..windows).rev() {
+        for _ in 0..c {
+            acc = acc.add(&acc);
+        }
+
+        let mut buckets = vec![IDENTITY; (1 << c) - 1];
+        for (p, s) in points.iter().zip(scalars) {
+            let d = window_value(s, w * c, c);
+            if d != 0 {
+                buckets[d - 1] = buckets[d - 1].add(p);
+            }
+        }
+
+        let mut running = IDENTITY;
+        let mut sum = IDENTITY;
+        for bucket in buckets.iter().rev() {
+            running = running.add(bucket);
+            sum = sum.add(&running);
+        }
+        acc = acc.add(&sum);
+    }
+
+    let p: Option<G1Affine> = G1Affine::from_uncompressed_unchecked(&acc.to_uncompressed()).into();
+    G1Projective::from(p.expect("a sum of points of the curve is on the curve"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::Bls12381Sha256;
+    use crate::curve::pippenger_portable;
+    use crate::generators::make_generators;
+    use ff::Field;
+    use group::{Curve, Group};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn adx_bmi2() -> bool {
+        std::is_x86_feature_detected!("adx") && std::is_x86_feature_detected!("bmi2")
+    }
+
+    #[test]
+    fn field_arithmetic_matches_bls12_381_plus() {
+        if !adx_bmi2() {
+            return;
+        }
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..16 {
+            let (p, q) = (G1Projective::random(&mut rng), G1Projective::random(&mut rng));
+            let (pa, qa) = (p.to_affine().to_uncompressed(), q.to_affine().to_uncompressed());
+            unsafe {
+                let (pp, qp) = (Point::from_uncompressed(&pa), Point::from_uncompressed(&qa));
+                assert_eq!(pp.to_uncompressed(), pa);
+                assert_eq!(pp.add(&qp).to_uncompressed(), (p + q).to_affine().to_uncompressed());
+                assert_eq!(pp.add(&pp).to_uncompressed(), p.double().to_affine().to_uncompressed());
+                assert_eq!(pp.add(&IDENTITY).to_uncompressed(), pa);
+                let neg = Point::from_uncompressed(&(-p).to_affine().to_uncompressed());
+                assert_eq!(pp.add(&neg).to_uncompressed(), G1Affine::identity().to_uncompressed());
+            }
+        }
+    }
+
+    #[test]
+    fn pippenger_matches_the_portable_build() {
+        if !adx_bmi2() {
+            return;
+        }
+        let mut rng = StdRng::seed_from_u64(2);
+        for n in [16, 17, 64, 200] {
+            let points = make_generators::<Bls12381Sha256>(None, n).message_generators;
+            let mut scalars: Vec<_> = (0..n).map(|_| crate::Scalar::random(&mut rng)).collect();
+            scalars[1] = crate::Scalar::zero();
+            let mut affine = vec![G1Affine::identity(); n];
+            G1Projective::batch_normalize(&points, &mut affine);
+            let octets: Vec<[u8; 32]> = scalars.iter().map(|s| s.to_bytes()).collect();
+            let adx = unsafe { pippenger(&affine, &octets) };
+            assert_eq!(adx, pippenger_portable(&affine, &octets), "{} points", n);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+// Experimental, not defined by the draft: one object for several signatures of the same issuer over
+// disjoint parts of a message vector, e.g. a credential whose attributes were signed in batches.
+// Each part lists the indexes of the messages its signature signs, in order, and is a signature
+// over exactly those messages. Since W is shared, the checks e(A_j, W) * e(A_j * e_j - B_j, P2) == 1
+// combine with random r_j into e(sum r_j * A_j, W) * e(sum r_j * (A_j * e_j - B_j), P2) == 1, two
+// pairings however many signatures are aggregated. The object is no smaller than its signatures and
+// the format may change, it is meant for experiments of the working group
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar};
+use crate::error::BbsError;
+use crate::generators::make_generators;
+use crate::key_pair::PublicKey;
+use crate::limits::Limits;
+use crate::signature::{calculate_b_vartime, calculate_domain, Signature};
+
+pub struct AggregateSignature {
+    parts: Vec<(Vec<usize>, Signature)>,
+}
+
+impl AggregateSignature {
+    // Each part is the message indexes of a signature and the signature, InvalidDisclosedIndexes when
+    // an index is in two parts and InvalidLength when a part has no messages
+    pub fn aggregate(parts: Vec<(Vec<usize>, Signature)>) -> Result<Self, BbsError> {
+        let mut seen = BTreeSet::new();
+        for (indexes, _) in &parts {
+            if indexes.is_empty() {
+                return Err(BbsError::InvalidLength("every signature signs at least one message"));
+            }
+            for &i in indexes {
+                if !seen.insert(i) {
+                    return Err(BbsError::InvalidDisclosedIndexes);
+                }
+            }
+        }
+        Ok(AggregateSignature { parts })
+    }
+
+    pub fn parts(&self) -> &[(Vec<usize>, Signature)] {
+        &self.parts
+    }
+
+    // Verifies every signature over its messages of messages, with the header shared by all of them.
+    // False when an index is out of range
+    #[cfg(feature = "std")]
+    pub fn verify<'a, X>(&self, pk: &PublicKey, header: Option<&[u8]>, messages: &[Scalar]) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.verify_with_rng::<X, _>(pk, header, messages, &mut rand::thread_rng())
+    }
+
+    // As verify, with the r_j drawn from rng
+    pub fn verify_with_rng<'a, X, R>(&self, pk: &PublicKey, header: Option<&[u8]>, messages: &[Scalar], rng: &mut R) -> bool
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        if self.parts.is_empty() || !pk.validate() || !Limits::DEFAULT.allows(messages.len()) {
+            return false;
+        }
+
+        let mut w_term = Backend::g1_identity();
+        let mut p2_term = Backend::g1_identity();
+        for (indexes, signature) in &self.parts {
+            if Backend::g1_is_identity(&signature.a) {
+                return false;
+            }
+            let part: Option<Vec<Scalar>> = indexes.iter().map(|&i| messages.get(i).copied()).collect();
+            let part = match part {
+                Some(part) => part,
+                None => return false,
+            };
+
+            let generators = make_generators::<X>(None, part.len() + 2);
+            let domain = calculate_domain::<X>(pk, &generators.message_generators, header);
+            let b = calculate_b_vartime(&generators, signature.s, domain, &part);
+
+            let r = Scalar::random(&mut *rng);
+            w_term += signature.a * r;
+            p2_term += (signature.a * signature.e - b) * r;
+        }
+
+        Backend::pairing_product_is_identity(&[(w_term, pk.0), (p2_term, Backend::g2_generator())])
+    }
+
+    // I2OSP(parts, 4) followed by each part as I2OSP(L, 4), the L indexes as I2OSP(i, 4) and the
+    // signature
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut octets = Vec::new();
+        octets.extend_from_slice(&(self.parts.len() as u32).to_be_bytes());
+        for (indexes, signature) in &self.parts {
+            octets.extend_from_slice(&(indexes.len() as u32).to_be_bytes());
+            for &i in indexes {
+                octets.extend_from_slice(&(i as u32).to_be_bytes());
+            }
+            octets.extend_from_slice(&signature.to_bytes());
+        }
+        octets
+    }
+
+    pub fn from_bytes(octets: &[u8]) -> Result<Self, BbsError> {
+        let mut rest = octets;
+        let count = take_u32(&mut rest)?;
+        let mut parts = Vec::new();
+        for _ in 0..count {
+            let len = take_u32(&mut rest)?;
+            let mut indexes = Vec::new();
+            for _ in 0..len {
+                indexes.push(take_u32(&mut rest)? as usize);
+            }
+            if rest.len() < 112 {
+                return Err(BbsError::InvalidLength("an aggregate signature ends inside a signature"));
+            }
+            let (signature, tail) = rest.split_at(112);
+            parts.push((indexes, Signature::from_bytes(signature)?));
+            rest = tail;
+        }
+        if !rest.is_empty() {
+            return Err(BbsError::InvalidLength("octets after the aggregate signature"));
+        }
+        Self::aggregate(parts)
+    }
+}
+
+fn take_u32(rest: &mut &[u8]) -> Result<u32, BbsError> {
+    if rest.len() < 4 {
+        return Err(BbsError::InvalidLength("an aggregate signature ends inside a length"));
+    }
+    let (value, tail) = rest.split_at(4);
+    *rest = tail;
+    Ok(u32::from_be_bytes([value[0], value[1], value[2], value[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::Bls12381Sha256;
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    fn messages() -> Vec<Scalar> {
+        (1..=5u64).map(Scalar::from).collect()
+    }
+
+    // The messages at 0, 2 and 4 signed together, and those at 1 and 3
+    fn parts(key_pair: &KeyPair) -> Vec<(Vec<usize>, Signature)> {
+        let messages = messages();
+        [alloc::vec![0, 2, 4], alloc::vec![1, 3]]
+            .into_iter()
+            .map(|indexes| {
+                let part: Vec<Scalar> = indexes.iter().map(|&i| messages[i]).collect();
+                let signature = Signature::sign::<Bls12381Sha256>(&part, &key_pair.secret_key, Some(b"header")).unwrap();
+                (indexes, signature)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aggregate_verifies_its_parts() {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let aggregate = AggregateSignature::aggregate(parts(&key_pair)).unwrap();
+        let rng = &mut StdRng::seed_from_u64(0);
+        assert!(aggregate.verify_with_rng::<Bls12381Sha256, _>(&key_pair.public_key, Some(b"header"), &messages(), rng));
+
+        let decoded = AggregateSignature::from_bytes(&aggregate.to_bytes()).unwrap();
+        assert_eq!(decoded.parts().len(), 2);
+        assert_eq!(decoded.to_bytes(), aggregate.to_bytes());
+        assert!(decoded.verify_with_rng::<Bls12381Sha256, _>(&key_pair.public_key, Some(b"header"), &messages(), rng));
+    }
+
+    #[test]
+    fn aggregate_fails_when_a_part_does_not_verify() {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let aggregate = AggregateSignature::aggregate(parts(&key_pair)).unwrap();
+        let rng = &mut StdRng::seed_from_u64(1);
+
+        let mut changed = messages();
+        changed[3] = Scalar::from(7u64);
+        assert!(!aggregate.verify_with_rng::<Bls12381Sha256, _>(&key_pair.public_key, Some(b"header"), &changed, rng));
+        assert!(!aggregate.verify_with_rng::<Bls12381Sha256, _>(&key_pair.public_key, None, &messages(), rng));
+        assert!(!aggregate.verify_with_rng::<Bls12381Sha256, _>(&key_pair.public_key, Some(b"header"), &messages()[..4], rng));
+
+        let other = KeyPair::from_ikm(b"another-Test-IKM-to-generate-the-2nd-issuer-key", None).unwrap();
+        assert!(!aggregate.verify_with_rng::<Bls12381Sha256, _>(&other.public_key, Some(b"header"), &messages(), rng));
+
+        // Parts swapped between their indexes
+        let mut swapped = parts(&key_pair);
+        let (first, second) = swapped.split_at_mut(1);
+        core::mem::swap(&mut first[0].1, &mut second[0].1);
+        let swapped = AggregateSignature::aggregate(swapped).unwrap();
+        assert!(!swapped.verify_with_rng::<Bls12381Sha256, _>(&key_pair.public_key, Some(b"header"), &messages(), rng));
+
+        let empty = AggregateSignature::aggregate(Vec::new()).unwrap();
+        assert!(!empty.verify_with_rng::<Bls12381Sha256, _>(&key_pair.public_key, Some(b"header"), &messages(), rng));
+    }
+
+    #[test]
+    fn rejects_overlapping_and_empty_parts() {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let mut overlapping = parts(&key_pair);
+        overlapping[1].0 = alloc::vec![1, 2];
+        assert!(matches!(AggregateSignature::aggregate(overlapping), Err(BbsError::InvalidDisclosedIndexes)));
+
+        let mut empty = parts(&key_pair);
+        empty[1].0 = Vec::new();
+        assert!(matches!(AggregateSignature::aggregate(empty), Err(BbsError::InvalidLength(_))));
+    }
+
+    #[test]
+    fn from_bytes_rejects_truncated_and_trailing_octets() {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let octets = AggregateSignature::aggregate(parts(&key_pair)).unwrap().to_bytes();
+        for len in [0, 3, 4 + 3, 4 + 4 + 3 * 4 + 111, octets.len() - 1] {
+            assert!(matches!(AggregateSignature::from_bytes(&octets[..len]), Err(BbsError::InvalidLength(_))), "{} octets", len);
+        }
+        let mut trailing = octets.clone();
+        trailing.push(0);
+        assert!(matches!(AggregateSignature::from_bytes(&trailing), Err(BbsError::InvalidLength(_))));
+    }
+}
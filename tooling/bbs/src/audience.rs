@@ -0,0 +1,185 @@
+// Proofs bound to the verifier they are presented to. The presentation header is the encoding of an
+// Audience, the verifier's identifier, e.g. its origin "https://verifier.example", and the time after
+// which the proof is no longer accepted. The header is hashed into the challenge, so a proof presented
+// to another verifier, or after the expiry, fails verify_for_audience without further state on either
+// side. Replay to the same verifier before the expiry is not prevented, verifiers needing that still
+// have to send a nonce, see challenge::ChallengeStore
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::Scalar;
+use crate::encoding::i2osp;
+use crate::error::BbsError;
+use crate::key_pair::PublicKey;
+use crate::proof::Proof;
+use crate::signature::Signature;
+
+const AUDIENCE_TAG: &[u8] = b"BBS_AUDIENCE_V1_";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audience {
+    pub verifier: String,
+    // Seconds since the Unix epoch, the proof is accepted up to and including this second
+    pub expires_at: u64,
+}
+
+impl Audience {
+    pub fn new(verifier: &str, expires_at: u64) -> Self {
+        Audience { verifier: verifier.into(), expires_at }
+    }
+
+    // "BBS_AUDIENCE_V1_" || I2OSP(length(verifier), 8) || verifier || I2OSP(expires_at, 8), with the
+    // verifier as UTF-8
+    pub fn to_presentation_header(&self) -> Vec<u8> {
+        let mut ph = AUDIENCE_TAG.to_vec();
+        ph.extend_from_slice(&i2osp(self.verifier.len() as u64, 8));
+        ph.extend_from_slice(self.verifier.as_bytes());
+        ph.extend_from_slice(&i2osp(self.expires_at, 8));
+        ph
+    }
+
+    pub fn from_presentation_header(ph: &[u8]) -> Result<Self, BbsError> {
+        let rest = ph
+            .strip_prefix(AUDIENCE_TAG)
+            .ok_or_else(|| BbsError::Serialization("the presentation header is not an audience".into()))?;
+        if rest.len() < 16 {
+            return Err(BbsError::InvalidLength("an audience is at least 16 octets after the tag"));
+        }
+        let (len, rest) = rest.split_at(8);
+        let len = u64::from_be_bytes(len.try_into().expect("8 octets"));
+        if len != rest.len() as u64 - 8 {
+            return Err(BbsError::InvalidLength("the verifier length does not match the audience"));
+        }
+        let (verifier, expires_at) = rest.split_at(rest.len() - 8);
+        let verifier = String::from_utf8(verifier.to_vec())
+            .map_err(|_| BbsError::Serialization("the verifier is not UTF-8".into()))?;
+        Ok(Audience { verifier, expires_at: u64::from_be_bytes(expires_at.try_into().expect("8 octets")) })
+    }
+}
+
+// Proof::generate with the encoding of audience as the presentation header
+#[cfg(feature = "std")]
+pub fn generate_for_audience<'a, X>(
+    signature: &Signature,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    audience: &Audience,
+    messages: &[Scalar],
+    disclosed_indexes: &[usize],
+) -> Result<Proof, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    generate_for_audience_with_rng::<X, _>(signature, pk, header, audience, messages, disclosed_indexes, &mut rand::thread_rng())
+}
+
+// As generate_for_audience, with the random scalars drawn from rng
+pub fn generate_for_audience_with_rng<'a, X, R>(
+    signature: &Signature,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    audience: &Audience,
+    messages: &[Scalar],
+    disclosed_indexes: &[usize],
+    rng: &mut R,
+) -> Result<Proof, BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    let ph = audience.to_presentation_header();
+    Proof::generate_with_rng::<X, _>(signature, pk, header, Some(&ph), messages, disclosed_indexes, rng)
+}
+
+// Verifies a proof whose presentation header ph is an audience naming verifier and expiring no earlier
+// than now, in seconds since the Unix epoch. False when ph is not an audience
+pub fn verify_for_audience<'a, X>(
+    proof: &Proof,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: &[u8],
+    disclosed: &BTreeMap<usize, Scalar>,
+    verifier: &str,
+    now: u64,
+) -> bool
+where
+    X: BbsCiphersuite<'a>
+{
+    let audience = match Audience::from_presentation_header(ph) {
+        Ok(audience) => audience,
+        Err(_) => return false,
+    };
+    if audience.verifier != verifier || now > audience.expires_at {
+        return false;
+    }
+    proof.verify::<X>(pk, header, Some(ph), disclosed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::Bls12381Sha256;
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+    const VERIFIER: &str = "https://verifier.example";
+    const EXPIRES_AT: u64 = 1_700_000_300;
+
+    #[test]
+    fn proof_verifies_for_its_audience_until_it_expires() {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let messages: Vec<Scalar> = (1..=3u64).map(Scalar::from).collect();
+        let signature = Signature::sign::<Bls12381Sha256>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+        let audience = Audience::new(VERIFIER, EXPIRES_AT);
+        let rng = &mut StdRng::seed_from_u64(0);
+        let proof =
+            generate_for_audience_with_rng::<Bls12381Sha256, _>(&signature, &key_pair.public_key, Some(b"header"), &audience, &messages, &[0], rng)
+                .unwrap();
+
+        let ph = audience.to_presentation_header();
+        let disclosed = BTreeMap::from([(0, messages[0])]);
+        let verify = |ph: &[u8], verifier: &str, now: u64| {
+            verify_for_audience::<Bls12381Sha256>(&proof, &key_pair.public_key, Some(b"header"), ph, &disclosed, verifier, now)
+        };
+        assert!(verify(&ph, VERIFIER, EXPIRES_AT - 300));
+        assert!(verify(&ph, VERIFIER, EXPIRES_AT));
+
+        // Expired, presented to another verifier, or with the header of another audience
+        assert!(!verify(&ph, VERIFIER, EXPIRES_AT + 1));
+        assert!(!verify(&ph, "https://other.example", EXPIRES_AT));
+        let extended = Audience::new(VERIFIER, EXPIRES_AT + 3600).to_presentation_header();
+        assert!(!verify(&extended, VERIFIER, EXPIRES_AT + 1));
+        assert!(!verify(b"not an audience", VERIFIER, EXPIRES_AT));
+    }
+
+    #[test]
+    fn presentation_header_round_trip() {
+        let audience = Audience::new(VERIFIER, EXPIRES_AT);
+        let ph = audience.to_presentation_header();
+        assert_eq!(ph.len(), AUDIENCE_TAG.len() + 8 + VERIFIER.len() + 8);
+        assert_eq!(Audience::from_presentation_header(&ph).unwrap(), audience);
+        let empty = Audience::new("", 0);
+        assert_eq!(Audience::from_presentation_header(&empty.to_presentation_header()).unwrap(), empty);
+    }
+
+    #[test]
+    fn rejects_malformed_presentation_headers() {
+        let ph = Audience::new(VERIFIER, EXPIRES_AT).to_presentation_header();
+        assert!(matches!(Audience::from_presentation_header(&ph[1..]), Err(BbsError::Serialization(_))));
+        assert!(matches!(Audience::from_presentation_header(&ph[..AUDIENCE_TAG.len() + 15]), Err(BbsError::InvalidLength(_))));
+        assert!(matches!(Audience::from_presentation_header(&ph[..ph.len() - 1]), Err(BbsError::InvalidLength(_))));
+        let mut longer = ph.clone();
+        longer.push(0);
+        assert!(matches!(Audience::from_presentation_header(&longer), Err(BbsError::InvalidLength(_))));
+
+        let mut not_utf8 = ph.clone();
+        not_utf8[AUDIENCE_TAG.len() + 8] = 0xff;
+        assert!(matches!(Audience::from_presentation_header(&not_utf8), Err(BbsError::Serialization(_))));
+    }
+}
@@ -0,0 +1,394 @@
+// Blind signatures, following PreBlindSign, BlindSign and UnblindSign of the blind BBS extension draft:
+// the holder commits to messages that the signer signs without learning them, together with messages
+// the signer knows. The L messages of the signature are split by index, the holder's at the committed
+// indexes CGIdxs and the signer's at GIdxs, the two disjoint and together 0, ..., L - 1, and each
+// message uses the signer's generator H_i of its index, so the unblinded signature is a plain BBS
+// signature over the L messages. The proof of knowledge of the commitment's opening includes a nonce
+// chosen by the signer for the issuance, so a commitment can not be replayed to another issuance
+use alloc::vec;
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::encoding::{encode_for_hash, scalar_to_bytes};
+use crate::error::BbsError;
+use crate::generators::make_generators;
+use crate::hashing::hash_to_scalar;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::limits::check_message_count;
+use crate::secret::{SecretScalar, SecretScalars};
+use crate::signature::{calculate_domain, Signature};
+
+// C = Q_1 * secret_prover_blind + H_j1 * cm_1 + ... + H_jM * cm_M for the committed indexes j1, ..., jM,
+// with a proof of knowledge of the opening
+pub struct Commitment {
+    pub(crate) commit: G1,
+    pub(crate) c: Scalar,
+    pub(crate) s_hat: Scalar,
+    pub(crate) m_hat: Vec<Scalar>,
+}
+
+// A signature whose s is missing the holder's secret_prover_blind, see unblind_sign
+pub struct BlindSignature {
+    pub(crate) a: G1,
+    pub(crate) e: Scalar,
+    pub(crate) s: Scalar,
+}
+
+// PreBlindSign, run by the holder with the nonce the signer sent for this issuance. committed_indexes
+// are the ascending indexes of committed_messages among the messages of the signature. Returns the
+// commitment to send to the signer and the secret_prover_blind to keep
+#[cfg(feature = "std")]
+pub fn pre_blind_sign<'a, X>(
+    committed_messages: &[Scalar],
+    committed_indexes: &[usize],
+    nonce: &[u8],
+) -> Result<(Commitment, SecretScalar), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    pre_blind_sign_with_rng::<X, _>(committed_messages, committed_indexes, nonce, &mut rand::thread_rng())
+}
+
+// As pre_blind_sign, with secret_prover_blind and the blinding scalars of the proof drawn from rng
+pub fn pre_blind_sign_with_rng<'a, X, R>(
+    committed_messages: &[Scalar],
+    committed_indexes: &[usize],
+    nonce: &[u8],
+    rng: &mut R,
+) -> Result<(Commitment, SecretScalar), BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    if committed_messages.len() != committed_indexes.len() {
+        return Err(BbsError::InvalidLength("one committed index per committed message"));
+    }
+    let (q_1, h) = commitment_generators::<X>(committed_indexes)?;
+
+    let secret_prover_blind = SecretScalar(Scalar::random(&mut *rng));
+    let s_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let m_tilde = SecretScalars(committed_messages.iter().map(|_| Scalar::random(&mut *rng)).collect());
+
+    let mut commit = q_1 * secret_prover_blind.0;
+    let mut c_bar = q_1 * s_tilde.0;
+    for ((h, m), m_tilde) in h.iter().zip(committed_messages).zip(m_tilde.iter()) {
+        commit += h * m;
+        c_bar += h * m_tilde;
+    }
+
+    let c = calculate_commitment_challenge::<X>(&commit, &c_bar, committed_indexes, &h, nonce);
+
+    let commitment = Commitment {
+        commit,
+        c,
+        s_hat: s_tilde.0 + c * secret_prover_blind.0,
+        m_hat: committed_messages.iter().zip(m_tilde.iter()).map(|(m, m_tilde)| m_tilde + c * m).collect(),
+    };
+    Ok((commitment, secret_prover_blind))
+}
+
+impl Commitment {
+    // Run by the signer before BlindSign, checks the holder knows the opening of the commitment to the
+    // messages at committed_indexes, made for nonce
+    pub fn verify<'a, X>(&self, committed_indexes: &[usize], nonce: &[u8]) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if self.m_hat.len() != committed_indexes.len() {
+            return false;
+        }
+        let (q_1, h) = match commitment_generators::<X>(committed_indexes) {
+            Ok(generators) => generators,
+            Err(_) => return false,
+        };
+
+        // Cbar = Q_1 * s^ + H_j1 * m^_1 + ... + H_jM * m^_M - C * c
+        let mut c_bar = q_1 * self.s_hat - self.commit * self.c;
+        for (h, m_hat) in h.iter().zip(&self.m_hat) {
+            c_bar += h * m_hat;
+        }
+
+        self.c == calculate_commitment_challenge::<X>(&self.commit, &c_bar, committed_indexes, &h, nonce)
+    }
+
+    // C || c || s^ || m^_1 || ... || m^_M
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Backend::g1_to_bytes(&self.commit).to_vec();
+        for s in [&self.c, &self.s_hat].into_iter().chain(&self.m_hat) {
+            bytes.extend_from_slice(&scalar_to_bytes(s));
+        }
+        bytes
+    }
+}
+
+// BlindSign, run by the signer with the nonce it sent to the holder. messages are the signer's, at the
+// ascending indexes, and committed_indexes those of the holder's commitment. InvalidDisclosedIndexes
+// when the indexes overlap or are not 0, ..., L - 1 together, InvalidCommitment when the commitment
+// proof does not verify for them and nonce
+#[allow(clippy::too_many_arguments)]
+pub fn blind_sign<'a, X>(
+    sk: &SecretKey,
+    commitment: &Commitment,
+    committed_indexes: &[usize],
+    messages: &[Scalar],
+    indexes: &[usize],
+    header: Option<&[u8]>,
+    nonce: &[u8],
+) -> Result<BlindSignature, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    if messages.len() != indexes.len() {
+        return Err(BbsError::InvalidLength("one index per signer message"));
+    }
+    let l = check_indexes(indexes, committed_indexes)?;
+    check_message_count(l)?;
+    if !commitment.verify::<X>(committed_indexes, nonce) {
+        return Err(BbsError::InvalidCommitment);
+    }
+
+    // The domain of a signature over the L messages, which the unblinded signature is
+    let pk = sk.public_key();
+    let generators = make_generators::<X>(None, l + 2);
+    let domain = calculate_domain::<X>(&pk, &generators.message_generators, header);
+
+    let e_s_for_hash = encode_for_hash(&[&sk.0, &domain, &messages, &commitment.commit]);
+    let scalars = hash_to_scalar::<X>(&e_s_for_hash, 2, None);
+    let (e, s) = (scalars[0], scalars[1]);
+
+    // B = P1 + Q_1 * s'' + Q_2 * domain + H_i1 * msg_1 + ... + H_iK * msg_K + C
+    let q = &generators.message_generators;
+    let mut b = generators.g1_base_point + q[0] * s + q[1] * domain + commitment.commit;
+    for (&i, msg) in indexes.iter().zip(messages) {
+        b += q[2 + i] * msg;
+    }
+
+    let sk_e_inv: Scalar = Option::from((sk.0 + e).invert()).ok_or(BbsError::SigningFailed)?;
+
+    Ok(BlindSignature { a: b * sk_e_inv, e, s })
+}
+
+// UnblindSign, run by the holder, s = s'' + secret_prover_blind
+pub fn unblind_sign(blind_signature: &BlindSignature, secret_prover_blind: &SecretScalar) -> Signature {
+    blind_signature.unblind(secret_prover_blind)
+}
+
+impl BlindSignature {
+    pub fn unblind(&self, secret_prover_blind: &SecretScalar) -> Signature {
+        Signature {
+            a: self.a,
+            e: self.e,
+            s: self.s + secret_prover_blind.0,
+        }
+    }
+}
+
+// The L messages of the signature in index order, from the signer's messages at indexes and the
+// committed messages at committed_indexes. Proofs are generated and verified over these
+pub fn combine_messages(
+    messages: &[Scalar],
+    indexes: &[usize],
+    committed_messages: &[Scalar],
+    committed_indexes: &[usize],
+) -> Result<Vec<Scalar>, BbsError> {
+    if messages.len() != indexes.len() || committed_messages.len() != committed_indexes.len() {
+        return Err(BbsError::InvalidLength("one index per message"));
+    }
+    let l = check_indexes(indexes, committed_indexes)?;
+    let mut combined = vec![Scalar::zero(); l];
+    for (&i, m) in indexes.iter().zip(messages).chain(committed_indexes.iter().zip(committed_messages)) {
+        combined[i] = *m;
+    }
+    Ok(combined)
+}
+
+// Verifies an unblinded signature over the signer's messages and the holder's committed messages
+pub fn verify<'a, X>(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[Scalar],
+    indexes: &[usize],
+    committed_messages: &[Scalar],
+    committed_indexes: &[usize],
+    header: Option<&[u8]>,
+) -> bool
+where
+    X: BbsCiphersuite<'a>
+{
+    match combine_messages(messages, indexes, committed_messages, committed_indexes) {
+        Ok(messages) => signature.verify::<X>(pk, &messages, header),
+        Err(_) => false,
+    }
+}
+
+// L, when indexes and committed_indexes are ascending, disjoint and together 0, ..., L - 1
+fn check_indexes(indexes: &[usize], committed_indexes: &[usize]) -> Result<usize, BbsError> {
+    let l = indexes.len() + committed_indexes.len();
+    let ascending = |idxs: &[usize]| idxs.windows(2).all(|w| w[0] < w[1]) && idxs.iter().all(|&i| i < l);
+    if !ascending(indexes) || !ascending(committed_indexes) || indexes.iter().any(|i| committed_indexes.contains(i)) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+    Ok(l)
+}
+
+// Q_1 and the signer's generators H_j of the committed indexes
+fn commitment_generators<'a, X>(committed_indexes: &[usize]) -> Result<(G1, Vec<G1>), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    if committed_indexes.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+    let count = committed_indexes.last().map_or(0, |&j| j + 1);
+    check_message_count(count)?;
+    let generators = make_generators::<X>(None, count + 2).message_generators;
+    let h = committed_indexes.iter().map(|&j| generators[2 + j]).collect();
+    Ok((generators[0], h))
+}
+
+// c = hash_to_scalar(C || Cbar || I2OSP(M, 8) || I2OSP(j1, 8) || H_j1 || ... || I2OSP(jM, 8) || H_jM
+// || I2OSP(length(nonce), 8) || nonce, 1)
+fn calculate_commitment_challenge<'a, X>(commit: &G1, c_bar: &G1, committed_indexes: &[usize], h: &[G1], nonce: &[u8]) -> Scalar
+where
+    X: BbsCiphersuite<'a>
+{
+    let mut c_for_hash = encode_for_hash(&[commit, c_bar, &committed_indexes.len()]);
+    for (j, h) in committed_indexes.iter().zip(h) {
+        c_for_hash.extend_from_slice(&encode_for_hash(&[j, h]));
+    }
+    c_for_hash.extend_from_slice(&encode_for_hash(&[&nonce]));
+
+    hash_to_scalar::<X>(&c_for_hash, 1, None)[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+    const NONCE: &[u8] = b"issuance nonce";
+
+    // The holder commits to the messages at 1 and 3, the signer knows those at 0, 2 and 4
+    const COMMITTED_INDEXES: [usize; 2] = [1, 3];
+    const INDEXES: [usize; 3] = [0, 2, 4];
+
+    fn committed_messages() -> Vec<Scalar> {
+        vec![Scalar::from(11u64), Scalar::from(13u64)]
+    }
+
+    fn messages() -> Vec<Scalar> {
+        vec![Scalar::from(10u64), Scalar::from(12u64), Scalar::from(14u64)]
+    }
+
+    fn blind_round_trip<'a, X>(commitment: Commitment, secret_prover_blind: SecretScalar)
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let (messages, committed_messages) = (messages(), committed_messages());
+        assert!(commitment.verify::<X>(&COMMITTED_INDEXES, NONCE));
+
+        let blind_signature =
+            blind_sign::<X>(&key_pair.secret_key, &commitment, &COMMITTED_INDEXES, &messages, &INDEXES, Some(b"header"), NONCE)
+                .unwrap();
+        let signature = unblind_sign(&blind_signature, &secret_prover_blind);
+        assert!(verify::<X>(&signature, &key_pair.public_key, &messages, &INDEXES, &committed_messages, &COMMITTED_INDEXES, Some(b"header")));
+
+        // A plain BBS signature over the combined messages
+        let combined = combine_messages(&messages, &INDEXES, &committed_messages, &COMMITTED_INDEXES).unwrap();
+        assert!(signature.verify::<X>(&key_pair.public_key, &combined, Some(b"header")));
+
+        // Without the holder's secret_prover_blind the signature does not verify
+        let blind = Signature { a: blind_signature.a, e: blind_signature.e, s: blind_signature.s };
+        assert!(!blind.verify::<X>(&key_pair.public_key, &combined, Some(b"header")));
+
+        // Nor over other committed messages or another header
+        let other = [committed_messages[1], committed_messages[0]];
+        assert!(!verify::<X>(&signature, &key_pair.public_key, &messages, &INDEXES, &other, &COMMITTED_INDEXES, Some(b"header")));
+        assert!(!verify::<X>(&signature, &key_pair.public_key, &messages, &INDEXES, &committed_messages, &COMMITTED_INDEXES, None));
+    }
+
+    #[test]
+    fn pre_blind_sign_blind_sign_unblind_verify() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (commitment, secret_prover_blind) =
+            pre_blind_sign_with_rng::<Bls12381Shake256, _>(&committed_messages(), &COMMITTED_INDEXES, NONCE, rng).unwrap();
+        blind_round_trip::<Bls12381Shake256>(commitment, secret_prover_blind);
+
+        let (commitment, secret_prover_blind) =
+            pre_blind_sign::<Bls12381Sha256>(&committed_messages(), &COMMITTED_INDEXES, NONCE).unwrap();
+        blind_round_trip::<Bls12381Sha256>(commitment, secret_prover_blind);
+    }
+
+    #[test]
+    fn commitment_is_bound_to_the_nonce() {
+        let rng = &mut StdRng::seed_from_u64(1);
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let (commitment, _) =
+            pre_blind_sign_with_rng::<Bls12381Sha256, _>(&committed_messages(), &COMMITTED_INDEXES, NONCE, rng).unwrap();
+
+        // Replayed to another issuance
+        assert!(commitment.verify::<Bls12381Sha256>(&COMMITTED_INDEXES, NONCE));
+        assert!(!commitment.verify::<Bls12381Sha256>(&COMMITTED_INDEXES, b"another nonce"));
+        let result =
+            blind_sign::<Bls12381Sha256>(&key_pair.secret_key, &commitment, &COMMITTED_INDEXES, &messages(), &INDEXES, None, b"another nonce");
+        assert!(matches!(result, Err(BbsError::InvalidCommitment)));
+
+        // Nor does it verify for other indexes or in another ciphersuite
+        assert!(!commitment.verify::<Bls12381Sha256>(&[1, 2], NONCE));
+        assert!(!commitment.verify::<Bls12381Sha256>(&[1], NONCE));
+        assert!(!commitment.verify::<Bls12381Shake256>(&COMMITTED_INDEXES, NONCE));
+    }
+
+    #[test]
+    fn check_indexes_rejects_overlapping_and_non_contiguous_indexes() {
+        assert_eq!(check_indexes(&[0, 2, 4], &[1, 3]).unwrap(), 5);
+        assert_eq!(check_indexes(&[], &[0, 1]).unwrap(), 2);
+        assert_eq!(check_indexes(&[0, 1], &[]).unwrap(), 2);
+        assert_eq!(check_indexes(&[], &[]).unwrap(), 0);
+
+        // Overlapping
+        assert!(matches!(check_indexes(&[0, 1], &[1, 2]), Err(BbsError::InvalidDisclosedIndexes)));
+        // Non contiguous, 5 is beyond L = 4 and 3 is missing
+        assert!(matches!(check_indexes(&[0, 2], &[1, 5]), Err(BbsError::InvalidDisclosedIndexes)));
+        // Not ascending, or repeated
+        assert!(matches!(check_indexes(&[2, 0], &[1]), Err(BbsError::InvalidDisclosedIndexes)));
+        assert!(matches!(check_indexes(&[0, 0], &[1]), Err(BbsError::InvalidDisclosedIndexes)));
+
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let rng = &mut StdRng::seed_from_u64(2);
+        let (commitment, _) =
+            pre_blind_sign_with_rng::<Bls12381Sha256, _>(&committed_messages(), &COMMITTED_INDEXES, NONCE, rng).unwrap();
+        let result = blind_sign::<Bls12381Sha256>(&key_pair.secret_key, &commitment, &COMMITTED_INDEXES, &messages(), &[0, 1, 2], None, NONCE);
+        assert!(matches!(result, Err(BbsError::InvalidDisclosedIndexes)));
+        let result = pre_blind_sign_with_rng::<Bls12381Sha256, _>(&committed_messages(), &[3, 1], NONCE, rng);
+        assert!(matches!(result, Err(BbsError::InvalidDisclosedIndexes)));
+    }
+
+    #[test]
+    fn combine_messages_orders_by_index() {
+        let combined = combine_messages(&messages(), &INDEXES, &committed_messages(), &COMMITTED_INDEXES).unwrap();
+        let expected: Vec<Scalar> = (10..=14u64).map(Scalar::from).collect();
+        assert_eq!(combined, expected);
+
+        let combined = combine_messages(&[], &[], &committed_messages(), &[0, 1]).unwrap();
+        assert_eq!(combined, committed_messages());
+
+        assert!(matches!(
+            combine_messages(&messages(), &[0, 2], &committed_messages(), &COMMITTED_INDEXES),
+            Err(BbsError::InvalidLength(_))
+        ));
+        assert!(matches!(
+            combine_messages(&messages(), &[0, 1, 2], &committed_messages(), &COMMITTED_INDEXES),
+            Err(BbsError::InvalidDisclosedIndexes)
+        ));
+    }
+}
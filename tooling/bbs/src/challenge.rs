@@ -0,0 +1,96 @@
+// Fresh presentation headers for verifiers. The verifier issues a random 32 octet nonce, the holder
+// uses it as the presentation header of the proof and the verifier consumes it when the proof
+// arrives, so a proof is accepted once and only before its nonce expires. ChallengeStore keeps the
+// issued nonces. InMemoryChallengeStore serves a single process, verifiers running several instances
+// implement store and consume over a shared database, with consume removing the nonce atomically
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use rand::RngCore;
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::Scalar;
+use crate::error::BbsError;
+use crate::key_pair::PublicKey;
+use crate::proof::Proof;
+
+pub const NONCE_LENGTH: usize = 32;
+
+pub trait ChallengeStore {
+    // Records nonce as issued, valid up to and including expires_at
+    fn store(&self, nonce: [u8; NONCE_LENGTH], expires_at: u64) -> Result<(), BbsError>;
+
+    // Removes nonce and returns its expiry, None when it was not issued or is already consumed
+    fn consume(&self, nonce: &[u8]) -> Result<Option<u64>, BbsError>;
+
+    // Draws a nonce valid for ttl seconds from now and stores it
+    fn issue(&self, ttl: u64, now: u64) -> Result<[u8; NONCE_LENGTH], BbsError> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        self.store(nonce, now.saturating_add(ttl))?;
+        Ok(nonce)
+    }
+
+    // Consumes nonce, true when it was issued, not consumed before and has not expired at now
+    fn redeem(&self, nonce: &[u8], now: u64) -> Result<bool, BbsError> {
+        Ok(matches!(self.consume(nonce)?, Some(expires_at) if now <= expires_at))
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryChallengeStore {
+    nonces: Mutex<HashMap<[u8; NONCE_LENGTH], u64>>,
+}
+
+impl InMemoryChallengeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Drops the nonces expired at now, which would never be redeemed
+    pub fn purge_expired(&self, now: u64) -> Result<(), BbsError> {
+        self.lock()?.retain(|_, &mut expires_at| now <= expires_at);
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<[u8; NONCE_LENGTH], u64>>, BbsError> {
+        self.nonces
+            .lock()
+            .map_err(|_| BbsError::Serialization("the challenge store is poisoned".into()))
+    }
+}
+
+impl ChallengeStore for InMemoryChallengeStore {
+    fn store(&self, nonce: [u8; NONCE_LENGTH], expires_at: u64) -> Result<(), BbsError> {
+        self.lock()?.insert(nonce, expires_at);
+        Ok(())
+    }
+
+    fn consume(&self, nonce: &[u8]) -> Result<Option<u64>, BbsError> {
+        let nonce: [u8; NONCE_LENGTH] = match nonce.try_into() {
+            Ok(nonce) => nonce,
+            Err(_) => return Ok(None),
+        };
+        Ok(self.lock()?.remove(&nonce))
+    }
+}
+
+// Verifies a proof whose presentation header ph is a nonce of store. The nonce is consumed before the
+// proof is checked, so it can not be retried with another proof even when this one fails
+pub fn verify_with_challenge<'a, X>(
+    store: &dyn ChallengeStore,
+    proof: &Proof,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: &[u8],
+    disclosed: &BTreeMap<usize, Scalar>,
+    now: u64,
+) -> Result<bool, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    if !store.redeem(ph, now)? {
+        return Ok(false);
+    }
+    Ok(proof.verify::<X>(pk, header, Some(ph), disclosed))
+}
@@ -0,0 +1,129 @@
+use alloc::vec::Vec;
+
+use sha3::Shake256;
+use sha2::Sha256;
+#[cfg(feature = "non-standard-suites")]
+use sha2::Sha512;
+
+use crate::curve::G1;
+use crate::generators;
+use crate::hashing::{ExpandMessage, ExpandMsgXmd, ExpandMsgXof};
+
+// Implements CiphersuiteParams with the seeds and DSTs derived from the ciphersuite_id literal,
+// followed by the group trait of the suite, BbsCiphersuite or G2Ciphersuite
+macro_rules! bbs_ciphersuite {
+    ($suite:ident, $id:literal, $expander:ty) => {
+        bbs_ciphersuite!($suite, $id, $expander, BbsCiphersuite);
+    };
+    ($suite:ident, $id:literal, $expander:ty, $group_trait:ident) => {
+        impl<'a> $group_trait<'a> for $suite {}
+
+        impl<'a> CiphersuiteParams<'a> for $suite {
+            const ID: &'a [u8] = $id.as_bytes();
+            const GENERATOR_SEED: &'a [u8] = concat!($id, "MESSAGE_GENERATOR_SEED").as_bytes();
+            const BP_GENERATOR_SEED: &'a [u8] = concat!($id, "BP_MESSAGE_GENERATOR_SEED").as_bytes();
+            const GENERATOR_SEED_DST: &'a [u8] = concat!($id, "SIG_GENERATOR_SEED_").as_bytes();
+            const GENERATOR_DST: &'a [u8] = concat!($id, "SIG_GENERATOR_DST_").as_bytes();
+            const HASH_TO_SCALAR_DST: &'a [u8] = concat!($id, "H2S_").as_bytes();
+            const MAP_MSG_TO_SCALAR_AS_HASH_DST: &'a [u8] = concat!($id, "MAP_MSG_TO_SCALAR_AS_HASH_").as_bytes();
+            const MOCK_RANDOM_SCALARS_DST: &'a [u8] = concat!($id, "MOCK_RANDOM_SCALARS_DST_").as_bytes();
+            type Expander = $expander;
+        }
+    };
+}
+
+// The ciphersuite id and the hashing parameters derived from it, shared by the suites of both groups.
+// Not enough to use a suite, the operations are bound by BbsCiphersuite or G2Ciphersuite
+pub trait CiphersuiteParams<'a> {
+    const ID: &'a [u8];
+
+    const OCTET_SCALAR_LENGTH: usize = 32;
+    const EXPAND_LEN: usize = crate::hashing::EXPAND_LEN;
+
+    // ciphersuite_id followed by the spec's suffix, see bbs_ciphersuite!
+    const GENERATOR_SEED: &'a [u8];
+    const BP_GENERATOR_SEED: &'a [u8];
+    const GENERATOR_SEED_DST: &'a [u8];
+    const GENERATOR_DST: &'a [u8];
+    const HASH_TO_SCALAR_DST: &'a [u8];
+    const MAP_MSG_TO_SCALAR_AS_HASH_DST: &'a [u8];
+    const MOCK_RANDOM_SCALARS_DST: &'a [u8];
+
+    type Expander: ExpandMessage;
+
+    fn generator_seed() -> Vec<u8> {
+        Self::GENERATOR_SEED.to_vec()
+    }
+
+    // The G1 base point generator seed
+    fn bp_generator_seed() -> Vec<u8> {
+        Self::BP_GENERATOR_SEED.to_vec()
+    }
+
+    fn generator_seed_dst() -> Vec<u8> {
+        Self::GENERATOR_SEED_DST.to_vec()
+    }
+
+    fn generator_dst() -> Vec<u8> {
+        Self::GENERATOR_DST.to_vec()
+    }
+
+    // The default dst used by hash_to_scalar
+    fn hash_to_scalar_dst() -> Vec<u8> {
+        Self::HASH_TO_SCALAR_DST.to_vec()
+    }
+
+    // The default dst used by MapMessageToScalarAsHash
+    fn map_message_to_scalar_dst() -> Vec<u8> {
+        Self::MAP_MSG_TO_SCALAR_AS_HASH_DST.to_vec()
+    }
+
+    // The dst used by mocked_calculate_random_scalars when generating test vectors
+    fn mock_random_scalars_dst() -> Vec<u8> {
+        Self::MOCK_RANDOM_SCALARS_DST.to_vec()
+    }
+}
+
+// Ciphersuites of the scheme as specified, the generators are G1 points and the public key a G2 point
+pub trait BbsCiphersuite<'a>: CiphersuiteParams<'a> {
+    // The length of a compressed G1 point
+    const OCTET_POINT_LENGTH: usize = 48;
+
+    // The ciphersuite's fixed G1 point P1
+    fn p1() -> G1
+    where
+        Self: Sized
+    {
+        generators::make_g1_base_point::<Self>()
+    }
+
+    // create_generators(count) with the ciphersuite's global generator seed
+    fn create_generators(count: usize) -> Vec<G1>
+    where
+        Self: Sized
+    {
+        generators::create_generators::<Self>(&Self::generator_seed(), count)
+    }
+}
+
+// Ciphersuites of the G2 variant in crate::g2, the generators are G2 points and the public key a G1 point.
+// Disjoint from BbsCiphersuite, so a G2 suite does not type check with the G1 operations
+pub trait G2Ciphersuite<'a>: CiphersuiteParams<'a> {
+    // The length of a compressed G2 point
+    const OCTET_POINT_LENGTH: usize = 96;
+}
+
+pub struct Bls12381Shake256;
+pub struct Bls12381Sha256;
+pub struct Bls12381G2Shake256;
+pub struct Bls12381G2Sha256;
+// Experimental, not defined by the draft
+#[cfg(feature = "non-standard-suites")]
+pub struct Bls12381Sha512;
+
+bbs_ciphersuite!(Bls12381Shake256, "BBS_BLS12381G1_XOF:SHAKE-256_SSWU_RO_", ExpandMsgXof<Shake256>);
+bbs_ciphersuite!(Bls12381Sha256, "BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_", ExpandMsgXmd<Sha256>);
+bbs_ciphersuite!(Bls12381G2Shake256, "BBS_BLS12381G2_XOF:SHAKE-256_SSWU_RO_", ExpandMsgXof<Shake256>, G2Ciphersuite);
+bbs_ciphersuite!(Bls12381G2Sha256, "BBS_BLS12381G2_XMD:SHA-256_SSWU_RO_", ExpandMsgXmd<Sha256>, G2Ciphersuite);
+#[cfg(feature = "non-standard-suites")]
+bbs_ciphersuite!(Bls12381Sha512, "BBS_BLS12381G1_XMD:SHA-512_SSWU_RO_", ExpandMsgXmd<Sha512>);
@@ -0,0 +1,348 @@
+// COSE encodings for mdoc/mDL style experimentation: BBS public keys as COSE_Key, and signatures and
+// proofs in CBOR envelopes shaped as COSE_Sign1, [protected, unprotected, payload, signature].
+//
+// The payload is the CBOR array of the octet string messages. The BBS header is the CBOR array
+// ["Signature1", protected, external_aad], as the Sig_structure of COSE_Sign1 without the payload,
+// since the messages are signed individually. A proof envelope carries the protected header of the
+// signature it was derived from, its unprotected header the presentation header and the disclosed
+// indexes, and its payload only the disclosed messages.
+//
+// The algorithm and curve values of the BBS-in-COSE draft are not assigned yet. The alg header is the
+// ciphersuite_id, COSE allows text algorithm names, and crv is the draft's suggested value
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use rand::{CryptoRng, RngCore};
+use serde_cbor::Value;
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar};
+use crate::error::BbsError;
+use crate::hashing::map_message_to_scalar_as_hash;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::proof::Proof;
+use crate::signature::Signature;
+
+// COSE_Key labels and values
+const KEY_KTY: i128 = 1;
+const KEY_KID: i128 = 2;
+const KEY_CRV: i128 = -1;
+const KEY_X: i128 = -2;
+const KEY_Y: i128 = -3;
+const KTY_EC2: i128 = 2;
+pub const CRV_BLS12381G2: i128 = 14;
+
+// Header labels, the proof headers are private use labels
+const ALG: i128 = 1;
+pub const HEADER_PRESENTATION_HEADER: i128 = -70001;
+pub const HEADER_DISCLOSED_INDEXES: i128 = -70002;
+
+// CBOR tag 18, COSE_Sign1
+const COSE_SIGN1_TAG: u8 = 0xd2;
+
+impl PublicKey {
+    // {1: 2, 2: kid, -1: 14, -2: x, -3: y}, kid is omitted when None
+    pub fn to_cose_key(&self, kid: Option<&[u8]>) -> Vec<u8> {
+        let xy = Backend::g2_to_uncompressed_bytes(&self.0);
+        let mut key = BTreeMap::new();
+        key.insert(Value::Integer(KEY_KTY), Value::Integer(KTY_EC2));
+        if let Some(kid) = kid {
+            key.insert(Value::Integer(KEY_KID), Value::Bytes(kid.to_vec()));
+        }
+        key.insert(Value::Integer(KEY_CRV), Value::Integer(CRV_BLS12381G2));
+        key.insert(Value::Integer(KEY_X), Value::Bytes(xy[..96].to_vec()));
+        key.insert(Value::Integer(KEY_Y), Value::Bytes(xy[96..].to_vec()));
+        encode(&Value::Map(key))
+    }
+
+    // Other labels, e.g. kid or alg, are ignored
+    pub fn from_cose_key(bytes: &[u8]) -> Result<Self, BbsError> {
+        let key = decode(bytes)?;
+        let key = as_map(&key, "key")?;
+        if key.get(&Value::Integer(KEY_KTY)) != Some(&Value::Integer(KTY_EC2))
+            || key.get(&Value::Integer(KEY_CRV)) != Some(&Value::Integer(CRV_BLS12381G2))
+        {
+            return Err(BbsError::Serialization("not a BLS12381G2 COSE_Key".to_string()));
+        }
+        let x = as_bytes(key.get(&Value::Integer(KEY_X)), "key x")?;
+        let y = as_bytes(key.get(&Value::Integer(KEY_Y)), "key y")?;
+        if x.len() != 96 || y.len() != 96 {
+            return Err(BbsError::InvalidLength("x and y must be 96 octets"));
+        }
+        let mut xy = [0u8; 192];
+        xy[..96].copy_from_slice(x);
+        xy[96..].copy_from_slice(y);
+
+        let w = Backend::g2_from_uncompressed_bytes(&xy).ok_or(BbsError::InvalidPoint)?;
+        if Backend::g2_is_identity(&w) {
+            return Err(BbsError::InvalidPoint);
+        }
+        Ok(PublicKey(w))
+    }
+}
+
+// A signature over octet string messages, as issued
+pub struct CoseSignature {
+    pub(crate) protected: Vec<u8>,
+    pub(crate) messages: Vec<Vec<u8>>,
+    pub(crate) signature: Signature,
+}
+
+impl CoseSignature {
+    // external_aad is bound into the signature but not carried in the envelope, as in COSE
+    pub fn sign<'a, X>(sk: &SecretKey, messages: &[Vec<u8>], external_aad: &[u8]) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let protected = protected_header::<X>();
+        let header = sig_structure(&protected, external_aad);
+        let signature = Signature::sign::<X>(&message_scalars::<X>(messages)?, sk, Some(&header))?;
+        Ok(CoseSignature {
+            protected,
+            messages: messages.to_vec(),
+            signature,
+        })
+    }
+
+    // Fails when the envelope is for another ciphersuite
+    pub fn verify<'a, X>(&self, pk: &PublicKey, external_aad: &[u8]) -> Result<bool, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        check_alg::<X>(&self.protected)?;
+        let header = sig_structure(&self.protected, external_aad);
+        Ok(self.signature.verify::<X>(pk, &message_scalars::<X>(&self.messages)?, Some(&header)))
+    }
+
+    pub fn messages(&self) -> &[Vec<u8>] {
+        &self.messages
+    }
+
+    // The tagged COSE_Sign1 structure
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let envelope = Value::Array(alloc::vec![
+            Value::Bytes(self.protected.clone()),
+            Value::Map(BTreeMap::new()),
+            Value::Bytes(encode_messages(&self.messages)),
+            Value::Bytes(self.signature.to_bytes().to_vec()),
+        ]);
+        [&[COSE_SIGN1_TAG][..], &encode(&envelope)[..]].concat()
+    }
+
+    // The tag is optional
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        let bytes = bytes.strip_prefix(&[COSE_SIGN1_TAG]).unwrap_or(bytes);
+        let envelope = decode(bytes)?;
+        let [protected, _, payload, signature] = as_envelope(&envelope)?;
+        Ok(CoseSignature {
+            protected: as_bytes(Some(protected), "protected header")?.to_vec(),
+            messages: decode_messages(as_bytes(Some(payload), "payload")?)?,
+            signature: Signature::from_bytes(as_bytes(Some(signature), "signature")?)?,
+        })
+    }
+}
+
+// A proof derived from a CoseSignature, with the disclosed messages
+pub struct CoseProof {
+    pub(crate) protected: Vec<u8>,
+    pub(crate) presentation_header: Vec<u8>,
+    pub(crate) disclosed_indexes: Vec<usize>,
+    pub(crate) disclosed_messages: Vec<Vec<u8>>,
+    pub(crate) proof: Proof,
+}
+
+impl CoseProof {
+    // disclosed_indexes are zero based and must be in ascending order, external_aad must be the one the
+    // signature was created with
+    pub fn derive<'a, X, R>(
+        signature: &CoseSignature,
+        pk: &PublicKey,
+        external_aad: &[u8],
+        presentation_header: &[u8],
+        disclosed_indexes: &[usize],
+        rng: &mut R,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        check_alg::<X>(&signature.protected)?;
+        let header = sig_structure(&signature.protected, external_aad);
+        let proof = Proof::generate_with_rng::<X, R>(
+            &signature.signature,
+            pk,
+            Some(&header),
+            Some(presentation_header),
+            &message_scalars::<X>(&signature.messages)?,
+            disclosed_indexes,
+            rng,
+        )?;
+        Ok(CoseProof {
+            protected: signature.protected.clone(),
+            presentation_header: presentation_header.to_vec(),
+            disclosed_indexes: disclosed_indexes.to_vec(),
+            disclosed_messages: disclosed_indexes.iter().map(|&i| signature.messages[i].clone()).collect(),
+            proof,
+        })
+    }
+
+    pub fn verify<'a, X>(&self, pk: &PublicKey, external_aad: &[u8]) -> Result<bool, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        check_alg::<X>(&self.protected)?;
+        if self.disclosed_indexes.len() != self.disclosed_messages.len() {
+            return Err(BbsError::InvalidDisclosedIndexes);
+        }
+        let disclosed_messages = self
+            .disclosed_indexes
+            .iter()
+            .copied()
+            .zip(message_scalars::<X>(&self.disclosed_messages)?)
+            .collect();
+        let header = sig_structure(&self.protected, external_aad);
+        Ok(self.proof.verify::<X>(pk, Some(&header), Some(&self.presentation_header), &disclosed_messages))
+    }
+
+    // Keyed by the index the message was signed at
+    pub fn disclosed_messages(&self) -> BTreeMap<usize, &[u8]> {
+        self.disclosed_indexes
+            .iter()
+            .copied()
+            .zip(self.disclosed_messages.iter().map(|m| m.as_slice()))
+            .collect()
+    }
+
+    // Untagged, the envelope is not a COSE_Sign1 a COSE library could verify
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut unprotected = BTreeMap::new();
+        unprotected.insert(
+            Value::Integer(HEADER_PRESENTATION_HEADER),
+            Value::Bytes(self.presentation_header.clone()),
+        );
+        unprotected.insert(
+            Value::Integer(HEADER_DISCLOSED_INDEXES),
+            Value::Array(self.disclosed_indexes.iter().map(|&i| Value::Integer(i as i128)).collect()),
+        );
+        encode(&Value::Array(alloc::vec![
+            Value::Bytes(self.protected.clone()),
+            Value::Map(unprotected),
+            Value::Bytes(encode_messages(&self.disclosed_messages)),
+            Value::Bytes(self.proof.to_bytes()),
+        ]))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        let envelope = decode(bytes)?;
+        let [protected, unprotected, payload, proof] = as_envelope(&envelope)?;
+        let unprotected = as_map(unprotected, "unprotected header")?;
+        let disclosed_indexes = match unprotected.get(&Value::Integer(HEADER_DISCLOSED_INDEXES)) {
+            Some(Value::Array(indexes)) => indexes
+                .iter()
+                .map(|index| match index {
+                    Value::Integer(i) if *i >= 0 && *i <= usize::MAX as i128 => Ok(*i as usize),
+                    _ => Err(BbsError::InvalidDisclosedIndexes),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => return Err(invalid("disclosed indexes")),
+        };
+        Ok(CoseProof {
+            protected: as_bytes(Some(protected), "protected header")?.to_vec(),
+            presentation_header: as_bytes(
+                unprotected.get(&Value::Integer(HEADER_PRESENTATION_HEADER)),
+                "presentation header",
+            )?
+            .to_vec(),
+            disclosed_indexes,
+            disclosed_messages: decode_messages(as_bytes(Some(payload), "payload")?)?,
+            proof: Proof::from_bytes(as_bytes(Some(proof), "proof")?)?,
+        })
+    }
+}
+
+// {1: ciphersuite_id}
+fn protected_header<'a, X>() -> Vec<u8>
+where
+    X: BbsCiphersuite<'a>
+{
+    let mut header = BTreeMap::new();
+    header.insert(Value::Integer(ALG), Value::Text(String::from_utf8_lossy(X::ID).into_owned()));
+    encode(&Value::Map(header))
+}
+
+fn check_alg<'a, X>(protected: &[u8]) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let header = decode(protected)?;
+    match as_map(&header, "protected header")?.get(&Value::Integer(ALG)) {
+        Some(Value::Text(alg)) if alg.as_bytes() == X::ID => Ok(()),
+        _ => Err(BbsError::Serialization("the envelope is for another ciphersuite".to_string())),
+    }
+}
+
+fn sig_structure(protected: &[u8], external_aad: &[u8]) -> Vec<u8> {
+    encode(&Value::Array(alloc::vec![
+        Value::Text("Signature1".to_string()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(external_aad.to_vec()),
+    ]))
+}
+
+fn message_scalars<'a, X>(messages: &[Vec<u8>]) -> Result<Vec<Scalar>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    messages.iter().map(|m| map_message_to_scalar_as_hash::<X>(m, None)).collect()
+}
+
+fn encode_messages(messages: &[Vec<u8>]) -> Vec<u8> {
+    encode(&Value::Array(messages.iter().cloned().map(Value::Bytes).collect()))
+}
+
+fn decode_messages(payload: &[u8]) -> Result<Vec<Vec<u8>>, BbsError> {
+    match decode(payload)? {
+        Value::Array(messages) => messages
+            .into_iter()
+            .map(|m| match m {
+                Value::Bytes(m) => Ok(m),
+                _ => Err(invalid("message")),
+            })
+            .collect(),
+        _ => Err(invalid("payload")),
+    }
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+    serde_cbor::to_vec(value).expect("a CBOR value always serializes")
+}
+
+fn decode(bytes: &[u8]) -> Result<Value, BbsError> {
+    serde_cbor::from_slice(bytes).map_err(|e| BbsError::Serialization(e.to_string()))
+}
+
+fn as_envelope(value: &Value) -> Result<&[Value; 4], BbsError> {
+    match value {
+        Value::Array(items) => items.as_slice().try_into().map_err(|_| invalid("envelope")),
+        _ => Err(invalid("envelope")),
+    }
+}
+
+fn as_map<'v>(value: &'v Value, what: &str) -> Result<&'v BTreeMap<Value, Value>, BbsError> {
+    match value {
+        Value::Map(map) => Ok(map),
+        _ => Err(invalid(what)),
+    }
+}
+
+fn as_bytes<'v>(value: Option<&'v Value>, what: &str) -> Result<&'v [u8], BbsError> {
+    match value {
+        Some(Value::Bytes(bytes)) => Ok(bytes),
+        _ => Err(invalid(what)),
+    }
+}
+
+fn invalid(what: &str) -> BbsError {
+    BbsError::Serialization(alloc::format!("invalid COSE {}", what))
+}
@@ -0,0 +1,79 @@
+// Which field arithmetic the curve operations run with. The bls12_381_plus arithmetic is portable
+// Rust compiled for the target features of the build: with RUSTFLAGS="-C target-cpu=native" or
+// "-C target-feature=+adx,+bmi2" on x86_64 LLVM may use mulx for the 384 bit multiplications, and the
+// binary then requires a CPU with them. With the adx feature the multi-scalar multiplication of
+// Verify and ProofVerify runs over the ADX and BMI2 arithmetic of adx.rs when the CPU has them, so one
+// binary runs on any x86_64 CPU. Pairings and the other operations stay on bls12_381_plus
+#[cfg(feature = "std")]
+const ARCH: &str = std::env::consts::ARCH;
+#[cfg(not(feature = "std"))]
+const ARCH: &str = if cfg!(target_arch = "x86_64") {
+    "x86_64"
+} else if cfg!(target_arch = "aarch64") {
+    "aarch64"
+} else {
+    "unknown"
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Acceleration {
+    Portable,
+    // ADX and BMI2 enabled at build time for the whole crate
+    AdxBmi2Static,
+    // The ADX and BMI2 multi-scalar multiplication of the adx feature, selected at runtime
+    AdxBmi2Runtime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuInfo {
+    pub arch: &'static str,
+    // Whether the CPU has ADX and BMI2, false when that can not be detected, e.g. without std
+    pub adx: bool,
+    pub bmi2: bool,
+    // Whether the crate was built with ADX and BMI2 enabled as target features
+    pub static_adx_bmi2: bool,
+    // Whether the crate was built with the adx feature for x86_64
+    pub runtime_dispatch: bool,
+    pub acceleration: Acceleration,
+}
+
+impl Acceleration {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Acceleration::Portable => "portable",
+            Acceleration::AdxBmi2Static => "adx+bmi2 (target features)",
+            Acceleration::AdxBmi2Runtime => "adx+bmi2 multi-scalar multiplication (runtime dispatch)",
+        }
+    }
+}
+
+pub fn cpu_info() -> CpuInfo {
+    let (adx, bmi2) = detect();
+    let static_adx_bmi2 = cfg!(all(target_arch = "x86_64", target_feature = "adx", target_feature = "bmi2"));
+    let runtime_dispatch = cfg!(all(feature = "adx", target_arch = "x86_64"));
+    let acceleration = if runtime_dispatch && adx && bmi2 {
+        Acceleration::AdxBmi2Runtime
+    } else if static_adx_bmi2 {
+        Acceleration::AdxBmi2Static
+    } else {
+        Acceleration::Portable
+    };
+    CpuInfo { arch: ARCH, adx, bmi2, static_adx_bmi2, runtime_dispatch, acceleration }
+}
+
+// Whether adx::pippenger can run, cached by the std detection macro
+#[cfg(all(feature = "adx", target_arch = "x86_64"))]
+pub(crate) fn adx_bmi2_detected() -> bool {
+    std::is_x86_feature_detected!("adx") && std::is_x86_feature_detected!("bmi2")
+}
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+fn detect() -> (bool, bool) {
+    (std::is_x86_feature_detected!("adx"), std::is_x86_feature_detected!("bmi2"))
+}
+
+// Without std only what the build enabled is known
+#[cfg(not(all(feature = "std", target_arch = "x86_64")))]
+fn detect() -> (bool, bool) {
+    (cfg!(target_feature = "adx"), cfg!(target_feature = "bmi2"))
+}
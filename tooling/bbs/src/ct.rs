@@ -0,0 +1,170 @@
+// dudect style timing tests (Reparaz, Balasch, Verbauwhede, "Dude, is my code constant time?").
+// An operation is timed with a fixed secret input and with random secret inputs, interleaved in
+// random order, and Welch's t-test compares the two timing distributions. A |t| above
+// T_THRESHOLD means the timing depends on the secret input, e.g. a secret dependent branch
+// introduced in the scalar arithmetic. Run with cargo run --release --example ct --features ct-tests
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use ff::Field;
+use rand::{Rng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::Scalar;
+use crate::hashing::map_message_to_scalar_as_hash;
+use crate::key_pair::SecretKey;
+use crate::proof::Proof;
+use crate::signature::Signature;
+
+// The threshold dudect uses to report a leak with high confidence
+pub const T_THRESHOLD: f64 = 4.5;
+
+// Measurements above these percentiles are dropped before the t-test, to remove the long tail
+// caused by interrupts and scheduling. 1.0 keeps all measurements
+const CROP_PERCENTILES: [f64; 3] = [1.0, 0.9, 0.5];
+
+const MESSAGE_COUNT: usize = 5;
+const HEADER: &[u8] = b"ct-tests header";
+const PH: &[u8] = b"ct-tests presentation header";
+
+pub struct TimingReport {
+    pub operation: &'static str,
+    pub samples: usize,
+    // The largest |t| over the cropped measurement sets
+    pub max_t: f64,
+}
+
+impl TimingReport {
+    pub fn leaks(&self) -> bool {
+        self.max_t > T_THRESHOLD
+    }
+}
+
+// Signs the same messages with a fixed secret key or a random secret key
+pub fn test_sign<'a, X>(samples: usize) -> TimingReport
+where
+    X: BbsCiphersuite<'a>
+{
+    let messages = fixed_messages::<X>();
+    let fixed_sk = SecretKey::key_gen(&[0x01u8; 32], None).unwrap();
+
+    let inputs: Vec<(bool, SecretKey)> = random_classes(samples)
+        .into_iter()
+        .map(|random| (random, if random { random_secret_key() } else { secret_key_copy(&fixed_sk) }))
+        .collect();
+
+    measure("sign", &inputs, |sk| {
+        Signature::sign::<X>(&messages, sk, Some(HEADER)).unwrap();
+    })
+}
+
+// Generates a proof disclosing no messages over fixed signed messages or random signed messages
+pub fn test_proof_gen<'a, X>(samples: usize) -> TimingReport
+where
+    X: BbsCiphersuite<'a>
+{
+    let sk = SecretKey::key_gen(&[0x01u8; 32], None).unwrap();
+    let pk = sk.public_key();
+    let sign = |messages: Vec<Scalar>| {
+        let signature = Signature::sign::<X>(&messages, &sk, Some(HEADER)).unwrap();
+        (messages, signature)
+    };
+
+    let fixed_messages = fixed_messages::<X>();
+    let inputs: Vec<(bool, (Vec<Scalar>, Signature))> = random_classes(samples)
+        .into_iter()
+        .map(|random| {
+            let messages = if random { random_messages() } else { fixed_messages.clone() };
+            (random, sign(messages))
+        })
+        .collect();
+
+    measure("proof_gen", &inputs, |(messages, signature)| {
+        Proof::generate::<X>(signature, &pk, Some(HEADER), Some(PH), messages, &[]).unwrap();
+    })
+}
+
+// Times f on every input, the bool of an input tells whether it is from the random class
+fn measure<T, F>(operation: &'static str, inputs: &[(bool, T)], mut f: F) -> TimingReport
+where
+    F: FnMut(&T)
+{
+    let timings: Vec<(bool, f64)> = inputs
+        .iter()
+        .map(|(random, input)| {
+            let start = Instant::now();
+            f(input);
+            (*random, start.elapsed().as_nanos() as f64)
+        })
+        .collect();
+
+    let mut sorted: Vec<f64> = timings.iter().map(|&(_, t)| t).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let max_t = CROP_PERCENTILES
+        .iter()
+        .map(|&p| {
+            let threshold = sorted[((sorted.len() - 1) as f64 * p) as usize];
+            welch_t(timings.iter().filter(|&&(_, t)| t <= threshold).copied()).abs()
+        })
+        .fold(0.0, f64::max);
+
+    TimingReport {
+        operation,
+        samples: inputs.len(),
+        max_t,
+    }
+}
+
+// Welch's t statistic of the fixed class against the random class, with Welford's online
+// mean and variance
+fn welch_t<I>(timings: I) -> f64
+where
+    I: Iterator<Item = (bool, f64)>
+{
+    // (count, mean, sum of squared differences from the mean) per class
+    let mut classes = BTreeMap::new();
+    for (random, t) in timings {
+        let (n, mean, m2) = classes.entry(random).or_insert((0f64, 0f64, 0f64));
+        *n += 1.0;
+        let delta = t - *mean;
+        *mean += delta / *n;
+        *m2 += delta * (t - *mean);
+    }
+
+    match (classes.get(&false), classes.get(&true)) {
+        (Some(&(n0, mean0, m20)), Some(&(n1, mean1, m21))) if n0 > 1.0 && n1 > 1.0 => {
+            let (var0, var1) = (m20 / (n0 - 1.0), m21 / (n1 - 1.0));
+            (mean0 - mean1) / (var0 / n0 + var1 / n1).sqrt()
+        }
+        _ => 0.0,
+    }
+}
+
+fn random_classes(samples: usize) -> Vec<bool> {
+    let mut rng = rand::thread_rng();
+    (0..samples).map(|_| rng.gen()).collect()
+}
+
+fn random_secret_key() -> SecretKey {
+    let mut ikm = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut ikm);
+    SecretKey::key_gen(&ikm, None).unwrap()
+}
+
+fn secret_key_copy(sk: &SecretKey) -> SecretKey {
+    SecretKey(sk.0)
+}
+
+fn fixed_messages<'a, X>() -> Vec<Scalar>
+where
+    X: BbsCiphersuite<'a>
+{
+    (0..MESSAGE_COUNT)
+        .map(|i| map_message_to_scalar_as_hash::<X>(&[i as u8], None).unwrap())
+        .collect()
+}
+
+fn random_messages() -> Vec<Scalar> {
+    (0..MESSAGE_COUNT).map(|_| Scalar::random(rand::thread_rng())).collect()
+}
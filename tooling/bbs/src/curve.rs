@@ -0,0 +1,274 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use bls12_381_plus::{multi_miller_loop, pairing, ExpandMsg, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt};
+use ff::PrimeField;
+use group::{Curve, Group};
+
+use crate::hashing::ExpandMessage;
+
+// The pairing friendly curve operations the scheme is built on. The rest of the crate only names
+// the Backend alias and the G1, G2 and Scalar aliases below, so another curve library is plugged in
+// by implementing this trait for it and changing Backend. The scalar field and group arithmetic are
+// those of the ff and group traits, encodings, identities and hashing to the curve are methods here,
+// and expand_message is the crate's own, see hashing::ExpandMessage
+pub trait PairingCurve {
+    type Scalar: PrimeField;
+    type G1: Group<Scalar = Self::Scalar>;
+    type G2: Group<Scalar = Self::Scalar>;
+    type Gt: PartialEq;
+
+    fn g1_generator() -> Self::G1;
+    fn g2_generator() -> Self::G2;
+
+    fn g1_identity() -> Self::G1;
+    fn g2_identity() -> Self::G2;
+
+    fn g1_is_identity(p: &Self::G1) -> bool;
+    fn g2_is_identity(p: &Self::G2) -> bool;
+
+    // On the curve and in the prime order subgroup
+    fn g1_is_valid(p: &Self::G1) -> bool;
+    fn g2_is_valid(p: &Self::G2) -> bool;
+
+    // point_to_octets, the compressed encoding
+    fn g1_to_bytes(p: &Self::G1) -> [u8; 48];
+    fn g2_to_bytes(p: &Self::G2) -> [u8; 96];
+
+    // octets_to_point, including the subgroup check
+    fn g1_from_bytes(bytes: &[u8; 48]) -> Option<Self::G1>;
+    fn g2_from_bytes(bytes: &[u8; 96]) -> Option<Self::G2>;
+
+    // octets_to_point without the subgroup check and with the compression flag taken as set, so the
+    // point only has to be on the curve. For lenient decoding of deliberately malformed vectors
+    fn g1_from_bytes_unchecked(bytes: &[u8; 48]) -> Option<Self::G1>;
+    fn g2_from_bytes_unchecked(bytes: &[u8; 96]) -> Option<Self::G2>;
+
+    // The uncompressed encoding x || y of G2 points, for key formats that carry both coordinates
+    fn g2_to_uncompressed_bytes(p: &Self::G2) -> [u8; 192];
+    fn g2_from_uncompressed_bytes(bytes: &[u8; 192]) -> Option<Self::G2>;
+
+    // hash_to_curve with the expand_message variant of the ciphersuite
+    fn hash_to_g1<E: ExpandMessage>(msg: &[u8], dst: &[u8]) -> Self::G1;
+    fn hash_to_g2<E: ExpandMessage>(msg: &[u8], dst: &[u8]) -> Self::G2;
+
+    fn pairing(p: &Self::G1, q: &Self::G2) -> Self::Gt;
+
+    // e(p_1, q_1) * ... * e(p_n, q_n) == Identity_GT, sharing the final exponentiation
+    fn pairing_product_is_identity(terms: &[(Self::G1, Self::G2)]) -> bool;
+
+    // p_1 * s_1 + ... + p_n * s_n. Variable time in the scalars, only for public ones such as the
+    // messages and responses checked by Verify and ProofVerify
+    fn g1_sum_of_products_vartime(points: &[Self::G1], scalars: &[Self::Scalar]) -> Self::G1;
+
+    // Reduces a 64 octet little endian integer mod r
+    fn scalar_from_bytes_wide(bytes: &[u8; 64]) -> Self::Scalar;
+
+    // I2OSP(s, octet_scalar_length), big endian
+    fn scalar_to_bytes(s: &Self::Scalar) -> [u8; 32];
+
+    // The big endian integer, None when it is not less than r
+    fn scalar_from_bytes(bytes: &[u8; 32]) -> Option<Self::Scalar>;
+}
+
+pub struct Bls12381;
+
+pub type Backend = Bls12381;
+pub type G1 = <Backend as PairingCurve>::G1;
+pub type G2 = <Backend as PairingCurve>::G2;
+pub type Scalar = <Backend as PairingCurve>::Scalar;
+
+impl PairingCurve for Bls12381 {
+    type Scalar = bls12_381_plus::Scalar;
+    type G1 = G1Projective;
+    type G2 = G2Projective;
+    type Gt = Gt;
+
+    fn g1_generator() -> Self::G1 {
+        G1Projective::generator()
+    }
+
+    fn g2_generator() -> Self::G2 {
+        G2Projective::generator()
+    }
+
+    fn g1_identity() -> Self::G1 {
+        G1Projective::identity()
+    }
+
+    fn g2_identity() -> Self::G2 {
+        G2Projective::identity()
+    }
+
+    fn g1_is_identity(p: &Self::G1) -> bool {
+        bool::from(p.is_identity())
+    }
+
+    fn g2_is_identity(p: &Self::G2) -> bool {
+        bool::from(p.is_identity())
+    }
+
+    fn g1_is_valid(p: &Self::G1) -> bool {
+        let p = p.to_affine();
+        bool::from(p.is_on_curve() & p.is_torsion_free())
+    }
+
+    fn g2_is_valid(p: &Self::G2) -> bool {
+        let p = p.to_affine();
+        bool::from(p.is_on_curve() & p.is_torsion_free())
+    }
+
+    fn g1_to_bytes(p: &Self::G1) -> [u8; 48] {
+        p.to_affine().to_compressed()
+    }
+
+    fn g2_to_bytes(p: &Self::G2) -> [u8; 96] {
+        p.to_affine().to_compressed()
+    }
+
+    fn g1_from_bytes(bytes: &[u8; 48]) -> Option<Self::G1> {
+        let p: Option<G1Affine> = G1Affine::from_compressed(bytes).into();
+        p.map(G1Projective::from)
+    }
+
+    fn g2_from_bytes(bytes: &[u8; 96]) -> Option<Self::G2> {
+        let p: Option<G2Affine> = G2Affine::from_compressed(bytes).into();
+        p.map(G2Projective::from)
+    }
+
+    fn g1_from_bytes_unchecked(bytes: &[u8; 48]) -> Option<Self::G1> {
+        let mut bytes = *bytes;
+        bytes[0] |= 0x80;
+        let p: Option<G1Affine> = G1Affine::from_compressed_unchecked(&bytes).into();
+        p.map(G1Projective::from)
+    }
+
+    fn g2_from_bytes_unchecked(bytes: &[u8; 96]) -> Option<Self::G2> {
+        let mut bytes = *bytes;
+        bytes[0] |= 0x80;
+        let p: Option<G2Affine> = G2Affine::from_compressed_unchecked(&bytes).into();
+        p.map(G2Projective::from)
+    }
+
+    fn g2_to_uncompressed_bytes(p: &Self::G2) -> [u8; 192] {
+        p.to_affine().to_uncompressed()
+    }
+
+    fn g2_from_uncompressed_bytes(bytes: &[u8; 192]) -> Option<Self::G2> {
+        let p: Option<G2Affine> = G2Affine::from_uncompressed(bytes).into();
+        p.map(G2Projective::from)
+    }
+
+    fn hash_to_g1<E: ExpandMessage>(msg: &[u8], dst: &[u8]) -> Self::G1 {
+        G1Projective::hash::<Expander<E>>(msg, dst)
+    }
+
+    fn hash_to_g2<E: ExpandMessage>(msg: &[u8], dst: &[u8]) -> Self::G2 {
+        G2Projective::hash::<Expander<E>>(msg, dst)
+    }
+
+    fn pairing(p: &Self::G1, q: &Self::G2) -> Self::Gt {
+        pairing(&p.to_affine(), &q.to_affine())
+    }
+
+    fn pairing_product_is_identity(terms: &[(Self::G1, Self::G2)]) -> bool {
+        let prepared: Vec<(G1Affine, G2Prepared)> = terms
+            .iter()
+            .map(|(p, q)| (p.to_affine(), G2Prepared::from(q.to_affine())))
+            .collect();
+        let refs: Vec<(&G1Affine, &G2Prepared)> = prepared.iter().map(|(p, q)| (p, q)).collect();
+
+        bool::from(multi_miller_loop(&refs).final_exponentiation().is_identity())
+    }
+
+    fn g1_sum_of_products_vartime(points: &[Self::G1], scalars: &[Self::Scalar]) -> Self::G1 {
+        let n = points.len().min(scalars.len());
+        if n < PIPPENGER_THRESHOLD {
+            return points.iter().zip(scalars).fold(G1Projective::identity(), |acc, (p, s)| acc + p * s);
+        }
+
+        let mut affine = vec![G1Affine::identity(); n];
+        G1Projective::batch_normalize(&points[..n], &mut affine);
+        let scalars: Vec<[u8; 32]> = scalars[..n].iter().map(|s| s.to_bytes()).collect();
+
+        #[cfg(all(feature = "adx", target_arch = "x86_64"))]
+        if crate::cpu::adx_bmi2_detected() {
+            // Safety: the CPU has the features the function is built with
+            return unsafe { crate::adx::pippenger(&affine, &scalars) };
+        }
+        pippenger_portable(&affine, &scalars)
+    }
+
+    fn scalar_from_bytes_wide(bytes: &[u8; 64]) -> Self::Scalar {
+        bls12_381_plus::Scalar::from_bytes_wide(bytes)
+    }
+
+    fn scalar_to_bytes(s: &Self::Scalar) -> [u8; 32] {
+        let mut bytes = s.to_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    fn scalar_from_bytes(bytes: &[u8; 32]) -> Option<Self::Scalar> {
+        let mut le = *bytes;
+        le.reverse();
+        bls12_381_plus::Scalar::from_bytes(&le).into()
+    }
+}
+
+// The crate's expand_message as the ExpandMsg of bls12_381_plus hash_to_curve
+struct Expander<E>(PhantomData<E>);
+
+impl<E: ExpandMessage> ExpandMsg for Expander<E> {
+    fn expand_message(msg: &[u8], dst: &[u8], buf: &mut [u8]) {
+        E::expand_message(msg, dst, buf)
+    }
+}
+
+// Below this many terms the bucket method loses to a multiplication per point
+const PIPPENGER_THRESHOLD: usize = 16;
+
+// Pippenger's bucket method: the scalars are cut into windows of c bits and, from the most
+// significant window down, each point is added to the bucket of its window value, the buckets are
+// summed as sum_d d * bucket_d with a running sum and the accumulator is shifted by c doublings.
+// That is about 255 / c * (n + 2^c) additions instead of 255 doublings and additions per point. With
+// the adx feature adx::pippenger is the same method over field arithmetic built with ADX and BMI2
+pub(crate) fn pippenger_portable(affine: &[G1Affine], scalars: &[[u8; 32]]) -> G1Projective {
+    let c = pippenger_window(affine.len());
+    let windows = (255 + c - 1) / c;
+
+    let mut acc = G1Projective::identity();
+    for w in (0..windows).rev() {
+        for _ in 0..c {
+            acc = acc.double();
+        }
+
+        let mut buckets = vec![G1Projective::identity(); (1 << c) - 1];
+        for (p, s) in affine.iter().zip(scalars) {
+            let d = window_value(s, w * c, c);
+            if d != 0 {
+                buckets[d - 1] += p;
+            }
+        }
+
+        let mut running = G1Projective::identity();
+        let mut sum = G1Projective::identity();
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            sum += running;
+        }
+        acc += sum;
+    }
+    acc
+}
+
+// The window size c ~ ln(n) + 2 for n points
+pub(crate) fn pippenger_window(n: usize) -> usize {
+    (usize::BITS - n.leading_zeros()) as usize * 69 / 100 + 2
+}
+
+// The c bits of a little endian scalar starting at bit start
+pub(crate) fn window_value(s: &[u8; 32], start: usize, c: usize) -> usize {
+    (start..(start + c).min(256)).fold(0, |d, bit| d | (((s[bit / 8] >> (bit % 8)) & 1) as usize) << (bit - start))
+}
@@ -0,0 +1,365 @@
+// The bbs-2023 cryptosuite of W3C Data Integrity (VC-DI-BBS): base proofs created by the issuer and
+// derived proofs created by the holder, with their multibase encoded proofValue.
+//
+// The module works on canonical N-Quads, the JSON-LD canonicalization and the selection of the quads
+// matched by the mandatory and selective JSON pointers are left to the caller. Blank node labels are
+// _:c14nN as produced by URDNA2015, label_map replaces them with the HMAC shuffled labels _:bM of the
+// cryptosuite. Signatures and proofs use the BLS12-381-SHA-256 ciphersuite and are encoded as by the
+// version of the draft implemented by this crate
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use hmac::{Hmac, Mac, NewMac};
+use rand::{CryptoRng, RngCore};
+use serde_cbor::Value;
+use sha2::{Digest, Sha256};
+
+use crate::ciphersuites::Bls12381Sha256;
+use crate::curve::Scalar;
+use crate::error::BbsError;
+use crate::hashing::map_message_to_scalar_as_hash;
+use crate::key_pair::{KeyPair, PublicKey};
+use crate::proof::Proof;
+use crate::signature::Signature;
+
+pub const CRYPTOSUITE: &str = "bbs-2023";
+
+// CBOR tags prefixed to the proofValue of base and derived proofs
+const BASE_PROOF_HEADER: [u8; 3] = [0xd9, 0x5d, 0x02];
+const DERIVED_PROOF_HEADER: [u8; 3] = [0xd9, 0x5d, 0x03];
+
+pub struct BaseProof {
+    pub(crate) signature: Signature,
+    // SHA-256(proof configuration) || SHA-256(mandatory quads)
+    pub(crate) header: Vec<u8>,
+    pub(crate) public_key: PublicKey,
+    pub(crate) hmac_key: [u8; 32],
+    pub(crate) mandatory_pointers: Vec<String>,
+}
+
+impl BaseProof {
+    // proof_config is the canonical proof configuration, mandatory and non_mandatory are the relabeled
+    // quads of the document, the latter are signed as the BBS messages
+    pub fn create(
+        key_pair: &KeyPair,
+        proof_config: &str,
+        mandatory: &[String],
+        non_mandatory: &[String],
+        hmac_key: [u8; 32],
+        mandatory_pointers: Vec<String>,
+    ) -> Result<Self, BbsError> {
+        let header = bbs_header(proof_config, mandatory);
+        let messages = message_scalars(non_mandatory)?;
+        Ok(BaseProof {
+            signature: Signature::sign::<Bls12381Sha256>(&messages, &key_pair.secret_key, Some(&header))?,
+            header,
+            public_key: PublicKey(key_pair.public_key.0),
+            hmac_key,
+            mandatory_pointers,
+        })
+    }
+
+    // The holder's check of a base proof, with the quads it relabeled using hmac_key
+    pub fn verify(&self, proof_config: &str, mandatory: &[String], non_mandatory: &[String]) -> Result<bool, BbsError> {
+        if bbs_header(proof_config, mandatory) != self.header {
+            return Ok(false);
+        }
+        let messages = message_scalars(non_mandatory)?;
+        Ok(self.signature.verify::<Bls12381Sha256>(&self.public_key, &messages, Some(&self.header)))
+    }
+
+    pub fn hmac_key(&self) -> &[u8; 32] {
+        &self.hmac_key
+    }
+
+    pub fn mandatory_pointers(&self) -> &[String] {
+        &self.mandatory_pointers
+    }
+
+    // u || base64url(0xd95d02 || CBOR [signature, header, public key, HMAC key, mandatory pointers])
+    pub fn proof_value(&self) -> String {
+        let components = Value::Array(alloc::vec![
+            Value::Bytes(self.signature.to_bytes().to_vec()),
+            Value::Bytes(self.header.clone()),
+            Value::Bytes(self.public_key.to_bytes().to_vec()),
+            Value::Bytes(self.hmac_key.to_vec()),
+            Value::Array(self.mandatory_pointers.iter().cloned().map(Value::Text).collect()),
+        ]);
+        multibase(&BASE_PROOF_HEADER, &components)
+    }
+
+    pub fn from_proof_value(proof_value: &str) -> Result<Self, BbsError> {
+        let components = from_multibase(proof_value, &BASE_PROOF_HEADER)?;
+        match components.as_slice() {
+            [Value::Bytes(signature), Value::Bytes(header), Value::Bytes(public_key), Value::Bytes(hmac_key), Value::Array(pointers)] => {
+                let public_key = public_key
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| BbsError::InvalidLength("a public key is 96 octets"))?;
+                Ok(BaseProof {
+                    signature: Signature::from_bytes(signature)?,
+                    header: header.clone(),
+                    public_key: PublicKey::from_bytes(public_key)?,
+                    hmac_key: hmac_key
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| BbsError::InvalidLength("an HMAC key is 32 octets"))?,
+                    mandatory_pointers: pointers
+                        .iter()
+                        .map(|pointer| match pointer {
+                            Value::Text(pointer) => Ok(pointer.clone()),
+                            _ => Err(invalid_proof_value()),
+                        })
+                        .collect::<Result<_, _>>()?,
+                })
+            }
+            _ => Err(invalid_proof_value()),
+        }
+    }
+}
+
+pub struct DerivedProof {
+    pub(crate) proof: Proof,
+    // N of _:c14nN in the reveal document to M of _:bM in the signed document
+    pub(crate) label_map: BTreeMap<usize, usize>,
+    // Positions of the mandatory quads among the relabeled quads of the reveal document
+    pub(crate) mandatory_indexes: Vec<usize>,
+    // The indexes of the disclosed BBS messages
+    pub(crate) selective_indexes: Vec<usize>,
+    pub(crate) presentation_header: Vec<u8>,
+}
+
+impl DerivedProof {
+    // messages are the non_mandatory quads the base proof was created over, selective_indexes the ones
+    // disclosed, in ascending order. label_map and mandatory_indexes describe the reveal document
+    pub fn derive<R>(
+        base: &BaseProof,
+        messages: &[String],
+        selective_indexes: &[usize],
+        label_map: BTreeMap<usize, usize>,
+        mandatory_indexes: Vec<usize>,
+        presentation_header: &[u8],
+        rng: &mut R,
+    ) -> Result<Self, BbsError>
+    where
+        R: RngCore + CryptoRng
+    {
+        let proof = Proof::generate_with_rng::<Bls12381Sha256, R>(
+            &base.signature,
+            &base.public_key,
+            Some(&base.header),
+            Some(presentation_header),
+            &message_scalars(messages)?,
+            selective_indexes,
+            rng,
+        )?;
+        Ok(DerivedProof {
+            proof,
+            label_map,
+            mandatory_indexes,
+            selective_indexes: selective_indexes.to_vec(),
+            presentation_header: presentation_header.to_vec(),
+        })
+    }
+
+    // reveal_quads are the canonical quads of the reveal document, labeled _:c14nN. They are relabeled
+    // with the label map and split into the mandatory quads and the disclosed messages
+    pub fn verify(&self, pk: &PublicKey, proof_config: &str, reveal_quads: &[String]) -> Result<bool, BbsError> {
+        let label_map = self
+            .label_map
+            .iter()
+            .map(|(c14n, b)| (alloc::format!("c14n{}", c14n), alloc::format!("b{}", b)))
+            .collect();
+        let quads = relabel(reveal_quads, &label_map)?;
+
+        let mandatory_indexes: BTreeSet<usize> = self.mandatory_indexes.iter().copied().collect();
+        if mandatory_indexes.iter().any(|&i| i >= quads.len()) {
+            return Err(BbsError::InvalidDisclosedIndexes);
+        }
+        let (mandatory, non_mandatory): (Vec<_>, Vec<_>) = quads
+            .into_iter()
+            .enumerate()
+            .partition(|(i, _)| mandatory_indexes.contains(i));
+        let mandatory: Vec<String> = mandatory.into_iter().map(|(_, quad)| quad).collect();
+        let non_mandatory: Vec<String> = non_mandatory.into_iter().map(|(_, quad)| quad).collect();
+        if non_mandatory.len() != self.selective_indexes.len() {
+            return Err(BbsError::InvalidDisclosedIndexes);
+        }
+
+        let disclosed_messages = self
+            .selective_indexes
+            .iter()
+            .copied()
+            .zip(message_scalars(&non_mandatory)?)
+            .collect();
+        let header = bbs_header(proof_config, &mandatory);
+        Ok(self.proof.verify::<Bls12381Sha256>(pk, Some(&header), Some(&self.presentation_header), &disclosed_messages))
+    }
+
+    // u || base64url(0xd95d03 || CBOR [proof, label map, mandatory indexes, selective indexes, presentation header])
+    pub fn proof_value(&self) -> String {
+        let indexes = |indexes: &[usize]| Value::Array(indexes.iter().map(|&i| Value::Integer(i as i128)).collect());
+        let components = Value::Array(alloc::vec![
+            Value::Bytes(self.proof.to_bytes()),
+            Value::Map(
+                self.label_map
+                    .iter()
+                    .map(|(&c14n, &b)| (Value::Integer(c14n as i128), Value::Integer(b as i128)))
+                    .collect()
+            ),
+            indexes(&self.mandatory_indexes),
+            indexes(&self.selective_indexes),
+            Value::Bytes(self.presentation_header.clone()),
+        ]);
+        multibase(&DERIVED_PROOF_HEADER, &components)
+    }
+
+    pub fn from_proof_value(proof_value: &str) -> Result<Self, BbsError> {
+        let components = from_multibase(proof_value, &DERIVED_PROOF_HEADER)?;
+        match components.as_slice() {
+            [Value::Bytes(proof), Value::Map(label_map), Value::Array(mandatory_indexes), Value::Array(selective_indexes), Value::Bytes(presentation_header)] => {
+                Ok(DerivedProof {
+                    proof: Proof::from_bytes(proof)?,
+                    label_map: label_map
+                        .iter()
+                        .map(|(c14n, b)| Ok::<_, BbsError>((as_index(c14n)?, as_index(b)?)))
+                        .collect::<Result<_, BbsError>>()?,
+                    mandatory_indexes: mandatory_indexes.iter().map(as_index).collect::<Result<_, _>>()?,
+                    selective_indexes: selective_indexes.iter().map(as_index).collect::<Result<_, _>>()?,
+                    presentation_header: presentation_header.clone(),
+                })
+            }
+            _ => Err(invalid_proof_value()),
+        }
+    }
+}
+
+// The shuffled label map of the issuer: the HMAC of every c14nN label, base64url encoded, is sorted and
+// the label at position M becomes bM
+pub fn label_map(canonical_quads: &[String], hmac_key: &[u8; 32]) -> Result<BTreeMap<String, String>, BbsError> {
+    let mut labels = BTreeSet::new();
+    for quad in canonical_quads {
+        for_each_blank_node(quad, |label| {
+            labels.insert(label.to_string());
+            label.to_string()
+        })?;
+    }
+
+    let mut digests: Vec<(String, String)> = labels
+        .into_iter()
+        .map(|label| {
+            let mut mac = Hmac::<Sha256>::new_from_slice(hmac_key).expect("HMAC takes keys of any length");
+            mac.update(label.as_bytes());
+            let digest = base64::encode_config(mac.finalize().into_bytes(), base64::URL_SAFE_NO_PAD);
+            (digest, label)
+        })
+        .collect();
+    digests.sort();
+    Ok(digests
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, label))| (label, alloc::format!("b{}", i)))
+        .collect())
+}
+
+// Replaces the blank node labels of the quads as given by label_map, the result is sorted as canonical
+// N-Quads are. An error when a label is missing from the map
+pub fn relabel(quads: &[String], label_map: &BTreeMap<String, String>) -> Result<Vec<String>, BbsError> {
+    let mut relabeled = Vec::with_capacity(quads.len());
+    for quad in quads {
+        let mut missing = false;
+        let quad = for_each_blank_node(quad, |label| match label_map.get(label) {
+            Some(new_label) => new_label.clone(),
+            None => {
+                missing = true;
+                label.to_string()
+            }
+        })?;
+        if missing {
+            return Err(BbsError::Serialization("a blank node label is missing from the label map".to_string()));
+        }
+        relabeled.push(quad);
+    }
+    relabeled.sort();
+    Ok(relabeled)
+}
+
+// Rewrites the blank node labels of an N-Quad with f, skipping IRIs and literals, which may contain _:
+fn for_each_blank_node<F>(quad: &str, mut f: F) -> Result<String, BbsError>
+where
+    F: FnMut(&str) -> String
+{
+    let malformed = || BbsError::Serialization(alloc::format!("malformed N-Quad {}", quad));
+    let mut rewritten = String::with_capacity(quad.len());
+    let mut rest = quad;
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            '<' => rest.find('>').ok_or_else(malformed)? + 1,
+            '"' => {
+                let mut escaped = false;
+                let end = rest[1..]
+                    .char_indices()
+                    .find(|&(_, c)| {
+                        let closing = c == '"' && !escaped;
+                        escaped = c == '\\' && !escaped;
+                        closing
+                    })
+                    .ok_or_else(malformed)?
+                    .0;
+                end + 2
+            }
+            '_' if rest.starts_with("_:") => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                rewritten.push_str("_:");
+                rewritten.push_str(&f(&rest[2..end]));
+                rest = &rest[end..];
+                continue;
+            }
+            c => c.len_utf8(),
+        };
+        rewritten.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    Ok(rewritten)
+}
+
+fn bbs_header(proof_config: &str, mandatory: &[String]) -> Vec<u8> {
+    let proof_hash = Sha256::digest(proof_config.as_bytes());
+    let mandatory_hash = Sha256::digest(mandatory.concat().as_bytes());
+    [&proof_hash[..], &mandatory_hash[..]].concat()
+}
+
+fn message_scalars(quads: &[String]) -> Result<Vec<Scalar>, BbsError> {
+    quads
+        .iter()
+        .map(|quad| map_message_to_scalar_as_hash::<Bls12381Sha256>(quad.as_bytes(), None))
+        .collect()
+}
+
+fn multibase(header: &[u8; 3], components: &Value) -> String {
+    let cbor = serde_cbor::to_vec(components).expect("a CBOR value always serializes");
+    let encoded = base64::encode_config([&header[..], &cbor[..]].concat(), base64::URL_SAFE_NO_PAD);
+    alloc::format!("u{}", encoded)
+}
+
+fn from_multibase(proof_value: &str, header: &[u8; 3]) -> Result<Vec<Value>, BbsError> {
+    let encoded = proof_value.strip_prefix('u').ok_or_else(invalid_proof_value)?;
+    let bytes = base64::decode_config(encoded, base64::URL_SAFE_NO_PAD).map_err(|_| invalid_proof_value())?;
+    let cbor = bytes.strip_prefix(&header[..]).ok_or_else(invalid_proof_value)?;
+    match serde_cbor::from_slice(cbor).map_err(|e| BbsError::Serialization(e.to_string()))? {
+        Value::Array(components) => Ok(components),
+        _ => Err(invalid_proof_value()),
+    }
+}
+
+fn as_index(value: &Value) -> Result<usize, BbsError> {
+    match value {
+        Value::Integer(i) if *i >= 0 && *i <= usize::MAX as i128 => Ok(*i as usize),
+        _ => Err(invalid_proof_value()),
+    }
+}
+
+fn invalid_proof_value() -> BbsError {
+    BbsError::Serialization("not a bbs-2023 proofValue".to_string())
+}
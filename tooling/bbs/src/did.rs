@@ -0,0 +1,174 @@
+// did:key identifiers of public keys, as used by DID and verifiable credential tooling. The method
+// specific identifier is the multibase base58btc encoding, z prefixed, of the multicodec
+// bls12_381-g2-pub, 0xeb as the unsigned varint 0xeb 0x01, followed by point_to_octets_g2 of the key.
+// The verification method of the key is the DID with the identifier repeated as its fragment, so
+// resolve takes either and returns the key proofs and signatures are verified with
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::error::BbsError;
+use crate::key_pair::PublicKey;
+
+pub const DID_KEY_PREFIX: &str = "did:key:";
+
+// The unsigned varint of the multicodec bls12_381-g2-pub
+pub const BLS12_381_G2_PUB: [u8; 2] = [0xeb, 0x01];
+
+const BASE58BTC: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// did:key:z...
+pub fn to_did_key(pk: &PublicKey) -> String {
+    format!("{}{}", DID_KEY_PREFIX, method_specific_id(pk))
+}
+
+// The key of a did:key, an error when it is not a did:key of a BLS12-381 G2 key
+pub fn from_did_key(did: &str) -> Result<PublicKey, BbsError> {
+    let id = did.strip_prefix(DID_KEY_PREFIX).ok_or_else(|| invalid_did(did))?;
+    let encoded = id.strip_prefix('z').ok_or_else(|| invalid_did(did))?;
+    let octets = base58_decode(encoded).ok_or_else(|| invalid_did(did))?;
+    let octets = octets.strip_prefix(&BLS12_381_G2_PUB[..]).ok_or_else(|| invalid_did(did))?;
+    let octets = octets.try_into().map_err(|_| BbsError::InvalidLength("a public key is 96 octets"))?;
+    PublicKey::from_bytes(octets)
+}
+
+// did:key:z...#z..., the id of the verification method in the DID document of the key
+pub fn verification_method(pk: &PublicKey) -> String {
+    let id = method_specific_id(pk);
+    format!("{}{}#{}", DID_KEY_PREFIX, id, id)
+}
+
+// The key a DID or verification method refers to. A fragment other than the method specific id
+// names no key of the DID document and is an error
+pub fn resolve(did_url: &str) -> Result<PublicKey, BbsError> {
+    let (did, fragment) = match did_url.split_once('#') {
+        Some((did, fragment)) => (did, Some(fragment)),
+        None => (did_url, None),
+    };
+    let pk = from_did_key(did)?;
+    match fragment {
+        Some(fragment) if fragment != &did[DID_KEY_PREFIX.len()..] => Err(invalid_did(did_url)),
+        _ => Ok(pk),
+    }
+}
+
+fn method_specific_id(pk: &PublicKey) -> String {
+    let octets = [&BLS12_381_G2_PUB[..], &pk.to_bytes()[..]].concat();
+    format!("z{}", base58_encode(&octets))
+}
+
+fn invalid_did(did: &str) -> BbsError {
+    BbsError::Serialization(format!("{} is not a did:key of a BLS12-381 G2 public key", did))
+}
+
+// Leading zero octets become leading 1s, the rest is the big endian number in base 58
+pub(crate) fn base58_encode(octets: &[u8]) -> String {
+    let zeros = octets.iter().take_while(|&&b| b == 0).count();
+    // Little endian base 58 digits
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in &octets[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut encoded = String::with_capacity(zeros + digits.len());
+    encoded.extend(core::iter::repeat('1').take(zeros));
+    encoded.extend(digits.iter().rev().map(|&digit| BASE58BTC[digit as usize] as char));
+    encoded
+}
+
+pub(crate) fn base58_decode(encoded: &str) -> Option<Vec<u8>> {
+    let zeros = encoded.bytes().take_while(|&c| c == b'1').count();
+    // Little endian octets
+    let mut octets: Vec<u8> = Vec::new();
+    for c in encoded.bytes().skip(zeros) {
+        let mut carry = BASE58BTC.iter().position(|&digit| digit == c)? as u32;
+        for octet in octets.iter_mut() {
+            carry += (*octet as u32) * 58;
+            *octet = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            octets.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(octets.iter().rev());
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_pair::SecretKey;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    fn public_key() -> PublicKey {
+        SecretKey::key_gen(IKM, None).unwrap().public_key()
+    }
+
+    #[test]
+    fn did_key_round_trip() {
+        let pk = public_key();
+        let did = to_did_key(&pk);
+        assert!(did.starts_with("did:key:z"));
+        assert_eq!(from_did_key(&did).unwrap().to_bytes(), pk.to_bytes());
+        assert_eq!(resolve(&did).unwrap().to_bytes(), pk.to_bytes());
+    }
+
+    #[test]
+    fn verification_method_resolves_to_the_key() {
+        let pk = public_key();
+        let method = verification_method(&pk);
+        let (did, fragment) = method.split_once('#').unwrap();
+        assert_eq!(did, to_did_key(&pk));
+        assert_eq!(fragment, &did[DID_KEY_PREFIX.len()..]);
+        assert_eq!(resolve(&method).unwrap().to_bytes(), pk.to_bytes());
+    }
+
+    #[test]
+    fn resolve_rejects_another_fragment() {
+        let did = to_did_key(&public_key());
+        assert!(matches!(resolve(&format!("{}#key-1", did)), Err(BbsError::Serialization(_))));
+    }
+
+    #[test]
+    fn from_did_key_rejects_other_identifiers() {
+        let did = to_did_key(&public_key());
+        let id = &did[DID_KEY_PREFIX.len() + 1..];
+        // Another method, another multibase, a character outside the alphabet
+        assert!(matches!(from_did_key(&format!("did:web:z{}", id)), Err(BbsError::Serialization(_))));
+        assert!(matches!(from_did_key(&format!("did:key:m{}", id)), Err(BbsError::Serialization(_))));
+        assert!(matches!(from_did_key(&format!("did:key:z0{}", id)), Err(BbsError::Serialization(_))));
+
+        // Another multicodec
+        let ed25519 = [&[0xed, 0x01][..], &public_key().to_bytes()[..]].concat();
+        assert!(matches!(from_did_key(&format!("did:key:z{}", base58_encode(&ed25519))), Err(BbsError::Serialization(_))));
+
+        // A truncated key
+        let truncated = [&BLS12_381_G2_PUB[..], &public_key().to_bytes()[..95]].concat();
+        assert!(matches!(from_did_key(&format!("did:key:z{}", base58_encode(&truncated))), Err(BbsError::InvalidLength(_))));
+    }
+
+    #[test]
+    fn base58_round_trip() {
+        assert_eq!(base58_encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(base58_encode(&[0, 0, 1]), "112");
+        assert_eq!(base58_encode(&[]), "");
+        assert_eq!(base58_decode("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+        assert_eq!(base58_decode("112").unwrap(), [0, 0, 1]);
+        assert_eq!(base58_decode("0OIl"), None);
+    }
+}
@@ -0,0 +1,128 @@
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use ff::Field;
+use sha2::Sha256;
+use sha3::Shake256;
+
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::encoding::{encode_for_hash, i2osp, os2ip};
+use crate::error::BbsError;
+use crate::generators::Generators;
+use crate::hashing::{ExpandMessage, ExpandMsgXmd, ExpandMsgXof};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DynExpander {
+    XmdSha256,
+    XofShake256,
+}
+
+// A ciphersuite defined at runtime, e.g. loaded from a file, for experimenting with ciphersuite ids
+// and DSTs without adding a BbsCiphersuite implementation. Covers generator creation and hashing
+#[derive(Clone, Debug)]
+pub struct DynCiphersuite {
+    pub ciphersuite_id: Vec<u8>,
+    pub expander: DynExpander,
+    pub generator_seed: Vec<u8>,
+    pub bp_generator_seed: Vec<u8>,
+    pub generator_seed_dst: Vec<u8>,
+    pub generator_dst: Vec<u8>,
+    pub hash_to_scalar_dst: Vec<u8>,
+    pub map_message_to_scalar_dst: Vec<u8>,
+    // Used as both seed_len for create_generators and expand_len for hash_to_scalar
+    pub expand_len: usize,
+}
+
+impl DynCiphersuite {
+    // The seeds and DSTs are derived from ciphersuite_id as for the BbsCiphersuite implementations
+    pub fn new(ciphersuite_id: &[u8], expander: DynExpander) -> Self {
+        DynCiphersuite {
+            ciphersuite_id: ciphersuite_id.to_vec(),
+            expander,
+            generator_seed: [ciphersuite_id, b"MESSAGE_GENERATOR_SEED"].concat(),
+            bp_generator_seed: [ciphersuite_id, b"BP_MESSAGE_GENERATOR_SEED"].concat(),
+            generator_seed_dst: [ciphersuite_id, b"SIG_GENERATOR_SEED_"].concat(),
+            generator_dst: [ciphersuite_id, b"SIG_GENERATOR_DST_"].concat(),
+            hash_to_scalar_dst: [ciphersuite_id, b"H2S_"].concat(),
+            map_message_to_scalar_dst: [ciphersuite_id, b"MAP_MSG_TO_SCALAR_AS_HASH_"].concat(),
+            expand_len: 48,
+        }
+    }
+
+    pub fn expand_message(&self, msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+        let mut uniform_bytes = vec![0u8; len_in_bytes];
+        match self.expander {
+            DynExpander::XmdSha256 => ExpandMsgXmd::<Sha256>::expand_message(msg, dst, &mut uniform_bytes),
+            DynExpander::XofShake256 => ExpandMsgXof::<Shake256>::expand_message(msg, dst, &mut uniform_bytes),
+        }
+        uniform_bytes
+    }
+
+    fn hash_to_g1(&self, msg: &[u8], dst: &[u8]) -> G1 {
+        match self.expander {
+            DynExpander::XmdSha256 => Backend::hash_to_g1::<ExpandMsgXmd<Sha256>>(msg, dst),
+            DynExpander::XofShake256 => Backend::hash_to_g1::<ExpandMsgXof<Shake256>>(msg, dst),
+        }
+    }
+
+    pub fn make_generators(&self, seed: Option<&[u8]>, len: usize) -> Generators {
+        let seed = seed.unwrap_or(&self.generator_seed);
+
+        Generators {
+            g1_base_point: self.create_generators(&self.bp_generator_seed, 1)[0],
+            message_generators: self.create_generators(seed, len),
+            seed: seed.to_vec(),
+            ciphersuite_id: self.ciphersuite_id.clone(),
+            state: None,
+        }
+    }
+
+    // As generators::create_generators
+    pub fn create_generators(&self, generator_seed: &[u8], count: usize) -> Vec<G1> {
+        let mut v = self.expand_message(generator_seed, &self.generator_seed_dst, self.expand_len);
+        let mut n = 1;
+        let mut seen = BTreeSet::new();
+        let mut generators = Vec::with_capacity(count);
+        while generators.len() < count {
+            v = self.expand_message(&[v.as_slice(), &i2osp(n, 4)].concat(), &self.generator_seed_dst, self.expand_len);
+            n += 1;
+
+            let candidate = self.hash_to_g1(&v, &self.generator_dst);
+            if Backend::g1_is_identity(&candidate) || !seen.insert(Backend::g1_to_bytes(&candidate)) {
+                continue;
+            }
+            generators.push(candidate);
+        }
+        generators
+    }
+
+    // As hashing::hash_to_scalar, dst defaults to hash_to_scalar_dst
+    pub fn hash_to_scalar(&self, msg_octets: &[u8], count: usize, dst: Option<&[u8]>) -> Vec<Scalar> {
+        let dst = dst.unwrap_or(&self.hash_to_scalar_dst);
+        let len_in_bytes = count * self.expand_len;
+
+        let mut t = 0;
+        loop {
+            let msg_prime = [msg_octets, &i2osp(t, 1), &i2osp(count as u64, 4)].concat();
+            let uniform_bytes = self.expand_message(&msg_prime, dst, len_in_bytes);
+
+            let scalars: Vec<Scalar> = uniform_bytes.chunks(self.expand_len).map(os2ip).collect();
+            if scalars.iter().all(|s| !bool::from(s.is_zero())) {
+                return scalars;
+            }
+            t += 1;
+        }
+    }
+
+    // As hashing::map_message_to_scalar_as_hash, dst defaults to map_message_to_scalar_dst
+    pub fn map_message_to_scalar_as_hash(&self, msg: &[u8], dst: Option<&[u8]>) -> Result<Scalar, BbsError> {
+        let dst = dst.unwrap_or(&self.map_message_to_scalar_dst);
+        if dst.len() > 255 {
+            return Err(BbsError::InvalidLength("dst must be at most 255 octets"));
+        }
+
+        let msg_for_hash = encode_for_hash(&[&msg]);
+        Ok(self.hash_to_scalar(&msg_for_hash, 1, Some(dst))[0])
+    }
+}
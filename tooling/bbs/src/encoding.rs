@@ -0,0 +1,220 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use ff::Field;
+
+use crate::curve::{Backend, PairingCurve, Scalar, G1, G2};
+use crate::error::BbsError;
+use crate::key_pair::PublicKey;
+
+// I2OSP as defined in RFC8017. Panics with "integer too large" when value does not fit in length
+// octets, RFC8017's error, since every caller passes a length chosen for the value, e.g. the
+// I2OSP(n, 4) counters and the lengths of the DSTs that are checked beforehand
+pub fn i2osp(value: u64, length: usize) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    if length < 8 {
+        assert!(value >> (8 * length) == 0, "integer too large");
+        bytes[8 - length..].to_vec()
+    } else {
+        [vec![0u8; length - 8], bytes.to_vec()].concat()
+    }
+}
+
+// OS2IP as defined in RFC8017 reduced mod r, panics when given more than 64 octets
+pub fn os2ip(bytes: &[u8]) -> Scalar {
+    let mut wide = [0u8; 64];
+    wide[..bytes.len()].copy_from_slice(bytes);
+    wide[..bytes.len()].reverse();
+    Backend::scalar_from_bytes_wide(&wide)
+}
+
+// I2OSP(s, octet_scalar_length)
+pub fn scalar_to_bytes(s: &Scalar) -> [u8; 32] {
+    Backend::scalar_to_bytes(s)
+}
+
+// OS2IP of octet_scalar_length octets, rejects values that are not less than r
+pub fn scalar_from_bytes(bytes: &[u8; 32]) -> Result<Scalar, BbsError> {
+    Backend::scalar_from_bytes(bytes).ok_or(BbsError::InvalidScalar)
+}
+
+// The element encodings of encode_for_hash: points with point_to_octets, scalars with
+// I2OSP(s, octet_scalar_length), integers with I2OSP(n, 8), public keys as they are and other octet
+// strings, including ASCII strings such as the ciphersuite id, prefixed with I2OSP(length, 8). A
+// slice of points or scalars is its elements one after the other, as the spec's arrays are flattened
+pub trait SpecSerialize {
+    fn serialize_into(&self, octets: &mut Vec<u8>);
+}
+
+// encode_for_hash((el_1, ..., el_n)), e.g. encode_for_hash(&[pk, &l, &generators, &X::ID, &header])
+pub fn encode_for_hash(elements: &[&dyn SpecSerialize]) -> Vec<u8> {
+    let mut octets = Vec::new();
+    for el in elements {
+        el.serialize_into(&mut octets);
+    }
+    octets
+}
+
+impl<T: SpecSerialize + ?Sized> SpecSerialize for &T {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        (**self).serialize_into(octets)
+    }
+}
+
+impl SpecSerialize for G1 {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        octets.extend_from_slice(&Backend::g1_to_bytes(self));
+    }
+}
+
+impl SpecSerialize for G2 {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        octets.extend_from_slice(&Backend::g2_to_bytes(self));
+    }
+}
+
+impl SpecSerialize for Scalar {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        octets.extend_from_slice(&scalar_to_bytes(self));
+    }
+}
+
+impl SpecSerialize for u64 {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        octets.extend_from_slice(&i2osp(*self, 8));
+    }
+}
+
+impl SpecSerialize for usize {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        (*self as u64).serialize_into(octets)
+    }
+}
+
+impl SpecSerialize for PublicKey {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        octets.extend_from_slice(&self.to_bytes());
+    }
+}
+
+impl SpecSerialize for [u8] {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        self.len().serialize_into(octets);
+        octets.extend_from_slice(self);
+    }
+}
+
+impl SpecSerialize for [G1] {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        self.iter().for_each(|p| p.serialize_into(octets));
+    }
+}
+
+impl SpecSerialize for [G2] {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        self.iter().for_each(|p| p.serialize_into(octets));
+    }
+}
+
+impl SpecSerialize for [Scalar] {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        self.iter().for_each(|s| s.serialize_into(octets));
+    }
+}
+
+// How the octets of signatures, proofs and public keys are decoded. Strict is the spec's decoding and
+// the one of from_bytes: only canonical encodings, points with the compression flag set, x less than
+// p and in the subgroup, not the identity, and scalars neither 0 nor at least r. Lenient is for test
+// tooling building malformed vectors: flags are ignored, points only have to be on the curve and may
+// be the identity, scalars may be 0 and are reduced mod r. Verifiers must use Strict, and to_bytes
+// always writes the canonical encoding of what was decoded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeserializeMode {
+    Strict,
+    Lenient,
+}
+
+// octets_to_point for the G1 points of a signature or proof, rejects Identity_G1
+pub(crate) fn g1_from_octets(bytes: &[u8]) -> Result<G1, BbsError> {
+    g1_from_octets_with_mode(bytes, DeserializeMode::Strict)
+}
+
+pub(crate) fn g1_from_octets_with_mode(bytes: &[u8], mode: DeserializeMode) -> Result<G1, BbsError> {
+    let bytes = bytes.try_into().map_err(|_| BbsError::InvalidLength("a G1 point is 48 octets"))?;
+    match mode {
+        DeserializeMode::Strict => {
+            let p = Backend::g1_from_bytes(bytes).ok_or(BbsError::InvalidPoint)?;
+            if Backend::g1_is_identity(&p) {
+                return Err(BbsError::InvalidPoint);
+            }
+            Ok(p)
+        }
+        DeserializeMode::Lenient => Backend::g1_from_bytes_unchecked(bytes).ok_or(BbsError::InvalidPoint),
+    }
+}
+
+// The scalars of a signature or proof, rejects 0 and values that are not less than r
+pub(crate) fn nonzero_scalar_from_octets(bytes: &[u8]) -> Result<Scalar, BbsError> {
+    nonzero_scalar_from_octets_with_mode(bytes, DeserializeMode::Strict)
+}
+
+pub(crate) fn nonzero_scalar_from_octets_with_mode(bytes: &[u8], mode: DeserializeMode) -> Result<Scalar, BbsError> {
+    let bytes: &[u8; 32] = bytes.try_into().map_err(|_| BbsError::InvalidLength("a scalar is 32 octets"))?;
+    match mode {
+        DeserializeMode::Strict => {
+            let s = scalar_from_bytes(bytes)?;
+            if bool::from(s.is_zero()) {
+                return Err(BbsError::InvalidScalar);
+            }
+            Ok(s)
+        }
+        DeserializeMode::Lenient => Ok(os2ip(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn i2osp_known_vectors() {
+        assert_eq!(i2osp(0, 1), [0x00]);
+        assert_eq!(i2osp(255, 1), [0xff]);
+        assert_eq!(i2osp(256, 2), [0x01, 0x00]);
+        assert_eq!(i2osp(1, 4), [0x00, 0x00, 0x00, 0x01]);
+        assert_eq!(i2osp(0x0102030405060708, 8), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(i2osp(u64::MAX, 10), [0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        assert!(i2osp(0, 0).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "integer too large")]
+    fn i2osp_panics_when_the_value_does_not_fit() {
+        i2osp(256, 1);
+    }
+
+    #[test]
+    fn os2ip_known_vectors() {
+        assert_eq!(os2ip(&[]), Scalar::zero());
+        assert_eq!(os2ip(&[0x01, 0x00]), Scalar::from(256u64));
+
+        // (2^512 - 1) mod r and OS2IP(0x00 || 0x01 || ... || 0x3f) mod r
+        let expected = hex("0748d9d99f59ff1105d314967254398f2b6cedcb87925c23c999e990f3f29c6c");
+        assert_eq!(scalar_to_bytes(&os2ip(&[0xff; 64])).to_vec(), expected);
+        let octets: Vec<u8> = (0..64).collect();
+        let expected = hex("6d31d8684aab1a3910d9770d3affb7e74ac05cee3b11e7ca194c48de6e4f23ec");
+        assert_eq!(scalar_to_bytes(&os2ip(&octets)).to_vec(), expected);
+    }
+
+    #[test]
+    fn os2ip_inverts_i2osp() {
+        for value in [0, 1, 255, 256, u32::MAX as u64, u64::MAX] {
+            assert_eq!(os2ip(&i2osp(value, 8)), Scalar::from(value));
+            assert_eq!(os2ip(&i2osp(value, 32)), Scalar::from(value));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use alloc::string::String;
+
+// The errors of the library and the tooling built on it. Display comes from thiserror, which
+// needs std, so without std the variants only implement Debug
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum BbsError {
+    // Names what has the wrong length, e.g. "IKM must be at least 32 octets"
+    #[cfg_attr(feature = "std", error("invalid length: {0}"))]
+    InvalidLength(&'static str),
+    // Not a valid encoding of a point in the subgroup, or the identity where it is not allowed
+    #[cfg_attr(feature = "std", error("invalid point"))]
+    InvalidPoint,
+    // Not less than r, or 0 where it is not allowed
+    #[cfg_attr(feature = "std", error("invalid scalar"))]
+    InvalidScalar,
+    #[cfg_attr(feature = "std", error("disclosed indexes must be ascending and less than the number of messages"))]
+    InvalidDisclosedIndexes,
+    #[cfg_attr(feature = "std", error("the commitment proof does not verify"))]
+    InvalidCommitment,
+    // SK + e = 0 mod r
+    #[cfg_attr(feature = "std", error("signing failed"))]
+    SigningFailed,
+    // r1 = 0, or the caller supplied the wrong number of random scalars
+    #[cfg_attr(feature = "std", error("proof generation failed"))]
+    ProofGenerationFailed,
+    // More messages than the max_messages of the limits::Limits of the call
+    #[cfg_attr(feature = "std", error("{0} messages exceed the limit of max_messages"))]
+    TooManyMessages(usize),
+    // Generators made for another ciphersuite than the one of the operation
+    #[cfg_attr(feature = "std", error("the generators were made for a different ciphersuite"))]
+    CiphersuiteMismatch,
+    // IssuerKeyRing::add of a key id already in the ring, or retire of one not in it
+    #[cfg_attr(feature = "std", error("invalid key id: {0}"))]
+    InvalidKeyId(String),
+    // LabeledMessages::register of a label already registered, or a label that is not registered
+    #[cfg_attr(feature = "std", error("invalid label: {0}"))]
+    InvalidLabel(String),
+    // A generator in the identity, outside the G1 subgroup or repeated, see Generators::validate
+    #[cfg_attr(feature = "std", error("invalid generator: {0}"))]
+    InvalidGenerator(String),
+    // Malformed generator cache files, and the encodings written by the tooling
+    #[cfg_attr(feature = "std", error("serialization error: {0}"))]
+    Serialization(String),
+    #[cfg(feature = "std")]
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "pkcs11")]
+    #[error("PKCS#11 error: {0}")]
+    Pkcs11(#[from] cryptoki::error::Error),
+}
@@ -0,0 +1,664 @@
+// C API for linking the crate from mobile SDKs. include/bbs.h is generated from this module with
+// cbindgen, see cbindgen.toml. Messages are octet strings mapped to scalars with
+// map_message_to_scalar_as_hash, an empty header or presentation header is treated as absent. A panic
+// is caught at the boundary and returned as BBS_ERROR_PANIC, it never unwinds into the caller
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::{ptr, slice};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::ciphersuites::{BbsCiphersuite, Bls12381Sha256, Bls12381Shake256};
+use crate::hashing::map_message_to_scalar_as_hash;
+use crate::{Proof, PublicKey, Scalar, SecretKey, Signature};
+
+/// The ciphersuite ids an operation runs with, any other id is rejected with BBS_ERROR_INVALID_ARGUMENT.
+/// Passed as a plain integer, a C enum value outside the Rust enum's variants would be undefined
+/// behaviour on the Rust side
+pub const BBS_CIPHERSUITE_BLS12381_SHA256: u32 = 0;
+pub const BBS_CIPHERSUITE_BLS12381_SHAKE256: u32 = 1;
+
+/// Result codes, BBS_ERROR_OK on success. The C counterpart of crate::BbsError: codes 1 to 9 name
+/// the argument that was rejected, each other crate::BbsError variant has its own code from 10 on.
+/// BBS_ERROR_PANIC is not a crate::BbsError, codes added after it continue from 23
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BbsError {
+    Ok = 0,
+    /// A null pointer with a non zero length or a null output pointer
+    InvalidArgument = 1,
+    InvalidSecretKey = 2,
+    InvalidPublicKey = 3,
+    InvalidSignature = 4,
+    InvalidProof = 5,
+    InvalidMessage = 6,
+    /// Disclosed indexes out of range or not in ascending order
+    InvalidDisclosedIndexes = 7,
+    SigningFailed = 8,
+    /// The signature or proof is well formed but does not verify
+    VerificationFailed = 9,
+    InvalidLength = 10,
+    InvalidPoint = 11,
+    InvalidScalar = 12,
+    InvalidCommitment = 13,
+    /// r1 = 0 while generating the proof
+    ProofGenerationFailed = 14,
+    /// More messages than the default max_messages of limits::Limits
+    TooManyMessages = 15,
+    CiphersuiteMismatch = 16,
+    InvalidGenerator = 17,
+    Serialization = 18,
+    Io = 19,
+    Pkcs11 = 20,
+    InvalidKeyId = 21,
+    /// The library panicked, a bug to report rather than a rejected input
+    Panic = 22,
+    InvalidLabel = 23,
+}
+
+/// Borrowed input bytes, data may be null when len is 0
+#[repr(C)]
+pub struct ByteSlice {
+    pub data: *const u8,
+    pub len: usize,
+}
+
+/// Bytes allocated by the library, released with bbs_byte_buffer_free
+#[repr(C)]
+pub struct ByteBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+/// Signs the messages, writing the 112 octet signature to signature_out
+///
+/// # Safety
+///
+/// Every ByteSlice must reference len readable bytes, messages must reference messages_len
+/// ByteSlices and signature_out 112 writable bytes
+#[no_mangle]
+pub unsafe extern "C" fn bbs_sign(
+    suite: u32,
+    secret_key: ByteSlice,
+    header: ByteSlice,
+    messages: *const ByteSlice,
+    messages_len: usize,
+    signature_out: *mut u8,
+) -> BbsError {
+    guard(|| {
+        let sign_fn = match suite {
+            BBS_CIPHERSUITE_BLS12381_SHA256 => sign::<Bls12381Sha256>,
+            BBS_CIPHERSUITE_BLS12381_SHAKE256 => sign::<Bls12381Shake256>,
+            _ => return BbsError::InvalidArgument,
+        };
+
+        if signature_out.is_null() {
+            return BbsError::InvalidArgument;
+        }
+        let signature = match (secret_key.bytes(), header.bytes(), byte_slices(messages, messages_len)) {
+            (Ok(secret_key), Ok(header), Ok(messages)) => sign_fn(secret_key, header, &messages),
+            _ => return BbsError::InvalidArgument,
+        };
+
+        match signature {
+            Ok(signature) => {
+                ptr::copy_nonoverlapping(signature.as_ptr(), signature_out, signature.len());
+                BbsError::Ok
+            }
+            Err(e) => e,
+        }
+    })
+}
+
+/// Verifies the 112 octet signature, BBS_ERROR_VERIFICATION_FAILED when it does not verify
+///
+/// # Safety
+///
+/// Every ByteSlice must reference len readable bytes and messages must reference messages_len ByteSlices
+#[no_mangle]
+pub unsafe extern "C" fn bbs_verify(
+    suite: u32,
+    public_key: ByteSlice,
+    signature: ByteSlice,
+    header: ByteSlice,
+    messages: *const ByteSlice,
+    messages_len: usize,
+) -> BbsError {
+    guard(|| {
+        let verify_fn = match suite {
+            BBS_CIPHERSUITE_BLS12381_SHA256 => verify::<Bls12381Sha256>,
+            BBS_CIPHERSUITE_BLS12381_SHAKE256 => verify::<Bls12381Shake256>,
+            _ => return BbsError::InvalidArgument,
+        };
+
+        match (public_key.bytes(), signature.bytes(), header.bytes(), byte_slices(messages, messages_len)) {
+            (Ok(public_key), Ok(signature), Ok(header), Ok(messages)) => {
+                result_code(verify_fn(public_key, signature, header, &messages))
+            }
+            _ => BbsError::InvalidArgument,
+        }
+    })
+}
+
+/// Generates a proof disclosing the messages at the zero based, ascending disclosed_indexes. On
+/// success proof_out holds the proof, to be released with bbs_byte_buffer_free
+///
+/// # Safety
+///
+/// Every ByteSlice must reference len readable bytes, messages must reference messages_len
+/// ByteSlices, disclosed_indexes disclosed_indexes_len indexes and proof_out a writable ByteBuffer
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn bbs_proof_gen(
+    suite: u32,
+    public_key: ByteSlice,
+    signature: ByteSlice,
+    header: ByteSlice,
+    ph: ByteSlice,
+    messages: *const ByteSlice,
+    messages_len: usize,
+    disclosed_indexes: *const usize,
+    disclosed_indexes_len: usize,
+    proof_out: *mut ByteBuffer,
+) -> BbsError {
+    guard(|| {
+        let proof_gen_fn = match suite {
+            BBS_CIPHERSUITE_BLS12381_SHA256 => proof_gen::<Bls12381Sha256>,
+            BBS_CIPHERSUITE_BLS12381_SHAKE256 => proof_gen::<Bls12381Shake256>,
+            _ => return BbsError::InvalidArgument,
+        };
+
+        if proof_out.is_null() {
+            return BbsError::InvalidArgument;
+        }
+        let proof = match (
+            public_key.bytes(),
+            signature.bytes(),
+            header.bytes(),
+            ph.bytes(),
+            byte_slices(messages, messages_len),
+            raw_slice(disclosed_indexes, disclosed_indexes_len),
+        ) {
+            (Ok(public_key), Ok(signature), Ok(header), Ok(ph), Ok(messages), Ok(disclosed_indexes)) => {
+                proof_gen_fn(public_key, signature, header, ph, &messages, disclosed_indexes)
+            }
+            _ => return BbsError::InvalidArgument,
+        };
+
+        match proof {
+            Ok(proof) => {
+                *proof_out = ByteBuffer::from_vec(proof);
+                BbsError::Ok
+            }
+            Err(e) => e,
+        }
+    })
+}
+
+/// Verifies a proof, disclosed_messages[i] is the message signed at disclosed_indexes[i]. The
+/// indexes must be ascending without repeats, BBS_ERROR_INVALID_DISCLOSED_INDEXES otherwise, and
+/// BBS_ERROR_VERIFICATION_FAILED when the proof does not verify
+///
+/// # Safety
+///
+/// Every ByteSlice must reference len readable bytes, disclosed_messages must reference
+/// disclosed_len ByteSlices and disclosed_indexes disclosed_len indexes
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn bbs_proof_verify(
+    suite: u32,
+    public_key: ByteSlice,
+    proof: ByteSlice,
+    header: ByteSlice,
+    ph: ByteSlice,
+    disclosed_messages: *const ByteSlice,
+    disclosed_indexes: *const usize,
+    disclosed_len: usize,
+) -> BbsError {
+    guard(|| {
+        let proof_verify_fn = match suite {
+            BBS_CIPHERSUITE_BLS12381_SHA256 => proof_verify::<Bls12381Sha256>,
+            BBS_CIPHERSUITE_BLS12381_SHAKE256 => proof_verify::<Bls12381Shake256>,
+            _ => return BbsError::InvalidArgument,
+        };
+
+        match (
+            public_key.bytes(),
+            proof.bytes(),
+            header.bytes(),
+            ph.bytes(),
+            byte_slices(disclosed_messages, disclosed_len),
+            raw_slice(disclosed_indexes, disclosed_len),
+        ) {
+            (Ok(public_key), Ok(proof), Ok(header), Ok(ph), Ok(disclosed_messages), Ok(disclosed_indexes)) => {
+                result_code(proof_verify_fn(public_key, proof, header, ph, &disclosed_messages, disclosed_indexes))
+            }
+            _ => BbsError::InvalidArgument,
+        }
+    })
+}
+
+/// Releases a buffer returned by the library
+///
+/// # Safety
+///
+/// buffer must have been returned by the library and not been released before
+#[no_mangle]
+pub unsafe extern "C" fn bbs_byte_buffer_free(buffer: ByteBuffer) {
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        if !buffer.data.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)));
+        }
+    }));
+}
+
+impl From<crate::BbsError> for BbsError {
+    fn from(e: crate::BbsError) -> Self {
+        match e {
+            crate::BbsError::InvalidLength(_) => BbsError::InvalidLength,
+            crate::BbsError::InvalidPoint => BbsError::InvalidPoint,
+            crate::BbsError::InvalidScalar => BbsError::InvalidScalar,
+            crate::BbsError::InvalidDisclosedIndexes => BbsError::InvalidDisclosedIndexes,
+            crate::BbsError::InvalidCommitment => BbsError::InvalidCommitment,
+            crate::BbsError::SigningFailed => BbsError::SigningFailed,
+            crate::BbsError::ProofGenerationFailed => BbsError::ProofGenerationFailed,
+            crate::BbsError::TooManyMessages(_) => BbsError::TooManyMessages,
+            crate::BbsError::CiphersuiteMismatch => BbsError::CiphersuiteMismatch,
+            crate::BbsError::InvalidKeyId(_) => BbsError::InvalidKeyId,
+            crate::BbsError::InvalidLabel(_) => BbsError::InvalidLabel,
+            crate::BbsError::InvalidGenerator(_) => BbsError::InvalidGenerator,
+            crate::BbsError::Serialization(_) => BbsError::Serialization,
+            crate::BbsError::Io(_) => BbsError::Io,
+            #[cfg(feature = "pkcs11")]
+            crate::BbsError::Pkcs11(_) => BbsError::Pkcs11,
+        }
+    }
+}
+
+impl ByteSlice {
+    unsafe fn bytes<'b>(&self) -> Result<&'b [u8], BbsError> {
+        raw_slice(self.data, self.len)
+    }
+}
+
+impl ByteBuffer {
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        ByteBuffer {
+            data: Box::into_raw(bytes.into_boxed_slice()) as *mut u8,
+            len,
+        }
+    }
+}
+
+unsafe fn raw_slice<'b, T>(data: *const T, len: usize) -> Result<&'b [T], BbsError> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if data.is_null() {
+        return Err(BbsError::InvalidArgument);
+    }
+    Ok(slice::from_raw_parts(data, len))
+}
+
+unsafe fn byte_slices<'b>(data: *const ByteSlice, len: usize) -> Result<Vec<&'b [u8]>, BbsError> {
+    raw_slice(data, len)?.iter().map(|m| m.bytes()).collect()
+}
+
+// Runs the body of an exported function, a panic is returned as BbsError::Panic instead of unwinding
+// across the C boundary, which is undefined behaviour
+fn guard<F>(body: F) -> BbsError
+where
+    F: FnOnce() -> BbsError
+{
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(BbsError::Panic)
+}
+
+fn result_code(result: Result<(), BbsError>) -> BbsError {
+    result.err().unwrap_or(BbsError::Ok)
+}
+
+fn non_empty(bytes: &[u8]) -> Option<&[u8]> {
+    if bytes.is_empty() {
+        None
+    } else {
+        Some(bytes)
+    }
+}
+
+fn message_scalars<'a, X>(messages: &[&[u8]]) -> Result<Vec<Scalar>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    messages
+        .iter()
+        .map(|msg| map_message_to_scalar_as_hash::<X>(msg, None).map_err(|_| BbsError::InvalidMessage))
+        .collect()
+}
+
+fn secret_key_from(bytes: &[u8]) -> Result<SecretKey, BbsError> {
+    let bytes = bytes.try_into().map_err(|_| BbsError::InvalidSecretKey)?;
+    SecretKey::from_bytes(bytes).map_err(|_| BbsError::InvalidSecretKey)
+}
+
+fn public_key_from(bytes: &[u8]) -> Result<PublicKey, BbsError> {
+    let bytes = bytes.try_into().map_err(|_| BbsError::InvalidPublicKey)?;
+    PublicKey::from_bytes(bytes).map_err(|_| BbsError::InvalidPublicKey)
+}
+
+fn signature_from(bytes: &[u8]) -> Result<Signature, BbsError> {
+    Signature::from_bytes(bytes).map_err(|_| BbsError::InvalidSignature)
+}
+
+fn sign<'a, X>(secret_key: &[u8], header: &[u8], messages: &[&[u8]]) -> Result<[u8; 112], BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let sk = secret_key_from(secret_key)?;
+    let messages = message_scalars::<X>(messages)?;
+    let signature = Signature::sign::<X>(&messages, &sk, non_empty(header))?;
+    Ok(signature.to_bytes())
+}
+
+fn verify<'a, X>(public_key: &[u8], signature: &[u8], header: &[u8], messages: &[&[u8]]) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let signature = signature_from(signature)?;
+    let messages = message_scalars::<X>(messages)?;
+    if !signature.verify::<X>(&pk, &messages, non_empty(header)) {
+        return Err(BbsError::VerificationFailed);
+    }
+    Ok(())
+}
+
+fn proof_gen<'a, X>(
+    public_key: &[u8],
+    signature: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    messages: &[&[u8]],
+    disclosed_indexes: &[usize],
+) -> Result<Vec<u8>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let signature = signature_from(signature)?;
+    let messages = message_scalars::<X>(messages)?;
+    let proof = Proof::generate::<X>(&signature, &pk, non_empty(header), non_empty(ph), &messages, disclosed_indexes)?;
+    Ok(proof.to_bytes())
+}
+
+fn proof_verify<'a, X>(
+    public_key: &[u8],
+    proof: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    disclosed_messages: &[&[u8]],
+    disclosed_indexes: &[usize],
+) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key_from(public_key)?;
+    let proof = Proof::from_bytes(proof).map_err(|e| match e {
+        crate::BbsError::TooManyMessages(_) => BbsError::TooManyMessages,
+        _ => BbsError::InvalidProof,
+    })?;
+    // The map would silently drop repeated indexes and sort the rest, pairing the messages with
+    // other indexes than the caller gave them
+    if disclosed_indexes.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+    let messages = message_scalars::<X>(disclosed_messages)?;
+    let disclosed: BTreeMap<usize, Scalar> = disclosed_indexes.iter().copied().zip(messages).collect();
+    if !proof.verify::<X>(&pk, non_empty(header), non_empty(ph), &disclosed) {
+        return Err(BbsError::VerificationFailed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_pair::KeyPair;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+    const SUITES: [u32; 2] = [BBS_CIPHERSUITE_BLS12381_SHA256, BBS_CIPHERSUITE_BLS12381_SHAKE256];
+
+    fn slice(bytes: &[u8]) -> ByteSlice {
+        ByteSlice { data: bytes.as_ptr(), len: bytes.len() }
+    }
+
+    fn null_slice(len: usize) -> ByteSlice {
+        ByteSlice { data: ptr::null(), len }
+    }
+
+    fn messages() -> Vec<&'static [u8]> {
+        ["message 1", "message 2", "", "message 4"].iter().map(|m| m.as_bytes()).collect()
+    }
+
+    fn keys() -> ([u8; 32], [u8; 96]) {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        (key_pair.secret_key.to_bytes(), key_pair.public_key.to_bytes())
+    }
+
+    unsafe fn sign_messages(suite: u32, sk: &[u8], header: &[u8], messages: &[&[u8]]) -> (BbsError, [u8; 112]) {
+        let slices: Vec<ByteSlice> = messages.iter().map(|m| slice(m)).collect();
+        let mut signature = [0u8; 112];
+        let code = bbs_sign(suite, slice(sk), slice(header), slices.as_ptr(), slices.len(), signature.as_mut_ptr());
+        (code, signature)
+    }
+
+    unsafe fn verify_messages(suite: u32, pk: &[u8], signature: &[u8], header: &[u8], messages: &[&[u8]]) -> BbsError {
+        let slices: Vec<ByteSlice> = messages.iter().map(|m| slice(m)).collect();
+        bbs_verify(suite, slice(pk), slice(signature), slice(header), slices.as_ptr(), slices.len())
+    }
+
+    unsafe fn generate_proof(suite: u32, pk: &[u8], signature: &[u8], messages: &[&[u8]], disclosed: &[usize]) -> (BbsError, Vec<u8>) {
+        let slices: Vec<ByteSlice> = messages.iter().map(|m| slice(m)).collect();
+        let mut buffer = ByteBuffer { data: ptr::null_mut(), len: 0 };
+        let code = bbs_proof_gen(
+            suite,
+            slice(pk),
+            slice(signature),
+            slice(b"header"),
+            slice(b"presentation header"),
+            slices.as_ptr(),
+            slices.len(),
+            disclosed.as_ptr(),
+            disclosed.len(),
+            &mut buffer,
+        );
+        let proof = if buffer.data.is_null() { Vec::new() } else { slice::from_raw_parts(buffer.data, buffer.len).to_vec() };
+        bbs_byte_buffer_free(buffer);
+        (code, proof)
+    }
+
+    unsafe fn verify_proof(suite: u32, pk: &[u8], proof: &[u8], disclosed_messages: &[&[u8]], disclosed: &[usize]) -> BbsError {
+        let slices: Vec<ByteSlice> = disclosed_messages.iter().map(|m| slice(m)).collect();
+        bbs_proof_verify(
+            suite,
+            slice(pk),
+            slice(proof),
+            slice(b"header"),
+            slice(b"presentation header"),
+            slices.as_ptr(),
+            disclosed.as_ptr(),
+            disclosed.len(),
+        )
+    }
+
+    #[test]
+    fn sign_verify_round_trip() {
+        let (sk, pk) = keys();
+        let messages = messages();
+        for suite in SUITES {
+            unsafe {
+                let (code, signature) = sign_messages(suite, &sk, b"header", &messages);
+                assert_eq!(code, BbsError::Ok);
+                assert_eq!(verify_messages(suite, &pk, &signature, b"header", &messages), BbsError::Ok);
+
+                let mut tampered = messages.clone();
+                tampered[1] = "another message".as_bytes();
+                assert_eq!(verify_messages(suite, &pk, &signature, b"header", &tampered), BbsError::VerificationFailed);
+                assert_eq!(verify_messages(suite, &pk, &signature, b"other header", &messages), BbsError::VerificationFailed);
+            }
+        }
+    }
+
+    #[test]
+    fn signature_of_one_suite_fails_under_the_other() {
+        let (sk, pk) = keys();
+        let messages = messages();
+        unsafe {
+            let (_, signature) = sign_messages(BBS_CIPHERSUITE_BLS12381_SHA256, &sk, b"", &messages);
+            assert_eq!(
+                verify_messages(BBS_CIPHERSUITE_BLS12381_SHAKE256, &pk, &signature, b"", &messages),
+                BbsError::VerificationFailed
+            );
+        }
+    }
+
+    #[test]
+    fn proof_gen_verify_round_trip() {
+        let (sk, pk) = keys();
+        let messages = messages();
+        for suite in SUITES {
+            unsafe {
+                let (_, signature) = sign_messages(suite, &sk, b"header", &messages);
+                let (code, proof) = generate_proof(suite, &pk, &signature, &messages, &[0, 2]);
+                assert_eq!(code, BbsError::Ok);
+
+                let disclosed_messages = [messages[0], messages[2]];
+                assert_eq!(verify_proof(suite, &pk, &proof, &disclosed_messages, &[0, 2]), BbsError::Ok);
+                assert_eq!(verify_proof(suite, &pk, &proof, &disclosed_messages, &[0, 3]), BbsError::VerificationFailed);
+                assert_eq!(verify_proof(suite, &pk, &proof, &[messages[1], messages[2]], &[0, 2]), BbsError::VerificationFailed);
+                assert_eq!(
+                    verify_proof(suite, &pk, &proof, &disclosed_messages, &[2, 0]),
+                    BbsError::InvalidDisclosedIndexes
+                );
+                assert_eq!(verify_proof(suite, &pk, &proof[1..], &disclosed_messages, &[0, 2]), BbsError::InvalidProof);
+            }
+        }
+    }
+
+    #[test]
+    fn proof_gen_rejects_out_of_range_indexes() {
+        let (sk, pk) = keys();
+        let messages = messages();
+        unsafe {
+            let (_, signature) = sign_messages(BBS_CIPHERSUITE_BLS12381_SHA256, &sk, b"header", &messages);
+            let (code, proof) = generate_proof(BBS_CIPHERSUITE_BLS12381_SHA256, &pk, &signature, &messages, &[4]);
+            assert_eq!(code, BbsError::InvalidDisclosedIndexes);
+            assert!(proof.is_empty());
+        }
+    }
+
+    #[test]
+    fn unknown_suite_is_invalid_argument() {
+        let (sk, pk) = keys();
+        let messages = messages();
+        unsafe {
+            let (_, signature) = sign_messages(BBS_CIPHERSUITE_BLS12381_SHA256, &sk, b"", &messages);
+            assert_eq!(sign_messages(2, &sk, b"", &messages).0, BbsError::InvalidArgument);
+            assert_eq!(verify_messages(u32::MAX, &pk, &signature, b"", &messages), BbsError::InvalidArgument);
+            assert_eq!(generate_proof(2, &pk, &signature, &messages, &[0]).0, BbsError::InvalidArgument);
+            assert_eq!(verify_proof(2, &pk, &[0; 272], &[messages[0]], &[0]), BbsError::InvalidArgument);
+        }
+    }
+
+    #[test]
+    fn null_pointers() {
+        let (sk, pk) = keys();
+        let messages = messages();
+        let suite = BBS_CIPHERSUITE_BLS12381_SHA256;
+        unsafe {
+            // A null pointer with len 0 is an empty input
+            let mut signature = [0u8; 112];
+            let code = bbs_sign(suite, slice(&sk), null_slice(0), ptr::null(), 0, signature.as_mut_ptr());
+            assert_eq!(code, BbsError::Ok);
+            assert_eq!(bbs_verify(suite, slice(&pk), slice(&signature), null_slice(0), ptr::null(), 0), BbsError::Ok);
+
+            let code = bbs_sign(suite, slice(&sk), null_slice(4), ptr::null(), 0, signature.as_mut_ptr());
+            assert_eq!(code, BbsError::InvalidArgument);
+            let code = bbs_sign(suite, slice(&sk), null_slice(0), ptr::null(), 2, signature.as_mut_ptr());
+            assert_eq!(code, BbsError::InvalidArgument);
+            let code = bbs_sign(suite, slice(&sk), null_slice(0), ptr::null(), 0, ptr::null_mut());
+            assert_eq!(code, BbsError::InvalidArgument);
+
+            let slices = [slice(messages[0]), null_slice(1)];
+            let code = bbs_verify(suite, slice(&pk), slice(&signature), null_slice(0), slices.as_ptr(), slices.len());
+            assert_eq!(code, BbsError::InvalidArgument);
+
+            let code = bbs_proof_gen(
+                suite,
+                slice(&pk),
+                slice(&signature),
+                null_slice(0),
+                null_slice(0),
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+                ptr::null_mut(),
+            );
+            assert_eq!(code, BbsError::InvalidArgument);
+
+            let code = bbs_proof_verify(suite, slice(&pk), null_slice(0), null_slice(0), null_slice(0), ptr::null(), ptr::null(), 1);
+            assert_eq!(code, BbsError::InvalidArgument);
+
+            bbs_byte_buffer_free(ByteBuffer { data: ptr::null_mut(), len: 0 });
+        }
+    }
+
+    #[test]
+    fn malformed_keys_and_signatures() {
+        let (sk, pk) = keys();
+        let messages = messages();
+        let suite = BBS_CIPHERSUITE_BLS12381_SHA256;
+        unsafe {
+            assert_eq!(sign_messages(suite, &sk[1..], b"", &messages).0, BbsError::InvalidSecretKey);
+            assert_eq!(sign_messages(suite, &[0; 32], b"", &messages).0, BbsError::InvalidSecretKey);
+
+            let (_, signature) = sign_messages(suite, &sk, b"", &messages);
+            assert_eq!(verify_messages(suite, &pk[1..], &signature, b"", &messages), BbsError::InvalidPublicKey);
+            assert_eq!(verify_messages(suite, &[0; 96], &signature, b"", &messages), BbsError::InvalidPublicKey);
+            assert_eq!(verify_messages(suite, &pk, &signature[1..], b"", &messages), BbsError::InvalidSignature);
+            assert_eq!(generate_proof(suite, &pk, &[0; 112], &messages, &[0]).0, BbsError::InvalidSignature);
+        }
+    }
+
+    #[test]
+    fn error_codes() {
+        assert_eq!(BbsError::from(crate::BbsError::InvalidLength("")), BbsError::InvalidLength);
+        assert_eq!(BbsError::from(crate::BbsError::InvalidPoint), BbsError::InvalidPoint);
+        assert_eq!(BbsError::from(crate::BbsError::InvalidScalar), BbsError::InvalidScalar);
+        assert_eq!(BbsError::from(crate::BbsError::InvalidDisclosedIndexes), BbsError::InvalidDisclosedIndexes);
+        assert_eq!(BbsError::from(crate::BbsError::InvalidCommitment), BbsError::InvalidCommitment);
+        assert_eq!(BbsError::from(crate::BbsError::SigningFailed), BbsError::SigningFailed);
+        assert_eq!(BbsError::from(crate::BbsError::ProofGenerationFailed), BbsError::ProofGenerationFailed);
+        assert_eq!(BbsError::from(crate::BbsError::TooManyMessages(0)), BbsError::TooManyMessages);
+        assert_eq!(BbsError::from(crate::BbsError::CiphersuiteMismatch), BbsError::CiphersuiteMismatch);
+        assert_eq!(BbsError::from(crate::BbsError::InvalidGenerator(String::new())), BbsError::InvalidGenerator);
+        assert_eq!(BbsError::from(crate::BbsError::Serialization(String::new())), BbsError::Serialization);
+        assert_eq!(BbsError::from(crate::BbsError::InvalidKeyId(String::new())), BbsError::InvalidKeyId);
+        assert_eq!(BbsError::from(crate::BbsError::InvalidLabel(String::new())), BbsError::InvalidLabel);
+
+        // The codes are part of the C API, see include/bbs.h
+        assert_eq!(BbsError::Ok as u32, 0);
+        assert_eq!(BbsError::VerificationFailed as u32, 9);
+        assert_eq!(BbsError::InvalidLength as u32, 10);
+        assert_eq!(BbsError::InvalidKeyId as u32, 21);
+        assert_eq!(BbsError::Panic as u32, 22);
+        assert_eq!(BbsError::InvalidLabel as u32, 23);
+    }
+
+    #[test]
+    fn panics_are_returned_as_a_code() {
+        assert_eq!(guard(|| panic!("bug")), BbsError::Panic);
+        assert_eq!(guard(|| BbsError::Ok), BbsError::Ok);
+    }
+}
@@ -0,0 +1,284 @@
+// The G2 variant of the scheme, the roles of the groups are swapped: the public key is a G1 point
+// while the generators and the signature's A are G2 points, trading larger signatures for smaller keys.
+// Only Sign and Verify are defined for the variant, there is no ProofGen or ProofVerify: the proofs of
+// proof.rs are over G1 generators, and the G2 suites are not BbsCiphersuite so they do not type check
+// with proof.rs or the other G1 operations
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::ciphersuites::G2Ciphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1, G2};
+use crate::encoding::{encode_for_hash, i2osp, nonzero_scalar_from_octets, scalar_to_bytes, SpecSerialize};
+use crate::error::BbsError;
+use crate::hashing::{expand_message, hash_to_scalar};
+use crate::key_pair::SecretKey;
+use crate::limits::{check_message_count, Limits};
+
+// seed_len as in generators.rs, the seed expansion does not depend on the group
+const SEED_LEN: usize = 48;
+
+pub struct Generators {
+    pub g2_base_point: G2,
+    pub message_generators: Vec<G2>,
+}
+
+pub struct PublicKey(pub(crate) G1);
+
+pub struct Signature {
+    pub(crate) a: G2,
+    pub(crate) e: Scalar,
+    pub(crate) s: Scalar,
+}
+
+pub fn make_generators<'a, X>(seed: Option<&[u8]>, len: usize) -> Generators
+where
+    X: G2Ciphersuite<'a>
+{
+    let default_seed = &X::generator_seed();
+    let seed = seed.unwrap_or(default_seed);
+
+    Generators {
+        g2_base_point: create_generators::<X>(&X::bp_generator_seed(), 1)[0],
+        message_generators: create_generators::<X>(seed, len),
+    }
+}
+
+// create_generators with hash_to_curve_g2, identity and repeated points are skipped by re-expanding v
+pub fn create_generators<'a, X>(generator_seed: &[u8], count: usize) -> Vec<G2>
+where
+    X: G2Ciphersuite<'a>
+{
+    let seed_dst = X::generator_seed_dst();
+    let generator_dst = X::generator_dst();
+
+    let mut v = expand_message::<X>(generator_seed, &seed_dst, SEED_LEN);
+    let mut n = 1;
+    let mut seen = BTreeSet::new();
+    let mut generators = Vec::with_capacity(count);
+    while generators.len() < count {
+        v = expand_message::<X>(&[v.as_slice(), &i2osp(n, 4)].concat(), &seed_dst, SEED_LEN);
+        n += 1;
+
+        let candidate = Backend::hash_to_g2::<X::Expander>(&v, &generator_dst);
+        if Backend::g2_is_identity(&candidate) || !seen.insert(Backend::g2_to_bytes(&candidate)) {
+            continue;
+        }
+        generators.push(candidate);
+    }
+    generators
+}
+
+impl PublicKey {
+    // SkToPk with the G1 base point
+    pub fn from_secret_key(sk: &SecretKey) -> Self {
+        PublicKey(Backend::g1_generator() * sk.0)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 48] {
+        Backend::g1_to_bytes(&self.0)
+    }
+
+    // from_compressed performs the subgroup check
+    pub fn from_bytes(bytes: &[u8; 48]) -> Result<Self, BbsError> {
+        let w = Backend::g1_from_bytes(bytes).ok_or(BbsError::InvalidPoint)?;
+        if Backend::g1_is_identity(&w) {
+            return Err(BbsError::InvalidPoint);
+        }
+        Ok(PublicKey(w))
+    }
+}
+
+// A public key is hashed as it is, without a length prefix
+impl SpecSerialize for PublicKey {
+    fn serialize_into(&self, octets: &mut Vec<u8>) {
+        octets.extend_from_slice(&self.to_bytes());
+    }
+}
+
+impl Signature {
+    pub fn sign<'a, X>(messages: &[Scalar], sk: &SecretKey, header: Option<&[u8]>) -> Result<Self, BbsError>
+    where
+        X: G2Ciphersuite<'a>
+    {
+        check_message_count(messages.len())?;
+        let pk = PublicKey::from_secret_key(sk);
+        let generators = make_generators::<X>(None, messages.len() + 2);
+        let domain = calculate_domain::<X>(&pk, &generators.message_generators, header);
+
+        let e_s_for_hash = encode_for_hash(&[&sk.0, &domain, &messages]);
+        let scalars = hash_to_scalar::<X>(&e_s_for_hash, 2, None);
+        let (e, s) = (scalars[0], scalars[1]);
+
+        let b = calculate_b(&generators, s, domain, messages);
+
+        // (SK + e) = 0 mod r would produce A = Identity_G2
+        let sk_e_inv: Scalar = Option::from((sk.0 + e).invert()).ok_or(BbsError::SigningFailed)?;
+
+        Ok(Signature { a: b * sk_e_inv, e, s })
+    }
+
+    // A || e || s, with A a compressed G2 point
+    pub fn to_bytes(&self) -> [u8; 160] {
+        let mut bytes = [0u8; 160];
+        bytes[..96].copy_from_slice(&Backend::g2_to_bytes(&self.a));
+        bytes[96..128].copy_from_slice(&scalar_to_bytes(&self.e));
+        bytes[128..].copy_from_slice(&scalar_to_bytes(&self.s));
+        bytes
+    }
+
+    // Strict decoding of to_bytes, rejects Identity_G2, points outside the subgroup and 0 scalars
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        if bytes.len() != 160 {
+            return Err(BbsError::InvalidLength("a G2 variant signature is 160 octets"));
+        }
+
+        let mut a_bytes = [0u8; 96];
+        a_bytes.copy_from_slice(&bytes[..96]);
+        let a = Backend::g2_from_bytes(&a_bytes).ok_or(BbsError::InvalidPoint)?;
+        if Backend::g2_is_identity(&a) {
+            return Err(BbsError::InvalidPoint);
+        }
+
+        Ok(Signature {
+            a,
+            e: nonzero_scalar_from_octets(&bytes[96..128])?,
+            s: nonzero_scalar_from_octets(&bytes[128..])?,
+        })
+    }
+
+    pub fn verify<'a, X>(&self, pk: &PublicKey, messages: &[Scalar], header: Option<&[u8]>) -> bool
+    where
+        X: G2Ciphersuite<'a>
+    {
+        if Backend::g2_is_identity(&self.a) || !Limits::DEFAULT.allows(messages.len()) {
+            return false;
+        }
+
+        let generators = make_generators::<X>(None, messages.len() + 2);
+        let domain = calculate_domain::<X>(pk, &generators.message_generators, header);
+        let b = calculate_b(&generators, self.s, domain, messages);
+
+        // e(W + P * e, A) == e(P, B), as e(W + P * e, A) * e(-P, B) == 1
+        let w_e = pk.0 + Backend::g1_generator() * self.e;
+        Backend::pairing_product_is_identity(&[(w_e, self.a), (-Backend::g1_generator(), b)])
+    }
+}
+
+// B = P + Q_1 * s + Q_2 * domain + H_1 * msg_1 + ... + H_L * msg_L, in G2
+fn calculate_b(generators: &Generators, s: Scalar, domain: Scalar, messages: &[Scalar]) -> G2 {
+    let q = &generators.message_generators;
+
+    let mut b = generators.g2_base_point + q[0] * s + q[1] * domain;
+    for (h, msg) in q[2..].iter().zip(messages) {
+        b += h * msg;
+    }
+    b
+}
+
+// As signature::calculate_domain, with the 48 octet PK and 96 octet generators
+fn calculate_domain<'a, X>(pk: &PublicKey, generators: &[G2], header: Option<&[u8]>) -> Scalar
+where
+    X: G2Ciphersuite<'a>
+{
+    let header = header.unwrap_or(&[]);
+    let l = generators.len() - 2;
+
+    let dom_for_hash = encode_for_hash(&[pk, &l, &generators, &X::ID, &header]);
+
+    hash_to_scalar::<X>(&dom_for_hash, 1, None)[0]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381G2Sha256, Bls12381G2Shake256};
+    use crate::key_pair::KeyPair;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    fn sign_and_verify<'a, X>()
+    where
+        X: G2Ciphersuite<'a>
+    {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let pk = PublicKey::from_secret_key(&key_pair.secret_key);
+        let messages: Vec<Scalar> = (1..=5u64).map(Scalar::from).collect();
+
+        let signature = Signature::sign::<X>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+        assert!(signature.verify::<X>(&pk, &messages, Some(b"header")));
+
+        let decoded = Signature::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(decoded.verify::<X>(&pk, &messages, Some(b"header")));
+        assert_eq!(decoded.to_bytes(), signature.to_bytes());
+
+        let mut tampered = messages.clone();
+        tampered[2] += Scalar::from(1u64);
+        assert!(!signature.verify::<X>(&pk, &tampered, Some(b"header")));
+        assert!(!signature.verify::<X>(&pk, &messages, Some(b"other header")));
+        assert!(!signature.verify::<X>(&pk, &messages[..4], Some(b"header")));
+
+        let other = KeyPair::from_ikm(b"this-IS-just-an-Test-IKM-to-generate-another-key", None).unwrap();
+        assert!(!signature.verify::<X>(&PublicKey::from_secret_key(&other.secret_key), &messages, Some(b"header")));
+    }
+
+    #[test]
+    fn sign_verify() {
+        sign_and_verify::<Bls12381G2Sha256>();
+        sign_and_verify::<Bls12381G2Shake256>();
+    }
+
+    #[test]
+    fn suites_have_distinct_generators() {
+        let sha256 = make_generators::<Bls12381G2Sha256>(None, 3);
+        let shake256 = make_generators::<Bls12381G2Shake256>(None, 3);
+        assert_ne!(sha256.g2_base_point, shake256.g2_base_point);
+        assert_ne!(sha256.message_generators, shake256.message_generators);
+    }
+
+    #[test]
+    fn signature_of_one_suite_fails_under_the_other() {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let pk = PublicKey::from_secret_key(&key_pair.secret_key);
+        let messages = [Scalar::from(7u64)];
+
+        let signature = Signature::sign::<Bls12381G2Sha256>(&messages, &key_pair.secret_key, None).unwrap();
+        assert!(!signature.verify::<Bls12381G2Shake256>(&pk, &messages, None));
+    }
+
+    #[test]
+    fn public_key_round_trip() {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let pk = PublicKey::from_secret_key(&key_pair.secret_key);
+        assert_eq!(PublicKey::from_bytes(&pk.to_bytes()).unwrap().to_bytes(), pk.to_bytes());
+
+        let identity = Backend::g1_to_bytes(&Backend::g1_identity());
+        assert!(matches!(PublicKey::from_bytes(&identity), Err(BbsError::InvalidPoint)));
+    }
+
+    #[test]
+    fn from_bytes_rejects_malformed_signatures() {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let signature = Signature::sign::<Bls12381G2Sha256>(&[Scalar::from(1u64)], &key_pair.secret_key, None).unwrap();
+        let bytes = signature.to_bytes();
+
+        assert!(matches!(Signature::from_bytes(&bytes[..159]), Err(BbsError::InvalidLength(_))));
+        assert!(matches!(Signature::from_bytes(&[bytes.as_slice(), &[0]].concat()), Err(BbsError::InvalidLength(_))));
+
+        let mut identity_a = bytes;
+        identity_a[..96].copy_from_slice(&Backend::g2_to_bytes(&Backend::g2_identity()));
+        assert!(matches!(Signature::from_bytes(&identity_a), Err(BbsError::InvalidPoint)));
+
+        let mut not_a_point = bytes;
+        not_a_point[1..96].fill(0xff);
+        assert!(matches!(Signature::from_bytes(&not_a_point), Err(BbsError::InvalidPoint)));
+
+        let mut zero_e = bytes;
+        zero_e[96..128].fill(0);
+        assert!(matches!(Signature::from_bytes(&zero_e), Err(BbsError::InvalidScalar)));
+
+        let mut s_above_r = bytes;
+        s_above_r[128..].fill(0xff);
+        assert!(matches!(Signature::from_bytes(&s_above_r), Err(BbsError::InvalidScalar)));
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, G1};
+use crate::error::BbsError;
+use crate::generators::{GeneratorIter, GeneratorState, Generators};
+
+const MAGIC: &[u8; 8] = b"BBSGENv1";
+
+// Memoizes generators per (ciphersuite, seed), a request for count generators is served from any
+// cached list at least that long since create_generators(seed, n) is a prefix of create_generators(seed, n + 1)
+#[derive(Default)]
+pub struct GeneratorCache {
+    entries: BTreeMap<(Vec<u8>, Vec<u8>), CacheEntry>,
+}
+
+#[derive(Default)]
+struct CacheEntry {
+    generators: Vec<G1>,
+    // Not persisted, entries loaded from a file are extended by creating them again
+    state: Option<GeneratorState>,
+}
+
+impl GeneratorCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // As generators::make_generators, only hashing to the curve on a cache miss
+    pub fn make_generators<'a, X>(&mut self, seed: Option<&[u8]>, len: usize) -> Generators
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let default_seed = &X::generator_seed();
+        let seed = seed.unwrap_or(default_seed);
+
+        let g1_base_point = self.create_generators::<X>(&X::bp_generator_seed(), 1)[0];
+        let message_generators = self.create_generators::<X>(seed, len);
+
+        // The creation state is only valid for the generators it stopped after
+        let entry = &self.entries[&(X::ID.to_vec(), seed.to_vec())];
+        let state = if entry.generators.len() == len { entry.state.clone() } else { None };
+
+        Generators {
+            g1_base_point,
+            message_generators,
+            seed: seed.to_vec(),
+            ciphersuite_id: X::ID.to_vec(),
+            state,
+        }
+    }
+
+    pub fn create_generators<'a, X>(&mut self, generator_seed: &[u8], count: usize) -> Vec<G1>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let entry = self.entries
+            .entry((X::ID.to_vec(), generator_seed.to_vec()))
+            .or_insert_with(CacheEntry::default);
+
+        let have = entry.generators.len();
+        if have < count {
+            let mut iter = match entry.state.take() {
+                Some(state) => GeneratorIter::<X>::resume(state),
+                None => {
+                    entry.generators.clear();
+                    GeneratorIter::<X>::new(generator_seed)
+                }
+            };
+            let missing = count - entry.generators.len();
+            entry.generators.extend(iter.by_ref().take(missing));
+            entry.state = Some(iter.into_state());
+        }
+        entry.generators[..count].to_vec()
+    }
+
+    // Layout: MAGIC || I2OSP(entries, 4) || per entry
+    // I2OSP(len(id), 2) || id || I2OSP(len(seed), 2) || seed || I2OSP(count, 4) || compressed points
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BbsError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(self.entries.len() as u32).to_be_bytes())?;
+        for ((id, seed), entry) in &self.entries {
+            writer.write_all(&(id.len() as u16).to_be_bytes())?;
+            writer.write_all(id)?;
+            writer.write_all(&(seed.len() as u16).to_be_bytes())?;
+            writer.write_all(seed)?;
+            writer.write_all(&(entry.generators.len() as u32).to_be_bytes())?;
+            for g in &entry.generators {
+                writer.write_all(&Backend::g1_to_bytes(g))?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    // Points are decompressed with the subgroup check, a corrupted file fails with InvalidPoint
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BbsError> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(BbsError::Serialization("not a generator cache file".to_string()));
+        }
+
+        let mut entries = BTreeMap::new();
+        for _ in 0..read_u32(&mut reader)? {
+            let id_len = read_u16(&mut reader)?;
+            let id = read_octets(&mut reader, id_len as usize)?;
+            let seed_len = read_u16(&mut reader)?;
+            let seed = read_octets(&mut reader, seed_len as usize)?;
+
+            let count = read_u32(&mut reader)?;
+            let mut generators = Vec::new();
+            for _ in 0..count {
+                let mut compressed = [0u8; 48];
+                reader.read_exact(&mut compressed)?;
+                let point = Backend::g1_from_bytes(&compressed);
+                generators.push(point.ok_or(BbsError::InvalidPoint)?);
+            }
+
+            entries.insert((id, seed), CacheEntry { generators, state: None });
+        }
+
+        Ok(GeneratorCache { entries })
+    }
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> io::Result<u16> {
+    let mut bytes = [0u8; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_be_bytes(bytes))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn read_octets<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
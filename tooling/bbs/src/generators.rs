@@ -0,0 +1,376 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, G1};
+use crate::encoding::i2osp;
+use crate::error::BbsError;
+use crate::hashing::expand_message;
+use crate::precomputed::{FixedBaseTable, PrecomputedGenerators};
+use crate::trace::trace_value;
+
+// seed_len = ceil((ceil(log2(r)) + k) / 8) for BLS12-381 with k = 128
+const SEED_LEN: usize = 48;
+
+pub struct Generators {
+    pub g1_base_point: G1,
+    pub message_generators: Vec<G1>,
+    // The generator_seed the message generators were created from
+    pub seed: Vec<u8>,
+    pub(crate) ciphersuite_id: Vec<u8>,
+    // Where create_generators stopped after the last message generator, None when unknown
+    pub(crate) state: Option<GeneratorState>,
+}
+
+impl Generators {
+    // Grows message_generators to at least count, continuing the creation where it stopped
+    // instead of deriving the existing generators again. CiphersuiteMismatch unless X is the
+    // ciphersuite used to make them
+    pub fn ensure<'a, X>(&mut self, count: usize) -> Result<(), BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if self.ciphersuite_id != X::ID {
+            return Err(BbsError::CiphersuiteMismatch);
+        }
+
+        let have = self.message_generators.len();
+        if count <= have {
+            return Ok(());
+        }
+
+        match self.state.take() {
+            Some(state) => {
+                let mut iter = GeneratorIter::<X>::resume(state);
+                self.message_generators.extend(iter.by_ref().take(count - have));
+                self.state = Some(iter.into_state());
+            }
+            None => {
+                let mut iter = GeneratorIter::<X>::new(&self.seed);
+                self.message_generators = iter.by_ref().take(count).collect();
+                self.state = Some(iter.into_state());
+            }
+        }
+        Ok(())
+    }
+
+    // Checks that P1 and the message generators are in the G1 subgroup, not the identity and distinct,
+    // as create_generators makes them. For generators received from elsewhere, e.g. another
+    // implementation, see validate_generators
+    pub fn validate(&self) -> Result<(), BbsError> {
+        validate_generators(Some(&self.g1_base_point), &self.message_generators)
+    }
+
+    // Builds fixed base tables for the first count message generators, Q_1 and Q_2 included, for
+    // Signature::sign_precomputed. Worth it for an issuer that signs many times, see src/precomputed.rs
+    pub fn precompute(self, count: usize) -> PrecomputedGenerators {
+        let count = count.min(self.message_generators.len());
+        let tables = self.message_generators[..count].iter().map(FixedBaseTable::new).collect();
+        PrecomputedGenerators { generators: self, tables }
+    }
+
+    // As ensure, with the new message generators hashed to the curve in parallel
+    #[cfg(feature = "rayon")]
+    pub fn ensure_parallel<'a, X>(&mut self, count: usize) -> Result<(), BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if self.ciphersuite_id != X::ID {
+            return Err(BbsError::CiphersuiteMismatch);
+        }
+
+        let have = self.message_generators.len();
+        if count <= have {
+            return Ok(());
+        }
+
+        match self.state.take() {
+            Some(state) => {
+                let mut iter = GeneratorIter::<X>::resume(state);
+                self.message_generators.extend(take_parallel(&mut iter, count - have));
+                self.state = Some(iter.into_state());
+            }
+            None => {
+                let mut iter = GeneratorIter::<X>::new(&self.seed);
+                self.message_generators = take_parallel(&mut iter, count);
+                self.state = Some(iter.into_state());
+            }
+        }
+        Ok(())
+    }
+}
+
+// The checks of Generators::validate over points decoded by the caller. message_generators[i] is
+// named G_(i + 1) in the error, which names the first invalid point
+pub fn validate_generators(g1_base_point: Option<&G1>, message_generators: &[G1]) -> Result<(), BbsError> {
+    let base_point = g1_base_point.map(|p| (String::from("P1"), p));
+    let named = message_generators.iter().enumerate().map(|(i, p)| (format!("G_{}", i + 1), p));
+
+    let mut seen: BTreeMap<[u8; 48], String> = BTreeMap::new();
+    for (name, p) in base_point.into_iter().chain(named) {
+        if Backend::g1_is_identity(p) {
+            return Err(BbsError::InvalidGenerator(format!("{} is the identity", name)));
+        }
+        if !Backend::g1_is_valid(p) {
+            return Err(BbsError::InvalidGenerator(format!("{} is not in the G1 subgroup", name)));
+        }
+        if let Some(first) = seen.get(&Backend::g1_to_bytes(p)) {
+            return Err(BbsError::InvalidGenerator(format!("{} repeats {}", name, first)));
+        }
+        seen.insert(Backend::g1_to_bytes(p), name);
+    }
+    Ok(())
+}
+
+pub fn make_generators<'a, X>(seed: Option<&[u8]>, len: usize) -> Generators
+where
+    X: BbsCiphersuite<'a>
+{
+
+    let default_seed = &X::generator_seed();
+    let seed = seed.unwrap_or(default_seed);
+
+    let mut iter = GeneratorIter::<X>::new(seed);
+    let message_generators = iter.by_ref().take(len).collect();
+
+    Generators {
+        g1_base_point: make_g1_base_point::<X>(),
+        message_generators,
+        seed: seed.to_vec(),
+        ciphersuite_id: X::ID.to_vec(),
+        state: Some(iter.into_state()),
+    }
+}
+
+// As make_generators, with the message generators hashed to the curve in parallel
+#[cfg(feature = "rayon")]
+pub fn make_generators_parallel<'a, X>(seed: Option<&[u8]>, len: usize) -> Generators
+where
+    X: BbsCiphersuite<'a>
+{
+    let default_seed = &X::generator_seed();
+    let seed = seed.unwrap_or(default_seed);
+
+    let mut iter = GeneratorIter::<X>::new(seed);
+    let message_generators = take_parallel(&mut iter, len);
+
+    Generators {
+        g1_base_point: make_g1_base_point::<X>(),
+        message_generators,
+        seed: seed.to_vec(),
+        ciphersuite_id: X::ID.to_vec(),
+        state: Some(iter.into_state()),
+    }
+}
+
+// P1, the first generator created from the ciphersuite's base point seed
+pub fn make_g1_base_point<'a, X>() -> G1
+where
+    X: BbsCiphersuite<'a>
+{
+    create_generators::<X>(&X::bp_generator_seed(), 1)[0]
+}
+
+pub fn create_generators<'a, X>(generator_seed: &[u8], count: usize) -> Vec<G1>
+where
+    X: BbsCiphersuite<'a>
+{
+    GeneratorIter::<X>::new(generator_seed).take(count).collect()
+}
+
+// Same output as create_generators, the v values are expanded sequentially while the
+// hash to curve calls run across the rayon thread pool
+#[cfg(feature = "rayon")]
+pub fn create_generators_parallel<'a, X>(generator_seed: &[u8], count: usize) -> Vec<G1>
+where
+    X: BbsCiphersuite<'a>
+{
+    take_parallel(&mut GeneratorIter::<X>::new(generator_seed), count)
+}
+
+#[cfg(feature = "rayon")]
+fn take_parallel<'a, X>(iter: &mut GeneratorIter<'a, X>, count: usize) -> Vec<G1>
+where
+    X: BbsCiphersuite<'a>
+{
+    let mut generators = Vec::with_capacity(count);
+
+    // Rejected candidates are rare, each round derives the generators still missing
+    while generators.len() < count {
+        let vs: Vec<Vec<u8>> = (generators.len()..count).map(|_| iter.next_v()).collect();
+        let generator_dst = &iter.generator_dst;
+        let candidates: Vec<G1> = vs
+            .par_iter()
+            .map(|v| Backend::hash_to_g1::<X::Expander>(v, generator_dst))
+            .collect();
+        generators.extend(candidates.into_iter().filter(|candidate| iter.state.accept(candidate)));
+    }
+    generators
+}
+
+// The variables of the create_generators loop between two generators
+#[derive(Clone)]
+pub(crate) struct GeneratorState {
+    v: Vec<u8>,
+    n: u64,
+    // Compressed encodings of the generators returned so far, to reject repeated points. None when
+    // streaming, see GeneratorIter::streaming
+    seen: Option<BTreeSet<[u8; 48]>>,
+}
+
+impl GeneratorState {
+    // Identity and repeated points are rejected, the caller then continues with the next v
+    fn accept(&mut self, candidate: &G1) -> bool {
+        if Backend::g1_is_identity(candidate) {
+            return false;
+        }
+        match &mut self.seen {
+            Some(seen) => seen.insert(Backend::g1_to_bytes(candidate)),
+            None => true,
+        }
+    }
+}
+
+// The create_generators procedure as an unbounded iterator, generators are derived one at a time
+pub struct GeneratorIter<'a, X> {
+    seed_dst: Vec<u8>,
+    generator_dst: Vec<u8>,
+    state: GeneratorState,
+    _suite: PhantomData<(&'a (), X)>,
+}
+
+impl<'a, X> GeneratorIter<'a, X>
+where
+    X: BbsCiphersuite<'a>
+{
+    pub fn new(generator_seed: &[u8]) -> Self {
+        trace_value!(trace, "generator_seed", generator_seed);
+        let seed_dst = X::generator_seed_dst();
+        let v = expand_message::<X>(generator_seed, &seed_dst, SEED_LEN);
+
+        Self::resume_with(seed_dst, GeneratorState { v, n: 1, seen: Some(BTreeSet::new()) })
+    }
+
+    // As new without the history of the points returned so far, so memory stays flat however many
+    // generators are taken. Only the repeat check of create_generators needs the history, and a repeat
+    // among the hash_to_curve outputs of distinct v values has probability about n^2 / r, below 2^-200
+    // for 2^24 generators, so the output is that of create_generators. Identity points are still
+    // rejected
+    pub fn streaming(generator_seed: &[u8]) -> Self {
+        let mut iter = Self::new(generator_seed);
+        iter.state.seen = None;
+        iter
+    }
+
+    pub(crate) fn resume(state: GeneratorState) -> Self {
+        Self::resume_with(X::generator_seed_dst(), state)
+    }
+
+    fn resume_with(seed_dst: Vec<u8>, state: GeneratorState) -> Self {
+        GeneratorIter {
+            seed_dst,
+            generator_dst: X::generator_dst(),
+            state,
+            _suite: PhantomData,
+        }
+    }
+
+    pub(crate) fn into_state(self) -> GeneratorState {
+        self.state
+    }
+
+    // v = expand_message(v || I2OSP(n, 4), seed_dst, seed_len)
+    fn next_v(&mut self) -> Vec<u8> {
+        let state = &mut self.state;
+        state.v = expand_message::<X>(&[state.v.as_slice(), &i2osp(state.n, 4)].concat(), &self.seed_dst, SEED_LEN);
+        state.n += 1;
+        state.v.clone()
+    }
+}
+
+impl<'a, X> Iterator for GeneratorIter<'a, X>
+where
+    X: BbsCiphersuite<'a>
+{
+    type Item = G1;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let v = self.next_v();
+            let candidate = Backend::hash_to_g1::<X::Expander>(&v, &self.generator_dst);
+            if self.state.accept(&candidate) {
+                trace_value!(trace, "generator", candidate);
+                return Some(candidate);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+
+    fn streams_the_generators_of_make_generators<'a, X>()
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let len = 4096;
+        let generators = make_generators::<X>(None, len);
+        let mut iter = GeneratorIter::<X>::streaming(&X::generator_seed());
+        for (i, g) in iter.by_ref().take(len).enumerate() {
+            assert_eq!(g, generators.message_generators[i], "G_{}", i + 1);
+        }
+        assert!(iter.state.seen.is_none());
+    }
+
+    #[test]
+    fn streaming_matches_make_generators() {
+        streams_the_generators_of_make_generators::<Bls12381Sha256>();
+        streams_the_generators_of_make_generators::<Bls12381Shake256>();
+    }
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    // The quoted values of a generators.json written by message-generators, in order: seed, P1 and
+    // the message generators. The ciphersuite id is the only value that is not hex
+    fn fixture_values(json: &str) -> Vec<Vec<u8>> {
+        json.split('"')
+            .skip(1)
+            .step_by(2)
+            .filter(|s| !s.is_empty() && s.len() % 2 == 0 && s.bytes().all(|b| b.is_ascii_hexdigit()))
+            .map(hex)
+            .collect()
+    }
+
+    fn matches_the_fixture<'a, X>(json: &str)
+    where
+        X: BbsCiphersuite<'a>
+    {
+        assert!(json.contains(core::str::from_utf8(X::ID).unwrap()));
+        let values = fixture_values(json);
+        let (seed, points) = values.split_first().unwrap();
+        let (bp, message_generators) = points.split_first().unwrap();
+        assert_eq!(seed, &X::generator_seed());
+
+        let generators = make_generators::<X>(None, message_generators.len());
+        assert_eq!(&Backend::g1_to_bytes(&generators.g1_base_point)[..], &bp[..], "P1");
+        for (i, expected) in message_generators.iter().enumerate() {
+            assert_eq!(&Backend::g1_to_bytes(&generators.message_generators[i])[..], &expected[..], "G_{}", i + 1);
+        }
+    }
+
+    #[test]
+    fn make_generators_matches_the_fixtures() {
+        matches_the_fixture::<Bls12381Shake256>(include_str!("../../fixtures/bls12-381-shake-256/generators.json"));
+        matches_the_fixture::<Bls12381Sha256>(include_str!("../../fixtures/bls12-381-sha-256/generators.json"));
+    }
+}
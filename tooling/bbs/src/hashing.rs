@@ -0,0 +1,194 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use ff::Field;
+use sha2::digest::generic_array::typenum::Unsigned;
+use sha2::digest::{BlockInput, Digest, ExtendableOutput, Update, XofReader};
+
+use crate::ciphersuites::CiphersuiteParams;
+use crate::curve::Scalar;
+use crate::encoding::{encode_for_hash, i2osp, os2ip};
+use crate::error::BbsError;
+use crate::trace::trace_value;
+
+// expand_len = ceil((ceil(log2(r)) + k) / 8) for BLS12-381 with k = 128
+pub const EXPAND_LEN: usize = 48;
+
+// expand_message of RFC 9380 section 5.3, implemented here rather than taken from the curve backend so
+// the ciphersuites and hashing do not depend on it, see curve.rs. Panics when len_in_bytes is more
+// than the variant allows, 255 hash outputs for expand_message_xmd and 65535 octets
+pub trait ExpandMessage {
+    fn expand_message(msg: &[u8], dst: &[u8], uniform_bytes: &mut [u8]);
+}
+
+// expand_message_xmd with the hash function H, e.g. ExpandMsgXmd<Sha256>
+pub struct ExpandMsgXmd<H>(PhantomData<H>);
+
+// expand_message_xof with the extendable output function H, e.g. ExpandMsgXof<Shake256>
+pub struct ExpandMsgXof<H>(PhantomData<H>);
+
+// A DST over 255 octets is replaced by a hash of it, RFC 9380 section 5.3.3
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+impl<H> ExpandMessage for ExpandMsgXmd<H>
+where
+    H: Digest + BlockInput
+{
+    fn expand_message(msg: &[u8], dst: &[u8], uniform_bytes: &mut [u8]) {
+        let b_in_bytes = <H as Digest>::output_size();
+        let s_in_bytes = H::BlockSize::to_usize();
+        let len_in_bytes = uniform_bytes.len();
+        let ell = (len_in_bytes + b_in_bytes - 1) / b_in_bytes;
+        assert!(ell <= 255 && len_in_bytes <= 65535, "expand_message_xmd len_in_bytes is too large");
+
+        let hashed_dst;
+        let dst = if dst.len() > 255 {
+            hashed_dst = H::new().chain(OVERSIZE_DST_PREFIX).chain(dst).finalize();
+            hashed_dst.as_slice()
+        } else {
+            dst
+        };
+        let dst_prime = [dst, &i2osp(dst.len() as u64, 1)].concat();
+
+        let b_0 = H::new()
+            .chain(vec![0u8; s_in_bytes])
+            .chain(msg)
+            .chain(i2osp(len_in_bytes as u64, 2))
+            .chain([0u8])
+            .chain(&dst_prime)
+            .finalize();
+        let mut b_i = H::new().chain(&b_0).chain([1u8]).chain(&dst_prime).finalize();
+        for (i, chunk) in uniform_bytes.chunks_mut(b_in_bytes).enumerate() {
+            if i > 0 {
+                let xor: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+                b_i = H::new().chain(xor).chain([i as u8 + 1]).chain(&dst_prime).finalize();
+            }
+            chunk.copy_from_slice(&b_i[..chunk.len()]);
+        }
+    }
+}
+
+impl<H> ExpandMessage for ExpandMsgXof<H>
+where
+    H: Default + Update + ExtendableOutput
+{
+    fn expand_message(msg: &[u8], dst: &[u8], uniform_bytes: &mut [u8]) {
+        let len_in_bytes = uniform_bytes.len();
+        assert!(len_in_bytes <= 65535, "expand_message_xof len_in_bytes is too large");
+
+        // ceil(2 * k / 8) octets for k = 128
+        let mut hashed_dst = [0u8; 32];
+        let dst = if dst.len() > 255 {
+            let mut h = H::default();
+            h.update(OVERSIZE_DST_PREFIX);
+            h.update(dst);
+            h.finalize_xof().read(&mut hashed_dst);
+            &hashed_dst[..]
+        } else {
+            dst
+        };
+
+        let mut h = H::default();
+        h.update(msg);
+        h.update(i2osp(len_in_bytes as u64, 2));
+        h.update(dst);
+        h.update(i2osp(dst.len() as u64, 1));
+        h.finalize_xof().read(uniform_bytes);
+    }
+}
+
+// The ciphersuite's expand_message operation, either expand_message_xmd or expand_message_xof
+pub fn expand_message<'a, X>(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8>
+where
+    X: CiphersuiteParams<'a>
+{
+    let mut uniform_bytes = vec![0u8; len_in_bytes];
+    X::Expander::expand_message(msg, dst, &mut uniform_bytes);
+    uniform_bytes
+}
+
+// Hashes msg_octets to count non-zero scalars, dst defaults to ciphersuite_id || "H2S_"
+pub fn hash_to_scalar<'a, X>(msg_octets: &[u8], count: usize, dst: Option<&[u8]>) -> Vec<Scalar>
+where
+    X: CiphersuiteParams<'a>
+{
+    let default_dst = X::hash_to_scalar_dst();
+    let dst = dst.unwrap_or(&default_dst);
+    let len_in_bytes = count * X::EXPAND_LEN;
+
+    let mut t = 0;
+    loop {
+        let msg_prime = [msg_octets, &i2osp(t, 1), &i2osp(count as u64, 4)].concat();
+        let uniform_bytes = expand_message::<X>(&msg_prime, dst, len_in_bytes);
+
+        let scalars: Vec<Scalar> = uniform_bytes.chunks(X::EXPAND_LEN).map(os2ip).collect();
+        if scalars.iter().all(|s| !bool::from(s.is_zero())) {
+            return scalars;
+        }
+        t += 1;
+    }
+}
+
+// MapMessageToScalarAsHash, dst defaults to ciphersuite_id || "MAP_MSG_TO_SCALAR_AS_HASH_"
+pub fn map_message_to_scalar_as_hash<'a, X>(msg: &[u8], dst: Option<&[u8]>) -> Result<Scalar, BbsError>
+where
+    X: CiphersuiteParams<'a>
+{
+    let default_dst = X::map_message_to_scalar_dst();
+    let dst = dst.unwrap_or(&default_dst);
+    if dst.len() > 255 {
+        return Err(BbsError::InvalidLength("dst must be at most 255 octets"));
+    }
+
+    let msg_for_hash = encode_for_hash(&[&msg]);
+    let scalar = hash_to_scalar::<X>(&msg_for_hash, 1, Some(dst))[0];
+    trace_value!(trace, "msg_scalar", scalar);
+    Ok(scalar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Sha256;
+    use sha3::Shake256;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn expand<E: ExpandMessage>(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+        let mut uniform_bytes = vec![0u8; len_in_bytes];
+        E::expand_message(msg, dst, &mut uniform_bytes);
+        uniform_bytes
+    }
+
+    // RFC 9380 appendix K.1
+    #[test]
+    fn expand_message_xmd_sha256_vectors() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            expand::<ExpandMsgXmd<Sha256>>(b"", dst, 0x20),
+            hex("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235")
+        );
+        assert_eq!(
+            expand::<ExpandMsgXmd<Sha256>>(b"abc", dst, 0x80),
+            hex(concat!(
+                "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b6437a",
+                "647e6c3163d40b76a73cf6a5674ef1d890f95b664ee0afa5359a5c4e07985635",
+                "bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583e2cb00",
+                "058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b4c895f40"
+            ))
+        );
+    }
+
+    // RFC 9380 appendix K.6
+    #[test]
+    fn expand_message_xof_shake256_vectors() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHAKE256";
+        assert_eq!(
+            expand::<ExpandMsgXof<Shake256>>(b"", dst, 0x20),
+            hex("2ffc05c48ed32b95d72e807f6eab9f7530dd1c2f013914c8fed38c5ccc15ad76")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+// Hierarchical deterministic derivation of issuer keys from one master seed, e.g. a signing key per
+// credential type. It follows BIP32 hardened derivation, with HKDF-SHA256 in place of HMAC-SHA512 and
+// the secret keys produced by KeyGen: the key material of a child is derived from the secret key and
+// chain code of its parent and the child index, so a child key reveals neither its parent nor its
+// siblings. There is no non-hardened derivation, public keys can not be derived without the secret key
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::encoding::{i2osp, scalar_to_bytes};
+use crate::error::BbsError;
+use crate::key_pair::{KeyPair, SecretKey};
+
+// Child index i of a path is the hardened index i + 2^31, written i' or ih
+pub const HARDENED: u32 = 1 << 31;
+
+const MASTER_SALT: &[u8] = b"BBS-HD-MASTER-SALT-";
+const CHILD_INFO: &[u8] = b"BBS-HD-CHILD-";
+const KEY_INFO: &[u8] = b"BBS-HD-KEY-";
+
+pub struct ExtendedSecretKey {
+    pub secret_key: SecretKey,
+    chain_code: Zeroizing<[u8; 32]>,
+}
+
+impl ExtendedSecretKey {
+    // The root of the tree, path m. The seed must be at least 32 octets
+    pub fn master(seed: &[u8]) -> Result<Self, BbsError> {
+        if seed.len() < 32 {
+            return Err(BbsError::InvalidLength("a seed must be at least 32 octets"));
+        }
+        Self::from_key_material(Some(MASTER_SALT), seed, &[])
+    }
+
+    // The key at path, e.g. m/0'/1', from the master seed
+    pub fn from_path(seed: &[u8], path: &str) -> Result<Self, BbsError> {
+        let mut key = Self::master(seed)?;
+        for index in parse_path(path)? {
+            key = key.derive_child(index)?;
+        }
+        Ok(key)
+    }
+
+    // The hardened child index of this key, index must be at least 2^31
+    pub fn derive_child(&self, index: u32) -> Result<Self, BbsError> {
+        if index < HARDENED {
+            return Err(BbsError::Serialization("only hardened derivation is supported".to_string()));
+        }
+        let ikm = Zeroizing::new([&[0u8][..], &scalar_to_bytes(&self.secret_key.0)[..], &i2osp(index as u64, 4)[..]].concat());
+        Self::from_key_material(Some(&self.chain_code[..]), &ikm, CHILD_INFO)
+    }
+
+    pub fn key_pair(&self) -> KeyPair {
+        let secret_key = SecretKey(self.secret_key.0);
+        KeyPair {
+            public_key: secret_key.public_key(),
+            secret_key,
+        }
+    }
+
+    // 80 octets of HKDF output, the first 48 are the IKM of KeyGen and the last 32 the chain code
+    fn from_key_material(salt: Option<&[u8]>, ikm: &[u8], info: &[u8]) -> Result<Self, BbsError> {
+        let mut okm = Zeroizing::new([0u8; 80]);
+        Hkdf::<Sha256>::new(salt, ikm)
+            .expand(info, &mut *okm)
+            .map_err(|_| BbsError::InvalidLength("80 octets of HKDF output"))?;
+
+        let mut chain_code = Zeroizing::new([0u8; 32]);
+        chain_code.copy_from_slice(&okm[48..]);
+        Ok(ExtendedSecretKey {
+            secret_key: SecretKey::key_gen(&okm[..48], Some(KEY_INFO))?,
+            chain_code,
+        })
+    }
+}
+
+// Parses m/i'/j'/..., every index must be hardened, marked with ' or h
+pub fn parse_path(path: &str) -> Result<Vec<u32>, BbsError> {
+    let invalid = || BbsError::Serialization("invalid derivation path, expected m/i'/j'/...".to_string());
+
+    let mut components = path.split('/');
+    if components.next() != Some("m") {
+        return Err(invalid());
+    }
+    components
+        .map(|component| {
+            let index = component
+                .strip_suffix('\'')
+                .or_else(|| component.strip_suffix('h'))
+                .ok_or_else(invalid)?;
+            match index.parse::<u32>() {
+                Ok(index) if index < HARDENED => Ok(index + HARDENED),
+                _ => Err(invalid()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::Bls12381Sha256;
+    use crate::curve::Scalar;
+    use crate::signature::Signature;
+
+    const SEED: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    #[test]
+    fn path_derivation_matches_child_derivation() {
+        let key = ExtendedSecretKey::from_path(SEED, "m/0'/1h").unwrap();
+        let child = ExtendedSecretKey::master(SEED).unwrap().derive_child(HARDENED).unwrap().derive_child(HARDENED + 1).unwrap();
+        assert_eq!(key.secret_key.to_bytes(), child.secret_key.to_bytes());
+        let master = ExtendedSecretKey::master(SEED).unwrap();
+        assert_eq!(ExtendedSecretKey::from_path(SEED, "m").unwrap().secret_key.to_bytes(), master.secret_key.to_bytes());
+
+        // Derived keys sign as any other key
+        let key_pair = key.key_pair();
+        let messages = [Scalar::from(1u64)];
+        let signature = Signature::sign::<Bls12381Sha256>(&messages, &key_pair.secret_key, None).unwrap();
+        assert!(signature.verify::<Bls12381Sha256>(&key_pair.public_key, &messages, None));
+    }
+
+    #[test]
+    fn children_differ_from_their_parent_and_siblings() {
+        let paths = ["m", "m/0'", "m/1'", "m/0'/0'", "m/1'/0'"];
+        let keys: Vec<[u8; 32]> = paths
+            .iter()
+            .map(|path| ExtendedSecretKey::from_path(SEED, path).unwrap().secret_key.to_bytes())
+            .collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(keys[i + 1..].iter().all(|other| other != key), "{}", paths[i]);
+        }
+
+        let other_seed = [SEED, b"-other"].concat();
+        assert_ne!(ExtendedSecretKey::from_path(&other_seed, "m/0'").unwrap().secret_key.to_bytes(), keys[1]);
+    }
+
+    #[test]
+    fn rejects_short_seeds_and_non_hardened_indexes() {
+        assert!(matches!(ExtendedSecretKey::master(&SEED[..31]), Err(BbsError::InvalidLength(_))));
+        let master = ExtendedSecretKey::master(SEED).unwrap();
+        assert!(matches!(master.derive_child(0), Err(BbsError::Serialization(_))));
+        assert!(matches!(master.derive_child(HARDENED - 1), Err(BbsError::Serialization(_))));
+    }
+
+    #[test]
+    fn parses_hardened_paths_only() {
+        assert!(parse_path("m").unwrap().is_empty());
+        assert_eq!(parse_path("m/0'/1h/2147483647'").unwrap(), [HARDENED, HARDENED + 1, u32::MAX]);
+        for path in ["", "0'", "m/", "m/0", "m/0'/1", "m/-1'", "m/2147483648'", "m/x'", "n/0'", "m//0'"] {
+            assert!(matches!(parse_path(path), Err(BbsError::Serialization(_))), "{}", path);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// The BBS JWS algorithm of the JOSE registration draft, for JWT based stacks. The payload is a JSON
+// array, each element is one message: the element's JSON serialization is mapped to a scalar with
+// MapMessageToScalarAsHash. The JWS is detached (RFC 7515 appendix F), BASE64URL(header)..BASE64URL(sig),
+// the payload is conveyed separately, e.g. as the claims of a credential, and supplied to verify.
+//
+// The BBS header is the JWS Signing Input without the payload, ASCII(BASE64URL(UTF8(JWS Protected
+// Header))). alg is BBS for the SHA-256 ciphersuite and BBS-SHAKE256 for the SHAKE-256 ciphersuite
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde_json::{json, Value};
+
+use crate::ciphersuites::{BbsCiphersuite, Bls12381Sha256, Bls12381Shake256, CiphersuiteParams};
+use crate::curve::Scalar;
+use crate::error::BbsError;
+use crate::hashing::map_message_to_scalar_as_hash;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::signature::Signature;
+
+pub const ALG_BBS: &str = "BBS";
+pub const ALG_BBS_SHAKE256: &str = "BBS-SHAKE256";
+
+// The alg of the ciphersuite, the other ciphersuites have none
+pub fn jws_alg<'a, X>() -> Result<&'static str, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    if X::ID == Bls12381Sha256::ID {
+        Ok(ALG_BBS)
+    } else if X::ID == Bls12381Shake256::ID {
+        Ok(ALG_BBS_SHAKE256)
+    } else {
+        Err(BbsError::Serialization("the ciphersuite has no JWS alg".to_string()))
+    }
+}
+
+// The detached compact serialization of a JWS over payload, with kid in the protected header when given
+pub fn sign<'a, X>(sk: &SecretKey, payload: &[Value], kid: Option<&str>) -> Result<String, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let mut header = json!({ "alg": jws_alg::<X>()? });
+    if let Some(kid) = kid {
+        header["kid"] = kid.into();
+    }
+    let protected = base64url(header.to_string().as_bytes());
+    let signature = Signature::sign::<X>(&message_scalars::<X>(payload)?, sk, Some(protected.as_bytes()))?;
+    Ok(alloc::format!("{}..{}", protected, base64url(&signature.to_bytes())))
+}
+
+// Fails when the JWS is malformed, not detached or for another alg
+pub fn verify<'a, X>(jws: &str, pk: &PublicKey, payload: &[Value]) -> Result<bool, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let (protected, signature) = jws
+        .split_once("..")
+        .ok_or_else(|| BbsError::Serialization("not a detached compact JWS".to_string()))?;
+    if protected_header(jws)?["alg"] != jws_alg::<X>()? {
+        return Err(BbsError::Serialization("the JWS is for another alg".to_string()));
+    }
+    let signature = Signature::from_bytes(&from_base64url(signature)?)?;
+    Ok(signature.verify::<X>(pk, &message_scalars::<X>(payload)?, Some(protected.as_bytes())))
+}
+
+// The decoded JWS Protected Header, e.g. to look up the key by kid before verifying
+pub fn protected_header(jws: &str) -> Result<Value, BbsError> {
+    let protected = jws.split('.').next().unwrap_or_default();
+    serde_json::from_slice(&from_base64url(protected)?).map_err(|e| BbsError::Serialization(e.to_string()))
+}
+
+fn message_scalars<'a, X>(payload: &[Value]) -> Result<Vec<Scalar>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    payload
+        .iter()
+        .map(|message| map_message_to_scalar_as_hash::<X>(message.to_string().as_bytes(), None))
+        .collect()
+}
+
+fn base64url(octets: &[u8]) -> String {
+    base64::encode_config(octets, base64::URL_SAFE_NO_PAD)
+}
+
+fn from_base64url(encoded: &str) -> Result<Vec<u8>, BbsError> {
+    base64::decode_config(encoded, base64::URL_SAFE_NO_PAD).map_err(|e| BbsError::Serialization(e.to_string()))
+}
@@ -0,0 +1,180 @@
+// Selective disclosure of plain JSON documents, for ecosystems without JSON-LD. As the disclosures of
+// SD-JWT, every leaf value of the document is one message, which the holder discloses or hides, but
+// no salts are needed: a hidden BBS message is not revealed by the proof however guessable it is.
+//
+// A message is the JSON text [path, value], path being the object keys and array indexes leading to
+// the leaf, e.g. [["credentialSubject","degrees",0],"BSc"], so that keys and indexes are told apart.
+// Leaves are scalars, empty objects and empty arrays, in document order with object keys sorted. The
+// API names leaves by their JSON pointer, e.g. /credentialSubject/degrees/0
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde_json::{json, Value};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::Scalar;
+use crate::error::BbsError;
+use crate::hashing::map_message_to_scalar_as_hash;
+
+pub struct JsonMessages {
+    pointers: Vec<String>,
+    messages: Vec<Vec<u8>>,
+}
+
+impl JsonMessages {
+    pub fn from_json(document: &Value) -> Self {
+        let mut json_messages = JsonMessages {
+            pointers: Vec::new(),
+            messages: Vec::new(),
+        };
+        json_messages.add_leaves(document, &mut Vec::new());
+        json_messages
+    }
+
+    pub fn messages(&self) -> &[Vec<u8>] {
+        &self.messages
+    }
+
+    // The JSON pointer of every message
+    pub fn pointers(&self) -> &[String] {
+        &self.pointers
+    }
+
+    // The messages mapped to scalars, to sign or to generate a proof with
+    pub fn scalars<'a, X>(&self) -> Result<Vec<Scalar>, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.messages
+            .iter()
+            .map(|message| map_message_to_scalar_as_hash::<X>(message, None))
+            .collect()
+    }
+
+    // The indexes of the messages at or below the JSON pointers, in ascending order as taken by proof
+    // generation. An error when a pointer selects nothing
+    pub fn indexes(&self, pointers: &[String]) -> Result<Vec<usize>, BbsError> {
+        let mut indexes = Vec::new();
+        for pointer in pointers {
+            let prefix = alloc::format!("{}/", pointer);
+            let matches: Vec<usize> = (0..self.pointers.len())
+                .filter(|&i| pointer.is_empty() || self.pointers[i] == *pointer || self.pointers[i].starts_with(&prefix))
+                .collect();
+            if matches.is_empty() {
+                return Err(BbsError::Serialization(alloc::format!("the JSON pointer {} selects nothing", pointer)));
+            }
+            indexes.extend(matches);
+        }
+        indexes.sort_unstable();
+        indexes.dedup();
+        Ok(indexes)
+    }
+
+    // The disclosed messages keyed by index, as sent to the verifier along with the proof
+    pub fn disclose(&self, indexes: &[usize]) -> BTreeMap<usize, Vec<u8>> {
+        indexes.iter().map(|&i| (i, self.messages[i].clone())).collect()
+    }
+
+    fn add_leaves(&mut self, value: &Value, path: &mut Vec<Value>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    path.push(Value::String(key.clone()));
+                    self.add_leaves(value, path);
+                    path.pop();
+                }
+            }
+            Value::Array(values) if !values.is_empty() => {
+                for (i, value) in values.iter().enumerate() {
+                    path.push(Value::from(i));
+                    self.add_leaves(value, path);
+                    path.pop();
+                }
+            }
+            leaf => {
+                self.pointers.push(pointer(path));
+                self.messages.push(json!([path, leaf]).to_string().into_bytes());
+            }
+        }
+    }
+}
+
+// The disclosed messages mapped to scalars, as taken by proof verification
+pub fn disclosed_scalars<'a, X>(disclosed: &BTreeMap<usize, Vec<u8>>) -> Result<BTreeMap<usize, Scalar>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    disclosed
+        .iter()
+        .map(|(&i, message)| Ok::<_, BbsError>((i, map_message_to_scalar_as_hash::<X>(message, None)?)))
+        .collect()
+}
+
+// The document made of the disclosed leaves. Arrays keep the indexes of their disclosed elements, the
+// hidden ones before them are null
+pub fn reconstruct(disclosed: &BTreeMap<usize, Vec<u8>>) -> Result<Value, BbsError> {
+    let mut document = Value::Null;
+    for message in disclosed.values() {
+        let (path, leaf) = match serde_json::from_slice(message) {
+            Ok(Value::Array(mut pair)) if pair.len() == 2 => {
+                let leaf = pair.pop().expect("two elements");
+                match pair.pop() {
+                    Some(Value::Array(path)) => (path, leaf),
+                    _ => return Err(invalid_message()),
+                }
+            }
+            _ => return Err(invalid_message()),
+        };
+
+        let mut node = &mut document;
+        for segment in path {
+            node = match segment {
+                Value::String(key) => {
+                    if node.is_null() {
+                        *node = Value::Object(Default::default());
+                    }
+                    node.as_object_mut()
+                        .ok_or_else(conflicting_paths)?
+                        .entry(key)
+                        .or_insert(Value::Null)
+                }
+                Value::Number(i) => {
+                    let i = i.as_u64().ok_or_else(invalid_message)? as usize;
+                    if node.is_null() {
+                        *node = Value::Array(Vec::new());
+                    }
+                    let values = node.as_array_mut().ok_or_else(conflicting_paths)?;
+                    if values.len() <= i {
+                        values.resize(i + 1, Value::Null);
+                    }
+                    &mut values[i]
+                }
+                _ => return Err(invalid_message()),
+            };
+        }
+        if !node.is_null() {
+            return Err(conflicting_paths());
+        }
+        *node = leaf;
+    }
+    Ok(document)
+}
+
+// RFC 6901
+fn pointer(path: &[Value]) -> String {
+    path.iter()
+        .map(|segment| match segment {
+            Value::String(key) => alloc::format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+            index => alloc::format!("/{}", index),
+        })
+        .collect()
+}
+
+fn invalid_message() -> BbsError {
+    BbsError::Serialization("not a JSON [path, value] message".to_string())
+}
+
+fn conflicting_paths() -> BbsError {
+    BbsError::Serialization("the disclosed messages have conflicting paths".to_string())
+}
@@ -0,0 +1,856 @@
+// JSON-LD credentials as canonical N-Quads, split into BBS messages at the claim level. The document is
+// converted to RDF, canonicalized with URDNA2015 and every quad remembers the JSON pointer of the value
+// it came from, so JSON pointers select quads as the mandatory and selective pointers of bbs-2023 do.
+//
+// Only the subset of JSON-LD used by credentials is supported: contexts inline or supplied by a
+// ContextLoader, terms, compact IRIs, @vocab, keyword aliases, type coercion, @graph containers and
+// property and type scoped contexts. Other features are rejected, and as in the safe mode of Data
+// Integrity so are values expansion would drop, e.g. properties without a term definition, as they
+// would not be signed
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde_json::{Map, Number, Value};
+
+use crate::error::BbsError;
+use crate::rdf::{
+    canonical_labels, canonicalize, Quad, Term, RDF_LANG_STRING, RDF_TYPE, XSD_BOOLEAN, XSD_DOUBLE, XSD_INTEGER,
+    XSD_STRING,
+};
+
+// Remote contexts referencing other remote contexts, the depth is bounded
+const MAX_CONTEXT_DEPTH: usize = 8;
+
+// Resolves the URL of a remote context to its document, a JSON object with an @context member. Contexts
+// are not fetched, the caller supplies the documents of the contexts it accepts
+pub trait ContextLoader {
+    fn load(&self, url: &str) -> Option<Value>;
+}
+
+// Context documents keyed by URL
+impl ContextLoader for BTreeMap<String, Value> {
+    fn load(&self, url: &str) -> Option<Value> {
+        self.get(url).cloned()
+    }
+}
+
+pub struct CanonicalDocument {
+    quads: Vec<Quad>,
+    nquads: Vec<String>,
+    // The values each quad was produced by, more than one when values produce the same quad
+    sources: Vec<Vec<QuadSource>>,
+}
+
+// The quads of a document split by the mandatory pointers, message i is quad message_indexes[i]
+pub struct MessageSplit {
+    pub mandatory: Vec<String>,
+    pub messages: Vec<String>,
+    pub mandatory_indexes: Vec<usize>,
+    pub message_indexes: Vec<usize>,
+}
+
+// What the holder discloses: the canonical quads of the mandatory and selected quads, labeled _:c14nN,
+// with the label map, the positions of the mandatory quads among them and the disclosed message indexes,
+// as taken by data_integrity::DerivedProof
+pub struct Disclosure {
+    pub reveal_quads: Vec<String>,
+    pub label_map: BTreeMap<usize, usize>,
+    pub mandatory_indexes: Vec<usize>,
+    pub selective_indexes: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum QuadKind {
+    // A literal or IRI value
+    Value,
+    // The edge to a nested node object
+    Link,
+    // rdf:type of a node
+    Type,
+}
+
+#[derive(Clone, Debug)]
+struct QuadSource {
+    // The JSON pointer of the value
+    pointer: String,
+    // The JSON pointer of the node object the quad is about
+    node: String,
+    kind: QuadKind,
+}
+
+impl CanonicalDocument {
+    pub fn from_json_ld<L>(document: &Value, loader: &L) -> Result<Self, BbsError>
+    where
+        L: ContextLoader
+    {
+        let node = document
+            .as_object()
+            .ok_or_else(|| unsupported("a document that is not a JSON object"))?;
+        let mut to_rdf = ToRdf {
+            loader,
+            quads: Vec::new(),
+            blank_nodes: BTreeMap::new(),
+            next_blank_node: 0,
+        };
+        to_rdf.node(&Context::default(), None, node, "", None)?;
+
+        let quads: Vec<Quad> = to_rdf.quads.iter().map(|(quad, _)| quad.clone()).collect();
+        let labels = canonical_labels(&quads);
+        Ok(Self::sorted(
+            to_rdf
+                .quads
+                .into_iter()
+                .map(|(quad, source)| (quad.relabel(&labels), alloc::vec![source]))
+                .collect(),
+        ))
+    }
+
+    // The canonical N-Quads, sorted, each terminated by a newline
+    pub fn nquads(&self) -> &[String] {
+        &self.nquads
+    }
+
+    // The document with its blank nodes relabeled, e.g. with data_integrity::label_map
+    pub fn relabel(&self, labels: &BTreeMap<String, String>) -> Self {
+        Self::sorted(
+            self.quads
+                .iter()
+                .zip(&self.sources)
+                .map(|(quad, sources)| (quad.relabel(labels), sources.clone()))
+                .collect(),
+        )
+    }
+
+    // The indexes of the quads selected by the JSON pointers: the quads of the values at or below a
+    // pointer, with the edges leading to it and the types of the nodes on the way, as selectJsonLd keeps
+    // their id and type. An error when a pointer selects nothing
+    pub fn select(&self, pointers: &[String]) -> Result<BTreeSet<usize>, BbsError> {
+        let mut selected = BTreeSet::new();
+        for pointer in pointers {
+            let matches: Vec<usize> = (0..self.quads.len())
+                .filter(|&i| self.sources[i].iter().any(|source| source.selected_by(pointer)))
+                .collect();
+            if matches.is_empty() {
+                return Err(BbsError::Serialization(alloc::format!("the JSON pointer {} selects nothing", pointer)));
+            }
+            selected.extend(matches);
+        }
+        Ok(selected)
+    }
+
+    // The mandatory quads and the others, which are signed as the BBS messages
+    pub fn split(&self, mandatory_pointers: &[String]) -> Result<MessageSplit, BbsError> {
+        let mandatory_indexes = self.select(mandatory_pointers)?;
+        let message_indexes: Vec<usize> = (0..self.quads.len()).filter(|i| !mandatory_indexes.contains(i)).collect();
+        Ok(MessageSplit {
+            mandatory: mandatory_indexes.iter().map(|&i| self.nquads[i].clone()).collect(),
+            messages: message_indexes.iter().map(|&i| self.nquads[i].clone()).collect(),
+            mandatory_indexes: mandatory_indexes.into_iter().collect(),
+            message_indexes,
+        })
+    }
+
+    // The holder's disclosure of the claims at selective_pointers, together with the mandatory ones. The
+    // document must be relabeled with the label map of the base proof
+    pub fn disclose(&self, split: &MessageSplit, selective_pointers: &[String]) -> Result<Disclosure, BbsError> {
+        let mut revealed = self.select(selective_pointers)?;
+        revealed.extend(split.mandatory_indexes.iter().copied());
+
+        let quads: Vec<Quad> = revealed.iter().map(|&i| self.quads[i].clone()).collect();
+        let (reveal_quads, labels) = canonicalize(&quads);
+        let label_map = labels
+            .iter()
+            .map(|(label, canonical)| {
+                let b = label.strip_prefix('b').and_then(|n| n.parse().ok());
+                let c14n = canonical.strip_prefix("c14n").and_then(|n| n.parse().ok());
+                b.zip(c14n)
+                    .map(|(b, c14n)| (c14n, b))
+                    .ok_or_else(|| BbsError::Serialization("the document is not relabeled with bN labels".to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        // The verifier relabels the reveal quads and sorts them, as self.nquads are sorted
+        let mandatory: BTreeSet<usize> = split.mandatory_indexes.iter().copied().collect();
+        let message_index: BTreeMap<usize, usize> =
+            split.message_indexes.iter().enumerate().map(|(message, &quad)| (quad, message)).collect();
+        Ok(Disclosure {
+            reveal_quads,
+            label_map,
+            mandatory_indexes: revealed
+                .iter()
+                .enumerate()
+                .filter(|(_, i)| mandatory.contains(i))
+                .map(|(position, _)| position)
+                .collect(),
+            selective_indexes: revealed.iter().filter_map(|i| message_index.get(i).copied()).collect(),
+        })
+    }
+
+    // Sorted by N-Quad, quads that are the same are merged
+    fn sorted(mut quads: Vec<(Quad, Vec<QuadSource>)>) -> Self {
+        let mut keyed: Vec<(String, Quad, Vec<QuadSource>)> = quads
+            .drain(..)
+            .map(|(quad, sources)| (quad.to_nquad(), quad, sources))
+            .collect();
+        keyed.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut document = CanonicalDocument {
+            quads: Vec::new(),
+            nquads: Vec::new(),
+            sources: Vec::new(),
+        };
+        for (nquad, quad, sources) in keyed {
+            if document.nquads.last() == Some(&nquad) {
+                document.sources.last_mut().expect("sources of the last quad").extend(sources);
+                continue;
+            }
+            document.quads.push(quad);
+            document.nquads.push(nquad);
+            document.sources.push(sources);
+        }
+        document
+    }
+}
+
+impl QuadSource {
+    fn selected_by(&self, pointer: &str) -> bool {
+        let within = |inner: &str, outer: &str| inner == outer || inner.starts_with(&alloc::format!("{}/", outer));
+        match self.kind {
+            _ if within(&self.pointer, pointer) => true,
+            QuadKind::Link => pointer != self.pointer && within(pointer, &self.pointer),
+            QuadKind::Type => within(pointer, &self.node),
+            QuadKind::Value => false,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct Context {
+    terms: BTreeMap<String, TermDefinition>,
+    vocab: Option<String>,
+    // Set by type scoped contexts, which nested node objects do not inherit
+    previous: Option<Box<Context>>,
+}
+
+#[derive(Clone)]
+struct TermDefinition {
+    // None when the term maps to null
+    id: Option<String>,
+    // @id, @vocab or a datatype IRI
+    type_mapping: Option<String>,
+    // @container @graph, the values are node objects in a graph of their own
+    graph: bool,
+    context: Option<Value>,
+}
+
+// The terms of a local context that are being defined, for terms referencing other terms
+struct LocalContext<'l> {
+    map: &'l Map<String, Value>,
+    defined: BTreeMap<String, bool>,
+}
+
+fn process_context<L>(active: &Context, local: &Value, loader: &L, propagate: bool) -> Result<Context, BbsError>
+where
+    L: ContextLoader
+{
+    let mut result = active.clone();
+    if !propagate && result.previous.is_none() {
+        result.previous = Some(Box::new(active.clone()));
+    }
+    process_local_context(&mut result, local, loader, 0)?;
+    Ok(result)
+}
+
+fn process_local_context<L>(result: &mut Context, local: &Value, loader: &L, depth: usize) -> Result<(), BbsError>
+where
+    L: ContextLoader
+{
+    match local {
+        Value::Array(contexts) => {
+            for context in contexts {
+                process_local_context(result, context, loader, depth)?;
+            }
+            Ok(())
+        }
+        Value::Null => {
+            *result = Context {
+                previous: result.previous.take(),
+                ..Context::default()
+            };
+            Ok(())
+        }
+        Value::String(url) => {
+            if depth >= MAX_CONTEXT_DEPTH {
+                return Err(unsupported("remote contexts nested this deep"));
+            }
+            let document = loader
+                .load(url)
+                .ok_or_else(|| BbsError::Serialization(alloc::format!("the context {} is not known", url)))?;
+            let context = document
+                .get("@context")
+                .ok_or_else(|| BbsError::Serialization(alloc::format!("the context {} has no @context", url)))?;
+            process_local_context(result, context, loader, depth + 1)
+        }
+        Value::Object(map) => {
+            let mut local = LocalContext {
+                map,
+                defined: BTreeMap::new(),
+            };
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("@version", _) | ("@protected", _) => {}
+                    ("@vocab", Value::Null) => result.vocab = None,
+                    ("@vocab", Value::String(vocab)) => {
+                        result.vocab = Some(
+                            expand_iri_defining(result, &mut local, vocab, true)?
+                                .ok_or_else(|| invalid("@vocab"))?,
+                        );
+                    }
+                    ("@base", Value::Null) | ("@language", Value::Null) | ("@direction", Value::Null) => {}
+                    (keyword, _) if keyword.starts_with('@') => return Err(unsupported(keyword)),
+                    _ => {}
+                }
+            }
+            for term in map.keys().filter(|key| !key.starts_with('@')) {
+                create_term(result, &mut local, term)?;
+            }
+            Ok(())
+        }
+        _ => Err(invalid("@context")),
+    }
+}
+
+fn create_term(active: &mut Context, local: &mut LocalContext, term: &str) -> Result<(), BbsError> {
+    match local.defined.get(term) {
+        Some(true) => return Ok(()),
+        Some(false) => return Err(invalid(&alloc::format!("term {}, its definition is cyclic", term))),
+        None => {}
+    }
+    local.defined.insert(term.to_string(), false);
+
+    let map = local.map;
+    let definition = match &map[term] {
+        Value::Null => TermDefinition {
+            id: None,
+            type_mapping: None,
+            graph: false,
+            context: None,
+        },
+        Value::String(id) => TermDefinition {
+            id: Some(term_iri(active, local, term, id)?),
+            type_mapping: None,
+            graph: false,
+            context: None,
+        },
+        Value::Object(definition) => {
+            let id = match definition.get("@id") {
+                Some(Value::Null) => None,
+                Some(Value::String(id)) => Some(term_iri(active, local, term, id)?),
+                Some(_) => return Err(invalid(term)),
+                None => Some(default_term_iri(active, local, term)?),
+            };
+            let type_mapping = match definition.get("@type") {
+                None => None,
+                Some(Value::String(t)) if t == "@id" || t == "@vocab" => Some(t.clone()),
+                Some(Value::String(t)) if t.starts_with('@') => return Err(unsupported(t)),
+                Some(Value::String(t)) => Some(
+                    expand_iri_defining(active, local, t, true)?
+                        .filter(|t| is_absolute(t))
+                        .ok_or_else(|| invalid(term))?,
+                ),
+                Some(_) => return Err(invalid(term)),
+            };
+            let containers = match definition.get("@container") {
+                None => Vec::new(),
+                Some(Value::String(container)) => alloc::vec![container.as_str()],
+                Some(Value::Array(containers)) => containers
+                    .iter()
+                    .map(|container| container.as_str().ok_or_else(|| invalid(term)))
+                    .collect::<Result<_, _>>()?,
+                Some(_) => return Err(invalid(term)),
+            };
+            if let Some(container) = containers.iter().find(|c| **c != "@set" && **c != "@graph") {
+                return Err(unsupported(container));
+            }
+            if let Some(key) = definition
+                .keys()
+                .find(|key| !["@id", "@type", "@container", "@context", "@protected", "@prefix"].contains(&key.as_str()))
+            {
+                return Err(unsupported(key));
+            }
+            TermDefinition {
+                id,
+                type_mapping,
+                graph: containers.contains(&"@graph"),
+                context: definition.get("@context").cloned(),
+            }
+        }
+        _ => return Err(invalid(term)),
+    };
+    active.terms.insert(term.to_string(), definition);
+    local.defined.insert(term.to_string(), true);
+    Ok(())
+}
+
+// The IRI of a term definition, a keyword for an alias such as "id": "@id"
+fn term_iri(active: &mut Context, local: &mut LocalContext, term: &str, id: &str) -> Result<String, BbsError> {
+    if id.starts_with('@') {
+        return Ok(id.to_string());
+    }
+    if id == term {
+        return default_term_iri(active, local, term);
+    }
+    expand_iri_defining(active, local, id, true)?
+        .filter(|iri| is_absolute(iri))
+        .ok_or_else(|| invalid(term))
+}
+
+// A term without @id is a compact or absolute IRI, or relative to @vocab
+fn default_term_iri(active: &mut Context, local: &mut LocalContext, term: &str) -> Result<String, BbsError> {
+    if let Some((prefix, _)) = term.split_once(':') {
+        if local.map.contains_key(prefix) && prefix != term {
+            create_term(active, local, prefix)?;
+        }
+        return expand_compact_iri(active, term).ok_or_else(|| invalid(term));
+    }
+    match &active.vocab {
+        Some(vocab) => Ok(alloc::format!("{}{}", vocab, term)),
+        None => Err(invalid(term)),
+    }
+}
+
+// IRI expansion within a local context, defining the terms value refers to first
+fn expand_iri_defining(
+    active: &mut Context,
+    local: &mut LocalContext,
+    value: &str,
+    vocab: bool,
+) -> Result<Option<String>, BbsError> {
+    if vocab && local.map.contains_key(value) {
+        create_term(active, local, value)?;
+    }
+    if let Some((prefix, _)) = value.split_once(':') {
+        if local.map.contains_key(prefix) {
+            create_term(active, local, prefix)?;
+        }
+    }
+    Ok(expand_iri(active, value, vocab))
+}
+
+// None when value does not expand to an IRI, or to a term mapped to null
+fn expand_iri(active: &Context, value: &str, vocab: bool) -> Option<String> {
+    if value.starts_with('@') {
+        return Some(value.to_string());
+    }
+    if vocab {
+        if let Some(definition) = active.terms.get(value) {
+            return definition.id.clone();
+        }
+    }
+    if value.contains(':') {
+        return expand_compact_iri(active, value);
+    }
+    match &active.vocab {
+        Some(vocab_iri) if vocab => Some(alloc::format!("{}{}", vocab_iri, value)),
+        _ => None,
+    }
+}
+
+fn expand_compact_iri(active: &Context, value: &str) -> Option<String> {
+    let (prefix, suffix) = value.split_once(':')?;
+    if prefix == "_" || suffix.starts_with("//") {
+        return Some(value.to_string());
+    }
+    match active.terms.get(prefix) {
+        Some(TermDefinition { id: Some(id), .. }) => Some(alloc::format!("{}{}", id, suffix)),
+        Some(_) => None,
+        None => Some(value.to_string()),
+    }
+}
+
+fn is_absolute(iri: &str) -> bool {
+    matches!(iri.split_once(':'), Some((scheme, _)) if !scheme.is_empty() && scheme != "_")
+}
+
+struct ToRdf<'l, L> {
+    loader: &'l L,
+    quads: Vec<(Quad, QuadSource)>,
+    // Blank node labels of the document to the generated ones
+    blank_nodes: BTreeMap<String, String>,
+    next_blank_node: usize,
+}
+
+impl<'l, L> ToRdf<'l, L>
+where
+    L: ContextLoader
+{
+    // Emits the quads of a node object, returning its subject
+    fn node(
+        &mut self,
+        active: &Context,
+        property_context: Option<&Value>,
+        node: &Map<String, Value>,
+        pointer: &str,
+        graph: Option<&Term>,
+    ) -> Result<Term, BbsError> {
+        let only_id = node.len() == 1 && node.keys().all(|key| expand_iri(active, key, true).as_deref() == Some("@id"));
+        let mut context = match &active.previous {
+            Some(previous) if !only_id => (**previous).clone(),
+            _ => active.clone(),
+        };
+        if let Some(property_context) = property_context {
+            context = process_context(&context, property_context, self.loader, true)?;
+        }
+        if let Some(local) = node.get("@context") {
+            context = process_context(&context, local, self.loader, true)?;
+        }
+
+        // Types are expanded before their scoped contexts are applied, in lexicographical order
+        let type_context = context.clone();
+        let type_keys: Vec<&String> = node
+            .keys()
+            .filter(|key| *key != "@context" && expand_iri(&type_context, key, true).as_deref() == Some("@type"))
+            .collect();
+        let mut types = Vec::new();
+        for key in &type_keys {
+            let type_pointer = alloc::format!("{}/{}", pointer, escape_pointer(key));
+            match &node[*key] {
+                Value::String(t) => types.push((type_pointer, t.as_str())),
+                Value::Array(ts) => {
+                    for (i, t) in ts.iter().enumerate() {
+                        let t = t.as_str().ok_or_else(|| invalid("@type"))?;
+                        types.push((alloc::format!("{}/{}", type_pointer, i), t));
+                    }
+                }
+                _ => return Err(invalid("@type")),
+            }
+        }
+        let mut scoped_types: Vec<&str> = types.iter().map(|(_, t)| *t).collect();
+        scoped_types.sort_unstable();
+        for t in scoped_types {
+            if let Some(TermDefinition { context: Some(scoped), .. }) = type_context.terms.get(t) {
+                context = process_context(&context, scoped, self.loader, false)?;
+            }
+        }
+
+        let id = node
+            .iter()
+            .find(|(key, _)| *key != "@context" && expand_iri(&context, key, true).as_deref() == Some("@id"));
+        let subject = match id {
+            Some((_, Value::String(id))) => self.resource(&context, id)?,
+            Some((key, _)) => return Err(invalid(key)),
+            None => self.blank_node(),
+        };
+
+        for (type_pointer, t) in types {
+            let iri = expand_iri(&type_context, t, true)
+                .filter(|iri| is_absolute(iri))
+                .ok_or_else(|| dropped(&type_pointer))?;
+            self.emit(&subject, RDF_TYPE, Term::Iri(iri), graph, type_pointer, pointer, QuadKind::Type);
+        }
+
+        for (key, value) in node {
+            if key == "@context" || type_keys.contains(&key) {
+                continue;
+            }
+            let value_pointer = alloc::format!("{}/{}", pointer, escape_pointer(key));
+            let property = expand_iri(&context, key, true).ok_or_else(|| dropped(&value_pointer))?;
+            match property.as_str() {
+                "@id" => continue,
+                keyword if keyword.starts_with('@') => return Err(unsupported(keyword)),
+                iri if !is_absolute(iri) => return Err(dropped(&value_pointer)),
+                _ => {}
+            }
+
+            let definition = context.terms.get(key);
+            let values: Vec<(String, &Value)> = match value {
+                Value::Array(values) => values.iter().enumerate().map(|(i, v)| (alloc::format!("{}/{}", value_pointer, i), v)).collect(),
+                value => alloc::vec![(value_pointer, value)],
+            };
+            for (value_pointer, value) in values {
+                if value.is_array() {
+                    return Err(unsupported("lists of lists"));
+                }
+                if let Some((object, kind)) = self.object(&context, definition, value, &value_pointer, graph)? {
+                    self.emit(&subject, &property, object, graph, value_pointer, pointer, kind);
+                }
+            }
+        }
+        Ok(subject)
+    }
+
+    // The object of a property value, None for null
+    fn object(
+        &mut self,
+        context: &Context,
+        definition: Option<&TermDefinition>,
+        value: &Value,
+        pointer: &str,
+        graph: Option<&Term>,
+    ) -> Result<Option<(Term, QuadKind)>, BbsError> {
+        let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+        let datatype = type_mapping.filter(|t| !t.starts_with('@'));
+        let object = match value {
+            Value::Null => return Ok(None),
+            Value::String(s) => match type_mapping {
+                Some("@id") => self.resource(context, s)?,
+                Some("@vocab") => Term::Iri(
+                    expand_iri(context, s, true)
+                        .filter(|iri| is_absolute(iri))
+                        .ok_or_else(|| dropped(pointer))?,
+                ),
+                _ => literal(s, datatype.unwrap_or(XSD_STRING), None),
+            },
+            Value::Bool(b) => literal(&b.to_string(), datatype.unwrap_or(XSD_BOOLEAN), None),
+            Value::Number(n) => number_literal(n, datatype),
+            Value::Object(map) => {
+                let keys: Vec<Option<String>> = map.keys().map(|key| expand_iri(context, key, true)).collect();
+                if keys.iter().any(|key| key.as_deref() == Some("@value")) {
+                    return self.value_object(context, map, pointer).map(|term| Some((term, QuadKind::Value)));
+                }
+                if let Some(keyword) = keys.iter().flatten().find(|key| *key == "@list" || *key == "@set") {
+                    return Err(unsupported(keyword));
+                }
+                // The edge leads to the nested node, or with @container @graph to the graph it is in
+                let scoped = definition.and_then(|d| d.context.as_ref());
+                let object = match definition {
+                    Some(TermDefinition { graph: true, .. }) => {
+                        let named_graph = self.blank_node();
+                        self.node(context, scoped, map, pointer, Some(&named_graph))?;
+                        named_graph
+                    }
+                    _ => self.node(context, scoped, map, pointer, graph)?,
+                };
+                return Ok(Some((object, QuadKind::Link)));
+            }
+            Value::Array(_) => return Err(unsupported("lists of lists")),
+        };
+        Ok(Some((object, QuadKind::Value)))
+    }
+
+    fn value_object(&mut self, context: &Context, map: &Map<String, Value>, pointer: &str) -> Result<Term, BbsError> {
+        let mut value = None;
+        let mut datatype = None;
+        let mut language = None;
+        for (key, v) in map {
+            match (expand_iri(context, key, true).as_deref(), v) {
+                (Some("@value"), v) => value = Some(v),
+                (Some("@type"), Value::String(t)) if t.starts_with('@') => return Err(unsupported(t)),
+                (Some("@type"), Value::String(t)) => {
+                    datatype = Some(expand_iri(context, t, true).filter(|t| is_absolute(t)).ok_or_else(|| dropped(pointer))?)
+                }
+                (Some("@language"), Value::String(l)) => language = Some(l.clone()),
+                _ => return Err(invalid(&alloc::format!("value object at {}", pointer))),
+            }
+        }
+        match (value, language) {
+            (Some(Value::String(s)), Some(language)) if datatype.is_none() => Ok(literal(s, RDF_LANG_STRING, Some(language))),
+            (Some(Value::String(s)), None) => Ok(literal(s, datatype.as_deref().unwrap_or(XSD_STRING), None)),
+            (Some(Value::Bool(b)), None) => Ok(literal(&b.to_string(), datatype.as_deref().unwrap_or(XSD_BOOLEAN), None)),
+            (Some(Value::Number(n)), None) => Ok(number_literal(n, datatype.as_deref())),
+            _ => Err(invalid(&alloc::format!("value object at {}", pointer))),
+        }
+    }
+
+    // An IRI, or a blank node for _: identifiers
+    fn resource(&mut self, context: &Context, id: &str) -> Result<Term, BbsError> {
+        if let Some(label) = id.strip_prefix("_:") {
+            if let Some(generated) = self.blank_nodes.get(label) {
+                return Ok(Term::BlankNode(generated.clone()));
+            }
+            let generated = self.blank_node();
+            if let Term::BlankNode(generated) = &generated {
+                self.blank_nodes.insert(label.to_string(), generated.clone());
+            }
+            return Ok(generated);
+        }
+        expand_iri(context, id, false)
+            .filter(|iri| is_absolute(iri))
+            .map(Term::Iri)
+            .ok_or_else(|| unsupported(&alloc::format!("the relative IRI {}", id)))
+    }
+
+    // The labels are replaced by canonicalization
+    fn blank_node(&mut self) -> Term {
+        self.next_blank_node += 1;
+        Term::BlankNode(alloc::format!("b{}", self.next_blank_node - 1))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn emit(&mut self, subject: &Term, predicate: &str, object: Term, graph: Option<&Term>, pointer: String, node: &str, kind: QuadKind) {
+        let quad = Quad {
+            subject: subject.clone(),
+            predicate: Term::Iri(predicate.to_string()),
+            object,
+            graph: graph.cloned(),
+        };
+        let source = QuadSource {
+            pointer,
+            node: node.to_string(),
+            kind,
+        };
+        self.quads.push((quad, source));
+    }
+}
+
+fn literal(value: &str, datatype: &str, language: Option<String>) -> Term {
+    Term::Literal {
+        value: value.to_string(),
+        datatype: datatype.to_string(),
+        language,
+    }
+}
+
+// Integral numbers are xsd:integer and the others xsd:double in canonical form, e.g. 1.1E0, as by
+// JSON-LD to RDF
+fn number_literal(n: &Number, datatype: Option<&str>) -> Term {
+    let f = n.as_f64().unwrap_or_default();
+    let integral = n.is_i64() || n.is_u64() || (f.fract() == 0.0 && f.abs() < 1e21);
+    let datatype = datatype.unwrap_or(if integral { XSD_INTEGER } else { XSD_DOUBLE });
+    let value = if integral && datatype != XSD_DOUBLE {
+        if n.is_i64() || n.is_u64() {
+            n.to_string()
+        } else {
+            alloc::format!("{}", f as i128)
+        }
+    } else {
+        let exponential = alloc::format!("{:.15e}", f);
+        let (mantissa, exponent) = exponential.split_once('e').expect("exponential notation");
+        let mantissa = mantissa.trim_end_matches('0');
+        let mantissa = if mantissa.ends_with('.') { alloc::format!("{}0", mantissa) } else { mantissa.to_string() };
+        alloc::format!("{}E{}", mantissa, exponent)
+    };
+    literal(&value, datatype, None)
+}
+
+// RFC 6901
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn dropped(pointer: &str) -> BbsError {
+    BbsError::Serialization(alloc::format!("{} would be dropped by JSON-LD expansion", pointer))
+}
+
+fn unsupported(feature: &str) -> BbsError {
+    BbsError::Serialization(alloc::format!("JSON-LD {} is not supported", feature))
+}
+
+fn invalid(what: &str) -> BbsError {
+    BbsError::Serialization(alloc::format!("invalid JSON-LD {}", what))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn credential() -> Value {
+        json!({
+            "@context": {
+                "@vocab": "https://example.org/vocab#",
+                "id": "@id",
+                "type": "@type"
+            },
+            "id": "urn:cred:1",
+            "type": "Credential",
+            "issuer": "did:example:issuer",
+            "credentialSubject": {
+                "name": "Alice",
+                "age": 30,
+                "address": { "city": "Paris" }
+            }
+        })
+    }
+
+    fn pointers(pointers: &[&str]) -> Vec<String> {
+        pointers.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn no_contexts() -> BTreeMap<String, Value> {
+        BTreeMap::new()
+    }
+
+    #[test]
+    fn credential_to_canonical_nquads() {
+        let document = CanonicalDocument::from_json_ld(&credential(), &no_contexts()).unwrap();
+        let expected = [
+            "<urn:cred:1> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://example.org/vocab#Credential> .\n",
+            "<urn:cred:1> <https://example.org/vocab#credentialSubject> _:c14n0 .\n",
+            "<urn:cred:1> <https://example.org/vocab#issuer> \"did:example:issuer\" .\n",
+            "_:c14n0 <https://example.org/vocab#address> _:c14n1 .\n",
+            "_:c14n0 <https://example.org/vocab#age> \"30\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n",
+            "_:c14n0 <https://example.org/vocab#name> \"Alice\" .\n",
+            "_:c14n1 <https://example.org/vocab#city> \"Paris\" .\n",
+        ];
+        assert_eq!(document.nquads(), expected);
+    }
+
+    #[test]
+    fn remote_contexts_are_resolved_by_the_loader() {
+        let mut loader = no_contexts();
+        loader.insert("https://example.org/context".to_string(), json!({ "@context": credential()["@context"] }));
+        let mut remote = credential();
+        remote["@context"] = json!("https://example.org/context");
+
+        let document = CanonicalDocument::from_json_ld(&remote, &loader).unwrap();
+        let inline = CanonicalDocument::from_json_ld(&credential(), &no_contexts()).unwrap();
+        assert_eq!(document.nquads(), inline.nquads());
+
+        assert!(matches!(CanonicalDocument::from_json_ld(&remote, &no_contexts()), Err(BbsError::Serialization(_))));
+    }
+
+    #[test]
+    fn pointers_select_the_quads_of_their_values() {
+        let document = CanonicalDocument::from_json_ld(&credential(), &no_contexts()).unwrap();
+
+        // A value with the type of the node it is in, and the city with the edges leading to it
+        let split = document.split(&pointers(&["/issuer"])).unwrap();
+        assert_eq!(split.mandatory_indexes, [0, 2]);
+        assert_eq!(split.mandatory[1], "<urn:cred:1> <https://example.org/vocab#issuer> \"did:example:issuer\" .\n");
+        assert_eq!(split.message_indexes, [1, 3, 4, 5, 6]);
+        let selected = document.select(&pointers(&["/credentialSubject/address/city"])).unwrap();
+        assert_eq!(selected, BTreeSet::from([0, 1, 3, 6]));
+        let selected = document.select(&pointers(&["/type"])).unwrap();
+        assert_eq!(selected, BTreeSet::from([0]));
+
+        assert!(matches!(document.select(&pointers(&["/credentialSubject/email"])), Err(BbsError::Serialization(_))));
+    }
+
+    #[test]
+    fn disclose_labels_the_revealed_quads() {
+        let document = CanonicalDocument::from_json_ld(&credential(), &no_contexts()).unwrap();
+        let labels: BTreeMap<String, String> =
+            [("c14n0", "b0"), ("c14n1", "b1")].iter().map(|(from, to)| (from.to_string(), to.to_string())).collect();
+        let document = document.relabel(&labels);
+        let split = document.split(&pointers(&["/issuer"])).unwrap();
+
+        let disclosure = document.disclose(&split, &pointers(&["/credentialSubject/name"])).unwrap();
+        // The type, the edge to the subject and the name, with the mandatory issuer
+        assert_eq!(disclosure.reveal_quads.len(), 4);
+        assert_eq!(disclosure.mandatory_indexes, [0, 2]);
+        assert_eq!(disclosure.selective_indexes, [0, 3]);
+        assert_eq!(disclosure.label_map, BTreeMap::from([(0, 0)]));
+
+        // Without the bN labels of a base proof
+        let unlabeled = CanonicalDocument::from_json_ld(&credential(), &no_contexts()).unwrap();
+        let split = unlabeled.split(&pointers(&["/issuer"])).unwrap();
+        let result = unlabeled.disclose(&split, &pointers(&["/credentialSubject/name"]));
+        assert!(matches!(result, Err(BbsError::Serialization(_))));
+    }
+
+    #[test]
+    fn rejects_values_expansion_would_drop_and_unsupported_features() {
+        let context = json!({ "id": "@id", "name": "https://example.org/vocab#name" });
+        let undefined = json!({ "@context": context, "name": "Alice", "age": 30 });
+        let list = json!({ "@context": context, "name": { "@list": ["Alice"] } });
+        let relative = json!({ "@context": context, "id": "cred/1", "name": "Alice" });
+        let not_an_object = json!(["Alice"]);
+        for document in [undefined, list, relative, not_an_object] {
+            let result = CanonicalDocument::from_json_ld(&document, &no_contexts());
+            assert!(matches!(result, Err(BbsError::Serialization(_))), "{}", document);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+// Encodings of BBS keys for key management tooling: JWK, following the BLS12381G2 key representation
+// of the JOSE and COSE registrations (kty EC, crv BLS12381G2, the x and y coordinates of the public key
+// and d, the secret key), and PKCS#8 and SubjectPublicKeyInfo DER and PEM.
+//
+// No OID is registered for BBS keys. The DER encodings use the algorithm identifier BBS_KEY_OID without
+// parameters, with the keys laid out as for Ed25519 in RFC 8410: the public key is the 96 octet compressed
+// point in the BIT STRING, the private key the 32 octet secret key wrapped in an OCTET STRING
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde_json::{json, Value};
+
+use crate::curve::{Backend, PairingCurve};
+use crate::encoding::nonzero_scalar_from_octets;
+use crate::error::BbsError;
+use crate::key_pair::{KeyPair, PublicKey, SecretKey};
+
+// 2.25.278178878634509937512306649111771801165, an OID derived from a UUID (ITU-T X.667), which needs
+// no registration. To be replaced once an OID is assigned to BBS keys
+pub const BBS_KEY_OID: &[u8] = &[
+    0x69, 0x83, 0xa2, 0xc7, 0xa6, 0xe8, 0x86, 0xd3, 0x82, 0x97, 0xb7, 0x88, 0xff, 0xbb, 0xaa, 0xbd, 0xf3, 0xe3, 0xcc, 0x4d,
+];
+
+const JWK_KTY: &str = "EC";
+const JWK_CRV: &str = "BLS12381G2";
+
+impl PublicKey {
+    // {"kty": "EC", "crv": "BLS12381G2", "x": ..., "y": ...}
+    pub fn to_jwk(&self) -> Value {
+        let xy = Backend::g2_to_uncompressed_bytes(&self.0);
+        json!({
+            "kty": JWK_KTY,
+            "crv": JWK_CRV,
+            "x": base64url(&xy[..96]),
+            "y": base64url(&xy[96..]),
+        })
+    }
+
+    // Any other members, e.g. kid or d, are ignored
+    pub fn from_jwk(jwk: &Value) -> Result<Self, BbsError> {
+        if jwk["kty"] != JWK_KTY || jwk["crv"] != JWK_CRV {
+            return Err(BbsError::Serialization("not a BLS12381G2 JWK".to_string()));
+        }
+        let mut xy = [0u8; 192];
+        xy[..96].copy_from_slice(&jwk_member(jwk, "x", 96)?);
+        xy[96..].copy_from_slice(&jwk_member(jwk, "y", 96)?);
+
+        let w = Backend::g2_from_uncompressed_bytes(&xy).ok_or(BbsError::InvalidPoint)?;
+        if Backend::g2_is_identity(&w) {
+            return Err(BbsError::InvalidPoint);
+        }
+        Ok(PublicKey(w))
+    }
+
+    // SubjectPublicKeyInfo
+    pub fn to_der(&self) -> Vec<u8> {
+        subject_public_key_info(&self.to_bytes())
+    }
+
+    pub fn from_der(bytes: &[u8]) -> Result<Self, BbsError> {
+        let octets = key_octets(bytes, &subject_public_key_info(&[0u8; 96]), 96)?;
+        PublicKey::from_bytes(octets.try_into().expect("96 octets"))
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem("PUBLIC KEY", &self.to_der())
+    }
+
+    pub fn from_pem(pem: &str) -> Result<Self, BbsError> {
+        Self::from_der(&from_pem(pem, "PUBLIC KEY")?)
+    }
+}
+
+impl KeyPair {
+    // The public JWK with the secret key as d
+    pub fn to_jwk(&self) -> Value {
+        let mut jwk = self.public_key.to_jwk();
+        jwk["d"] = base64url(&self.secret_key.to_bytes()).into();
+        jwk
+    }
+
+    // The public key is checked against d
+    pub fn from_jwk(jwk: &Value) -> Result<Self, BbsError> {
+        let public_key = PublicKey::from_jwk(jwk)?;
+        let secret_key = SecretKey(nonzero_scalar_from_octets(&jwk_member(jwk, "d", 32)?)?);
+        if secret_key.public_key().0 != public_key.0 {
+            return Err(BbsError::Serialization("d does not match the public key".to_string()));
+        }
+        Ok(KeyPair { secret_key, public_key })
+    }
+
+    // PKCS#8 PrivateKeyInfo, the public key is derived again when decoding
+    pub fn to_der(&self) -> Vec<u8> {
+        private_key_info(&self.secret_key.to_bytes())
+    }
+
+    pub fn from_der(bytes: &[u8]) -> Result<Self, BbsError> {
+        let octets = key_octets(bytes, &private_key_info(&[0u8; 32]), 32)?;
+        let secret_key = SecretKey(nonzero_scalar_from_octets(octets)?);
+        Ok(KeyPair {
+            public_key: secret_key.public_key(),
+            secret_key,
+        })
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem("PRIVATE KEY", &self.to_der())
+    }
+
+    pub fn from_pem(pem: &str) -> Result<Self, BbsError> {
+        Self::from_der(&from_pem(pem, "PRIVATE KEY")?)
+    }
+}
+
+// SEQUENCE { SEQUENCE { OBJECT IDENTIFIER BBS_KEY_OID }, BIT STRING public_key }
+fn subject_public_key_info(public_key: &[u8]) -> Vec<u8> {
+    let bit_string = [&[0u8][..], public_key].concat();
+    der(0x30, &[algorithm_identifier(), der(0x03, &bit_string)].concat())
+}
+
+// SEQUENCE { INTEGER 0, SEQUENCE { OBJECT IDENTIFIER BBS_KEY_OID }, OCTET STRING { OCTET STRING secret_key } }
+fn private_key_info(secret_key: &[u8]) -> Vec<u8> {
+    let private_key = der(0x04, secret_key);
+    der(0x30, &[der(0x02, &[0]), algorithm_identifier(), der(0x04, &private_key)].concat())
+}
+
+fn algorithm_identifier() -> Vec<u8> {
+    der(0x30, &der(0x06, BBS_KEY_OID))
+}
+
+// A DER TLV, with the long form of the length above 127 octets
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut tlv = alloc::vec![tag];
+    match content.len() {
+        len if len < 0x80 => tlv.push(len as u8),
+        len if len < 0x100 => tlv.extend_from_slice(&[0x81, len as u8]),
+        len => tlv.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+    }
+    tlv.extend_from_slice(content);
+    tlv
+}
+
+// The encodings have a fixed layout, so they are decoded by comparing everything but the trailing key
+// octets with the encoding of a zero key
+fn key_octets<'b>(bytes: &'b [u8], template: &[u8], key_len: usize) -> Result<&'b [u8], BbsError> {
+    let prefix = &template[..template.len() - key_len];
+    if bytes.len() != template.len() || !bytes.starts_with(prefix) {
+        return Err(BbsError::Serialization("not a DER encoded BBS key".to_string()));
+    }
+    Ok(&bytes[prefix.len()..])
+}
+
+// RFC 7468 textual encoding, base64 in lines of 64 characters
+fn pem(label: &str, der: &[u8]) -> String {
+    let encoded = base64::encode(der);
+    let mut pem = alloc::format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(core::str::from_utf8(line).expect("base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str(&alloc::format!("-----END {}-----\n", label));
+    pem
+}
+
+fn from_pem(pem: &str, label: &str) -> Result<Vec<u8>, BbsError> {
+    let begin = alloc::format!("-----BEGIN {}-----", label);
+    let end = alloc::format!("-----END {}-----", label);
+    let body = pem
+        .trim()
+        .strip_prefix(&begin)
+        .and_then(|pem| pem.strip_suffix(&end))
+        .ok_or_else(|| BbsError::Serialization(alloc::format!("not a PEM encoded {}", label)))?;
+    let encoded: String = body.split_whitespace().collect();
+    base64::decode(encoded).map_err(|e| BbsError::Serialization(e.to_string()))
+}
+
+fn base64url(octets: &[u8]) -> String {
+    base64::encode_config(octets, base64::URL_SAFE_NO_PAD)
+}
+
+fn jwk_member(jwk: &Value, name: &str, len: usize) -> Result<Vec<u8>, BbsError> {
+    let octets = jwk[name]
+        .as_str()
+        .map(|value| base64::decode_config(value, base64::URL_SAFE_NO_PAD))
+        .ok_or_else(|| BbsError::Serialization(alloc::format!("the JWK has no {}", name)))?
+        .map_err(|e| BbsError::Serialization(e.to_string()))?;
+    if octets.len() != len {
+        return Err(BbsError::InvalidLength("a JWK member has the wrong length"));
+    }
+    Ok(octets)
+}
@@ -0,0 +1,141 @@
+use ff::Field;
+use hkdf::Hkdf;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::curve::{Backend, PairingCurve, Scalar, G2};
+use crate::encoding::{i2osp, os2ip, scalar_from_bytes, scalar_to_bytes, DeserializeMode};
+use crate::error::BbsError;
+use crate::secret::zeroize_scalar;
+
+// L = ceil((3 * ceil(log2(r))) / 16)
+const KEYGEN_L: usize = 48;
+const KEYGEN_SALT: &[u8] = b"BBS-SIG-KEYGEN-SALT-";
+
+pub struct SecretKey(pub(crate) Scalar);
+
+pub struct PublicKey(pub(crate) G2);
+
+pub struct KeyPair {
+    pub secret_key: SecretKey,
+    pub public_key: PublicKey,
+}
+
+impl KeyPair {
+    #[cfg(feature = "std")]
+    pub fn generate() -> Self {
+        Self::generate_with_rng(&mut rand::thread_rng())
+    }
+
+    // IKM drawn from rng, e.g. a seeded rng for reproducible keys in tests and fixtures
+    pub fn generate_with_rng<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + CryptoRng
+    {
+        let mut ikm = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(&mut *ikm);
+
+        Self::from_ikm(&*ikm, None).expect("32 bytes of IKM is sufficient")
+    }
+
+    pub fn from_ikm(ikm: &[u8], key_info: Option<&[u8]>) -> Result<Self, BbsError> {
+        let secret_key = SecretKey::key_gen(ikm, key_info)?;
+        let public_key = secret_key.public_key();
+
+        Ok(KeyPair {
+            secret_key,
+            public_key,
+        })
+    }
+}
+
+impl SecretKey {
+    // KeyGen, with HKDF instantiated with SHA-256. IKM must be at least 32 bytes
+    pub fn key_gen(ikm: &[u8], key_info: Option<&[u8]>) -> Result<Self, BbsError> {
+        if ikm.len() < 32 {
+            return Err(BbsError::InvalidLength("IKM must be at least 32 octets"));
+        }
+
+        let ikm = Zeroizing::new([ikm, &i2osp(0, 1)].concat());
+        let info = [key_info.unwrap_or(&[]), &i2osp(KEYGEN_L as u64, 2)].concat();
+
+        let mut salt = KEYGEN_SALT.to_vec();
+        let mut sk = Scalar::zero();
+        while bool::from(sk.is_zero()) {
+            salt = Sha256::digest(&salt).to_vec();
+
+            let mut okm = Zeroizing::new([0u8; KEYGEN_L]);
+            Hkdf::<Sha256>::new(Some(&salt), &ikm)
+                .expand(&info, &mut *okm)
+                .map_err(|_| BbsError::InvalidLength("key_info is too long for HKDF-Expand"))?;
+            sk = os2ip(&*okm);
+        }
+
+        Ok(SecretKey(sk))
+    }
+
+    // SkToPk
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(Backend::g2_generator() * self.0)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        scalar_to_bytes(&self.0)
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, BbsError> {
+        let sk = scalar_from_bytes(bytes)?;
+        if bool::from(sk.is_zero()) {
+            return Err(BbsError::InvalidScalar);
+        }
+        Ok(SecretKey(sk))
+    }
+}
+
+// The secret key is overwritten with zero when dropped
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        zeroize_scalar(&mut self.0);
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretKey {}
+
+impl PublicKey {
+    // KeyValidate, rejects Identity_G2 and points that are not in the G2 subgroup. from_bytes already
+    // applies these checks, verification runs them again unless the caller opts out
+    pub fn validate(&self) -> bool {
+        !Backend::g2_is_identity(&self.0) && Backend::g2_is_valid(&self.0)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 96] {
+        Backend::g2_to_bytes(&self.0)
+    }
+
+    // octets_to_pubkey, from_compressed performs the subgroup check
+    pub fn from_bytes(bytes: &[u8; 96]) -> Result<Self, BbsError> {
+        Self::from_bytes_with_mode(bytes, DeserializeMode::Strict)
+    }
+
+    // As from_bytes, with the checks of mode. Verification still runs KeyValidate on a key decoded
+    // leniently, unless it is verified with verify_prevalidated
+    pub fn from_bytes_with_mode(bytes: &[u8; 96], mode: DeserializeMode) -> Result<Self, BbsError> {
+        match mode {
+            DeserializeMode::Strict => {
+                let w = Backend::g2_from_bytes(bytes).ok_or(BbsError::InvalidPoint)?;
+                if Backend::g2_is_identity(&w) {
+                    return Err(BbsError::InvalidPoint);
+                }
+                Ok(PublicKey(w))
+            }
+            DeserializeMode::Lenient => Backend::g2_from_bytes_unchecked(bytes).map(PublicKey).ok_or(BbsError::InvalidPoint),
+        }
+    }
+}
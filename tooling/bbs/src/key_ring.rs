@@ -0,0 +1,289 @@
+// Rotation of issuer keys. The issuer names each of its keys by a key id and signs with a header
+// starting with a KeyHeader, the key id and the epoch of issuance, so the header, and with it the key
+// it names, is bound to the signature and to every proof derived from it. Verifiers keep an
+// IssuerKeyRing of the issuer's public keys, each valid for issuance in a window of epochs, and the
+// verify functions select the key named by the header. A key is retired by closing its window:
+// credentials issued before keep verifying, a signature or proof claiming a later epoch does not
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::Scalar;
+use crate::encoding::i2osp;
+use crate::error::BbsError;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::proof::Proof;
+use crate::signature::Signature;
+
+const KEY_HEADER_TAG: &[u8] = b"BBS_KEY_ID_V1_";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyHeader {
+    pub key_id: String,
+    // The issuance time, e.g. seconds since the Unix epoch, in the unit of the key ring's windows
+    pub epoch: u64,
+}
+
+pub struct IssuerKey {
+    pub key_id: String,
+    pub pk: PublicKey,
+    // The first epoch the key issues at
+    pub not_before: u64,
+    // The first epoch the key no longer issues at, None while it is current
+    pub not_after: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct IssuerKeyRing {
+    keys: BTreeMap<String, IssuerKey>,
+}
+
+impl KeyHeader {
+    pub fn new(key_id: &str, epoch: u64) -> Self {
+        KeyHeader { key_id: key_id.into(), epoch }
+    }
+
+    // "BBS_KEY_ID_V1_" || I2OSP(length(key_id), 8) || key_id || I2OSP(epoch, 8) || header, the header of
+    // the signature with the application's header, if any, following the key id
+    pub fn to_header(&self, header: Option<&[u8]>) -> Vec<u8> {
+        let mut octets = KEY_HEADER_TAG.to_vec();
+        octets.extend_from_slice(&i2osp(self.key_id.len() as u64, 8));
+        octets.extend_from_slice(self.key_id.as_bytes());
+        octets.extend_from_slice(&i2osp(self.epoch, 8));
+        octets.extend_from_slice(header.unwrap_or(&[]));
+        octets
+    }
+
+    // The key header and the application's header following it
+    pub fn from_header(header: &[u8]) -> Result<(Self, &[u8]), BbsError> {
+        let rest = header
+            .strip_prefix(KEY_HEADER_TAG)
+            .ok_or_else(|| BbsError::Serialization("the header does not start with a key id".into()))?;
+        if rest.len() < 16 {
+            return Err(BbsError::InvalidLength("a key header is at least 16 octets after the tag"));
+        }
+        let (len, rest) = rest.split_at(8);
+        let len = u64::from_be_bytes(len.try_into().expect("8 octets"));
+        if len > rest.len() as u64 - 8 {
+            return Err(BbsError::InvalidLength("the key id length exceeds the header"));
+        }
+        let (key_id, rest) = rest.split_at(len as usize);
+        let (epoch, rest) = rest.split_at(8);
+        let key_id = String::from_utf8(key_id.to_vec())
+            .map_err(|_| BbsError::Serialization("the key id is not UTF-8".into()))?;
+        let epoch = u64::from_be_bytes(epoch.try_into().expect("8 octets"));
+        Ok((KeyHeader { key_id, epoch }, rest))
+    }
+}
+
+impl IssuerKey {
+    pub fn is_valid_at(&self, epoch: u64) -> bool {
+        self.not_before <= epoch && self.not_after.map_or(true, |not_after| epoch < not_after)
+    }
+}
+
+impl IssuerKeyRing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds a key issuing from not_before on, InvalidKeyId when key_id is already in the ring
+    pub fn add(&mut self, key_id: &str, pk: PublicKey, not_before: u64) -> Result<(), BbsError> {
+        if self.keys.contains_key(key_id) {
+            return Err(BbsError::InvalidKeyId(format!("{} is already in the key ring", key_id)));
+        }
+        let key = IssuerKey { key_id: key_id.into(), pk, not_before, not_after: None };
+        self.keys.insert(key_id.into(), key);
+        Ok(())
+    }
+
+    // Stops key_id issuing from not_after on, InvalidKeyId when it is not in the ring. Signatures of
+    // earlier epochs keep verifying
+    pub fn retire(&mut self, key_id: &str, not_after: u64) -> Result<(), BbsError> {
+        let key = self
+            .keys
+            .get_mut(key_id)
+            .ok_or_else(|| BbsError::InvalidKeyId(format!("{} is not in the key ring", key_id)))?;
+        key.not_after = Some(not_after);
+        Ok(())
+    }
+
+    // Rejects everything the key signed, e.g. once it is compromised
+    pub fn remove(&mut self, key_id: &str) -> Option<IssuerKey> {
+        self.keys.remove(key_id)
+    }
+
+    pub fn get(&self, key_id: &str) -> Option<&IssuerKey> {
+        self.keys.get(key_id)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &IssuerKey> {
+        self.keys.values()
+    }
+
+    // The keys valid for issuance at epoch, more than one while rotations overlap
+    pub fn valid_at(&self, epoch: u64) -> impl Iterator<Item = &IssuerKey> {
+        self.keys.values().filter(move |key| key.is_valid_at(epoch))
+    }
+
+    // The public key named by a header made with KeyHeader::to_header, None when the header names no
+    // key of the ring or an epoch outside the window of the key
+    pub fn select(&self, header: &[u8]) -> Option<&PublicKey> {
+        let (key_header, _) = KeyHeader::from_header(header).ok()?;
+        self.keys
+            .get(&key_header.key_id)
+            .filter(|key| key.is_valid_at(key_header.epoch))
+            .map(|key| &key.pk)
+    }
+
+    // Verifies a signature with the key its header selects
+    pub fn verify_signature<'a, X>(&self, signature: &Signature, messages: &[Scalar], header: &[u8]) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        match self.select(header) {
+            Some(pk) => signature.verify::<X>(pk, messages, Some(header)),
+            None => false,
+        }
+    }
+
+    // Verifies a proof with the key the header of its signature selects
+    pub fn verify_proof<'a, X>(
+        &self,
+        proof: &Proof,
+        header: &[u8],
+        ph: Option<&[u8]>,
+        disclosed_messages: &BTreeMap<usize, Scalar>,
+    ) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        match self.select(header) {
+            Some(pk) => proof.verify::<X>(pk, Some(header), ph, disclosed_messages),
+            None => false,
+        }
+    }
+}
+
+// Signature::sign with key_header, then header, as the header. Returns the header the holder passes on
+// to proof generation and the verifier to the key ring
+pub fn sign_with_key_id<'a, X>(
+    sk: &SecretKey,
+    key_header: &KeyHeader,
+    header: Option<&[u8]>,
+    messages: &[Scalar],
+) -> Result<(Signature, Vec<u8>), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let header = key_header.to_header(header);
+    let signature = Signature::sign::<X>(messages, sk, Some(&header))?;
+    Ok((signature, header))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+    const OTHER_IKM: &[u8] = b"another-Test-IKM-to-generate-the-2nd-issuer-key";
+
+    // "previous" issuing until 100, "current" from 100 on
+    fn key_ring() -> (KeyPair, IssuerKeyRing) {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let mut key_ring = IssuerKeyRing::new();
+        key_ring.add("previous", KeyPair::from_ikm(OTHER_IKM, None).unwrap().public_key, 0).unwrap();
+        key_ring.add("current", key_pair.secret_key.public_key(), 100).unwrap();
+        key_ring.retire("previous", 100).unwrap();
+        (key_pair, key_ring)
+    }
+
+    fn key_ring_verifies<'a, X>()
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let (key_pair, mut key_ring) = key_ring();
+        let messages = [Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64)];
+        let (signature, header) =
+            sign_with_key_id::<X>(&key_pair.secret_key, &KeyHeader::new("current", 150), Some(b"header"), &messages).unwrap();
+        assert!(header.ends_with(b"header"));
+        assert!(key_ring.verify_signature::<X>(&signature, &messages, &header));
+
+        let rng = &mut StdRng::seed_from_u64(0);
+        let proof =
+            Proof::generate_with_rng::<X, _>(&signature, &key_pair.public_key, Some(&header), None, &messages, &[0, 2], rng).unwrap();
+        let disclosed = BTreeMap::from([(0, messages[0]), (2, messages[2])]);
+        assert!(key_ring.verify_proof::<X>(&proof, &header, None, &disclosed));
+
+        // The header naming the other key, or an epoch before the key issues
+        let forged = KeyHeader::new("previous", 150).to_header(Some(b"header"));
+        assert!(!key_ring.verify_signature::<X>(&signature, &messages, &forged));
+        let early = KeyHeader::new("current", 50).to_header(Some(b"header"));
+        assert!(!key_ring.verify_signature::<X>(&signature, &messages, &early));
+
+        // Retired after the signature it keeps verifying, retired at its epoch it does not
+        key_ring.retire("current", 200).unwrap();
+        assert!(key_ring.verify_proof::<X>(&proof, &header, None, &disclosed));
+        key_ring.retire("current", 150).unwrap();
+        assert!(!key_ring.verify_signature::<X>(&signature, &messages, &header));
+        assert!(!key_ring.verify_proof::<X>(&proof, &header, None, &disclosed));
+    }
+
+    #[test]
+    fn key_ring_verifies_with_the_key_of_the_header() {
+        key_ring_verifies::<Bls12381Sha256>();
+        key_ring_verifies::<Bls12381Shake256>();
+    }
+
+    #[test]
+    fn key_ring_rejects_unknown_and_duplicate_key_ids() {
+        let (key_pair, mut key_ring) = key_ring();
+        assert!(matches!(key_ring.add("current", key_pair.public_key, 0), Err(BbsError::InvalidKeyId(_))));
+        assert!(matches!(key_ring.retire("next", 0), Err(BbsError::InvalidKeyId(_))));
+
+        assert!(key_ring.remove("previous").is_some());
+        assert!(key_ring.get("previous").is_none());
+        assert!(key_ring.select(&KeyHeader::new("previous", 50).to_header(None)).is_none());
+        assert_eq!(key_ring.keys().count(), 1);
+    }
+
+    #[test]
+    fn valid_at_follows_the_windows() {
+        let (_, key_ring) = key_ring();
+        let valid_at = |epoch| key_ring.valid_at(epoch).map(|key| key.key_id.as_str()).collect::<Vec<_>>();
+        assert_eq!(valid_at(0), ["previous"]);
+        assert_eq!(valid_at(99), ["previous"]);
+        assert_eq!(valid_at(100), ["current"]);
+    }
+
+    #[test]
+    fn key_header_round_trip() {
+        let key_header = KeyHeader::new("key-1", 1_700_000_000);
+        let header = key_header.to_header(Some(b"header"));
+        let (decoded, rest) = KeyHeader::from_header(&header).unwrap();
+        assert_eq!(decoded, key_header);
+        assert_eq!(rest, b"header");
+
+        let (decoded, rest) = KeyHeader::from_header(&KeyHeader::new("", 0).to_header(None)).unwrap();
+        assert_eq!(decoded, KeyHeader::new("", 0));
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn from_header_rejects_malformed_headers() {
+        let header = KeyHeader::new("key-1", 7).to_header(None);
+        assert!(matches!(KeyHeader::from_header(b"header"), Err(BbsError::Serialization(_))));
+        assert!(matches!(KeyHeader::from_header(&header[..header.len() - 1]), Err(BbsError::InvalidLength(_))));
+        assert!(matches!(KeyHeader::from_header(&header[..KEY_HEADER_TAG.len() + 8]), Err(BbsError::InvalidLength(_))));
+
+        let mut not_utf8 = header.clone();
+        not_utf8[KEY_HEADER_TAG.len() + 8] = 0xff;
+        assert!(matches!(KeyHeader::from_header(&not_utf8), Err(BbsError::Serialization(_))));
+    }
+}
@@ -0,0 +1,208 @@
+// Selective disclosure by label rather than by message index. Issuer, holder and verifier share the
+// layout of a credential, a label for every message index, e.g. ["givenName", "familyName",
+// "birthDate"], so the holder discloses ["givenName", "birthDate"] and the verifier gets the disclosed
+// messages keyed by label
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::Scalar;
+use crate::error::BbsError;
+use crate::key_pair::PublicKey;
+use crate::proof::Proof;
+use crate::signature::Signature;
+
+pub struct LabeledMessages {
+    labels: Vec<String>,
+}
+
+impl LabeledMessages {
+    // labels[i] is the label of message i, InvalidLabel when a label is repeated
+    pub fn new(labels: &[&str]) -> Result<Self, BbsError> {
+        let mut labeled = LabeledMessages { labels: Vec::new() };
+        for label in labels {
+            labeled.register(label)?;
+        }
+        Ok(labeled)
+    }
+
+    // Labels the next message index, which it returns
+    pub fn register(&mut self, label: &str) -> Result<usize, BbsError> {
+        if self.index(label).is_some() {
+            return Err(BbsError::InvalidLabel(format!("{} is registered twice", label)));
+        }
+        self.labels.push(label.into());
+        Ok(self.labels.len() - 1)
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn index(&self, label: &str) -> Option<usize> {
+        self.labels.iter().position(|l| l == label)
+    }
+
+    // The indexes of the labels in ascending order, as taken by proof generation. InvalidLabel for a
+    // label that is not registered
+    pub fn indexes(&self, labels: &[&str]) -> Result<Vec<usize>, BbsError> {
+        let mut indexes = labels
+            .iter()
+            .map(|label| {
+                self.index(label)
+                    .ok_or_else(|| BbsError::InvalidLabel(format!("{} is not registered", label)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        indexes.sort_unstable();
+        indexes.dedup();
+        Ok(indexes)
+    }
+
+    // Generates a proof disclosing the messages labeled disclose. Returns the proof with the disclosed
+    // messages keyed by index, both sent to the verifier
+    #[cfg(feature = "std")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn proof_gen<'a, X>(
+        &self,
+        signature: &Signature,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        messages: &[Scalar],
+        disclose: &[&str],
+    ) -> Result<(Proof, BTreeMap<usize, Scalar>), BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.proof_gen_with_rng::<X, _>(signature, pk, header, ph, messages, disclose, &mut rand::thread_rng())
+    }
+
+    // As proof_gen, with the random scalars drawn from rng
+    #[allow(clippy::too_many_arguments)]
+    pub fn proof_gen_with_rng<'a, X, R>(
+        &self,
+        signature: &Signature,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        messages: &[Scalar],
+        disclose: &[&str],
+        rng: &mut R,
+    ) -> Result<(Proof, BTreeMap<usize, Scalar>), BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        if messages.len() != self.labels.len() {
+            return Err(BbsError::InvalidLength("one message per label"));
+        }
+
+        let disclosed_indexes = self.indexes(disclose)?;
+        let proof = Proof::generate_with_rng::<X, _>(signature, pk, header, ph, messages, &disclosed_indexes, rng)?;
+        let disclosed = disclosed_indexes.iter().map(|&i| (i, messages[i])).collect();
+        Ok((proof, disclosed))
+    }
+
+    // Verifies the proof and returns the disclosed messages keyed by label, None when the proof does
+    // not verify or is not over one message per label
+    pub fn verify_proof<'a, X>(
+        &self,
+        proof: &Proof,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        disclosed: &BTreeMap<usize, Scalar>,
+    ) -> Option<BTreeMap<String, Scalar>>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if proof.m_hat().len() + disclosed.len() != self.labels.len() {
+            return None;
+        }
+        if !proof.verify::<X>(pk, header, ph, disclosed) {
+            return None;
+        }
+        Some(disclosed.iter().map(|(&i, msg)| (self.labels[i].clone(), *msg)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::Bls12381Sha256;
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    fn layout() -> LabeledMessages {
+        LabeledMessages::new(&["givenName", "familyName", "birthDate"]).unwrap()
+    }
+
+    #[test]
+    fn discloses_by_label() {
+        let layout = layout();
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let messages: Vec<Scalar> = (1..=3u64).map(Scalar::from).collect();
+        let signature = Signature::sign::<Bls12381Sha256>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+
+        let rng = &mut StdRng::seed_from_u64(0);
+        let disclose = ["birthDate", "givenName"];
+        let (proof, disclosed) = layout
+            .proof_gen_with_rng::<Bls12381Sha256, _>(&signature, &key_pair.public_key, Some(b"header"), Some(b"ph"), &messages, &disclose, rng)
+            .unwrap();
+        assert_eq!(disclosed, BTreeMap::from([(0, messages[0]), (2, messages[2])]));
+
+        let revealed = layout
+            .verify_proof::<Bls12381Sha256>(&proof, &key_pair.public_key, Some(b"header"), Some(b"ph"), &disclosed)
+            .unwrap();
+        assert_eq!(revealed.len(), 2);
+        assert_eq!(revealed["givenName"], messages[0]);
+        assert_eq!(revealed["birthDate"], messages[2]);
+
+        // Another presentation header, or a layout with another number of labels
+        let other_ph = layout.verify_proof::<Bls12381Sha256>(&proof, &key_pair.public_key, Some(b"header"), Some(b"other"), &disclosed);
+        assert!(other_ph.is_none());
+        let longer = LabeledMessages::new(&["givenName", "familyName", "birthDate", "email"]).unwrap();
+        assert!(longer.verify_proof::<Bls12381Sha256>(&proof, &key_pair.public_key, Some(b"header"), Some(b"ph"), &disclosed).is_none());
+
+        let result = layout.proof_gen_with_rng::<Bls12381Sha256, _>(&signature, &key_pair.public_key, None, None, &messages[..2], &[], rng);
+        assert!(matches!(result, Err(BbsError::InvalidLength(_))));
+        let result = layout.proof_gen_with_rng::<Bls12381Sha256, _>(&signature, &key_pair.public_key, None, None, &messages, &["email"], rng);
+        assert!(matches!(result, Err(BbsError::InvalidLabel(_))));
+    }
+
+    #[test]
+    fn registers_each_label_once() {
+        let mut layout = layout();
+        assert_eq!(layout.len(), 3);
+        assert_eq!(layout.register("email").unwrap(), 3);
+        assert_eq!(layout.index("email"), Some(3));
+        assert_eq!(layout.labels()[1], "familyName");
+
+        assert!(matches!(layout.register("givenName"), Err(BbsError::InvalidLabel(_))));
+        assert!(matches!(LabeledMessages::new(&["a", "b", "a"]), Err(BbsError::InvalidLabel(_))));
+        assert!(LabeledMessages::new(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn indexes_are_ascending_and_unique() {
+        let layout = layout();
+        assert_eq!(layout.indexes(&["birthDate", "givenName", "birthDate"]).unwrap(), [0, 2]);
+        assert!(layout.indexes(&[]).unwrap().is_empty());
+        assert!(matches!(layout.indexes(&["givenName", "email"]), Err(BbsError::InvalidLabel(_))));
+    }
+}
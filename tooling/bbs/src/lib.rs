@@ -0,0 +1,74 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "accumulator")]
+pub mod accumulator;
+#[cfg(all(feature = "adx", target_arch = "x86_64"))]
+mod adx;
+#[cfg(feature = "non-standard-aggregation")]
+pub mod aggregate;
+pub mod audience;
+pub mod blind;
+#[cfg(feature = "std")]
+pub mod challenge;
+pub mod ciphersuites;
+#[cfg(feature = "cose")]
+pub mod cose;
+pub mod cpu;
+#[cfg(feature = "ct-tests")]
+pub mod ct;
+pub mod curve;
+#[cfg(feature = "data-integrity")]
+pub mod data_integrity;
+pub mod did;
+pub mod dyn_ciphersuite;
+pub mod encoding;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod g2;
+#[cfg(feature = "std")]
+pub mod generator_cache;
+pub mod generators;
+pub mod hashing;
+pub mod hd;
+#[cfg(feature = "jose")]
+pub mod jose;
+#[cfg(feature = "json-messages")]
+pub mod json_messages;
+#[cfg(feature = "jsonld")]
+pub mod jsonld;
+#[cfg(feature = "key-formats")]
+pub mod key_formats;
+pub mod key_pair;
+pub mod key_ring;
+pub mod labeled;
+pub mod limits;
+pub mod link_secret;
+pub mod message_encoder;
+pub mod precomputed;
+#[cfg(feature = "pkcs11")]
+pub mod pkcs11;
+pub mod predicate;
+pub mod proof;
+#[cfg(all(any(test, feature = "proptest"), feature = "std"))]
+pub mod props;
+#[cfg(feature = "range-proofs")]
+pub mod range;
+pub mod rdf;
+pub mod secret;
+pub mod signature;
+#[cfg(feature = "async-signer")]
+pub mod signer;
+pub mod status_list;
+pub mod threshold;
+mod trace;
+#[cfg(feature = "vc")]
+pub mod vc;
+
+pub use curve::Scalar;
+pub use error::BbsError;
+pub use key_pair::{KeyPair, PublicKey, SecretKey};
+pub use proof::Proof;
+pub use signature::Signature;
@@ -0,0 +1,44 @@
+// The largest number of messages the operations accept. Every message costs a generator hashed to the
+// curve, so without a limit a verifier handed a proof with millions of m^ scalars, or an issuer handed
+// an absurd message list, would derive millions of generators before rejecting it. Sign, ProofGen and
+// octets_to_proof fail with BbsError::TooManyMessages above the limit, Verify and ProofVerify return
+// false. The operations use DEFAULT_MAX_MESSAGES, their _with_limits variants take the Limits of the
+// call, so callers serving several tenants apply each tenant's limit without affecting the others
+use crate::error::BbsError;
+
+pub const DEFAULT_MAX_MESSAGES: usize = 65536;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub max_messages: usize,
+}
+
+impl Limits {
+    pub const DEFAULT: Limits = Limits { max_messages: DEFAULT_MAX_MESSAGES };
+
+    pub fn new(max_messages: usize) -> Self {
+        Limits { max_messages }
+    }
+
+    pub fn allows(&self, count: usize) -> bool {
+        count <= self.max_messages
+    }
+
+    pub fn check_message_count(&self, count: usize) -> Result<(), BbsError> {
+        if !self.allows(count) {
+            return Err(BbsError::TooManyMessages(count));
+        }
+        Ok(())
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// Limits::DEFAULT.check_message_count, for the operations without a _with_limits variant
+pub(crate) fn check_message_count(count: usize) -> Result<(), BbsError> {
+    Limits::DEFAULT.check_message_count(count)
+}
@@ -0,0 +1,454 @@
+// Link secrets, as in AnonCreds: a secret scalar of the holder signed as the last message of every
+// credential issued to the holder, without the issuers learning it. At issuance the holder commits to
+// it with C = Q_1 * secret_prover_blind + H_{L+1} * link_secret, H_{L+1} being the generator of the
+// message after the L messages of the issuer, and proves knowledge of the opening as in the blind
+// module. The issuer adds C to B, so the unblinded signature is a plain BBS signature over the
+// issuer's messages followed by the link secret and proofs are generated as for any other signature.
+// LinkSecretProof shows that one hidden link secret underlies every credential of a presentation, by
+// linking all their BBS proofs to one commitment V as predicate::LinkedProof does for two
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::blind::BlindSignature;
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::encoding::{encode_for_hash, g1_from_octets, scalar_from_bytes, scalar_to_bytes};
+use crate::error::BbsError;
+use crate::generators::make_generators;
+use crate::hashing::hash_to_scalar;
+use crate::key_pair::SecretKey;
+use crate::limits::check_message_count;
+use crate::predicate::{linked_ph, opens_hidden_message, predicate_generators, LinkedCredential, LinkedPresentation};
+use crate::proof::Proof;
+use crate::secret::{SecretScalar, SecretScalars};
+use crate::signature::{calculate_b, calculate_domain};
+
+pub struct LinkSecret(SecretScalar);
+
+// C, with the proof of knowledge of its opening, for a credential of message_count issuer messages
+pub struct LinkSecretCommitment {
+    pub(crate) commit: G1,
+    pub(crate) message_count: usize,
+    pub(crate) c: Scalar,
+    pub(crate) s_hat: Scalar,
+    pub(crate) m_hat: Scalar,
+}
+
+impl LinkSecret {
+    #[cfg(feature = "std")]
+    pub fn generate() -> Self {
+        Self::generate_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn generate_with_rng<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + CryptoRng
+    {
+        LinkSecret(SecretScalar(Scalar::random(rng)))
+    }
+
+    // For the holder's wallet to store, an error when the octets are not a scalar
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, BbsError> {
+        Ok(LinkSecret(SecretScalar(scalar_from_bytes(bytes)?)))
+    }
+
+    pub fn to_bytes(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(scalar_to_bytes(&self.0 .0))
+    }
+
+    // The messages a credential issued with sign_with_link_secret is signed over: the issuer's messages
+    // followed by the link secret, at index messages.len()
+    pub fn append_to(&self, messages: &[Scalar]) -> Vec<Scalar> {
+        messages.iter().copied().chain([self.0 .0]).collect()
+    }
+
+    // Run by the holder before the issuance of a credential with message_count messages of the issuer,
+    // returns the commitment to send to the issuer and the secret_prover_blind to unblind with
+    #[cfg(feature = "std")]
+    pub fn commit<'a, X>(&self, message_count: usize) -> Result<(LinkSecretCommitment, SecretScalar), BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.commit_with_rng::<X, _>(message_count, &mut rand::thread_rng())
+    }
+
+    // As commit, with secret_prover_blind and the blinding scalars of the proof drawn from rng
+    pub fn commit_with_rng<'a, X, R>(
+        &self,
+        message_count: usize,
+        rng: &mut R,
+    ) -> Result<(LinkSecretCommitment, SecretScalar), BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        check_message_count(message_count + 1)?;
+        let (q_1, h) = commitment_generators::<X>(message_count);
+
+        let secret_prover_blind = SecretScalar(Scalar::random(&mut *rng));
+        let s_tilde = SecretScalar(Scalar::random(&mut *rng));
+        let m_tilde = SecretScalar(Scalar::random(&mut *rng));
+
+        let commit = q_1 * secret_prover_blind.0 + h * self.0 .0;
+        let c_bar = q_1 * s_tilde.0 + h * m_tilde.0;
+        let c = calculate_commitment_challenge::<X>(&commit, &c_bar, message_count, &h);
+
+        let commitment = LinkSecretCommitment {
+            commit,
+            message_count,
+            c,
+            s_hat: s_tilde.0 + c * secret_prover_blind.0,
+            m_hat: m_tilde.0 + c * self.0 .0,
+        };
+        Ok((commitment, secret_prover_blind))
+    }
+}
+
+impl LinkSecretCommitment {
+    pub fn message_count(&self) -> usize {
+        self.message_count
+    }
+
+    // Run by the issuer before signing, checks the holder knows the opening of the commitment
+    pub fn verify<'a, X>(&self) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if check_message_count(self.message_count + 1).is_err() {
+            return false;
+        }
+        let (q_1, h) = commitment_generators::<X>(self.message_count);
+
+        // Cbar = Q_1 * s^ + H_{L+1} * m^ - C * c
+        let c_bar = q_1 * self.s_hat + h * self.m_hat - self.commit * self.c;
+
+        self.c == calculate_commitment_challenge::<X>(&self.commit, &c_bar, self.message_count, &h)
+    }
+}
+
+// Run by the issuer, messages are its L messages. InvalidCommitment when the commitment proof is
+// invalid or the commitment is for another number of messages
+pub fn sign_with_link_secret<'a, X>(
+    sk: &SecretKey,
+    commitment: &LinkSecretCommitment,
+    messages: &[Scalar],
+    header: Option<&[u8]>,
+) -> Result<BlindSignature, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    check_message_count(messages.len() + 1)?;
+    if messages.len() != commitment.message_count || !commitment.verify::<X>() {
+        return Err(BbsError::InvalidCommitment);
+    }
+
+    // The domain is the one of a signature over L + 1 messages, which the unblinded signature is
+    let pk = sk.public_key();
+    let generators = make_generators::<X>(None, messages.len() + 3);
+    let domain = calculate_domain::<X>(&pk, &generators.message_generators, header);
+
+    let e_s_for_hash = encode_for_hash(&[&sk.0, &domain, &messages, &commitment.commit]);
+    let scalars = hash_to_scalar::<X>(&e_s_for_hash, 2, None);
+    let (e, s) = (scalars[0], scalars[1]);
+
+    // B = P1 + Q_1 * s'' + Q_2 * domain + H_1 * msg_1 + ... + H_L * msg_L + C
+    let b = calculate_b(&generators, s, domain, messages) + commitment.commit;
+
+    let sk_e_inv: Scalar = Option::from((sk.0 + e).invert()).ok_or(BbsError::SigningFailed)?;
+
+    Ok(BlindSignature { a: b * sk_e_inv, e, s })
+}
+
+// A proof that the hidden messages at the index of every credential, their link secret, are equal.
+// V = G * link_secret + H * gamma, and for every credential T_j = G * m~_j + H * gamma~_j with
+// gamma^_j = c_j * gamma + gamma~_j, c_j being the challenge of its BBS proof
+pub struct LinkSecretProof {
+    pub(crate) v: G1,
+    pub(crate) t: Vec<G1>,
+    pub(crate) gamma_hat: Vec<Scalar>,
+}
+
+impl LinkSecretProof {
+    // Generates the BBS proofs of credentials, in order, with the proof that they share the link secret.
+    // Every proof binds V and all the T_j into ph, so none is accepted in another presentation
+    pub fn generate<'a, X, R>(
+        credentials: &[LinkedCredential],
+        ph: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<(Vec<Proof>, Self), BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        let first = credentials
+            .first()
+            .ok_or(BbsError::InvalidLength("a link secret proof covers at least one credential"))?;
+        for credential in credentials {
+            if credential.index >= credential.messages.len() || credential.disclosed_indexes.contains(&credential.index) {
+                return Err(BbsError::InvalidDisclosedIndexes);
+            }
+        }
+        // The link secrets differ
+        let link_secret = first.messages[first.index];
+        if credentials.iter().any(|credential| credential.messages[credential.index] != link_secret) {
+            return Err(BbsError::ProofGenerationFailed);
+        }
+
+        let (g, h) = predicate_generators::<X>();
+        let gamma = SecretScalar(Scalar::random(&mut *rng));
+        let m_tilde = SecretScalars(credentials.iter().map(|_| Scalar::random(&mut *rng)).collect());
+        let gamma_tilde = SecretScalars(credentials.iter().map(|_| Scalar::random(&mut *rng)).collect());
+        let v = g * link_secret + h * gamma.0;
+        let t: Vec<G1> = m_tilde.iter().zip(gamma_tilde.iter()).map(|(m, gm)| g * m + h * gm).collect();
+        let linked_ph = linked_ph(ph, &v, &t[0], &t[1..]);
+
+        let mut proofs = Vec::with_capacity(credentials.len());
+        for (credential, m_tilde) in credentials.iter().zip(m_tilde.iter()) {
+            proofs.push(Proof::generate_with_blindings::<X, _>(
+                credential.signature,
+                credential.pk,
+                credential.header,
+                Some(&linked_ph),
+                credential.messages,
+                credential.disclosed_indexes,
+                &BTreeMap::from([(credential.index, *m_tilde)]),
+                &mut *rng,
+            )?);
+        }
+
+        let gamma_hat = proofs
+            .iter()
+            .zip(gamma_tilde.iter())
+            .map(|(proof, gamma_tilde)| proof.challenge() * gamma.0 + gamma_tilde)
+            .collect();
+        Ok((proofs, LinkSecretProof { v, t, gamma_hat }))
+    }
+
+    // Verifies the BBS proofs of presentations, in the order they were generated, and that the messages
+    // at their index are equal
+    pub fn verify<'a, X>(&self, presentations: &[LinkedPresentation], ph: Option<&[u8]>) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if presentations.is_empty() || presentations.len() != self.t.len() {
+            return false;
+        }
+        let linked_ph = linked_ph(ph, &self.v, &self.t[0], &self.t[1..]);
+        let generators = predicate_generators::<X>();
+
+        presentations
+            .iter()
+            .zip(self.t.iter().zip(&self.gamma_hat))
+            .all(|(presentation, (t, gamma_hat))| {
+                !presentation.disclosed_messages.contains_key(&presentation.index)
+                    && presentation.proof.verify::<X>(presentation.pk, presentation.header, Some(&linked_ph), presentation.disclosed_messages)
+                    && opens_hidden_message(
+                        presentation.proof,
+                        presentation.disclosed_messages,
+                        presentation.index,
+                        generators,
+                        &self.v,
+                        t,
+                        gamma_hat,
+                    )
+            })
+    }
+
+    // V, T_1, ..., T_n, gamma^_1, ..., gamma^_n
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(48 + self.t.len() * (48 + 32));
+        for p in [&self.v].into_iter().chain(&self.t) {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(p));
+        }
+        for s in &self.gamma_hat {
+            bytes.extend_from_slice(&scalar_to_bytes(s));
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        if bytes.len() < 48 + 48 + 32 || (bytes.len() - 48) % (48 + 32) != 0 {
+            return Err(BbsError::InvalidLength("a link secret proof is V and a point and a scalar per credential"));
+        }
+        let count = (bytes.len() - 48) / (48 + 32);
+
+        let (points, scalars) = bytes.split_at(48 * (count + 1));
+        let points: Vec<G1> = points
+            .chunks(48)
+            .map(g1_from_octets)
+            .collect::<Result<_, _>>()?;
+        let gamma_hat: Vec<Scalar> = scalars
+            .chunks(32)
+            .map(|s| scalar_from_bytes(s.try_into().expect("32 octet chunk")))
+            .collect::<Result<_, _>>()?;
+
+        Ok(LinkSecretProof {
+            v: points[0],
+            t: points[1..].to_vec(),
+            gamma_hat,
+        })
+    }
+}
+
+// Q_1 and H_{L+1}, the generator of the link secret after L messages of the issuer
+fn commitment_generators<'a, X>(message_count: usize) -> (G1, G1)
+where
+    X: BbsCiphersuite<'a>
+{
+    let generators = make_generators::<X>(None, message_count + 3);
+    (generators.message_generators[0], generators.message_generators[message_count + 2])
+}
+
+// c = hash_to_scalar(C || Cbar || I2OSP(L, 8) || H_{L+1}, 1)
+fn calculate_commitment_challenge<'a, X>(commit: &G1, c_bar: &G1, message_count: usize, h: &G1) -> Scalar
+where
+    X: BbsCiphersuite<'a>
+{
+    let c_for_hash = encode_for_hash(&[commit, c_bar, &message_count, h]);
+
+    hash_to_scalar::<X>(&c_for_hash, 1, None)[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+    use crate::key_pair::KeyPair;
+    use crate::signature::Signature;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+    const OTHER_IKM: &[u8] = b"another-Test-IKM-to-generate-the-2nd-issuer-key";
+
+    // Two messages of the issuer followed by link_secret, as the holder and issuer run the issuance
+    fn issue<'a, X>(ikm: &[u8], link_secret: &LinkSecret, rng: &mut StdRng) -> (KeyPair, Vec<Scalar>, Signature)
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let key_pair = KeyPair::from_ikm(ikm, None).unwrap();
+        let messages = alloc::vec![Scalar::from(1u64), Scalar::from(2u64)];
+        let (commitment, secret_prover_blind) = link_secret.commit_with_rng::<X, _>(messages.len(), rng).unwrap();
+        assert!(commitment.verify::<X>());
+        let signature = sign_with_link_secret::<X>(&key_pair.secret_key, &commitment, &messages, Some(b"header"))
+            .unwrap()
+            .unblind(&secret_prover_blind);
+        let messages = link_secret.append_to(&messages);
+        assert!(signature.verify::<X>(&key_pair.public_key, &messages, Some(b"header")));
+        (key_pair, messages, signature)
+    }
+
+    // Disclosing the first message, the link secret is at 2
+    fn credential<'c>(key_pair: &'c KeyPair, messages: &'c [Scalar], signature: &'c Signature) -> LinkedCredential<'c> {
+        LinkedCredential {
+            signature,
+            pk: &key_pair.public_key,
+            header: Some(b"header"),
+            messages,
+            disclosed_indexes: &[0],
+            index: 2,
+        }
+    }
+
+    fn presentation<'c>(proof: &'c Proof, key_pair: &'c KeyPair, disclosed: &'c BTreeMap<usize, Scalar>) -> LinkedPresentation<'c> {
+        LinkedPresentation {
+            proof,
+            pk: &key_pair.public_key,
+            header: Some(b"header"),
+            disclosed_messages: disclosed,
+            index: 2,
+        }
+    }
+
+    fn linked_credentials<'a, X>()
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let link_secret = LinkSecret::generate_with_rng(rng);
+        let (key_pair_a, messages_a, signature_a) = issue::<X>(IKM, &link_secret, rng);
+        let (key_pair_b, messages_b, signature_b) = issue::<X>(OTHER_IKM, &link_secret, rng);
+
+        let credentials = [
+            credential(&key_pair_a, &messages_a, &signature_a),
+            credential(&key_pair_b, &messages_b, &signature_b),
+        ];
+        let (proofs, linked) = LinkSecretProof::generate::<X, _>(&credentials, Some(b"ph"), rng).unwrap();
+
+        let disclosed = BTreeMap::from([(0, Scalar::from(1u64))]);
+        let presentations = [
+            presentation(&proofs[0], &key_pair_a, &disclosed),
+            presentation(&proofs[1], &key_pair_b, &disclosed),
+        ];
+        assert!(linked.verify::<X>(&presentations, Some(b"ph")));
+        assert!(LinkSecretProof::from_bytes(&linked.to_bytes()).unwrap().verify::<X>(&presentations, Some(b"ph")));
+
+        // Another presentation header, the presentations swapped or one of them left out
+        assert!(!linked.verify::<X>(&presentations, Some(b"another ph")));
+        let swapped = [
+            presentation(&proofs[1], &key_pair_b, &disclosed),
+            presentation(&proofs[0], &key_pair_a, &disclosed),
+        ];
+        assert!(!linked.verify::<X>(&swapped, Some(b"ph")));
+        assert!(!linked.verify::<X>(&presentations[..1], Some(b"ph")));
+    }
+
+    #[test]
+    fn link_secret_proof_links_credentials_of_two_issuers() {
+        linked_credentials::<Bls12381Sha256>();
+        linked_credentials::<Bls12381Shake256>();
+    }
+
+    #[test]
+    fn credentials_over_another_link_secret_are_not_linked() {
+        let rng = &mut StdRng::seed_from_u64(1);
+        let (link_secret, other) = (LinkSecret::generate_with_rng(rng), LinkSecret::generate_with_rng(rng));
+        let (key_pair_a, messages_a, signature_a) = issue::<Bls12381Sha256>(IKM, &link_secret, rng);
+        let (key_pair_b, messages_b, signature_b) = issue::<Bls12381Sha256>(OTHER_IKM, &other, rng);
+
+        let credentials = [
+            credential(&key_pair_a, &messages_a, &signature_a),
+            credential(&key_pair_b, &messages_b, &signature_b),
+        ];
+        assert!(matches!(
+            LinkSecretProof::generate::<Bls12381Sha256, _>(&credentials, None, rng),
+            Err(BbsError::ProofGenerationFailed)
+        ));
+    }
+
+    #[test]
+    fn sign_with_link_secret_rejects_another_message_count() {
+        let rng = &mut StdRng::seed_from_u64(2);
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let link_secret = LinkSecret::generate_with_rng(rng);
+        let (commitment, _) = link_secret.commit_with_rng::<Bls12381Sha256, _>(3, rng).unwrap();
+        assert_eq!(commitment.message_count(), 3);
+
+        let messages = [Scalar::from(1u64), Scalar::from(2u64)];
+        assert!(matches!(
+            sign_with_link_secret::<Bls12381Sha256>(&key_pair.secret_key, &commitment, &messages, None),
+            Err(BbsError::InvalidCommitment)
+        ));
+        // A commitment of one ciphersuite does not verify in the other
+        assert!(!commitment.verify::<Bls12381Shake256>());
+    }
+
+    #[test]
+    fn link_secret_round_trip() {
+        let link_secret = LinkSecret::generate_with_rng(&mut StdRng::seed_from_u64(3));
+        let decoded = LinkSecret::from_bytes(&link_secret.to_bytes()).unwrap();
+        assert_eq!(decoded.append_to(&[]), link_secret.append_to(&[]));
+        assert!(LinkSecret::from_bytes(&[0xff; 32]).is_err());
+    }
+
+    #[test]
+    fn link_secret_proof_from_bytes_rejects_other_lengths() {
+        for length in [0, 48, 48 + 48, 48 + 48 + 32 + 1] {
+            assert!(matches!(LinkSecretProof::from_bytes(&alloc::vec![0u8; length]), Err(BbsError::InvalidLength(_))));
+        }
+    }
+}
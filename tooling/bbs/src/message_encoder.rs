@@ -0,0 +1,165 @@
+// Typed message values and their scalars, so that issuers and verifiers of a credential agree on the
+// message scalars without each inventing an encoding. Strings and byte strings are hashed with
+// MapMessageToScalarAsHash, a string as its UTF-8 octets, so they give the same scalars as untyped
+// messages. Integers, dates and booleans are the integer scalar itself, which range and predicate
+// proofs over hidden messages need:
+// - u64 n is n
+// - i64 n is n + 2^63, the order of the integers is kept within [0, 2^64)
+// - an ISO-8601 calendar date YYYY-MM-DD is the integer YYYYMMDD, ordered like the dates and below
+//   2^32 for the years 0 to 9999
+// - false is 0 and true is 1
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::Scalar;
+use crate::error::BbsError;
+use crate::hashing::map_message_to_scalar_as_hash;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageValue<'v> {
+    Str(&'v str),
+    U64(u64),
+    I64(i64),
+    // YYYY-MM-DD
+    Date(&'v str),
+    Bool(bool),
+    Bytes(&'v [u8]),
+}
+
+// Encodes message values to scalars, the hashed ones with dst, which defaults to
+// ciphersuite_id || "MAP_MSG_TO_SCALAR_AS_HASH_" as in map_message_to_scalar_as_hash
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessageEncoder<'d> {
+    dst: Option<&'d [u8]>,
+}
+
+impl<'d> MessageEncoder<'d> {
+    pub fn new() -> Self {
+        MessageEncoder { dst: None }
+    }
+
+    pub fn with_dst(dst: &'d [u8]) -> Self {
+        MessageEncoder { dst: Some(dst) }
+    }
+
+    pub fn encode<'a, X>(&self, value: &MessageValue) -> Result<Scalar, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        match *value {
+            MessageValue::Str(s) => map_message_to_scalar_as_hash::<X>(s.as_bytes(), self.dst),
+            MessageValue::Bytes(octets) => map_message_to_scalar_as_hash::<X>(octets, self.dst),
+            MessageValue::U64(n) => Ok(Scalar::from(n)),
+            MessageValue::I64(n) => Ok(Scalar::from((n as u64) ^ (1 << 63))),
+            MessageValue::Date(date) => parse_date(date).map(Scalar::from),
+            MessageValue::Bool(b) => Ok(Scalar::from(u64::from(b))),
+        }
+    }
+
+    pub fn encode_all<'a, X>(&self, values: &[MessageValue]) -> Result<Vec<Scalar>, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        values.iter().map(|value| self.encode::<X>(value)).collect()
+    }
+}
+
+// YYYY-MM-DD as the integer YYYYMMDD, an error unless it is a date of the proleptic Gregorian calendar
+fn parse_date(date: &str) -> Result<u64, BbsError> {
+    let invalid = || BbsError::Serialization("a date must be an ISO-8601 calendar date YYYY-MM-DD".to_string());
+
+    let octets = date.as_bytes();
+    if octets.len() != 10 || octets[4] != b'-' || octets[7] != b'-' {
+        return Err(invalid());
+    }
+    let number = |range: core::ops::Range<usize>| -> Result<u64, BbsError> {
+        let digits = &octets[range];
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return Err(invalid());
+        }
+        Ok(digits.iter().fold(0, |n, d| n * 10 + u64::from(d - b'0')))
+    };
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return Err(invalid()),
+    };
+    if day == 0 || day > days_in_month {
+        return Err(invalid());
+    }
+    Ok(year * 10000 + month * 100 + day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+
+    #[test]
+    fn encodes_typed_values() {
+        let encoder = MessageEncoder::new();
+        let encode = |value: MessageValue| encoder.encode::<Bls12381Sha256>(&value).unwrap();
+
+        assert_eq!(encode(MessageValue::U64(42)), Scalar::from(42u64));
+        assert_eq!(encode(MessageValue::I64(i64::MIN)), Scalar::from(0u64));
+        assert_eq!(encode(MessageValue::I64(-1)), Scalar::from((1u64 << 63) - 1));
+        assert_eq!(encode(MessageValue::I64(0)), Scalar::from(1u64 << 63));
+        assert_eq!(encode(MessageValue::I64(i64::MAX)), Scalar::from(u64::MAX));
+        assert_eq!(encode(MessageValue::Bool(false)), Scalar::from(0u64));
+        assert_eq!(encode(MessageValue::Bool(true)), Scalar::from(1u64));
+        assert_eq!(encode(MessageValue::Date("2024-02-29")), Scalar::from(20240229u64));
+        assert_eq!(encode(MessageValue::Date("0000-01-01")), Scalar::from(101u64));
+
+        // Strings are hashed as their UTF-8 octets, as untyped messages are
+        let hashed = map_message_to_scalar_as_hash::<Bls12381Sha256>(b"Alice", None).unwrap();
+        assert_eq!(encode(MessageValue::Str("Alice")), hashed);
+        assert_eq!(encode(MessageValue::Bytes(b"Alice")), hashed);
+        assert_ne!(encoder.encode::<Bls12381Shake256>(&MessageValue::Str("Alice")).unwrap(), hashed);
+
+        let with_dst = MessageEncoder::with_dst(b"another dst").encode::<Bls12381Sha256>(&MessageValue::Str("Alice")).unwrap();
+        assert_eq!(with_dst, map_message_to_scalar_as_hash::<Bls12381Sha256>(b"Alice", Some(b"another dst")).unwrap());
+        assert_ne!(with_dst, hashed);
+
+        let values = [MessageValue::U64(1), MessageValue::Bool(true), MessageValue::Str("Alice")];
+        assert_eq!(encoder.encode_all::<Bls12381Sha256>(&values).unwrap(), [Scalar::from(1u64), Scalar::from(1u64), hashed]);
+    }
+
+    #[test]
+    fn dates_keep_their_order() {
+        let dates = ["1999-12-31", "2000-01-01", "2000-02-29", "2000-03-01", "9999-12-31"];
+        let encoded: Vec<u64> = dates.iter().map(|date| parse_date(date).unwrap()).collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+        assert!(encoded[4] < 1 << 32);
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        let encoder = MessageEncoder::new();
+        let dates = [
+            "",
+            "2024-1-01",
+            "2024/01/01",
+            "20240101",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-04-31",
+            "2023-02-29",
+            "1900-02-29",
+            "2024-01-00",
+            "+024-01-01",
+            "2024-01-01T00:00:00Z",
+        ];
+        for date in dates {
+            let result = encoder.encode::<Bls12381Sha256>(&MessageValue::Date(date));
+            assert!(matches!(result, Err(BbsError::Serialization(_))), "{}", date);
+        }
+        let values = [MessageValue::U64(1), MessageValue::Date("2024-02-30")];
+        assert!(matches!(encoder.encode_all::<Bls12381Sha256>(&values), Err(BbsError::Serialization(_))));
+    }
+}
@@ -0,0 +1,104 @@
+// A BbsSigner over a secret key kept in a PKCS#11 token. PKCS#11 defines no BLS12-381 mechanism, so
+// the token can not compute with SK itself: for each signature the key is read from the token, used
+// and zeroized, and never stored by the signer. The key is either a generic secret object holding
+// I2OSP(SK, 32), which the token must allow to be read, or a blob wrapped with CKM_AES_KEY_WRAP_PAD
+// under an AES key of the token, which the token unwraps on every signature. A token with a vendor
+// mechanism for the scalar operations would implement BbsSigner directly
+use std::path::Path;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use zeroize::Zeroizing;
+
+use crate::curve::Scalar;
+use crate::error::BbsError;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::limits::Limits;
+use crate::signature::{Signature, SigningMode};
+use crate::signer::{sign_by_id, BbsSigner};
+
+pub enum KeySource {
+    // A CKO_SECRET_KEY object with the label whose CKA_VALUE is I2OSP(SK, 32)
+    Object { label: String },
+    // I2OSP(SK, 32) wrapped with CKM_AES_KEY_WRAP_PAD under the AES key with the label
+    Wrapped { wrapping_key_label: String, wrapped_key: Vec<u8> },
+}
+
+pub struct Pkcs11Signer {
+    session: Mutex<Session>,
+    source: KeySource,
+    pk: PublicKey,
+}
+
+impl Pkcs11Signer {
+    // Loads the PKCS#11 module, logs in to the token with token_label and checks that the key can be
+    // read, deriving the public key from it
+    pub fn open(module: &Path, token_label: &str, pin: &str, source: KeySource) -> Result<Self, BbsError> {
+        let pkcs11 = Pkcs11::new(module)?;
+        pkcs11.initialize(CInitializeArgs::OsThreads)?;
+
+        let mut slot = None;
+        for candidate in pkcs11.get_slots_with_token()? {
+            if pkcs11.get_token_info(candidate)?.label().trim_end() == token_label {
+                slot = Some(candidate);
+            }
+        }
+        let slot = slot.ok_or_else(|| BbsError::Serialization(format!("no PKCS#11 token {}", token_label)))?;
+
+        let session = pkcs11.open_ro_session(slot)?;
+        session.login(UserType::User, Some(pin))?;
+
+        let pk = secret_key(&session, &source)?.public_key();
+        Ok(Pkcs11Signer { session: Mutex::new(session), source, pk })
+    }
+}
+
+#[async_trait]
+impl BbsSigner for Pkcs11Signer {
+    fn public_key(&self) -> &PublicKey {
+        &self.pk
+    }
+
+    // The token calls block, so this runs to completion on the calling thread
+    async fn sign(&self, ciphersuite_id: &[u8], messages: &[Scalar], header: Option<&[u8]>) -> Result<Signature, BbsError> {
+        let session = self.session.lock().map_err(|_| BbsError::Serialization("the PKCS#11 session is poisoned".into()))?;
+        let sk = secret_key(&session, &self.source)?;
+        sign_by_id(ciphersuite_id, messages, &sk, header, SigningMode::Deterministic, &Limits::DEFAULT)
+    }
+}
+
+fn secret_key(session: &Session, source: &KeySource) -> Result<SecretKey, BbsError> {
+    let octets = match source {
+        KeySource::Object { label } => {
+            let key = find_key(session, label)?;
+            let value = session.get_attributes(key, &[AttributeType::Value])?;
+            match value.into_iter().next() {
+                Some(Attribute::Value(octets)) => Zeroizing::new(octets),
+                _ => return Err(BbsError::Serialization(format!("the PKCS#11 key {} can not be read", label))),
+            }
+        }
+        KeySource::Wrapped { wrapping_key_label, wrapped_key } => {
+            let wrapping_key = find_key(session, wrapping_key_label)?;
+            Zeroizing::new(session.decrypt(&Mechanism::AesKeyWrapPad, wrapping_key, wrapped_key)?)
+        }
+    };
+
+    let octets: &[u8; 32] = octets
+        .as_slice()
+        .try_into()
+        .map_err(|_| BbsError::InvalidLength("a secret key is 32 octets"))?;
+    SecretKey::from_bytes(octets)
+}
+
+fn find_key(session: &Session, label: &str) -> Result<ObjectHandle, BbsError> {
+    let template = [Attribute::Class(ObjectClass::SECRET_KEY), Attribute::Label(label.as_bytes().to_vec())];
+    session
+        .find_objects(&template)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| BbsError::Serialization(format!("no PKCS#11 key {}", label)))
+}
@@ -0,0 +1,79 @@
+// Fixed base tables for the generators of an issuer that signs many times. For each generator P
+// the table holds P * d * 16^w for every 4 bit digit d and window w, so P * s is 64 table lookups
+// and additions instead of 255 doublings and additions. The lookups read every entry of a row and
+// select the digit's one with subtle, so the multiplication stays constant time in s. A table is
+// 64 * 16 points, about 144 KiB per generator, made once with about 1200 group operations
+use alloc::vec::Vec;
+
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::generators::Generators;
+
+const WINDOW_BITS: usize = 4;
+const WINDOWS: usize = 256 / WINDOW_BITS;
+const DIGITS: usize = 1 << WINDOW_BITS;
+
+pub struct FixedBaseTable {
+    rows: Vec<[G1; DIGITS]>,
+}
+
+impl FixedBaseTable {
+    pub fn new(p: &G1) -> Self {
+        let mut base = *p;
+        let rows = (0..WINDOWS)
+            .map(|_| {
+                let mut row = [Backend::g1_identity(); DIGITS];
+                for d in 1..DIGITS {
+                    row[d] = row[d - 1] + base;
+                }
+                for _ in 0..WINDOW_BITS {
+                    base = base + base;
+                }
+                row
+            })
+            .collect();
+        FixedBaseTable { rows }
+    }
+
+    // P * s in constant time
+    pub fn mul(&self, s: &Scalar) -> G1 {
+        let bytes = Backend::scalar_to_bytes(s);
+        let mut acc = Backend::g1_identity();
+        for (w, row) in self.rows.iter().enumerate() {
+            let digit = (bytes[31 - w / 2] >> (4 * (w % 2))) & 0x0f;
+            let mut entry = Backend::g1_identity();
+            for (d, p) in row.iter().enumerate() {
+                entry.conditional_assign(p, (d as u8).ct_eq(&digit));
+            }
+            acc += entry;
+        }
+        acc
+    }
+}
+
+// Generators with fixed base tables for Q_1, Q_2 and the first message generators H_i, made by
+// Generators::precompute and passed to Signature::sign_precomputed
+pub struct PrecomputedGenerators {
+    pub generators: Generators,
+    pub(crate) tables: Vec<FixedBaseTable>,
+}
+
+impl PrecomputedGenerators {
+    // message_generators[i] * s, from the table when there is one for it
+    pub(crate) fn mul(&self, i: usize, s: &Scalar) -> G1 {
+        match self.tables.get(i) {
+            Some(table) => table.mul(s),
+            None => self.generators.message_generators[i] * s,
+        }
+    }
+
+    // B = P1 + Q_1 * s + Q_2 * domain + H_1 * msg_1 + ... + H_L * msg_L, as calculate_b
+    pub(crate) fn calculate_b(&self, s: Scalar, domain: Scalar, messages: &[Scalar]) -> G1 {
+        let mut b = self.generators.g1_base_point + self.mul(0, &s) + self.mul(1, &domain);
+        for (i, msg) in messages.iter().enumerate() {
+            b += self.mul(i + 2, msg);
+        }
+        b
+    }
+}
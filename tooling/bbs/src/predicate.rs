@@ -0,0 +1,539 @@
+// Predicate proofs over hidden messages, composed with a BBS proof through its challenge. A predicate
+// proof commits to the hidden message with V = G * msg + H * gamma, and T = G * m~ + H * gamma~ links V
+// to the message through its blinding factor in the BBS proof, as in the range module. The commitments
+// of the predicate's own sigma protocol are bound into the presentation header as well, so the BBS
+// challenge c is the Fiat-Shamir challenge of both proofs
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::encoding::{g1_from_octets, scalar_from_bytes, scalar_to_bytes};
+use crate::error::BbsError;
+use crate::generators::create_generators;
+use crate::key_pair::PublicKey;
+use crate::proof::Proof;
+use crate::secret::SecretScalar;
+use crate::signature::Signature;
+
+// A proof that the committed message is one of a set of scalars, e.g. the country codes of the EU,
+// without revealing which. It is an OR of proofs of knowledge of gamma in V - G * set_i = H * gamma,
+// the branches other than the true one are simulated (Cramer, Damgård and Schoenmakers)
+pub struct SetMembershipProof {
+    // V = G * msg + H * gamma
+    pub(crate) v: G1,
+    // T = G * m~ + H * gamma~
+    pub(crate) t: G1,
+    pub(crate) gamma_hat: Scalar,
+    // R_i, c_i and z_i of every branch, with c_1 + ... + c_k = c
+    pub(crate) commitments: Vec<G1>,
+    pub(crate) challenges: Vec<Scalar>,
+    pub(crate) responses: Vec<Scalar>,
+}
+
+// G and H of the predicate commitments, created from their own seed like the blind generators
+pub fn predicate_generators<'a, X>() -> (G1, G1)
+where
+    X: BbsCiphersuite<'a>
+{
+    let generators = create_generators::<X>(&[X::ID, b"PREDICATE_GENERATOR_SEED"].concat(), 2);
+    (generators[0], generators[1])
+}
+
+// Generates a BBS proof together with a proof that messages[index], which must not be disclosed, is in
+// set. The proof is verified with verify_set_membership and the same ph and set
+#[allow(clippy::too_many_arguments)]
+pub fn prove_set_membership<'a, X, R>(
+    signature: &Signature,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    messages: &[Scalar],
+    disclosed_indexes: &[usize],
+    index: usize,
+    set: &[Scalar],
+    rng: &mut R,
+) -> Result<(Proof, SetMembershipProof), BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    if set.is_empty() {
+        return Err(BbsError::InvalidLength("the set has at least one member"));
+    }
+    if index >= messages.len() || disclosed_indexes.contains(&index) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+    // The message is not in the set
+    let member = set
+        .iter()
+        .position(|s| *s == messages[index])
+        .ok_or(BbsError::ProofGenerationFailed)?;
+
+    let (g, h) = predicate_generators::<X>();
+    let gamma = SecretScalar(Scalar::random(&mut *rng));
+    let m_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let gamma_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let v = g * messages[index] + h * gamma.0;
+    let t = g * m_tilde.0 + h * gamma_tilde.0;
+
+    // The simulated branches pick c_i and z_i and derive R_i = H * z_i - (V - G * set_i) * c_i, the
+    // true branch commits to R = H * r
+    let r = SecretScalar(Scalar::random(&mut *rng));
+    let mut challenges: Vec<Scalar> = set.iter().map(|_| Scalar::random(&mut *rng)).collect();
+    let mut responses: Vec<Scalar> = set.iter().map(|_| Scalar::random(&mut *rng)).collect();
+    let commitments: Vec<G1> = set
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            if i == member {
+                h * r.0
+            } else {
+                h * responses[i] - (v - g * s) * challenges[i]
+            }
+        })
+        .collect();
+
+    let blindings = BTreeMap::from([(index, m_tilde.0)]);
+    let proof = Proof::generate_with_blindings::<X, _>(
+        signature,
+        pk,
+        header,
+        Some(&linked_ph(ph, &v, &t, &commitments)),
+        messages,
+        disclosed_indexes,
+        &blindings,
+        rng,
+    )?;
+    let c = proof.challenge();
+
+    // c_member = c - (the other c_i) and z_member = r + c_member * gamma
+    challenges[member] = Scalar::zero();
+    challenges[member] = c - challenges.iter().fold(Scalar::zero(), |sum, c_i| sum + c_i);
+    responses[member] = r.0 + challenges[member] * gamma.0;
+    let gamma_hat = c * gamma.0 + gamma_tilde.0;
+
+    Ok((proof, SetMembershipProof { v, t, gamma_hat, commitments, challenges, responses }))
+}
+
+// Verifies the BBS proof and that its hidden message at index, the zero based index the message was
+// signed at, is one of set
+#[allow(clippy::too_many_arguments)]
+pub fn verify_set_membership<'a, X>(
+    proof: &Proof,
+    membership: &SetMembershipProof,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    disclosed_messages: &BTreeMap<usize, Scalar>,
+    index: usize,
+    set: &[Scalar],
+) -> bool
+where
+    X: BbsCiphersuite<'a>
+{
+    let k = set.len();
+    if k == 0 || membership.commitments.len() != k || membership.challenges.len() != k || membership.responses.len() != k {
+        return false;
+    }
+    if disclosed_messages.contains_key(&index) {
+        return false;
+    }
+    let linked_ph = linked_ph(ph, &membership.v, &membership.t, &membership.commitments);
+    if !proof.verify::<X>(pk, header, Some(&linked_ph), disclosed_messages) {
+        return false;
+    }
+
+    let (g, h) = predicate_generators::<X>();
+    let c = proof.challenge();
+    if !opens_hidden_message(proof, disclosed_messages, index, (g, h), &membership.v, &membership.t, &membership.gamma_hat) {
+        return false;
+    }
+
+    // The branch challenges add up to the BBS challenge, and H * z_i == R_i + (V - G * set_i) * c_i
+    if membership.challenges.iter().fold(Scalar::zero(), |sum, c_i| sum + c_i) != c {
+        return false;
+    }
+    set.iter().enumerate().all(|(i, s)| {
+        h * membership.responses[i] == membership.commitments[i] + (membership.v - g * s) * membership.challenges[i]
+    })
+}
+
+impl SetMembershipProof {
+    // The commitment V to the hidden message
+    pub fn commitment(&self) -> G1 {
+        self.v
+    }
+
+    // V, T, R_1, ..., R_k, gamma^, c_1, ..., c_k, z_1, ..., z_k
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let k = self.commitments.len();
+        let mut bytes = Vec::with_capacity((2 + k) * 48 + (1 + 2 * k) * 32);
+        for p in [&self.v, &self.t].into_iter().chain(&self.commitments) {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(p));
+        }
+        for s in [&self.gamma_hat].into_iter().chain(&self.challenges).chain(&self.responses) {
+            bytes.extend_from_slice(&scalar_to_bytes(s));
+        }
+        bytes
+    }
+
+    // An error unless bytes holds V, T, gamma^ and the R_i, c_i and z_i of at least one branch
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        let fixed = 2 * 48 + 32;
+        let branch = 48 + 2 * 32;
+        if bytes.len() < fixed + branch || (bytes.len() - fixed) % branch != 0 {
+            return Err(BbsError::InvalidLength("a set membership proof is 2 points, a scalar and a point and 2 scalars per member"));
+        }
+        let k = (bytes.len() - fixed) / branch;
+
+        let (points, scalars) = bytes.split_at((2 + k) * 48);
+        let points: Vec<G1> = points
+            .chunks(48)
+            .map(g1_from_octets)
+            .collect::<Result<_, _>>()?;
+        let scalars: Vec<Scalar> = scalars
+            .chunks(32)
+            .map(|s| scalar_from_bytes(s.try_into().expect("32 octet chunk")))
+            .collect::<Result<_, _>>()?;
+
+        Ok(SetMembershipProof {
+            v: points[0],
+            t: points[1],
+            gamma_hat: scalars[0],
+            commitments: points[2..].to_vec(),
+            challenges: scalars[1..1 + k].to_vec(),
+            responses: scalars[1 + k..].to_vec(),
+        })
+    }
+}
+
+// A proof that a hidden message of one BBS proof equals a hidden message of another, e.g. the same
+// holder identifier in credentials of two issuers. Both proofs are linked to one commitment V, so the
+// message is the opening of V in both
+pub struct LinkedProof {
+    // V = G * msg + H * gamma
+    pub(crate) v: G1,
+    // T_A = G * m~_A + H * gamma~_A and T_B = G * m~_B + H * gamma~_B
+    pub(crate) t_a: G1,
+    pub(crate) t_b: G1,
+    pub(crate) gamma_hat_a: Scalar,
+    pub(crate) gamma_hat_b: Scalar,
+}
+
+// The holder's side of one of the two proofs of a LinkedProof
+pub struct LinkedCredential<'c> {
+    pub signature: &'c Signature,
+    pub pk: &'c PublicKey,
+    pub header: Option<&'c [u8]>,
+    pub messages: &'c [Scalar],
+    pub disclosed_indexes: &'c [usize],
+    // Zero based index of the linked message, which must not be disclosed
+    pub index: usize,
+}
+
+// The verifier's side of one of the two proofs of a LinkedProof
+pub struct LinkedPresentation<'c> {
+    pub proof: &'c Proof,
+    pub pk: &'c PublicKey,
+    pub header: Option<&'c [u8]>,
+    pub disclosed_messages: &'c BTreeMap<usize, Scalar>,
+    pub index: usize,
+}
+
+impl LinkedProof {
+    // Generates the BBS proofs of a and b together with the proof that their linked messages are equal.
+    // Both proofs bind V, T_A and T_B into ph, so neither is accepted with another partner
+    pub fn generate<'a, X, R>(
+        a: &LinkedCredential,
+        b: &LinkedCredential,
+        ph: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<(Proof, Proof, Self), BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        for credential in [a, b] {
+            if credential.index >= credential.messages.len() || credential.disclosed_indexes.contains(&credential.index) {
+                return Err(BbsError::InvalidDisclosedIndexes);
+            }
+        }
+        // The linked messages differ
+        if a.messages[a.index] != b.messages[b.index] {
+            return Err(BbsError::ProofGenerationFailed);
+        }
+
+        let (g, h) = predicate_generators::<X>();
+        let gamma = SecretScalar(Scalar::random(&mut *rng));
+        let m_tilde_a = SecretScalar(Scalar::random(&mut *rng));
+        let m_tilde_b = SecretScalar(Scalar::random(&mut *rng));
+        let gamma_tilde_a = SecretScalar(Scalar::random(&mut *rng));
+        let gamma_tilde_b = SecretScalar(Scalar::random(&mut *rng));
+        let v = g * a.messages[a.index] + h * gamma.0;
+        let t_a = g * m_tilde_a.0 + h * gamma_tilde_a.0;
+        let t_b = g * m_tilde_b.0 + h * gamma_tilde_b.0;
+        let linked_ph = linked_ph(ph, &v, &t_a, &[t_b]);
+
+        let mut generate = |credential: &LinkedCredential, m_tilde: &SecretScalar| {
+            Proof::generate_with_blindings::<X, _>(
+                credential.signature,
+                credential.pk,
+                credential.header,
+                Some(&linked_ph),
+                credential.messages,
+                credential.disclosed_indexes,
+                &BTreeMap::from([(credential.index, m_tilde.0)]),
+                &mut *rng,
+            )
+        };
+        let proof_a = generate(a, &m_tilde_a)?;
+        let proof_b = generate(b, &m_tilde_b)?;
+
+        let linked = LinkedProof {
+            v,
+            t_a,
+            t_b,
+            gamma_hat_a: proof_a.challenge() * gamma.0 + gamma_tilde_a.0,
+            gamma_hat_b: proof_b.challenge() * gamma.0 + gamma_tilde_b.0,
+        };
+        Ok((proof_a, proof_b, linked))
+    }
+
+    // Verifies both BBS proofs and that the message at a.index equals the message at b.index
+    pub fn verify<'a, X>(&self, a: &LinkedPresentation, b: &LinkedPresentation, ph: Option<&[u8]>) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let linked_ph = linked_ph(ph, &self.v, &self.t_a, &[self.t_b]);
+        let generators = predicate_generators::<X>();
+
+        [(a, &self.t_a, &self.gamma_hat_a), (b, &self.t_b, &self.gamma_hat_b)]
+            .into_iter()
+            .all(|(presentation, t, gamma_hat)| {
+                !presentation.disclosed_messages.contains_key(&presentation.index)
+                    && presentation.proof.verify::<X>(presentation.pk, presentation.header, Some(&linked_ph), presentation.disclosed_messages)
+                    && opens_hidden_message(
+                        presentation.proof,
+                        presentation.disclosed_messages,
+                        presentation.index,
+                        generators,
+                        &self.v,
+                        t,
+                        gamma_hat,
+                    )
+            })
+    }
+
+    // V, T_A, T_B, gamma^_A, gamma^_B
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3 * 48 + 2 * 32);
+        for p in [&self.v, &self.t_a, &self.t_b] {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(p));
+        }
+        for s in [&self.gamma_hat_a, &self.gamma_hat_b] {
+            bytes.extend_from_slice(&scalar_to_bytes(s));
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        if bytes.len() != 3 * 48 + 2 * 32 {
+            return Err(BbsError::InvalidLength("a linked proof is 3 points and 2 scalars"));
+        }
+
+        let (points, scalars) = bytes.split_at(3 * 48);
+        let points: Vec<G1> = points
+            .chunks(48)
+            .map(g1_from_octets)
+            .collect::<Result<_, _>>()?;
+        let scalars: Vec<Scalar> = scalars
+            .chunks(32)
+            .map(|s| scalar_from_bytes(s.try_into().expect("32 octet chunk")))
+            .collect::<Result<_, _>>()?;
+
+        Ok(LinkedProof {
+            v: points[0],
+            t_a: points[1],
+            t_b: points[2],
+            gamma_hat_a: scalars[0],
+            gamma_hat_b: scalars[1],
+        })
+    }
+}
+
+// G * m^ + H * gamma^ == T + V * c, with m^ the response of the hidden message at index, shows that V
+// commits to that message
+pub(crate) fn opens_hidden_message(
+    proof: &Proof,
+    disclosed_messages: &BTreeMap<usize, Scalar>,
+    index: usize,
+    (g, h): (G1, G1),
+    v: &G1,
+    t: &G1,
+    gamma_hat: &Scalar,
+) -> bool {
+    // The m^ scalars are in the order of the undisclosed indexes
+    let position = index - disclosed_messages.range(..index).count();
+    match proof.m_hat().get(position) {
+        Some(m_hat) => g * m_hat + h * gamma_hat == t + v * proof.challenge(),
+        None => false,
+    }
+}
+
+// ph || point_to_octets(V) || point_to_octets(T) || point_to_octets(R_1) || ..., the presentation header
+// of the linked BBS proof
+pub(crate) fn linked_ph(ph: Option<&[u8]>, v: &G1, t: &G1, commitments: &[G1]) -> Vec<u8> {
+    let mut linked_ph = ph.unwrap_or(&[]).to_vec();
+    for p in [v, t].into_iter().chain(commitments) {
+        linked_ph.extend_from_slice(&Backend::g1_to_bytes(p));
+    }
+    linked_ph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    // Three messages, the one at 1 hidden, disclosing 0 and 2
+    fn credential<'a, X>(ikm: &[u8], hidden: Scalar) -> (KeyPair, Vec<Scalar>, Signature, BTreeMap<usize, Scalar>)
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let key_pair = KeyPair::from_ikm(ikm, None).unwrap();
+        let messages = alloc::vec![Scalar::from(1u64), hidden, Scalar::from(3u64)];
+        let signature = Signature::sign::<X>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+        let disclosed = BTreeMap::from([(0, messages[0]), (2, messages[2])]);
+        (key_pair, messages, signature, disclosed)
+    }
+
+    fn set() -> Vec<Scalar> {
+        (40..45u64).map(Scalar::from).collect()
+    }
+
+    fn set_membership<'a, X>()
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let set = set();
+        let (key_pair, messages, signature, disclosed) = credential::<X>(IKM, set[3]);
+        let (proof, membership) =
+            prove_set_membership::<X, _>(&signature, &key_pair.public_key, Some(b"header"), Some(b"ph"), &messages, &[0, 2], 1, &set, rng)
+                .unwrap();
+        let verify = |membership: &SetMembershipProof, ph: &[u8], set: &[Scalar]| {
+            verify_set_membership::<X>(&proof, membership, &key_pair.public_key, Some(b"header"), Some(ph), &disclosed, 1, set)
+        };
+        assert!(verify(&membership, b"ph", &set));
+        assert!(verify(&SetMembershipProof::from_bytes(&membership.to_bytes()).unwrap(), b"ph", &set));
+
+        // Another presentation header or another set
+        assert!(!verify(&membership, b"another ph", &set));
+        let mut other = set.clone();
+        other[0] = Scalar::from(7u64);
+        assert!(!verify(&membership, b"ph", &other));
+        assert!(!verify(&membership, b"ph", &set[..4]));
+    }
+
+    #[test]
+    fn set_membership_proof_verifies() {
+        set_membership::<Bls12381Sha256>();
+        set_membership::<Bls12381Shake256>();
+    }
+
+    #[test]
+    fn set_membership_rejects_a_message_outside_the_set() {
+        let rng = &mut StdRng::seed_from_u64(1);
+        let set = set();
+        let (key_pair, messages, signature, _) = credential::<Bls12381Sha256>(IKM, Scalar::from(7u64));
+        let prove = |indexes: &[usize], set: &[Scalar], rng: &mut StdRng| {
+            prove_set_membership::<Bls12381Sha256, _>(&signature, &key_pair.public_key, None, None, &messages, indexes, 1, set, rng)
+        };
+        assert!(matches!(prove(&[0, 2], &set, rng), Err(BbsError::ProofGenerationFailed)));
+        assert!(matches!(prove(&[0, 2], &[], rng), Err(BbsError::InvalidLength(_))));
+        assert!(matches!(prove(&[1], &[messages[1]], rng), Err(BbsError::InvalidDisclosedIndexes)));
+    }
+
+    #[test]
+    fn linked_proof_shows_equal_hidden_messages() {
+        let rng = &mut StdRng::seed_from_u64(2);
+        let holder_id = Scalar::from(99u64);
+        let (key_pair_a, messages_a, signature_a, disclosed_a) = credential::<Bls12381Sha256>(IKM, holder_id);
+        let (key_pair_b, messages_b, signature_b, disclosed_b) =
+            credential::<Bls12381Sha256>(b"another-Test-IKM-to-generate-the-2nd-issuer-key", holder_id);
+
+        let a = LinkedCredential {
+            signature: &signature_a,
+            pk: &key_pair_a.public_key,
+            header: Some(b"header"),
+            messages: &messages_a,
+            disclosed_indexes: &[0, 2],
+            index: 1,
+        };
+        let b = LinkedCredential {
+            signature: &signature_b,
+            pk: &key_pair_b.public_key,
+            header: Some(b"header"),
+            messages: &messages_b,
+            disclosed_indexes: &[0, 2],
+            index: 1,
+        };
+        let (proof_a, proof_b, linked) = LinkedProof::generate::<Bls12381Sha256, _>(&a, &b, Some(b"ph"), rng).unwrap();
+
+        let presentation_a = LinkedPresentation {
+            proof: &proof_a,
+            pk: &key_pair_a.public_key,
+            header: Some(b"header"),
+            disclosed_messages: &disclosed_a,
+            index: 1,
+        };
+        let presentation_b = LinkedPresentation {
+            proof: &proof_b,
+            pk: &key_pair_b.public_key,
+            header: Some(b"header"),
+            disclosed_messages: &disclosed_b,
+            index: 1,
+        };
+        assert!(linked.verify::<Bls12381Sha256>(&presentation_a, &presentation_b, Some(b"ph")));
+        let decoded = LinkedProof::from_bytes(&linked.to_bytes()).unwrap();
+        assert!(decoded.verify::<Bls12381Sha256>(&presentation_a, &presentation_b, Some(b"ph")));
+
+        // Another presentation header, or the proofs swapped
+        assert!(!linked.verify::<Bls12381Sha256>(&presentation_a, &presentation_b, Some(b"another ph")));
+        assert!(!linked.verify::<Bls12381Sha256>(&presentation_b, &presentation_a, Some(b"ph")));
+
+        // Credentials whose messages at the index differ
+        let (key_pair_c, messages_c, signature_c, _) = credential::<Bls12381Sha256>(IKM, Scalar::from(98u64));
+        let c = LinkedCredential {
+            signature: &signature_c,
+            pk: &key_pair_c.public_key,
+            header: Some(b"header"),
+            messages: &messages_c,
+            disclosed_indexes: &[0, 2],
+            index: 1,
+        };
+        assert!(matches!(LinkedProof::generate::<Bls12381Sha256, _>(&a, &c, None, rng), Err(BbsError::ProofGenerationFailed)));
+    }
+
+    #[test]
+    fn from_bytes_rejects_malformed_octets() {
+        let branch = 48 + 2 * 32;
+        for len in [0, 2 * 48 + 32, 2 * 48 + 32 + branch - 1, 2 * 48 + 32 + branch + 1] {
+            assert!(matches!(SetMembershipProof::from_bytes(&alloc::vec![0; len]), Err(BbsError::InvalidLength(_))), "{} octets", len);
+        }
+        assert!(matches!(LinkedProof::from_bytes(&[0; 3 * 48 + 2 * 32 - 1]), Err(BbsError::InvalidLength(_))));
+
+        let mut identity = alloc::vec![0u8; 3 * 48 + 2 * 32];
+        identity[0] = 0xc0;
+        assert!(matches!(LinkedProof::from_bytes(&identity), Err(BbsError::InvalidPoint)));
+    }
+}
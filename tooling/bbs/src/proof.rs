@@ -0,0 +1,599 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::encoding::{
+    encode_for_hash, g1_from_octets_with_mode, nonzero_scalar_from_octets_with_mode, os2ip, scalar_to_bytes, DeserializeMode, SpecSerialize,
+};
+use crate::error::BbsError;
+use crate::generators::make_generators;
+use crate::hashing::{expand_message, hash_to_scalar};
+use crate::key_pair::PublicKey;
+use crate::limits::Limits;
+use crate::secret::{SecretScalar, SecretScalars};
+use crate::signature::{calculate_b, calculate_domain, Signature};
+use crate::trace::trace_value;
+
+pub struct Proof {
+    pub(crate) a_prime: G1,
+    pub(crate) a_bar: G1,
+    pub(crate) d: G1,
+    pub(crate) c: Scalar,
+    pub(crate) e_hat: Scalar,
+    pub(crate) r2_hat: Scalar,
+    pub(crate) r3_hat: Scalar,
+    pub(crate) s_hat: Scalar,
+    pub(crate) m_hat: Vec<Scalar>,
+}
+
+impl Proof {
+    // disclosed_indexes are zero based and must be in ascending order
+    #[cfg(feature = "std")]
+    pub fn generate<'a, X>(
+        signature: &Signature,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        messages: &[Scalar],
+        disclosed_indexes: &[usize],
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        Self::generate_with_rng::<X, _>(signature, pk, header, ph, messages, disclosed_indexes, &mut rand::thread_rng())
+    }
+
+    // As generate, with the random scalars drawn from rng
+    pub fn generate_with_rng<'a, X, R>(
+        signature: &Signature,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        messages: &[Scalar],
+        disclosed_indexes: &[usize],
+        rng: &mut R,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        Self::generate_with_limits::<X, _>(signature, pk, header, ph, messages, disclosed_indexes, &Limits::DEFAULT, rng)
+    }
+
+    // As generate_with_rng, TooManyMessages above the max_messages of limits
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_with_limits<'a, X, R>(
+        signature: &Signature,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        messages: &[Scalar],
+        disclosed_indexes: &[usize],
+        limits: &Limits,
+        rng: &mut R,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        Self::generate_with_random_scalars_and_limits::<X, _>(signature, pk, header, ph, messages, disclosed_indexes, limits, |count| {
+            calculate_random_scalars(rng, count)
+        })
+    }
+
+    // As generate_with_rng, with the blinding factors (m~) of some undisclosed messages, keyed by message
+    // index, supplied by the caller instead of drawn from rng. Another zero knowledge proof using the same
+    // blinding factor for the message, with its commitment bound into ph, proves a statement about the
+    // hidden message, e.g. that it lies in a range. The blinding factors must be uniformly random and secret
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_with_blindings<'a, X, R>(
+        signature: &Signature,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        messages: &[Scalar],
+        disclosed_indexes: &[usize],
+        blindings: &BTreeMap<usize, Scalar>,
+        rng: &mut R,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        // A blinding factor only applies to a message that is signed and not disclosed
+        if blindings.keys().any(|i| *i >= messages.len() || disclosed_indexes.contains(i)) {
+            return Err(BbsError::InvalidDisclosedIndexes);
+        }
+
+        Self::generate_with_random_scalars::<X, _>(signature, pk, header, ph, messages, disclosed_indexes, |count| {
+            let mut random_scalars = calculate_random_scalars(rng, count);
+            // m~ of the undisclosed messages follow r1, r2, e~, r2~, r3~ and s~, in message order
+            let undisclosed = (0..messages.len()).filter(|i| !disclosed_indexes.contains(i));
+            for (m_tilde, j) in random_scalars.iter_mut().skip(6).zip(undisclosed) {
+                if let Some(blinding) = blindings.get(&j) {
+                    *m_tilde = *blinding;
+                }
+            }
+            random_scalars
+        })
+    }
+
+    // As generate, with the random scalars supplied by the caller, e.g. mocked_calculate_random_scalars
+    pub fn generate_with_random_scalars<'a, X, F>(
+        signature: &Signature,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        messages: &[Scalar],
+        disclosed_indexes: &[usize],
+        calculate_random_scalars: F,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        F: FnOnce(usize) -> Vec<Scalar>
+    {
+        Self::generate_with_random_scalars_and_limits::<X, F>(signature, pk, header, ph, messages, disclosed_indexes, &Limits::DEFAULT, calculate_random_scalars)
+    }
+
+    // As generate_with_random_scalars, TooManyMessages above the max_messages of limits
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_with_random_scalars_and_limits<'a, X, F>(
+        signature: &Signature,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        messages: &[Scalar],
+        disclosed_indexes: &[usize],
+        limits: &Limits,
+        calculate_random_scalars: F,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        F: FnOnce(usize) -> Vec<Scalar>
+    {
+        let l = messages.len();
+        limits.check_message_count(l)?;
+        if disclosed_indexes.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(BbsError::InvalidDisclosedIndexes);
+        }
+        if disclosed_indexes.iter().any(|&i| i >= l) {
+            return Err(BbsError::InvalidDisclosedIndexes);
+        }
+
+        let disclosed: BTreeMap<usize, Scalar> = disclosed_indexes
+            .iter()
+            .map(|&i| (i, messages[i]))
+            .collect();
+        let undisclosed: Vec<usize> = (0..l).filter(|i| !disclosed.contains_key(i)).collect();
+
+        let generators = make_generators::<X>(None, l + 2);
+        let q_1 = generators.message_generators[0];
+        let h = &generators.message_generators[2..];
+
+        let domain = calculate_domain::<X>(pk, &generators.message_generators, header);
+        trace_value!(debug, "domain", domain);
+
+        // The random scalars and the values derived from them are zeroized when dropped
+        let random_scalars = SecretScalars(calculate_random_scalars(6 + undisclosed.len()));
+        if random_scalars.len() != 6 + undisclosed.len() {
+            return Err(BbsError::ProofGenerationFailed);
+        }
+        let (r1, r2, e_tilde, r2_tilde, r3_tilde, s_tilde) = (
+            &random_scalars[0],
+            &random_scalars[1],
+            &random_scalars[2],
+            &random_scalars[3],
+            &random_scalars[4],
+            &random_scalars[5],
+        );
+        let m_tilde = &random_scalars[6..];
+
+        let b = calculate_b(&generators, signature.s, domain, messages);
+        trace_value!(debug, "B", b);
+        let r3 = SecretScalar(Option::from(r1.invert()).ok_or(BbsError::ProofGenerationFailed)?);
+
+        let a_prime = signature.a * r1;
+        let a_bar = a_prime * (-signature.e) + b * r1;
+        let d = b * r1 + q_1 * r2;
+        let s_prime = SecretScalar(r2 * r3.0 + signature.s);
+
+        let c1 = a_prime * e_tilde + q_1 * r2_tilde;
+        let mut c2 = d * (-r3_tilde) + q_1 * s_tilde;
+        for (&j, m) in undisclosed.iter().zip(m_tilde) {
+            c2 += h[j] * m;
+        }
+
+        trace_value!(debug, "A'", a_prime);
+        trace_value!(debug, "Abar", a_bar);
+        trace_value!(debug, "D", d);
+        trace_value!(debug, "C1", c1);
+        trace_value!(debug, "C2", c2);
+        let c = calculate_challenge::<X>(&a_prime, &a_bar, &d, &c1, &c2, &disclosed, &domain, ph);
+        trace_value!(debug, "challenge", c);
+
+        let m_hat = undisclosed
+            .iter()
+            .zip(m_tilde)
+            .map(|(&j, m)| c * messages[j] + m)
+            .collect();
+
+        Ok(Proof {
+            a_prime,
+            a_bar,
+            d,
+            c,
+            e_hat: c * signature.e + e_tilde,
+            r2_hat: c * r2 + r2_tilde,
+            r3_hat: c * r3.0 + r3_tilde,
+            s_hat: c * s_prime.0 + s_tilde,
+            m_hat,
+        })
+    }
+
+    // The challenge c, shared with a proof linked through generate_with_blindings
+    pub fn challenge(&self) -> Scalar {
+        self.c
+    }
+
+    // The responses m^ = c * msg + m~ of the undisclosed messages, in message order. The response of a
+    // message blinded with generate_with_blindings is checked against the linked proof
+    pub fn m_hat(&self) -> &[Scalar] {
+        &self.m_hat
+    }
+
+    // proof_to_octets
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3 * 48 + (5 + self.m_hat.len()) * 32);
+        for p in [&self.a_prime, &self.a_bar, &self.d] {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(p));
+        }
+        let scalars = [&self.c, &self.e_hat, &self.r2_hat, &self.r3_hat, &self.s_hat];
+        for s in scalars.into_iter().chain(&self.m_hat) {
+            bytes.extend_from_slice(&scalar_to_bytes(s));
+        }
+        bytes
+    }
+
+    // octets_to_proof, an error unless bytes holds the 3 points, 5 scalars and whole m^ scalars. Like
+    // octets_to_signature, Identity_G1 points and scalars that are 0 or not less than r are rejected
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        Self::from_bytes_with_mode(bytes, DeserializeMode::Strict)
+    }
+
+    // As from_bytes, with the checks of mode. The length and the default max_messages are checked in
+    // both modes
+    pub fn from_bytes_with_mode(bytes: &[u8], mode: DeserializeMode) -> Result<Self, BbsError> {
+        Self::from_bytes_with_limits(bytes, mode, &Limits::DEFAULT)
+    }
+
+    // As from_bytes_with_mode, TooManyMessages when the proof has more m^ than the max_messages of limits
+    pub fn from_bytes_with_limits(bytes: &[u8], mode: DeserializeMode, limits: &Limits) -> Result<Self, BbsError> {
+        if bytes.len() < 3 * 48 + 5 * 32 || (bytes.len() - 3 * 48) % 32 != 0 {
+            return Err(BbsError::InvalidLength("a proof is 3 points followed by at least 5 scalars"));
+        }
+        limits.check_message_count((bytes.len() - 3 * 48) / 32 - 5)?;
+
+        let (points, scalars) = bytes.split_at(3 * 48);
+        let points: Vec<G1> = points
+            .chunks(48)
+            .map(|p| g1_from_octets_with_mode(p, mode))
+            .collect::<Result<_, _>>()?;
+        let scalars: Vec<Scalar> = scalars
+            .chunks(32)
+            .map(|s| nonzero_scalar_from_octets_with_mode(s, mode))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Proof {
+            a_prime: points[0],
+            a_bar: points[1],
+            d: points[2],
+            c: scalars[0],
+            e_hat: scalars[1],
+            r2_hat: scalars[2],
+            r3_hat: scalars[3],
+            s_hat: scalars[4],
+            m_hat: scalars[5..].to_vec(),
+        })
+    }
+
+    // disclosed_messages is keyed by the zero based index the message was signed at
+    pub fn verify<'a, X>(
+        &self,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        disclosed_messages: &BTreeMap<usize, Scalar>,
+    ) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.verify_with_limits::<X>(pk, header, ph, disclosed_messages, &Limits::DEFAULT)
+    }
+
+    // As verify, false above the max_messages of limits
+    pub fn verify_with_limits<'a, X>(
+        &self,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        disclosed_messages: &BTreeMap<usize, Scalar>,
+        limits: &Limits,
+    ) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        pk.validate() && self.verify_prevalidated_with_limits::<X>(pk, header, ph, disclosed_messages, limits)
+    }
+
+    // As verify without KeyValidate, for callers that already validated pk
+    pub fn verify_prevalidated<'a, X>(
+        &self,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        disclosed_messages: &BTreeMap<usize, Scalar>,
+    ) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.verify_prevalidated_with_limits::<X>(pk, header, ph, disclosed_messages, &Limits::DEFAULT)
+    }
+
+    fn verify_prevalidated_with_limits<'a, X>(
+        &self,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        disclosed_messages: &BTreeMap<usize, Scalar>,
+        limits: &Limits,
+    ) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if !self.verify_challenge::<X>(pk, header, ph, disclosed_messages, limits) {
+            return false;
+        }
+
+        // e(A', W) == e(Abar, P2), as e(A', W) * e(-Abar, P2) == 1 with one final exponentiation
+        Backend::pairing_product_is_identity(&[(self.a_prime, pk.0), (-self.a_bar, Backend::g2_generator())])
+    }
+
+    // Verifies (pk, header, ph, disclosed_messages, proof) tuples at once. The challenges are checked
+    // one by one, the pairing checks e(A', W) * e(-Abar, P2) == 1 are combined with random scalars r_i
+    // into prod e(r_i * A'_i, W_i) * e(-sum r_i * Abar_i, P2) == 1 with a single final exponentiation
+    #[allow(clippy::type_complexity)]
+    #[cfg(feature = "std")]
+    pub fn batch_verify<'a, X>(
+        items: &[(&PublicKey, Option<&[u8]>, Option<&[u8]>, &BTreeMap<usize, Scalar>, &Proof)],
+    ) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        Self::batch_verify_with_rng::<X, _>(items, &mut rand::thread_rng())
+    }
+
+    // As batch_verify, with the r_i drawn from rng
+    #[allow(clippy::type_complexity)]
+    pub fn batch_verify_with_rng<'a, X, R>(
+        items: &[(&PublicKey, Option<&[u8]>, Option<&[u8]>, &BTreeMap<usize, Scalar>, &Proof)],
+        rng: &mut R,
+    ) -> bool
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        let mut terms = Vec::with_capacity(items.len() + 1);
+        let mut a_bar_sum = Backend::g1_identity();
+
+        for &(pk, header, ph, disclosed_messages, proof) in items {
+            if !pk.validate() || !proof.verify_challenge::<X>(pk, header, ph, disclosed_messages, &Limits::DEFAULT) {
+                return false;
+            }
+
+            let r = Scalar::random(&mut *rng);
+            terms.push((proof.a_prime * r, pk.0));
+            a_bar_sum += proof.a_bar * r;
+        }
+        terms.push((-a_bar_sum, Backend::g2_generator()));
+
+        Backend::pairing_product_is_identity(&terms)
+    }
+
+    // The checks of ProofVerify before the pairing check, recomputing C1, C2 and the challenge
+    fn verify_challenge<'a, X>(
+        &self,
+        pk: &PublicKey,
+        header: Option<&[u8]>,
+        ph: Option<&[u8]>,
+        disclosed_messages: &BTreeMap<usize, Scalar>,
+        limits: &Limits,
+    ) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let l = self.m_hat.len() + disclosed_messages.len();
+        if disclosed_messages.keys().any(|&i| i >= l) || !limits.allows(l) {
+            return false;
+        }
+        if Backend::g1_is_identity(&self.a_prime) {
+            return false;
+        }
+
+        let generators = make_generators::<X>(None, l + 2);
+        let q_1 = generators.message_generators[0];
+        let q_2 = generators.message_generators[1];
+        let h = &generators.message_generators[2..];
+
+        let domain = calculate_domain::<X>(pk, &generators.message_generators, header);
+        trace_value!(debug, "domain", domain);
+
+        let c1 = (self.a_bar - self.d) * self.c + self.a_prime * self.e_hat + q_1 * self.r2_hat;
+
+        // T = P1 + Q_2 * domain + sum H_i * msg_i and C2 = T * c - D * r3^ + Q_1 * s^ + sum H_j * m^_j,
+        // all the scalars are public so both are multi-scalar multiplications
+        let (mut points, mut scalars) = (vec![q_2], vec![domain]);
+        for (&i, msg) in disclosed_messages {
+            points.push(h[i]);
+            scalars.push(*msg);
+        }
+        let t = generators.g1_base_point + Backend::g1_sum_of_products_vartime(&points, &scalars);
+
+        let (mut points, mut scalars) = (vec![t, self.d, q_1], vec![self.c, -self.r3_hat, self.s_hat]);
+        let undisclosed = (0..l).filter(|i| !disclosed_messages.contains_key(i));
+        for (j, m) in undisclosed.zip(&self.m_hat) {
+            points.push(h[j]);
+            scalars.push(*m);
+        }
+        let c2 = Backend::g1_sum_of_products_vartime(&points, &scalars);
+        trace_value!(debug, "T", t);
+        trace_value!(debug, "C1", c1);
+        trace_value!(debug, "C2", c2);
+
+        let cv = calculate_challenge::<X>(
+            &self.a_prime, &self.a_bar, &self.d, &c1, &c2, disclosed_messages, &domain, ph
+        );
+        trace_value!(debug, "challenge", cv);
+        self.c == cv
+    }
+}
+
+// c = hash_to_scalar(encode_for_hash((A', Abar, D, C1, C2, R, i1, ..., iR, msg_i1, ..., msg_iR, domain, ph)), 1)
+//
+// The Fiat-Shamir challenge of ProofGen and ProofVerify. disclosed maps the zero based index of
+// every disclosed message to its scalar, the indexes are hashed from 1 and in ascending order as in
+// the spec. domain is the output of signature::calculate_domain, a missing ph is the empty one
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_challenge<'a, X>(
+    a_prime: &G1,
+    a_bar: &G1,
+    d: &G1,
+    c1: &G1,
+    c2: &G1,
+    disclosed: &BTreeMap<usize, Scalar>,
+    domain: &Scalar,
+    ph: Option<&[u8]>,
+) -> Scalar
+where
+    X: BbsCiphersuite<'a>
+{
+    let ph = ph.unwrap_or(&[]);
+
+    let mut c_for_hash = encode_for_hash(&[a_prime, a_bar, d, c1, c2, &disclosed.len()]);
+    // The spec indexes messages from 1
+    for i in disclosed.keys() {
+        (i + 1).serialize_into(&mut c_for_hash);
+    }
+    for msg in disclosed.values() {
+        msg.serialize_into(&mut c_for_hash);
+    }
+    domain.serialize_into(&mut c_for_hash);
+    ph.serialize_into(&mut c_for_hash);
+
+    hash_to_scalar::<X>(&c_for_hash, 1, None)[0]
+}
+
+fn calculate_random_scalars<R>(rng: &mut R, count: usize) -> Vec<Scalar>
+where
+    R: RngCore + CryptoRng
+{
+    (0..count).map(|_| Scalar::random(&mut *rng)).collect()
+}
+
+// Deterministic replacement for calculate_random_scalars, only to be used for generating test vectors
+pub fn mocked_calculate_random_scalars<'a, X>(seed: &[u8], count: usize) -> Vec<Scalar>
+where
+    X: BbsCiphersuite<'a>
+{
+    expand_message::<X>(seed, &X::mock_random_scalars_dst(), count * X::EXPAND_LEN)
+        .chunks(X::EXPAND_LEN)
+        .map(os2ip)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::Bls12381Sha256;
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    // A proof of 4 messages disclosing 0 and 2, so with 2 m^ scalars
+    fn proof() -> (KeyPair, BTreeMap<usize, Scalar>, Vec<u8>) {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let messages: Vec<Scalar> = (1..=4u64).map(Scalar::from).collect();
+        let signature = Signature::sign::<Bls12381Sha256>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+        let proof = Proof::generate_with_rng::<Bls12381Sha256, _>(
+            &signature,
+            &key_pair.public_key,
+            Some(b"header"),
+            Some(b"ph"),
+            &messages,
+            &[0, 2],
+            &mut StdRng::seed_from_u64(1),
+        )
+        .unwrap();
+        let disclosed = BTreeMap::from([(0, messages[0]), (2, messages[2])]);
+        (key_pair, disclosed, proof.to_bytes())
+    }
+
+    #[test]
+    fn round_trip() {
+        let (key_pair, disclosed, bytes) = proof();
+        assert_eq!(bytes.len(), 3 * 48 + 7 * 32);
+        let decoded = Proof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert!(decoded.verify::<Bls12381Sha256>(&key_pair.public_key, Some(b"header"), Some(b"ph"), &disclosed));
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let (_, _, bytes) = proof();
+        for len in [0, 3 * 48 + 5 * 32 - 1, 3 * 48 + 4 * 32, bytes.len() - 1, bytes.len() + 1, bytes.len() + 31] {
+            let mut octets = bytes.clone();
+            octets.resize(len, 0);
+            assert!(matches!(Proof::from_bytes(&octets), Err(BbsError::InvalidLength(_))), "{} octets", len);
+        }
+    }
+
+    #[test]
+    fn rejects_identity_points() {
+        let (_, _, bytes) = proof();
+        for offset in [0, 48, 96] {
+            let mut octets = bytes.clone();
+            octets[offset..offset + 48].copy_from_slice(&[0; 48]);
+            octets[offset] = 0xc0;
+            assert!(matches!(Proof::from_bytes(&octets), Err(BbsError::InvalidPoint)), "point at {}", offset);
+        }
+    }
+
+    #[test]
+    fn rejects_non_canonical_scalars() {
+        let (_, _, bytes) = proof();
+        let mut r = scalar_to_bytes(&-Scalar::one());
+        r[31] += 1;
+        // c, e^, r2^, r3^, s^ and both m^
+        for offset in (3 * 48..bytes.len()).step_by(32) {
+            for scalar in [r, [0xff; 32], [0; 32]] {
+                let mut octets = bytes.clone();
+                octets[offset..offset + 32].copy_from_slice(&scalar);
+                assert!(matches!(Proof::from_bytes(&octets), Err(BbsError::InvalidScalar)), "{:02x?} at {}", scalar, offset);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_more_m_hat_than_the_limit() {
+        let (_, _, bytes) = proof();
+        let limited = Proof::from_bytes_with_limits(&bytes, DeserializeMode::Strict, &Limits::new(1));
+        assert!(matches!(limited, Err(BbsError::TooManyMessages(2))));
+        assert!(Proof::from_bytes_with_limits(&bytes, DeserializeMode::Strict, &Limits::new(2)).is_ok());
+    }
+}
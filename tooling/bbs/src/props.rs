@@ -0,0 +1,266 @@
+// Property based tests with proptest. Rather than the fixed vectors of the fixtures, each property
+// is checked over random keys, headers and messages, and proptest shrinks a failing input to a
+// minimal one: a signature always verifies, Sign is deterministic, a tampered signature or proof never does, the octet
+// encodings round-trip and OS2IP inverts I2OSP. cargo test checks them over a few cases, for more run
+// cargo run --release --example props --features proptest
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use ff::Field;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::Index;
+use proptest::test_runner::{Config, TestRunner};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::Scalar;
+use crate::encoding::{i2osp, os2ip, scalar_from_bytes, scalar_to_bytes};
+use crate::hashing::map_message_to_scalar_as_hash;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::proof::Proof;
+use crate::signature::{Signature, SigningMode};
+
+const MAX_MESSAGES: usize = 10;
+
+pub struct PropertyReport {
+    pub property: &'static str,
+    pub cases: u32,
+    // The minimal failing input and why it fails, None when the property held for every case
+    pub failure: Option<String>,
+}
+
+impl PropertyReport {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+// A key pair, a header and messages, each message paired with whether a proof discloses it
+#[derive(Debug, Clone)]
+struct Signed {
+    ikm: [u8; 32],
+    header: Vec<u8>,
+    messages: Vec<(Vec<u8>, bool)>,
+}
+
+fn signed() -> impl Strategy<Value = Signed> {
+    (any::<[u8; 32]>(), vec(any::<u8>(), 0..64), vec((vec(any::<u8>(), 0..64), any::<bool>()), 1..MAX_MESSAGES))
+        .prop_map(|(ikm, header, messages)| Signed { ikm, header, messages })
+}
+
+impl Signed {
+    fn sign<'a, X>(&self) -> Result<(PublicKey, Vec<Scalar>, Signature), TestCaseError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let sk = SecretKey::key_gen(&self.ikm, None).map_err(fail)?;
+        let messages = self
+            .messages
+            .iter()
+            .map(|(msg, _)| map_message_to_scalar_as_hash::<X>(msg, None))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(fail)?;
+        let signature = Signature::sign::<X>(&messages, &sk, self.header()).map_err(fail)?;
+        Ok((sk.public_key(), messages, signature))
+    }
+
+    fn header(&self) -> Option<&[u8]> {
+        Some(&self.header)
+    }
+
+    fn disclosed_indexes(&self) -> Vec<usize> {
+        (0..self.messages.len()).filter(|&i| self.messages[i].1).collect()
+    }
+}
+
+// Sign then Verify succeeds for any key, header and messages
+pub fn check_sign_verify<'a, X>(cases: u32) -> PropertyReport
+where
+    X: BbsCiphersuite<'a>
+{
+    run("sign-verify", cases, signed(), |signed| {
+        let (pk, messages, signature) = signed.sign::<X>()?;
+        prop_assert!(signature.verify::<X>(&pk, &messages, signed.header()));
+        Ok(())
+    })
+}
+
+// Sign is deterministic, signing again gives the same octets, while hedged signatures of the same
+// messages differ from it and from each other and verify
+pub fn check_deterministic_sign<'a, X>(cases: u32) -> PropertyReport
+where
+    X: BbsCiphersuite<'a>
+{
+    run("deterministic-sign", cases, (signed(), any::<[u8; 32]>()), |(signed, rng_seed)| {
+        let (pk, messages, signature) = signed.sign::<X>()?;
+        let sk = SecretKey::key_gen(&signed.ikm, None).map_err(fail)?;
+        let again = Signature::sign::<X>(&messages, &sk, signed.header()).map_err(fail)?;
+        prop_assert_eq!(again.to_bytes(), signature.to_bytes());
+
+        let mut rng = StdRng::from_seed(rng_seed);
+        let hedged = [(); 2].map(|_| Signature::sign_with_mode::<X, _>(&messages, &sk, signed.header(), SigningMode::Hedged, &mut rng));
+        let hedged = hedged.into_iter().collect::<Result<Vec<_>, _>>().map_err(fail)?;
+        prop_assert_ne!(hedged[0].to_bytes(), signature.to_bytes());
+        prop_assert_ne!(hedged[0].to_bytes(), hedged[1].to_bytes());
+        for hedged in &hedged {
+            prop_assert!(hedged.verify::<X>(&pk, &messages, signed.header()), "hedged signature does not verify");
+        }
+        Ok(())
+    })
+}
+
+// A signature with any one bit flipped is rejected, by octets_to_signature or by Verify, and so is
+// the signature over messages with one changed
+pub fn check_tampered_signature<'a, X>(cases: u32) -> PropertyReport
+where
+    X: BbsCiphersuite<'a>
+{
+    run("tampered-signature", cases, (signed(), any::<Index>(), any::<Index>()), |(signed, bit, message)| {
+        let (pk, mut messages, signature) = signed.sign::<X>()?;
+
+        let tampered = flip_bit(&signature.to_bytes(), &bit);
+        let valid = Signature::from_bytes(&tampered)
+            .map(|signature| signature.verify::<X>(&pk, &messages, signed.header()))
+            .unwrap_or(false);
+        prop_assert!(!valid, "signature with a flipped bit verifies");
+
+        let i = message.index(messages.len());
+        messages[i] += Scalar::one();
+        prop_assert!(!signature.verify::<X>(&pk, &messages, signed.header()), "modified message verifies");
+        Ok(())
+    })
+}
+
+// A proof over any subset of the messages verifies, and is rejected with any one bit flipped
+pub fn check_proof<'a, X>(cases: u32) -> PropertyReport
+where
+    X: BbsCiphersuite<'a>
+{
+    let ph = vec(any::<u8>(), 0..64);
+    run("proof-tampered-proof", cases, (signed(), ph, any::<Index>()), |(signed, ph, bit)| {
+        let (pk, messages, signature) = signed.sign::<X>()?;
+        let disclosed_indexes = signed.disclosed_indexes();
+        let proof = Proof::generate::<X>(&signature, &pk, signed.header(), Some(ph.as_slice()), &messages, &disclosed_indexes)
+            .map_err(fail)?;
+        let disclosed: BTreeMap<usize, Scalar> = disclosed_indexes.iter().map(|&i| (i, messages[i])).collect();
+        prop_assert!(proof.verify::<X>(&pk, signed.header(), Some(ph.as_slice()), &disclosed));
+
+        let tampered = flip_bit(&proof.to_bytes(), &bit);
+        let valid = Proof::from_bytes(&tampered)
+            .map(|proof| proof.verify::<X>(&pk, signed.header(), Some(ph.as_slice()), &disclosed))
+            .unwrap_or(false);
+        prop_assert!(!valid, "proof with a flipped bit verifies");
+        Ok(())
+    })
+}
+
+// Public keys, signatures and proofs decode from their octets and encode back to the same octets
+pub fn check_octets_round_trip<'a, X>(cases: u32) -> PropertyReport
+where
+    X: BbsCiphersuite<'a>
+{
+    run("octets-round-trip", cases, signed(), |signed| {
+        let (pk, messages, signature) = signed.sign::<X>()?;
+
+        let pk_octets = pk.to_bytes();
+        prop_assert_eq!(PublicKey::from_bytes(&pk_octets).map_err(fail)?.to_bytes(), pk_octets);
+
+        let signature_octets = signature.to_bytes();
+        prop_assert_eq!(Signature::from_bytes(&signature_octets).map_err(fail)?.to_bytes(), signature_octets);
+
+        let proof = Proof::generate::<X>(&signature, &pk, signed.header(), None, &messages, &signed.disclosed_indexes())
+            .map_err(fail)?;
+        let proof_octets = proof.to_bytes();
+        prop_assert_eq!(Proof::from_bytes(&proof_octets).map_err(fail)?.to_bytes(), proof_octets);
+        Ok(())
+    })
+}
+
+// OS2IP inverts I2OSP for integers and scalars, and scalar_from_bytes inverts scalar_to_bytes
+pub fn check_os2ip_i2osp(cases: u32) -> PropertyReport {
+    run("os2ip-i2osp", cases, (any::<u64>(), any::<[u8; 32]>()), |(n, wide)| {
+        prop_assert_eq!(os2ip(&i2osp(n, 8)), Scalar::from(n));
+
+        let s = os2ip(&wide);
+        let octets = scalar_to_bytes(&s);
+        prop_assert_eq!(os2ip(&octets), s);
+        prop_assert_eq!(scalar_from_bytes(&octets).map_err(fail)?, s);
+        Ok(())
+    })
+}
+
+fn run<S, F>(property: &'static str, cases: u32, strategy: S, test: F) -> PropertyReport
+where
+    S: Strategy,
+    S::Value: core::fmt::Debug,
+    F: Fn(S::Value) -> Result<(), TestCaseError>
+{
+    let mut runner = TestRunner::new(Config::with_cases(cases));
+    PropertyReport {
+        property,
+        cases,
+        failure: runner.run(&strategy, test).err().map(|e| e.to_string()),
+    }
+}
+
+fn flip_bit(octets: &[u8], bit: &Index) -> Vec<u8> {
+    let bit = bit.index(8 * octets.len());
+    let mut octets = octets.to_vec();
+    octets[bit / 8] ^= 0x80 >> (bit % 8);
+    octets
+}
+
+fn fail<E: core::fmt::Display>(e: E) -> TestCaseError {
+    TestCaseError::fail(e.to_string())
+}
+
+// cargo test runs every property over a few cases per ciphersuite, the example runs more
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+
+    const CASES: u32 = 8;
+
+    fn assert_passed(report: PropertyReport) {
+        assert!(report.passed(), "{}: {}", report.property, report.failure.unwrap_or_default());
+    }
+
+    #[test]
+    fn sign_verify() {
+        assert_passed(check_sign_verify::<Bls12381Sha256>(CASES));
+        assert_passed(check_sign_verify::<Bls12381Shake256>(CASES));
+    }
+
+    #[test]
+    fn deterministic_sign() {
+        assert_passed(check_deterministic_sign::<Bls12381Sha256>(CASES));
+        assert_passed(check_deterministic_sign::<Bls12381Shake256>(CASES));
+    }
+
+    #[test]
+    fn tampered_signature() {
+        assert_passed(check_tampered_signature::<Bls12381Sha256>(CASES));
+        assert_passed(check_tampered_signature::<Bls12381Shake256>(CASES));
+    }
+
+    #[test]
+    fn proof() {
+        assert_passed(check_proof::<Bls12381Sha256>(CASES));
+        assert_passed(check_proof::<Bls12381Shake256>(CASES));
+    }
+
+    #[test]
+    fn octets_round_trip() {
+        assert_passed(check_octets_round_trip::<Bls12381Sha256>(CASES));
+        assert_passed(check_octets_round_trip::<Bls12381Shake256>(CASES));
+    }
+
+    #[test]
+    fn os2ip_i2osp() {
+        assert_passed(check_os2ip_i2osp(CASES));
+    }
+}
@@ -0,0 +1,660 @@
+// Range proofs over hidden messages: a Bulletproofs range proof (Bünz et al., section 4.2, with the
+// inner product argument of section 3) that a message encoded as an integer scalar lies in [0, 2^bits),
+// built on G1 so it shares the scalar field of the BBS proof. The Pedersen commitment V of the message
+// and the commitment T of its blinding factor are bound into the presentation header of the BBS proof,
+// whose response m^ for the message, with gamma^, opens T + c * V
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::encoding::{g1_from_octets, i2osp, scalar_from_bytes, scalar_to_bytes};
+use crate::error::BbsError;
+use crate::generators::create_generators;
+use crate::hashing::hash_to_scalar;
+use crate::key_pair::PublicKey;
+use crate::proof::Proof;
+use crate::secret::{SecretScalar, SecretScalars};
+use crate::signature::Signature;
+
+// The supported bit lengths of a range, [0, 2^bits)
+pub const RANGE_BITS: [usize; 4] = [8, 16, 32, 64];
+
+pub struct RangeProof {
+    // V = G * msg + H * gamma
+    pub(crate) v: G1,
+    // T = G * m~ + H * gamma~, m~ being the blinding factor of the message in the BBS proof
+    pub(crate) t: G1,
+    pub(crate) gamma_hat: Scalar,
+    pub(crate) a: G1,
+    pub(crate) s: G1,
+    pub(crate) t1: G1,
+    pub(crate) t2: G1,
+    pub(crate) tau_x: Scalar,
+    pub(crate) mu: Scalar,
+    pub(crate) t_hat: Scalar,
+    // The inner product argument, log2(bits) rounds
+    pub(crate) l: Vec<G1>,
+    pub(crate) r: Vec<G1>,
+    pub(crate) a_final: Scalar,
+    pub(crate) b_final: Scalar,
+}
+
+// G, H, G_1, ..., G_bits, H_1, ..., H_bits, created from their own seed like the blind generators
+pub fn range_generators<'a, X>(bits: usize) -> Vec<G1>
+where
+    X: BbsCiphersuite<'a>
+{
+    create_generators::<X>(&[X::ID, b"RANGE_PROOF_GENERATOR_SEED"].concat(), 2 + 2 * bits)
+}
+
+// Generates a BBS proof together with a range proof that messages[index], which must not be disclosed,
+// is less than 2^bits. The proof is verified with verify and the same ph, not with Proof::verify
+#[allow(clippy::too_many_arguments)]
+pub fn prove<'a, X, R>(
+    signature: &Signature,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    messages: &[Scalar],
+    disclosed_indexes: &[usize],
+    index: usize,
+    bits: usize,
+    rng: &mut R,
+) -> Result<(Proof, RangeProof), BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    if !RANGE_BITS.contains(&bits) {
+        return Err(BbsError::InvalidLength("a range is 8, 16, 32 or 64 bits"));
+    }
+    if index >= messages.len() || disclosed_indexes.contains(&index) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+    // The message is not in the range
+    let value = scalar_to_u64(&messages[index])
+        .filter(|value| bits == 64 || value >> bits == 0)
+        .ok_or(BbsError::ProofGenerationFailed)?;
+
+    let generators = range_generators::<X>(bits);
+    let (g, h) = (generators[0], generators[1]);
+
+    let gamma = SecretScalar(Scalar::random(&mut *rng));
+    let m_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let gamma_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let v = g * messages[index] + h * gamma.0;
+    let t = g * m_tilde.0 + h * gamma_tilde.0;
+
+    let blindings = BTreeMap::from([(index, m_tilde.0)]);
+    let proof = Proof::generate_with_blindings::<X, _>(
+        signature,
+        pk,
+        header,
+        Some(&linked_ph(ph, &v, &t)),
+        messages,
+        disclosed_indexes,
+        &blindings,
+        rng,
+    )?;
+    let gamma_hat = proof.challenge() * gamma.0 + gamma_tilde.0;
+
+    let mut range_proof = prove_range::<X, _>(value, &gamma, &v, bits, &generators, rng)?;
+    range_proof.t = t;
+    range_proof.gamma_hat = gamma_hat;
+    Ok((proof, range_proof))
+}
+
+// Verifies the BBS proof and that its hidden message at index, the zero based index the message was
+// signed at, is less than 2^bits
+#[allow(clippy::too_many_arguments)]
+pub fn verify<'a, X>(
+    proof: &Proof,
+    range_proof: &RangeProof,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    disclosed_messages: &BTreeMap<usize, Scalar>,
+    index: usize,
+    bits: usize,
+) -> bool
+where
+    X: BbsCiphersuite<'a>
+{
+    if !RANGE_BITS.contains(&bits) || disclosed_messages.contains_key(&index) {
+        return false;
+    }
+    if range_proof.l.len() != log2(bits) || range_proof.r.len() != log2(bits) {
+        return false;
+    }
+    if !proof.verify::<X>(pk, header, Some(&linked_ph(ph, &range_proof.v, &range_proof.t)), disclosed_messages) {
+        return false;
+    }
+
+    // m^ of the message, the m^ scalars are in the order of the undisclosed indexes
+    let position = index - disclosed_messages.range(..index).count();
+    let m_hat = match proof.m_hat().get(position) {
+        Some(m_hat) => m_hat,
+        None => return false,
+    };
+
+    let generators = range_generators::<X>(bits);
+    let (g, h) = (generators[0], generators[1]);
+
+    // G * m^ + H * gamma^ == T + V * c shows V commits to the hidden message
+    if g * m_hat + h * range_proof.gamma_hat != range_proof.t + range_proof.v * proof.challenge() {
+        return false;
+    }
+
+    verify_range::<X>(range_proof, bits, &generators)
+}
+
+impl RangeProof {
+    // The commitment V to the hidden message
+    pub fn commitment(&self) -> G1 {
+        self.v
+    }
+
+    // V, T, A, S, T1, T2, L_1, R_1, ..., L_k, R_k, gamma^, tau_x, mu, t^, a, b
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity((6 + 2 * self.l.len()) * 48 + 6 * 32);
+        for p in [&self.v, &self.t, &self.a, &self.s, &self.t1, &self.t2] {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(p));
+        }
+        for (l, r) in self.l.iter().zip(&self.r) {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(l));
+            bytes.extend_from_slice(&Backend::g1_to_bytes(r));
+        }
+        for s in [&self.gamma_hat, &self.tau_x, &self.mu, &self.t_hat, &self.a_final, &self.b_final] {
+            bytes.extend_from_slice(&scalar_to_bytes(s));
+        }
+        bytes
+    }
+
+    // An error unless bytes holds the 6 points, the L and R points of 3 to 6 rounds and the 6 scalars
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        let fixed = 6 * 48 + 6 * 32;
+        let rounds = bytes.len().saturating_sub(fixed) / (2 * 48);
+        if bytes.len() != fixed + rounds * 2 * 48 || !RANGE_BITS.iter().any(|&bits| log2(bits) == rounds) {
+            return Err(BbsError::InvalidLength("a range proof is 6 points, 3 to 6 pairs of points and 6 scalars"));
+        }
+
+        let (points, scalars) = bytes.split_at((6 + 2 * rounds) * 48);
+        let points: Vec<G1> = points
+            .chunks(48)
+            .map(g1_from_octets)
+            .collect::<Result<_, _>>()?;
+        let scalars: Vec<Scalar> = scalars
+            .chunks(32)
+            .map(|s| scalar_from_bytes(s.try_into().expect("32 octet chunk")))
+            .collect::<Result<_, _>>()?;
+
+        Ok(RangeProof {
+            v: points[0],
+            t: points[1],
+            a: points[2],
+            s: points[3],
+            t1: points[4],
+            t2: points[5],
+            l: points[6..].iter().step_by(2).copied().collect(),
+            r: points[7..].iter().step_by(2).copied().collect(),
+            gamma_hat: scalars[0],
+            tau_x: scalars[1],
+            mu: scalars[2],
+            t_hat: scalars[3],
+            a_final: scalars[4],
+            b_final: scalars[5],
+        })
+    }
+}
+
+// The Bulletproof of V = G * value + H * gamma, with t and gamma_hat left for prove to fill in
+fn prove_range<'a, X, R>(
+    value: u64,
+    gamma: &SecretScalar,
+    v: &G1,
+    bits: usize,
+    generators: &[G1],
+    rng: &mut R,
+) -> Result<RangeProof, BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    let (g, h) = (generators[0], generators[1]);
+    let (g_vec, h_vec) = generators[2..].split_at(bits);
+
+    // a_L holds the bits of value and a_R = a_L - 1^n
+    let a_l = SecretScalars((0..bits).map(|i| Scalar::from((value >> i) & 1)).collect());
+    let a_r = SecretScalars(a_l.iter().map(|a| a - Scalar::one()).collect());
+    let alpha = SecretScalar(Scalar::random(&mut *rng));
+    let a = h * alpha.0 + multi_scalar_mul(g_vec, &a_l) + multi_scalar_mul(h_vec, &a_r);
+
+    let s_l = SecretScalars((0..bits).map(|_| Scalar::random(&mut *rng)).collect());
+    let s_r = SecretScalars((0..bits).map(|_| Scalar::random(&mut *rng)).collect());
+    let rho = SecretScalar(Scalar::random(&mut *rng));
+    let s = h * rho.0 + multi_scalar_mul(g_vec, &s_l) + multi_scalar_mul(h_vec, &s_r);
+
+    let mut transcript = start_transcript::<X>(v, bits);
+    append_points(&mut transcript, &[&a, &s]);
+    let y = challenge::<X>(&mut transcript);
+    let z = challenge::<X>(&mut transcript);
+    let z2 = z * z;
+    let y_n = powers(y, bits);
+    let two_n = powers(Scalar::from(2u64), bits);
+
+    // l(X) = l_0 + l_1 * X and r(X) = r_0 + r_1 * X
+    let l_0 = SecretScalars(a_l.iter().map(|a| a - z).collect());
+    let r_0 = SecretScalars((0..bits).map(|i| y_n[i] * (a_r[i] + z) + z2 * two_n[i]).collect());
+    let r_1 = SecretScalars((0..bits).map(|i| y_n[i] * s_r[i]).collect());
+
+    // t(X) = <l(X), r(X)> = t_0 + t_1 * X + t_2 * X^2
+    let t_1 = inner_product(&l_0, &r_1) + inner_product(&s_l, &r_0);
+    let t_2 = inner_product(&s_l, &r_1);
+    let tau_1 = SecretScalar(Scalar::random(&mut *rng));
+    let tau_2 = SecretScalar(Scalar::random(&mut *rng));
+    let t1 = g * t_1 + h * tau_1.0;
+    let t2 = g * t_2 + h * tau_2.0;
+
+    append_points(&mut transcript, &[&t1, &t2]);
+    let x = challenge::<X>(&mut transcript);
+
+    let l = SecretScalars((0..bits).map(|i| l_0[i] + s_l[i] * x).collect());
+    let r = SecretScalars((0..bits).map(|i| r_0[i] + r_1[i] * x).collect());
+    let t_hat = inner_product(&l, &r);
+    let tau_x = tau_2.0 * x * x + tau_1.0 * x + z2 * gamma.0;
+    let mu = alpha.0 + rho.0 * x;
+
+    append_scalars(&mut transcript, &[&t_hat, &tau_x, &mu]);
+    let w = challenge::<X>(&mut transcript);
+    let q = g * w;
+
+    // The inner product argument for <l, G> + <r, H'> with H'_i = H_i * y^-(i-1)
+    let y_inv_n = powers(invert(&y).ok_or(BbsError::ProofGenerationFailed)?, bits);
+    let mut g_vec = g_vec.to_vec();
+    let mut h_vec: Vec<G1> = h_vec.iter().zip(&y_inv_n).map(|(h, y)| h * y).collect();
+    let (mut a_vec, mut b_vec) = (l, r);
+    let (mut l_vec, mut r_vec) = (Vec::new(), Vec::new());
+    while g_vec.len() > 1 {
+        let n = g_vec.len() / 2;
+        let (a_lo, a_hi) = a_vec.split_at(n);
+        let (b_lo, b_hi) = b_vec.split_at(n);
+        let (g_lo, g_hi) = g_vec.split_at(n);
+        let (h_lo, h_hi) = h_vec.split_at(n);
+
+        let l_k = multi_scalar_mul(g_hi, a_lo) + multi_scalar_mul(h_lo, b_hi) + q * inner_product(a_lo, b_hi);
+        let r_k = multi_scalar_mul(g_lo, a_hi) + multi_scalar_mul(h_hi, b_lo) + q * inner_product(a_hi, b_lo);
+        append_points(&mut transcript, &[&l_k, &r_k]);
+        let u = challenge::<X>(&mut transcript);
+        let u_inv = invert(&u).ok_or(BbsError::ProofGenerationFailed)?;
+
+        let a_next = (0..n).map(|i| a_lo[i] * u + a_hi[i] * u_inv).collect();
+        let b_next = (0..n).map(|i| b_lo[i] * u_inv + b_hi[i] * u).collect();
+        g_vec = (0..n).map(|i| g_lo[i] * u_inv + g_hi[i] * u).collect();
+        h_vec = (0..n).map(|i| h_lo[i] * u + h_hi[i] * u_inv).collect();
+        a_vec = SecretScalars(a_next);
+        b_vec = SecretScalars(b_next);
+        l_vec.push(l_k);
+        r_vec.push(r_k);
+    }
+
+    Ok(RangeProof {
+        v: *v,
+        t: Backend::g1_identity(),
+        gamma_hat: Scalar::zero(),
+        a,
+        s,
+        t1,
+        t2,
+        tau_x,
+        mu,
+        t_hat,
+        l: l_vec,
+        r: r_vec,
+        a_final: a_vec[0],
+        b_final: b_vec[0],
+    })
+}
+
+fn verify_range<'a, X>(range_proof: &RangeProof, bits: usize, generators: &[G1]) -> bool
+where
+    X: BbsCiphersuite<'a>
+{
+    let (g, h) = (generators[0], generators[1]);
+    let (g_vec, h_vec) = generators[2..].split_at(bits);
+
+    let mut transcript = start_transcript::<X>(&range_proof.v, bits);
+    append_points(&mut transcript, &[&range_proof.a, &range_proof.s]);
+    let y = challenge::<X>(&mut transcript);
+    let z = challenge::<X>(&mut transcript);
+    let z2 = z * z;
+    let y_n = powers(y, bits);
+    let two_n = powers(Scalar::from(2u64), bits);
+
+    append_points(&mut transcript, &[&range_proof.t1, &range_proof.t2]);
+    let x = challenge::<X>(&mut transcript);
+
+    // G * t^ + H * tau_x == V * z^2 + G * delta(y, z) + T1 * x + T2 * x^2
+    let sum_y_n = y_n.iter().fold(Scalar::zero(), |sum, y| sum + y);
+    let sum_two_n = two_n.iter().fold(Scalar::zero(), |sum, t| sum + t);
+    let delta = (z - z2) * sum_y_n - z * z2 * sum_two_n;
+    if g * range_proof.t_hat + h * range_proof.tau_x
+        != range_proof.v * z2 + g * delta + range_proof.t1 * x + range_proof.t2 * (x * x)
+    {
+        return false;
+    }
+
+    append_scalars(&mut transcript, &[&range_proof.t_hat, &range_proof.tau_x, &range_proof.mu]);
+    let w = challenge::<X>(&mut transcript);
+    let q = g * w;
+
+    // P = A + S * x - <z * 1^n, G> + <z * y^n + z^2 * 2^n, H'> - H * mu + Q * t^
+    let y_inv = match invert(&y) {
+        Some(y_inv) => y_inv,
+        None => return false,
+    };
+    let y_inv_n = powers(y_inv, bits);
+    let mut g_vec = g_vec.to_vec();
+    let mut h_vec: Vec<G1> = h_vec.iter().zip(&y_inv_n).map(|(h, y)| h * y).collect();
+    let h_exponents: Vec<Scalar> = (0..bits).map(|i| z * y_n[i] + z2 * two_n[i]).collect();
+    let mut p = range_proof.a + range_proof.s * x + multi_scalar_mul(&g_vec, &alloc::vec![-z; bits])
+        + multi_scalar_mul(&h_vec, &h_exponents)
+        - h * range_proof.mu
+        + q * range_proof.t_hat;
+
+    for (l_k, r_k) in range_proof.l.iter().zip(&range_proof.r) {
+        append_points(&mut transcript, &[l_k, r_k]);
+        let u = challenge::<X>(&mut transcript);
+        let u_inv = match invert(&u) {
+            Some(u_inv) => u_inv,
+            None => return false,
+        };
+        p += l_k * (u * u) + r_k * (u_inv * u_inv);
+
+        let n = g_vec.len() / 2;
+        let (g_lo, g_hi) = g_vec.split_at(n);
+        let (h_lo, h_hi) = h_vec.split_at(n);
+        let g_next = (0..n).map(|i| g_lo[i] * u_inv + g_hi[i] * u).collect();
+        let h_next = (0..n).map(|i| h_lo[i] * u + h_hi[i] * u_inv).collect();
+        g_vec = g_next;
+        h_vec = h_next;
+    }
+
+    p == g_vec[0] * range_proof.a_final + h_vec[0] * range_proof.b_final + q * (range_proof.a_final * range_proof.b_final)
+}
+
+// ph || point_to_octets(V) || point_to_octets(T), the presentation header of the linked BBS proof
+fn linked_ph(ph: Option<&[u8]>, v: &G1, t: &G1) -> Vec<u8> {
+    let mut linked_ph = ph.unwrap_or(&[]).to_vec();
+    linked_ph.extend_from_slice(&Backend::g1_to_bytes(v));
+    linked_ph.extend_from_slice(&Backend::g1_to_bytes(t));
+    linked_ph
+}
+
+// The Fiat-Shamir transcript starts with the ciphersuite, the bit length and V
+fn start_transcript<'a, X>(v: &G1, bits: usize) -> Vec<u8>
+where
+    X: BbsCiphersuite<'a>
+{
+    let mut transcript = [X::ID, b"RANGE_PROOF_"].concat();
+    transcript.extend_from_slice(&i2osp(bits as u64, 8));
+    transcript.extend_from_slice(&Backend::g1_to_bytes(v));
+    transcript
+}
+
+fn append_points(transcript: &mut Vec<u8>, points: &[&G1]) {
+    for p in points {
+        transcript.extend_from_slice(&Backend::g1_to_bytes(p));
+    }
+}
+
+fn append_scalars(transcript: &mut Vec<u8>, scalars: &[&Scalar]) {
+    for s in scalars {
+        transcript.extend_from_slice(&scalar_to_bytes(s));
+    }
+}
+
+// A non-zero challenge, appended to the transcript so that the next one depends on it
+fn challenge<'a, X>(transcript: &mut Vec<u8>) -> Scalar
+where
+    X: BbsCiphersuite<'a>
+{
+    let dst = [X::ID, b"RANGE_PROOF_H2S_"].concat();
+    let c = hash_to_scalar::<X>(transcript, 1, Some(&dst))[0];
+    transcript.extend_from_slice(&scalar_to_bytes(&c));
+    c
+}
+
+// None for 0. The challenges are non-zero, see hash_to_scalar, the callers still reject a failed
+// inversion instead of relying on that
+fn invert(s: &Scalar) -> Option<Scalar> {
+    Option::from(s.invert())
+}
+
+// 1, x, x^2, ..., x^(n-1)
+fn powers(x: Scalar, n: usize) -> Vec<Scalar> {
+    let mut powers = Vec::with_capacity(n);
+    let mut power = Scalar::one();
+    for _ in 0..n {
+        powers.push(power);
+        power *= x;
+    }
+    powers
+}
+
+fn inner_product(a: &[Scalar], b: &[Scalar]) -> Scalar {
+    a.iter().zip(b).fold(Scalar::zero(), |sum, (a, b)| sum + a * b)
+}
+
+fn multi_scalar_mul(points: &[G1], scalars: &[Scalar]) -> G1 {
+    points.iter().zip(scalars).fold(Backend::g1_identity(), |sum, (p, s)| sum + p * s)
+}
+
+// The integer a message scalar encodes, if it fits in 64 bits
+fn scalar_to_u64(s: &Scalar) -> Option<u64> {
+    let bytes = scalar_to_bytes(s);
+    if bytes[..24].iter().any(|&b| b != 0) {
+        return None;
+    }
+    Some(u64::from_be_bytes(bytes[24..].try_into().expect("8 octets")))
+}
+
+fn log2(bits: usize) -> usize {
+    bits.trailing_zeros() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::Bls12381Sha256;
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+    // The range proof is over messages[1], messages[0] and messages[2] are disclosed
+    const INDEX: usize = 1;
+
+    struct Signed {
+        key_pair: KeyPair,
+        messages: Vec<Scalar>,
+        signature: Signature,
+        disclosed: BTreeMap<usize, Scalar>,
+    }
+
+    fn signed(value: Scalar) -> Signed {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let messages = alloc::vec![Scalar::from(10u64), value, Scalar::from(30u64), Scalar::from(40u64)];
+        let signature = Signature::sign::<Bls12381Sha256>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+        let disclosed = BTreeMap::from([(0, messages[0]), (2, messages[2])]);
+        Signed { key_pair, messages, signature, disclosed }
+    }
+
+    fn prove_value(signed: &Signed, bits: usize) -> Result<(Proof, RangeProof), BbsError> {
+        prove::<Bls12381Sha256, _>(
+            &signed.signature,
+            &signed.key_pair.public_key,
+            Some(b"header"),
+            Some(b"ph"),
+            &signed.messages,
+            &[0, 2],
+            INDEX,
+            bits,
+            &mut StdRng::seed_from_u64(1),
+        )
+    }
+
+    fn verifies(signed: &Signed, proof: &Proof, range_proof: &RangeProof, ph: &[u8], bits: usize) -> bool {
+        verify::<Bls12381Sha256>(
+            proof,
+            range_proof,
+            &signed.key_pair.public_key,
+            Some(b"header"),
+            Some(ph),
+            &signed.disclosed,
+            INDEX,
+            bits,
+        )
+    }
+
+    #[test]
+    fn prove_verify_all_bit_lengths() {
+        for bits in RANGE_BITS {
+            let largest = if bits == 64 { u64::MAX } else { (1 << bits) - 1 };
+            for value in [0, 1, largest] {
+                let signed = signed(Scalar::from(value));
+                let (proof, range_proof) = prove_value(&signed, bits).unwrap();
+                assert!(verifies(&signed, &proof, &range_proof, b"ph", bits), "{} in {} bits", value, bits);
+                assert_eq!(range_proof.l.len(), log2(bits));
+
+                let decoded = RangeProof::from_bytes(&range_proof.to_bytes()).unwrap();
+                assert_eq!(decoded.to_bytes(), range_proof.to_bytes());
+                assert!(verifies(&signed, &proof, &decoded, b"ph", bits));
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_values_out_of_range() {
+        for bits in [8, 16, 32] {
+            let signed = signed(Scalar::from(1u64 << bits));
+            assert!(matches!(prove_value(&signed, bits), Err(BbsError::ProofGenerationFailed)));
+        }
+        // Above 2^64, and -1 which is r - 1 as an integer
+        let signed_above = signed(Scalar::from(u64::MAX) + Scalar::one());
+        assert!(matches!(prove_value(&signed_above, 64), Err(BbsError::ProofGenerationFailed)));
+        let signed_negative = signed(-Scalar::one());
+        assert!(matches!(prove_value(&signed_negative, 64), Err(BbsError::ProofGenerationFailed)));
+    }
+
+    #[test]
+    fn rejects_unsupported_bits_and_disclosed_index() {
+        let signed = signed(Scalar::from(5u64));
+        assert!(matches!(prove_value(&signed, 12), Err(BbsError::InvalidLength(_))));
+
+        let disclosed_index = prove::<Bls12381Sha256, _>(
+            &signed.signature,
+            &signed.key_pair.public_key,
+            Some(b"header"),
+            None,
+            &signed.messages,
+            &[0, 1],
+            INDEX,
+            8,
+            &mut StdRng::seed_from_u64(1),
+        );
+        assert!(matches!(disclosed_index, Err(BbsError::InvalidDisclosedIndexes)));
+
+        let (proof, range_proof) = prove_value(&signed, 8).unwrap();
+        // Verified against other bit lengths, or with the message disclosed
+        assert!(!verifies(&signed, &proof, &range_proof, b"ph", 16));
+        assert!(!verifies(&signed, &proof, &range_proof, b"ph", 12));
+        let mut disclosed = signed.disclosed.clone();
+        disclosed.insert(INDEX, signed.messages[INDEX]);
+        assert!(!verify::<Bls12381Sha256>(
+            &proof,
+            &range_proof,
+            &signed.key_pair.public_key,
+            Some(b"header"),
+            Some(b"ph"),
+            &disclosed,
+            INDEX,
+            8,
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_proofs() {
+        let signed = signed(Scalar::from(200u64));
+        let (proof, range_proof) = prove_value(&signed, 8).unwrap();
+        let bytes = range_proof.to_bytes();
+        let g = Backend::g1_generator();
+
+        let tampered = [
+            RangeProof { t_hat: range_proof.t_hat + Scalar::one(), ..RangeProof::from_bytes(&bytes).unwrap() },
+            RangeProof { tau_x: range_proof.tau_x + Scalar::one(), ..RangeProof::from_bytes(&bytes).unwrap() },
+            RangeProof { a_final: range_proof.a_final + Scalar::one(), ..RangeProof::from_bytes(&bytes).unwrap() },
+            RangeProof { gamma_hat: range_proof.gamma_hat + Scalar::one(), ..RangeProof::from_bytes(&bytes).unwrap() },
+            RangeProof { a: range_proof.a + g, ..RangeProof::from_bytes(&bytes).unwrap() },
+            RangeProof { t1: range_proof.t1 + g, ..RangeProof::from_bytes(&bytes).unwrap() },
+        ];
+        for (i, tampered) in tampered.iter().enumerate() {
+            assert!(!verifies(&signed, &proof, tampered, b"ph", 8), "tampered field {}", i);
+        }
+
+        let mut l_swapped = RangeProof::from_bytes(&bytes).unwrap();
+        l_swapped.l.swap(0, 1);
+        assert!(!verifies(&signed, &proof, &l_swapped, b"ph", 8));
+    }
+
+    #[test]
+    fn rejects_tampered_commitment() {
+        let signed = signed(Scalar::from(200u64));
+        let (proof, range_proof) = prove_value(&signed, 8).unwrap();
+        let generators = range_generators::<Bls12381Sha256>(8);
+
+        // V moved to another value, also changing the presentation header of the BBS proof
+        let other = RangeProof { v: range_proof.v + generators[0], ..RangeProof::from_bytes(&range_proof.to_bytes()).unwrap() };
+        assert!(!verifies(&signed, &proof, &other, b"ph", 8));
+
+        let other_t = RangeProof { t: range_proof.t + generators[1], ..RangeProof::from_bytes(&range_proof.to_bytes()).unwrap() };
+        assert!(!verifies(&signed, &proof, &other_t, b"ph", 8));
+    }
+
+    #[test]
+    fn rejects_other_presentation_header() {
+        let signed = signed(Scalar::from(200u64));
+        let (proof, range_proof) = prove_value(&signed, 8).unwrap();
+        assert!(!verifies(&signed, &proof, &range_proof, b"another ph", 8));
+        assert!(!verifies(&signed, &proof, &range_proof, b"", 8));
+
+        // Nor does the BBS proof verify on its own, the commitments are part of its presentation header
+        assert!(!proof.verify::<Bls12381Sha256>(&signed.key_pair.public_key, Some(b"header"), Some(b"ph"), &signed.disclosed));
+    }
+
+    #[test]
+    fn from_bytes_rejects_wrong_lengths() {
+        let fixed = 6 * 48 + 6 * 32;
+        // 2 rounds would be a 4 bit range, 7 rounds 128 bits
+        for len in [0, fixed - 1, fixed, fixed + 2 * 2 * 48, fixed + 3 * 2 * 48 - 1, fixed + 3 * 2 * 48 + 1, fixed + 7 * 2 * 48] {
+            assert!(
+                matches!(RangeProof::from_bytes(&alloc::vec![0u8; len]), Err(BbsError::InvalidLength(_))),
+                "{} octets",
+                len
+            );
+        }
+
+        let signed = signed(Scalar::from(200u64));
+        let (_, range_proof) = prove_value(&signed, 8).unwrap();
+        let bytes = range_proof.to_bytes();
+        assert_eq!(bytes.len(), fixed + 3 * 2 * 48);
+        assert!(matches!(RangeProof::from_bytes(&bytes[..bytes.len() - 1]), Err(BbsError::InvalidLength(_))));
+
+        let mut identity_v = bytes.clone();
+        identity_v[..48].copy_from_slice(&Backend::g1_to_bytes(&Backend::g1_identity()));
+        assert!(matches!(RangeProof::from_bytes(&identity_v), Err(BbsError::InvalidPoint)));
+    }
+}
@@ -0,0 +1,457 @@
+// RDF quads, their canonical N-Quads serialization and the URDNA2015 canonicalization algorithm, which
+// relabels the blank nodes of a dataset to _:c14nN deterministically, so isomorphic datasets serialize
+// to the same quads. See https://www.w3.org/TR/rdf-canon/, as implemented by jsonld.js and rdf-canonize
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use sha2::{Digest, Sha256};
+
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+pub const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+pub const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+pub const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+pub const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Term {
+    Iri(String),
+    // The label without _:
+    BlankNode(String),
+    Literal {
+        value: String,
+        datatype: String,
+        language: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Quad {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+    // None for the default graph
+    pub graph: Option<Term>,
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Iri(iri) => write!(f, "<{}>", iri),
+            Term::BlankNode(label) => write!(f, "_:{}", label),
+            Term::Literal { value, datatype, language } => {
+                write!(f, "\"{}\"", escape(value))?;
+                match language {
+                    Some(language) => write!(f, "@{}", language),
+                    None if datatype == XSD_STRING => Ok(()),
+                    None => write!(f, "^^<{}>", datatype),
+                }
+            }
+        }
+    }
+}
+
+impl Quad {
+    // The canonical N-Quads line, terminated by a newline
+    pub fn to_nquad(&self) -> String {
+        match &self.graph {
+            Some(graph) => alloc::format!("{} {} {} {} .\n", self.subject, self.predicate, self.object, graph),
+            None => alloc::format!("{} {} {} .\n", self.subject, self.predicate, self.object),
+        }
+    }
+
+    // Replaces the blank node labels, those missing from labels are kept
+    pub fn relabel(&self, labels: &BTreeMap<String, String>) -> Quad {
+        let relabel = |term: &Term| match term {
+            Term::BlankNode(label) => Term::BlankNode(labels.get(label).cloned().unwrap_or_else(|| label.clone())),
+            term => term.clone(),
+        };
+        Quad {
+            subject: relabel(&self.subject),
+            predicate: self.predicate.clone(),
+            object: relabel(&self.object),
+            graph: self.graph.as_ref().map(relabel),
+        }
+    }
+
+    fn blank_nodes(&self) -> impl Iterator<Item = &str> {
+        [Some(&self.subject), Some(&self.object), self.graph.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter_map(|term| match term {
+                Term::BlankNode(label) => Some(label.as_str()),
+                _ => None,
+            })
+    }
+}
+
+// Canonical N-Quads escape only these characters
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// URDNA2015. Returns the map from the blank node labels of quads to their canonical labels c14nN
+pub fn canonical_labels(quads: &[Quad]) -> BTreeMap<String, String> {
+    let mut state = CanonicalizationState {
+        blank_node_quads: BTreeMap::new(),
+        canonical_issuer: IdentifierIssuer::new("c14n"),
+    };
+    // A dataset is a set, every quad is hashed once for each blank node in it
+    let mut unique: Vec<&Quad> = quads.iter().collect();
+    unique.sort();
+    unique.dedup();
+    for quad in unique {
+        let mut labels: Vec<&str> = quad.blank_nodes().collect();
+        labels.sort_unstable();
+        labels.dedup();
+        for label in labels {
+            state.blank_node_quads.entry(label.to_string()).or_insert_with(Vec::new).push(quad);
+        }
+    }
+
+    // Blank nodes whose first degree hash is unique are labeled in hash order
+    let mut hash_to_blank_nodes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for label in state.blank_node_quads.keys() {
+        hash_to_blank_nodes
+            .entry(state.hash_first_degree_quads(label))
+            .or_insert_with(Vec::new)
+            .push(label.clone());
+    }
+    let mut shared = Vec::new();
+    for (_, labels) in hash_to_blank_nodes {
+        match labels.as_slice() {
+            [label] => {
+                state.canonical_issuer.issue(label);
+            }
+            _ => shared.push(labels),
+        }
+    }
+
+    // The others by the N-degree hash of their neighbourhood
+    for labels in shared {
+        let mut hash_paths = Vec::new();
+        for label in &labels {
+            if state.canonical_issuer.get(label).is_some() {
+                continue;
+            }
+            let mut issuer = IdentifierIssuer::new("b");
+            issuer.issue(label);
+            hash_paths.push(state.hash_n_degree_quads(label, issuer));
+        }
+        hash_paths.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, issuer) in hash_paths {
+            for label in issuer.issued_order {
+                state.canonical_issuer.issue(&label);
+            }
+        }
+    }
+
+    state.canonical_issuer.issued
+}
+
+// The canonical N-Quads of quads, sorted, with the map from their blank node labels to the canonical ones
+pub fn canonicalize(quads: &[Quad]) -> (Vec<String>, BTreeMap<String, String>) {
+    let labels = canonical_labels(quads);
+    let mut nquads: Vec<String> = quads.iter().map(|quad| quad.relabel(&labels).to_nquad()).collect();
+    nquads.sort();
+    nquads.dedup();
+    (nquads, labels)
+}
+
+struct CanonicalizationState<'q> {
+    blank_node_quads: BTreeMap<String, Vec<&'q Quad>>,
+    canonical_issuer: IdentifierIssuer,
+}
+
+#[derive(Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: BTreeMap<String, String>,
+    issued_order: Vec<String>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        IdentifierIssuer {
+            prefix,
+            issued: BTreeMap::new(),
+            issued_order: Vec::new(),
+        }
+    }
+
+    fn get(&self, label: &str) -> Option<&String> {
+        self.issued.get(label)
+    }
+
+    fn issue(&mut self, label: &str) -> String {
+        if let Some(issued) = self.issued.get(label) {
+            return issued.clone();
+        }
+        let issued = alloc::format!("{}{}", self.prefix, self.issued_order.len());
+        self.issued.insert(label.to_string(), issued.clone());
+        self.issued_order.push(label.to_string());
+        issued
+    }
+}
+
+impl<'q> CanonicalizationState<'q> {
+    // The quads of the blank node with its label replaced by a and the other blank nodes by z
+    fn hash_first_degree_quads(&self, label: &str) -> String {
+        let mut nquads: Vec<String> = self.blank_node_quads[label]
+            .iter()
+            .map(|quad| {
+                let replace = |term: &Term| match term {
+                    Term::BlankNode(other) if other == label => Term::BlankNode("a".to_string()),
+                    Term::BlankNode(_) => Term::BlankNode("z".to_string()),
+                    term => term.clone(),
+                };
+                Quad {
+                    subject: replace(&quad.subject),
+                    predicate: quad.predicate.clone(),
+                    object: replace(&quad.object),
+                    graph: quad.graph.as_ref().map(replace),
+                }
+                .to_nquad()
+            })
+            .collect();
+        nquads.sort();
+        sha256_hex(nquads.concat().as_bytes())
+    }
+
+    fn hash_related_blank_node(&self, related: &str, quad: &Quad, issuer: &IdentifierIssuer, position: &str) -> String {
+        let identifier = match self.canonical_issuer.get(related).or_else(|| issuer.get(related)) {
+            Some(identifier) => alloc::format!("_:{}", identifier),
+            None => self.hash_first_degree_quads(related),
+        };
+        let mut input = position.to_string();
+        if position != "g" {
+            input.push_str(&quad.predicate.to_string());
+        }
+        input.push_str(&identifier);
+        sha256_hex(input.as_bytes())
+    }
+
+    fn hash_n_degree_quads(&self, label: &str, mut issuer: IdentifierIssuer) -> (String, IdentifierIssuer) {
+        let mut hash_to_related: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for quad in &self.blank_node_quads[label] {
+            let positions = [("s", Some(&quad.subject)), ("o", Some(&quad.object)), ("g", quad.graph.as_ref())];
+            for (position, term) in positions {
+                if let Some(Term::BlankNode(related)) = term {
+                    if related != label {
+                        let hash = self.hash_related_blank_node(related, quad, &issuer, position);
+                        hash_to_related.entry(hash).or_insert_with(Vec::new).push(related.clone());
+                    }
+                }
+            }
+        }
+
+        let mut data_to_hash = String::new();
+        for (related_hash, related) in hash_to_related {
+            data_to_hash.push_str(&related_hash);
+            let mut chosen_path = String::new();
+            let mut chosen_issuer = None;
+
+            'permutations: for permutation in permutations(&related) {
+                let mut issuer_copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion_list = Vec::new();
+                for related in &permutation {
+                    match self.canonical_issuer.get(related) {
+                        Some(canonical) => path.push_str(&alloc::format!("_:{}", canonical)),
+                        None => {
+                            if issuer_copy.get(related).is_none() {
+                                recursion_list.push(related.clone());
+                            }
+                            path.push_str(&alloc::format!("_:{}", issuer_copy.issue(related)));
+                        }
+                    }
+                    if exceeds(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+                for related in recursion_list {
+                    let (hash, result_issuer) = self.hash_n_degree_quads(&related, issuer_copy.clone());
+                    path.push_str(&alloc::format!("_:{}", issuer_copy.issue(&related)));
+                    path.push_str(&alloc::format!("<{}>", hash));
+                    issuer_copy = result_issuer;
+                    if exceeds(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+                if chosen_path.is_empty() || path < chosen_path {
+                    chosen_path = path;
+                    chosen_issuer = Some(issuer_copy);
+                }
+            }
+
+            data_to_hash.push_str(&chosen_path);
+            if let Some(chosen_issuer) = chosen_issuer {
+                issuer = chosen_issuer;
+            }
+        }
+        (sha256_hex(data_to_hash.as_bytes()), issuer)
+    }
+}
+
+// A path is abandoned once it can no longer be less than the chosen path
+fn exceeds(path: &str, chosen_path: &str) -> bool {
+    !chosen_path.is_empty() && path.len() >= chosen_path.len() && path > chosen_path
+}
+
+fn permutations(items: &[String]) -> Vec<Vec<String>> {
+    if items.len() <= 1 {
+        return alloc::vec![items.to_vec()];
+    }
+    let mut result = Vec::new();
+    for i in 0..items.len() {
+        let mut rest = items.to_vec();
+        let item = rest.remove(i);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, item.clone());
+            result.push(permutation);
+        }
+    }
+    result
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| alloc::format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "_:label" is a blank node, other terms are IRIs
+    fn term(term: &str) -> Term {
+        match term.strip_prefix("_:") {
+            Some(label) => Term::BlankNode(label.to_string()),
+            None => Term::Iri(term.to_string()),
+        }
+    }
+
+    fn quad(subject: &str, predicate: &str, object: &str) -> Quad {
+        Quad {
+            subject: term(subject),
+            predicate: term(predicate),
+            object: term(object),
+            graph: None,
+        }
+    }
+
+    fn canonical_nquads(quads: &[Quad]) -> String {
+        canonicalize(quads).0.concat()
+    }
+
+    // The examples of the unique and the shared first degree hashes in RDF Dataset Canonicalization
+    #[test]
+    fn unique_hashes() {
+        let quads = [
+            quad("http://example.com/#p", "http://example.com/#q", "_:e0"),
+            quad("http://example.com/#p", "http://example.com/#r", "_:e1"),
+            quad("_:e0", "http://example.com/#s", "http://example.com/#u"),
+            quad("_:e1", "http://example.com/#t", "http://example.com/#u"),
+        ];
+        let expected = "<http://example.com/#p> <http://example.com/#q> _:c14n0 .\n\
+                        <http://example.com/#p> <http://example.com/#r> _:c14n1 .\n\
+                        _:c14n0 <http://example.com/#s> <http://example.com/#u> .\n\
+                        _:c14n1 <http://example.com/#t> <http://example.com/#u> .\n";
+        assert_eq!(canonical_nquads(&quads), expected);
+    }
+
+    #[test]
+    fn shared_hashes() {
+        let quads = [
+            quad("http://example.com/#p", "http://example.com/#q", "_:e0"),
+            quad("http://example.com/#p", "http://example.com/#q", "_:e1"),
+            quad("_:e0", "http://example.com/#p", "_:e2"),
+            quad("_:e1", "http://example.com/#p", "_:e3"),
+            quad("_:e2", "http://example.com/#r", "_:e3"),
+        ];
+        let expected = "<http://example.com/#p> <http://example.com/#q> _:c14n2 .\n\
+                        <http://example.com/#p> <http://example.com/#q> _:c14n3 .\n\
+                        _:c14n0 <http://example.com/#r> _:c14n1 .\n\
+                        _:c14n2 <http://example.com/#p> _:c14n1 .\n\
+                        _:c14n3 <http://example.com/#p> _:c14n0 .\n";
+        assert_eq!(canonical_nquads(&quads), expected);
+    }
+
+    // A cycle of blank nodes, one of them naming the graph of an escaped literal
+    #[test]
+    fn cycle_with_a_named_graph() {
+        let mut quads = alloc::vec![
+            quad("_:x", "http://example.com/#p", "_:y"),
+            quad("_:y", "http://example.com/#p", "_:z"),
+            quad("_:z", "http://example.com/#p", "_:x"),
+        ];
+        quads.push(Quad {
+            subject: term("_:x"),
+            predicate: term("http://example.com/#q"),
+            object: Term::Literal {
+                value: "a\"b".to_string(),
+                datatype: XSD_STRING.to_string(),
+                language: None,
+            },
+            graph: Some(term("_:g")),
+        });
+        let expected = "_:c14n1 <http://example.com/#p> _:c14n3 .\n\
+                        _:c14n1 <http://example.com/#q> \"a\\\"b\" _:c14n0 .\n\
+                        _:c14n2 <http://example.com/#p> _:c14n1 .\n\
+                        _:c14n3 <http://example.com/#p> _:c14n2 .\n";
+        assert_eq!(canonical_nquads(&quads), expected);
+    }
+
+    #[test]
+    fn isomorphic_datasets_canonicalize_alike() {
+        let quads = [
+            quad("http://example.com/#p", "http://example.com/#q", "_:e0"),
+            quad("http://example.com/#p", "http://example.com/#q", "_:e1"),
+            quad("_:e0", "http://example.com/#p", "_:e2"),
+            quad("_:e1", "http://example.com/#p", "_:e3"),
+            quad("_:e2", "http://example.com/#r", "_:e3"),
+        ];
+        let labels: BTreeMap<String, String> = [("e0", "n3"), ("e1", "n0"), ("e2", "n2"), ("e3", "n1")]
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect();
+        let mut relabeled: Vec<Quad> = quads.iter().map(|quad| quad.relabel(&labels)).collect();
+        relabeled.reverse();
+        assert_eq!(canonical_nquads(&relabeled), canonical_nquads(&quads));
+
+        // Repeated quads are one quad of the dataset
+        let mut repeated = quads.to_vec();
+        repeated.push(quads[2].clone());
+        assert_eq!(canonical_nquads(&repeated), canonical_nquads(&quads));
+
+        // A dataset that is not isomorphic does not
+        let mut other = quads.to_vec();
+        other[4] = quad("_:e2", "http://example.com/#r", "_:e2");
+        assert_ne!(canonical_nquads(&other), canonical_nquads(&quads));
+    }
+
+    #[test]
+    fn literals_serialize_as_canonical_nquads() {
+        let literal = |value: &str, datatype: &str, language: Option<&str>| Term::Literal {
+            value: value.to_string(),
+            datatype: datatype.to_string(),
+            language: language.map(|l| l.to_string()),
+        };
+        assert_eq!(literal("a\\b\nc\rd", XSD_STRING, None).to_string(), "\"a\\\\b\\nc\\rd\"");
+        assert_eq!(literal("30", XSD_INTEGER, None).to_string(), "\"30\"^^<http://www.w3.org/2001/XMLSchema#integer>");
+        assert_eq!(literal("chat", RDF_LANG_STRING, Some("fr")).to_string(), "\"chat\"@fr");
+        // Other characters, tabs and non ASCII included, are written as they are
+        assert_eq!(literal("\té", XSD_STRING, None).to_string(), "\"\té\"");
+    }
+}
@@ -0,0 +1,61 @@
+// Secret scalars that are overwritten with zero when dropped, so they don't linger in the memory of
+// long running signers and holders
+use alloc::vec::Vec;
+use core::ops::Deref;
+use core::ptr;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+use ff::Field;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::curve::Scalar;
+
+// Scalar has no Zeroize implementation, it is overwritten with a volatile write followed by a
+// compiler fence the way zeroize clears Copy types
+pub(crate) fn zeroize_scalar(s: &mut Scalar) {
+    // Safety: s is a valid, aligned and exclusive reference
+    unsafe { ptr::write_volatile(s, Scalar::zero()) };
+    compiler_fence(Ordering::SeqCst);
+}
+
+// A single secret scalar, e.g. secret_prover_blind
+pub struct SecretScalar(pub(crate) Scalar);
+
+// The random scalars of proof generation and commitments
+pub struct SecretScalars(pub(crate) Vec<Scalar>);
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        zeroize_scalar(&mut self.0);
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalar {}
+
+impl Zeroize for SecretScalars {
+    fn zeroize(&mut self) {
+        self.0.iter_mut().for_each(zeroize_scalar);
+    }
+}
+
+impl Drop for SecretScalars {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalars {}
+
+impl Deref for SecretScalars {
+    type Target = [Scalar];
+
+    fn deref(&self) -> &[Scalar] {
+        &self.0
+    }
+}
@@ -0,0 +1,544 @@
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::encoding::{encode_for_hash, g1_from_octets_with_mode, nonzero_scalar_from_octets_with_mode, scalar_to_bytes, DeserializeMode};
+use crate::error::BbsError;
+use crate::generators::{make_generators, Generators};
+use crate::hashing::hash_to_scalar;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::limits::{check_message_count, Limits};
+use crate::precomputed::PrecomputedGenerators;
+use crate::trace::trace_value;
+
+pub struct Signature {
+    pub(crate) a: G1,
+    pub(crate) e: Scalar,
+    pub(crate) s: Scalar,
+}
+
+// How Sign derives e and s. Deterministic is the spec's Sign, e and s are hash_to_scalar of SK, the
+// domain and the messages, so the same key, header and messages always give the same signature, on
+// every platform. Hedged appends 32 random octets to the hashed input, so signing the same messages
+// twice gives two signatures while a broken rng still leaves e and s unpredictable. Both verify with
+// the same Verify
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SigningMode {
+    Deterministic,
+    Hedged,
+}
+
+impl Signature {
+    // Sign in SigningMode::Deterministic
+    pub fn sign<'a, X>(messages: &[Scalar], sk: &SecretKey, header: Option<&[u8]>) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        Self::sign_with_hedge::<X>(messages, sk, header, None, &Limits::DEFAULT)
+    }
+
+    // Sign in mode, drawing the random octets of SigningMode::Hedged from rng
+    pub fn sign_with_mode<'a, X, R>(
+        messages: &[Scalar],
+        sk: &SecretKey,
+        header: Option<&[u8]>,
+        mode: SigningMode,
+        rng: &mut R,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        Self::sign_with_limits::<X, R>(messages, sk, header, mode, &Limits::DEFAULT, rng)
+    }
+
+    // As sign_with_mode, TooManyMessages above the max_messages of limits
+    pub fn sign_with_limits<'a, X, R>(
+        messages: &[Scalar],
+        sk: &SecretKey,
+        header: Option<&[u8]>,
+        mode: SigningMode,
+        limits: &Limits,
+        rng: &mut R,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        match mode {
+            SigningMode::Deterministic => Self::sign_with_hedge::<X>(messages, sk, header, None, limits),
+            SigningMode::Hedged => {
+                let mut hedge = Zeroizing::new([0u8; 32]);
+                rng.fill_bytes(hedge.as_mut());
+                Self::sign_with_hedge::<X>(messages, sk, header, Some(hedge.as_ref()), limits)
+            }
+        }
+    }
+
+    fn sign_with_hedge<'a, X>(
+        messages: &[Scalar],
+        sk: &SecretKey,
+        header: Option<&[u8]>,
+        hedge: Option<&[u8]>,
+        limits: &Limits,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        limits.check_message_count(messages.len())?;
+        let pk = sk.public_key();
+        let generators = make_generators::<X>(None, messages.len() + 2);
+        let domain = calculate_domain::<X>(&pk, &generators.message_generators, header);
+        trace_value!(debug, "domain", domain);
+
+        let mut e_s_for_hash = encode_for_hash(&[&sk.0, &domain, &messages]);
+        if let Some(hedge) = hedge {
+            e_s_for_hash.extend_from_slice(hedge);
+        }
+        let scalars = hash_to_scalar::<X>(&e_s_for_hash, 2, None);
+        let (e, s) = (scalars[0], scalars[1]);
+        trace_value!(debug, "e", e);
+        trace_value!(debug, "s", s);
+
+        let b = calculate_b(&generators, s, domain, messages);
+        trace_value!(debug, "B", b);
+
+        // (SK + e) = 0 mod r would produce A = Identity_G1
+        let sk_e_inv: Scalar = Option::from((sk.0 + e).invert()).ok_or(BbsError::SigningFailed)?;
+
+        let a = b * sk_e_inv;
+        trace_value!(debug, "A", a);
+        Ok(Signature { a, e, s })
+    }
+
+    // As sign with the fixed base tables of generators, which must have at least L + 2 message
+    // generators. CiphersuiteMismatch unless they were made for X
+    pub fn sign_precomputed<'a, X>(
+        messages: &[Scalar],
+        sk: &SecretKey,
+        header: Option<&[u8]>,
+        generators: &PrecomputedGenerators,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        Self::sign_precomputed_with_limits::<X>(messages, sk, header, generators, &Limits::DEFAULT)
+    }
+
+    // As sign_precomputed, TooManyMessages above the max_messages of limits
+    pub fn sign_precomputed_with_limits<'a, X>(
+        messages: &[Scalar],
+        sk: &SecretKey,
+        header: Option<&[u8]>,
+        generators: &PrecomputedGenerators,
+        limits: &Limits,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if generators.generators.ciphersuite_id != X::ID {
+            return Err(BbsError::CiphersuiteMismatch);
+        }
+        limits.check_message_count(messages.len())?;
+        let q = &generators.generators.message_generators;
+        if q.len() < messages.len() + 2 {
+            return Err(BbsError::InvalidLength("fewer precomputed generators than messages + 2"));
+        }
+
+        let pk = sk.public_key();
+        let domain = calculate_domain::<X>(&pk, &q[..messages.len() + 2], header);
+        trace_value!(debug, "domain", domain);
+
+        let e_s_for_hash = encode_for_hash(&[&sk.0, &domain, &messages]);
+        let scalars = hash_to_scalar::<X>(&e_s_for_hash, 2, None);
+        let (e, s) = (scalars[0], scalars[1]);
+        trace_value!(debug, "e", e);
+        trace_value!(debug, "s", s);
+
+        let b = generators.calculate_b(s, domain, messages);
+        trace_value!(debug, "B", b);
+
+        let sk_e_inv: Scalar = Option::from((sk.0 + e).invert()).ok_or(BbsError::SigningFailed)?;
+
+        let a = b * sk_e_inv;
+        trace_value!(debug, "A", a);
+        Ok(Signature { a, e, s })
+    }
+
+    // Replaces messages[index], which is old, with new in a signature the issuer made, without the
+    // other messages. With B = A * (SK + e) the updated signature is
+    // A' = (B + Q_1 * (s' - s) + H_i * (new - old)) / (SK + e') for e' and s' hashed from SK, the
+    // signature and the change. Keeping e would reveal H_i / (SK + e), with which the holder could
+    // change the message at will. generators need Q_1, Q_2 and H_1 up to H_index only, e.g.
+    // make_generators(None, index + 3) or the generators of a cache, so no other generator is hashed.
+    // They must be made for X, else CiphersuiteMismatch. Only update signatures the issuer made
+    // itself, for an arbitrary A this signs an arbitrary B
+    pub fn update_message<'a, X>(
+        &self,
+        sk: &SecretKey,
+        generators: &Generators,
+        index: usize,
+        old: Scalar,
+        new: Scalar,
+    ) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if generators.ciphersuite_id != X::ID {
+            return Err(BbsError::CiphersuiteMismatch);
+        }
+        check_message_count(index + 1)?;
+        let q = &generators.message_generators;
+        if q.len() < index + 3 {
+            return Err(BbsError::InvalidLength("the generators must reach H_index"));
+        }
+
+        let e_s_for_hash = encode_for_hash(&[&sk.0, &self.a, &self.e, &self.s, &index, &old, &new, &&b"UPDATE_"[..]]);
+        let scalars = hash_to_scalar::<X>(&e_s_for_hash, 2, None);
+        let (e, s) = (scalars[0], scalars[1]);
+        trace_value!(debug, "e", e);
+        trace_value!(debug, "s", s);
+
+        let b = self.a * (sk.0 + self.e) + q[0] * (s - self.s) + q[index + 2] * (new - old);
+        trace_value!(debug, "B", b);
+
+        let sk_e_inv: Scalar = Option::from((sk.0 + e).invert()).ok_or(BbsError::SigningFailed)?;
+
+        let a = b * sk_e_inv;
+        trace_value!(debug, "A", a);
+        Ok(Signature { a, e, s })
+    }
+
+    // signature_to_octets
+    pub fn to_bytes(&self) -> [u8; 112] {
+        let mut bytes = [0u8; 112];
+        bytes[..48].copy_from_slice(&Backend::g1_to_bytes(&self.a));
+        bytes[48..80].copy_from_slice(&scalar_to_bytes(&self.e));
+        bytes[80..].copy_from_slice(&scalar_to_bytes(&self.s));
+        bytes
+    }
+
+    // octets_to_signature, an error for any length other than 112 octets, A = Identity_G1 or e and s
+    // that are 0 or not less than r
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        Self::from_bytes_with_mode(bytes, DeserializeMode::Strict)
+    }
+
+    // As from_bytes, with the checks of mode. The length is checked in both modes
+    pub fn from_bytes_with_mode(bytes: &[u8], mode: DeserializeMode) -> Result<Self, BbsError> {
+        if bytes.len() != 112 {
+            return Err(BbsError::InvalidLength("a signature is 112 octets"));
+        }
+
+        Ok(Signature {
+            a: g1_from_octets_with_mode(&bytes[..48], mode)?,
+            e: nonzero_scalar_from_octets_with_mode(&bytes[48..80], mode)?,
+            s: nonzero_scalar_from_octets_with_mode(&bytes[80..], mode)?,
+        })
+    }
+
+    pub fn verify<'a, X>(&self, pk: &PublicKey, messages: &[Scalar], header: Option<&[u8]>) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.verify_with_limits::<X>(pk, messages, header, &Limits::DEFAULT)
+    }
+
+    // As verify, false above the max_messages of limits
+    pub fn verify_with_limits<'a, X>(&self, pk: &PublicKey, messages: &[Scalar], header: Option<&[u8]>, limits: &Limits) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        pk.validate() && self.verify_prevalidated_with_limits::<X>(pk, messages, header, limits)
+    }
+
+    // As verify without KeyValidate, for callers that already validated pk, e.g. once per issuer key
+    pub fn verify_prevalidated<'a, X>(&self, pk: &PublicKey, messages: &[Scalar], header: Option<&[u8]>) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.verify_prevalidated_with_limits::<X>(pk, messages, header, &Limits::DEFAULT)
+    }
+
+    fn verify_prevalidated_with_limits<'a, X>(&self, pk: &PublicKey, messages: &[Scalar], header: Option<&[u8]>, limits: &Limits) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        if Backend::g1_is_identity(&self.a) || !limits.allows(messages.len()) {
+            return false;
+        }
+
+        let generators = make_generators::<X>(None, messages.len() + 2);
+        let domain = calculate_domain::<X>(pk, &generators.message_generators, header);
+        trace_value!(debug, "domain", domain);
+        let b = calculate_b_vartime(&generators, self.s, domain, messages);
+        trace_value!(debug, "B", b);
+
+        // e(A, W + P2 * e) == e(B, P2), checked as e(A, W + P2 * e) * e(-B, P2) == 1 so that both
+        // Miller loops share one final exponentiation
+        let w_e = pk.0 + Backend::g2_generator() * self.e;
+        Backend::pairing_product_is_identity(&[(self.a, w_e), (-b, Backend::g2_generator())])
+    }
+
+    // Verifies (pk, header, messages, signature) tuples at once. Each check e(A, W + P2 * e) == e(B, P2)
+    // is rewritten as e(A, W) * e(A * e - B, P2) == 1 and the checks are combined with random scalars r_i,
+    // so all P2 terms collapse into one pairing: prod e(r_i * A_i, W_i) * e(sum r_i * (A_i * e_i - B_i), P2) == 1
+    #[cfg(feature = "std")]
+    pub fn batch_verify<'a, X>(items: &[(&PublicKey, Option<&[u8]>, &[Scalar], &Signature)]) -> bool
+    where
+        X: BbsCiphersuite<'a>
+    {
+        Self::batch_verify_with_rng::<X, _>(items, &mut rand::thread_rng())
+    }
+
+    // As batch_verify, with the r_i drawn from rng
+    pub fn batch_verify_with_rng<'a, X, R>(items: &[(&PublicKey, Option<&[u8]>, &[Scalar], &Signature)], rng: &mut R) -> bool
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        let mut terms = Vec::with_capacity(items.len() + 1);
+        let mut p2_term = Backend::g1_identity();
+
+        for &(pk, header, messages, signature) in items {
+            if !pk.validate() || Backend::g1_is_identity(&signature.a) || !Limits::DEFAULT.allows(messages.len()) {
+                return false;
+            }
+
+            let generators = make_generators::<X>(None, messages.len() + 2);
+            let domain = calculate_domain::<X>(pk, &generators.message_generators, header);
+            let b = calculate_b_vartime(&generators, signature.s, domain, messages);
+
+            let r = Scalar::random(&mut *rng);
+            terms.push((signature.a * r, pk.0));
+            p2_term += (signature.a * signature.e - b) * r;
+        }
+        terms.push((p2_term, Backend::g2_generator()));
+
+        Backend::pairing_product_is_identity(&terms)
+    }
+}
+
+// B = P1 + Q_1 * s + Q_2 * domain + H_1 * msg_1 + ... + H_L * msg_L
+pub(crate) fn calculate_b(generators: &Generators, s: Scalar, domain: Scalar, messages: &[Scalar]) -> G1 {
+    let q = &generators.message_generators;
+
+    let mut b = generators.g1_base_point + q[0] * s + q[1] * domain;
+    for (h, msg) in q[2..].iter().zip(messages) {
+        b += h * msg;
+    }
+    b
+}
+
+// calculate_b with one multi-scalar multiplication, for Verify where s and the messages are public.
+// Sign and ProofGen keep calculate_b, which is constant time in the messages
+pub(crate) fn calculate_b_vartime(generators: &Generators, s: Scalar, domain: Scalar, messages: &[Scalar]) -> G1 {
+    let q = &generators.message_generators;
+    let scalars: Vec<Scalar> = [s, domain].iter().chain(messages).copied().collect();
+
+    generators.g1_base_point + Backend::g1_sum_of_products_vartime(q, &scalars)
+}
+
+// domain = hash_to_scalar(encode_for_hash((PK, L, Q_1, Q_2, H_1, ..., H_L, ciphersuite_id, header)), 1)
+//
+// The domain binds a signature and its proofs to the public key, the generators, the ciphersuite and
+// the header. generators are Q_1, Q_2 and H_1, ..., H_L, the message_generators of
+// make_generators(None, L + 2), at least Q_1 and Q_2 are required. A missing header is the empty one
+pub fn calculate_domain<'a, X>(pk: &PublicKey, generators: &[G1], header: Option<&[u8]>) -> Scalar
+where
+    X: BbsCiphersuite<'a>
+{
+    let header = header.unwrap_or(&[]);
+    let l = generators.len() - 2;
+
+    let dom_for_hash = encode_for_hash(&[pk, &l, &generators, &X::ID, &header]);
+
+    hash_to_scalar::<X>(&dom_for_hash, 1, None)[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+    use crate::hashing::map_message_to_scalar_as_hash;
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    fn signature() -> (KeyPair, Vec<Scalar>, [u8; 112]) {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let messages: Vec<Scalar> = (1..=3u64).map(Scalar::from).collect();
+        let signature = Signature::sign::<Bls12381Sha256>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+        (key_pair, messages, signature.to_bytes())
+    }
+
+    // The key pair, header, messages and signatures of tooling/fixtures/signature/signature001.json and
+    // signature004.json
+    const SK: &str = "47d2ede63ab4c329092b342ab526b1079dbc2595897d4f2ab2de4d841cbe7d56";
+    const HEADER: &str = "11223344556677889900aabbccddeeff";
+    const MESSAGES: [&str; 10] = [
+        "9872ad089e452c7b6e283dfac2a80d58e8d0ff71cc4d5e310a1debdda4a45f02",
+        "87a8bd656d49ee07b8110e1d8fd4f1dcef6fb9bc368c492d9bc8c4f98a739ac6",
+        "96012096adda3f13dd4adbe4eea481a4c4b5717932b73b00e31807d3c5894b90",
+        "ac55fb33a75909edac8994829b250779298aa75d69324a365733f16c333fa943",
+        "d183ddc6e2665aa4e2f088af9297b78c0d22b4290273db637ed33ff5cf703151",
+        "515ae153e22aae04ad16f759e07237b43022cb1ced4c176e0999c6a8ba5817cc",
+        "496694774c5604ab1b2544eababcf0f53278ff5040c1e77c811656e8220417a2",
+        "77fe97eb97a1ebe2e81e4e3597a3ee740a66e9ef2412472c23364568523f8b91",
+        "7372e9daa5ed31e6cd5c825eac1b855e84476a1d94932aa348e07b7320912416",
+        "c344136d9ab02da4dd5908bbba913ae6f58c2cc844b802a6f811f5fb075f9b80",
+    ];
+    const SINGLE_MESSAGE_SIGNATURE: &str = "8791800d401cbf3b6edb9947b33775262cfca2c2af94e3c11809b53b20c6eca1a0466504ea057fe4d56952c3de99cbda4c15220babfa31cf9a1bcd0376c865d96bdf64e76edd4cfc06613af8eb3c638d3406272b38f5e87deabd4e4955fdd1abe2e1ee6ef2f106e35b4aadbb3d5ce4b5";
+    const MULTI_MESSAGE_SIGNATURE: &str = "948ab0936308d498d3253ad3c4ccd06cd2705155bb237dcbe0f91bb9290c2936f88291831276f1d8965777fb4148b9de310312d2706bbde03f753074780ddcae715eda2bc0bc2d298a150d5e436a5b6a4b908c1c06e3d1caf7770c135165804d50263237d958927fa2df0e9954de1bb4";
+
+    fn hex(octets: &str) -> Vec<u8> {
+        (0..octets.len()).step_by(2).map(|i| u8::from_str_radix(&octets[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn deterministic_signing_gives_the_fixture_octets() {
+        let sk = SecretKey::from_bytes(&hex(SK).try_into().unwrap()).unwrap();
+        let header = hex(HEADER);
+        let messages: Vec<Scalar> = MESSAGES
+            .iter()
+            .map(|msg| map_message_to_scalar_as_hash::<Bls12381Shake256>(&hex(msg), None).unwrap())
+            .collect();
+
+        for (messages, expected) in [(&messages[..1], SINGLE_MESSAGE_SIGNATURE), (&messages[..], MULTI_MESSAGE_SIGNATURE)] {
+            let signature = Signature::sign::<Bls12381Shake256>(messages, &sk, Some(&header)).unwrap();
+            assert_eq!(signature.to_bytes().to_vec(), hex(expected));
+
+            // The rng is not drawn from in SigningMode::Deterministic
+            for seed in [0, 1] {
+                let mode = SigningMode::Deterministic;
+                let rng = &mut StdRng::seed_from_u64(seed);
+                let signature = Signature::sign_with_mode::<Bls12381Shake256, _>(messages, &sk, Some(&header), mode, rng).unwrap();
+                assert_eq!(signature.to_bytes().to_vec(), hex(expected));
+            }
+
+            let rng = &mut StdRng::seed_from_u64(0);
+            let hedged = Signature::sign_with_mode::<Bls12381Shake256, _>(messages, &sk, Some(&header), SigningMode::Hedged, rng).unwrap();
+            assert_ne!(hedged.to_bytes().to_vec(), hex(expected));
+            assert!(hedged.verify::<Bls12381Shake256>(&sk.public_key(), messages, Some(&header)));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let (key_pair, messages, bytes) = signature();
+        let decoded = Signature::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert!(decoded.verify::<Bls12381Sha256>(&key_pair.public_key, &messages, Some(b"header")));
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let (_, _, bytes) = signature();
+        for len in [0, 111, 113] {
+            let mut octets = bytes.to_vec();
+            octets.resize(len, 0);
+            assert!(matches!(Signature::from_bytes(&octets), Err(BbsError::InvalidLength(_))), "{} octets", len);
+        }
+    }
+
+    #[test]
+    fn rejects_identity_and_invalid_a() {
+        let (_, _, bytes) = signature();
+        let mut identity = bytes;
+        identity[..48].copy_from_slice(&[0; 48]);
+        identity[0] = 0xc0;
+        assert!(matches!(Signature::from_bytes(&identity), Err(BbsError::InvalidPoint)));
+
+        // Without the compression flag
+        let mut uncompressed = bytes;
+        uncompressed[0] &= 0x7f;
+        assert!(matches!(Signature::from_bytes(&uncompressed), Err(BbsError::InvalidPoint)));
+    }
+
+    #[test]
+    fn rejects_non_canonical_scalars() {
+        let (_, _, bytes) = signature();
+        // r, the smallest value that is not less than r, r - 1 ending in a 0 octet
+        let mut r = scalar_to_bytes(&-Scalar::one());
+        r[31] += 1;
+        for (offset, scalar) in [(48, r), (80, r), (48, [0xff; 32]), (80, [0xff; 32]), (48, [0; 32]), (80, [0; 32])] {
+            let mut octets = bytes;
+            octets[offset..offset + 32].copy_from_slice(&scalar);
+            assert!(matches!(Signature::from_bytes(&octets), Err(BbsError::InvalidScalar)), "{:02x?} at {}", scalar, offset);
+        }
+    }
+
+    fn update_message<'a, X>()
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let mut messages: Vec<Scalar> = (1..=3u64).map(Scalar::from).collect();
+        let signature = Signature::sign::<X>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+
+        for index in 0..messages.len() {
+            let generators = make_generators::<X>(None, index + 3);
+            let new = Scalar::from(100 + index as u64);
+            let updated = signature.update_message::<X>(&key_pair.secret_key, &generators, index, messages[index], new).unwrap();
+            assert!(updated.e != signature.e, "the update reuses e");
+            assert!(!updated.verify::<X>(&key_pair.public_key, &messages, Some(b"header")));
+
+            let mut updated_messages = messages.clone();
+            updated_messages[index] = new;
+            assert!(updated.verify::<X>(&key_pair.public_key, &updated_messages, Some(b"header")));
+            assert!(!updated.verify::<X>(&key_pair.public_key, &updated_messages, None));
+        }
+
+        // Updates chain, each from the signature the issuer made last
+        let generators = make_generators::<X>(None, messages.len() + 2);
+        let first = signature.update_message::<X>(&key_pair.secret_key, &generators, 0, messages[0], Scalar::from(7u64)).unwrap();
+        messages[0] = Scalar::from(7u64);
+        let second = first.update_message::<X>(&key_pair.secret_key, &generators, 2, messages[2], Scalar::from(9u64)).unwrap();
+        messages[2] = Scalar::from(9u64);
+        assert!(second.verify::<X>(&key_pair.public_key, &messages, Some(b"header")));
+    }
+
+    #[test]
+    fn sign_precomputed_gives_the_signature_of_sign() {
+        let (key_pair, messages, bytes) = signature();
+        let precomputed = make_generators::<Bls12381Sha256>(None, messages.len() + 2).precompute(messages.len() + 2);
+        let sk = &key_pair.secret_key;
+
+        let signature = Signature::sign_precomputed::<Bls12381Sha256>(&messages, sk, Some(b"header"), &precomputed).unwrap();
+        assert_eq!(signature.to_bytes(), bytes);
+
+        let limits = Limits::new(messages.len() - 1);
+        let result = Signature::sign_precomputed_with_limits::<Bls12381Sha256>(&messages, sk, Some(b"header"), &precomputed, &limits);
+        assert!(matches!(result, Err(BbsError::TooManyMessages(count)) if count == messages.len()));
+        let result = Signature::sign_precomputed::<Bls12381Shake256>(&messages, sk, Some(b"header"), &precomputed);
+        assert!(matches!(result, Err(BbsError::CiphersuiteMismatch)));
+    }
+
+    #[test]
+    fn update_message_signs_the_new_message() {
+        update_message::<Bls12381Sha256>();
+        update_message::<Bls12381Shake256>();
+    }
+
+    #[test]
+    fn update_message_rejects_other_generators() {
+        let (key_pair, messages, bytes) = signature();
+        let signature = Signature::from_bytes(&bytes).unwrap();
+        let sk = &key_pair.secret_key;
+
+        let shake = make_generators::<Bls12381Shake256>(None, 5);
+        let result = signature.update_message::<Bls12381Sha256>(sk, &shake, 0, messages[0], Scalar::from(7u64));
+        assert!(matches!(result, Err(BbsError::CiphersuiteMismatch)));
+
+        // H_3 is missing
+        let short = make_generators::<Bls12381Sha256>(None, 4);
+        let result = signature.update_message::<Bls12381Sha256>(sk, &short, 2, messages[2], Scalar::from(7u64));
+        assert!(matches!(result, Err(BbsError::InvalidLength(_))));
+    }
+}
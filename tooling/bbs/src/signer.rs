@@ -0,0 +1,94 @@
+// Signing with a secret key the caller does not hold, e.g. in an HSM, a KMS or a remote signing
+// service. Such a signer is reached asynchronously and holds keys for some ciphersuites only, so the
+// trait is async and object safe, with the ciphersuite named by its ciphersuite_id. LocalSigner is the
+// software signer over a SecretKey in memory. Tooling takes a &dyn BbsSigner and calls sign_with
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+
+use async_trait::async_trait;
+
+use crate::ciphersuites::{BbsCiphersuite, Bls12381Sha256, Bls12381Shake256, CiphersuiteParams};
+#[cfg(feature = "non-standard-suites")]
+use crate::ciphersuites::Bls12381Sha512;
+use crate::curve::Scalar;
+use crate::error::BbsError;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::limits::Limits;
+use crate::signature::{Signature, SigningMode};
+
+#[async_trait]
+pub trait BbsSigner: Send + Sync {
+    // The public key the signatures verify with
+    fn public_key(&self) -> &PublicKey;
+
+    // Sign of the ciphersuite with the id ciphersuite_id, an error for ciphersuites the signer does not
+    // support
+    async fn sign(&self, ciphersuite_id: &[u8], messages: &[Scalar], header: Option<&[u8]>) -> Result<Signature, BbsError>;
+}
+
+// signer.sign with the ciphersuite X
+pub async fn sign_with<'a, X>(signer: &dyn BbsSigner, messages: &[Scalar], header: Option<&[u8]>) -> Result<Signature, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    signer.sign(X::ID, messages, header).await
+}
+
+pub struct LocalSigner {
+    sk: SecretKey,
+    pk: PublicKey,
+    mode: SigningMode,
+    limits: Limits,
+}
+
+impl LocalSigner {
+    // Signs in SigningMode::Deterministic
+    pub fn new(sk: SecretKey) -> Self {
+        Self::with_mode(sk, SigningMode::Deterministic)
+    }
+
+    pub fn with_mode(sk: SecretKey, mode: SigningMode) -> Self {
+        let pk = sk.public_key();
+        LocalSigner { sk, pk, mode, limits: Limits::DEFAULT }
+    }
+
+    // Signs at most the max_messages of limits, TooManyMessages above
+    pub fn with_limits(self, limits: Limits) -> Self {
+        LocalSigner { limits, ..self }
+    }
+}
+
+#[async_trait]
+impl BbsSigner for LocalSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.pk
+    }
+
+    async fn sign(&self, ciphersuite_id: &[u8], messages: &[Scalar], header: Option<&[u8]>) -> Result<Signature, BbsError> {
+        sign_by_id(ciphersuite_id, messages, &self.sk, header, self.mode, &self.limits)
+    }
+}
+
+// Signature::sign_with_limits with the ciphersuite of ciphersuite_id, for signers holding the key in memory
+pub(crate) fn sign_by_id(
+    ciphersuite_id: &[u8],
+    messages: &[Scalar],
+    sk: &SecretKey,
+    header: Option<&[u8]>,
+    mode: SigningMode,
+    limits: &Limits,
+) -> Result<Signature, BbsError> {
+    let rng = &mut rand::thread_rng();
+    if ciphersuite_id == Bls12381Sha256::ID {
+        return Signature::sign_with_limits::<Bls12381Sha256, _>(messages, sk, header, mode, limits, rng);
+    }
+    if ciphersuite_id == Bls12381Shake256::ID {
+        return Signature::sign_with_limits::<Bls12381Shake256, _>(messages, sk, header, mode, limits, rng);
+    }
+    #[cfg(feature = "non-standard-suites")]
+    if ciphersuite_id == Bls12381Sha512::ID {
+        return Signature::sign_with_limits::<Bls12381Sha512, _>(messages, sk, header, mode, limits, rng);
+    }
+    Err(BbsError::Serialization(format!("unsupported ciphersuite {}", String::from_utf8_lossy(ciphersuite_id))))
+}
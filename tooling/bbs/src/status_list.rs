@@ -0,0 +1,333 @@
+// StatusList2021 style revocation without disclosing the status list index. The issuer signs the index
+// of the credential in its status list as a hidden message, the scalar of the integer index, and sets
+// the bit of a revoked credential in the list. The holder proves that the hidden index differs from
+// every set bit of the list the verifier fetched: with V = G * i + H * gamma linked to the message as
+// in the predicate module, and D_j = V - G * j = G * (i - j) + H * gamma for every revoked index j, a
+// Schnorr proof of a_j, b_j with G = D_j * a_j + H * b_j exists only when i - j is invertible. The
+// proof grows with the number of revoked credentials, not with the length of the list. Decoding the
+// encodedList of a StatusList2021Credential, base64url then GZIP, is left to the caller
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+use crate::encoding::{g1_from_octets, scalar_from_bytes, scalar_to_bytes};
+use crate::error::BbsError;
+use crate::key_pair::PublicKey;
+use crate::predicate::{linked_ph, opens_hidden_message, predicate_generators};
+use crate::proof::Proof;
+use crate::secret::{SecretScalar, SecretScalars};
+use crate::signature::Signature;
+
+// The bitstring of a status list, index 0 being the most significant bit of the first octet
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusList {
+    bits: Vec<u8>,
+}
+
+pub struct StatusProof {
+    // V = G * i + H * gamma
+    pub(crate) v: G1,
+    // T = G * m~ + H * gamma~, m~ being the blinding factor of the index in the BBS proof
+    pub(crate) t: G1,
+    pub(crate) gamma_hat: Scalar,
+    // R_j = D_j * a~_j + H * b~_j, a^_j and b^_j for every revoked index j in ascending order
+    pub(crate) commitments: Vec<G1>,
+    pub(crate) a_hat: Vec<Scalar>,
+    pub(crate) b_hat: Vec<Scalar>,
+}
+
+impl StatusList {
+    // A list of length indexes, none of them revoked. StatusList2021 lists hold at least 131072
+    pub fn new(length: usize) -> Self {
+        StatusList { bits: vec![0; (length + 7) / 8] }
+    }
+
+    // The decompressed encodedList
+    pub fn from_bytes(bits: Vec<u8>) -> Self {
+        StatusList { bits }
+    }
+
+    pub fn to_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    // Indexes past the end of the list are not revoked
+    pub fn is_revoked(&self, index: usize) -> bool {
+        self.bits.get(index / 8).map_or(false, |octet| octet >> (7 - index % 8) & 1 == 1)
+    }
+
+    // Run by the issuer, an error past the end of the list
+    pub fn revoke(&mut self, index: usize) -> Result<(), BbsError> {
+        let octet = self
+            .bits
+            .get_mut(index / 8)
+            .ok_or(BbsError::InvalidLength("the index is past the end of the status list"))?;
+        *octet |= 1 << (7 - index % 8);
+        Ok(())
+    }
+
+    pub fn revoked_indexes(&self) -> Vec<usize> {
+        (0..self.len()).filter(|&i| self.is_revoked(i)).collect()
+    }
+}
+
+// Generates a BBS proof together with a proof that messages[index], the hidden status list index of
+// the credential, is not revoked in status_list. The proof is verified with verify_status and the
+// same ph and list. ProofGenerationFailed when the credential is revoked
+#[allow(clippy::too_many_arguments)]
+pub fn prove_status<'a, X, R>(
+    signature: &Signature,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    messages: &[Scalar],
+    disclosed_indexes: &[usize],
+    index: usize,
+    status_list: &StatusList,
+    rng: &mut R,
+) -> Result<(Proof, StatusProof), BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    if index >= messages.len() || disclosed_indexes.contains(&index) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+    let status_index = messages[index];
+    let revoked: Vec<Scalar> = status_list.revoked_indexes().into_iter().map(|j| Scalar::from(j as u64)).collect();
+    // a_j = 1 / (i - j), b_j = -gamma * a_j
+    let inverses = SecretScalars(
+        revoked
+            .iter()
+            .map(|j| Option::from((status_index - j).invert()).ok_or(BbsError::ProofGenerationFailed))
+            .collect::<Result<_, _>>()?,
+    );
+
+    let (g, h) = predicate_generators::<X>();
+    let gamma = SecretScalar(Scalar::random(&mut *rng));
+    let m_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let gamma_tilde = SecretScalar(Scalar::random(&mut *rng));
+    let a_tilde = SecretScalars(revoked.iter().map(|_| Scalar::random(&mut *rng)).collect());
+    let b_tilde = SecretScalars(revoked.iter().map(|_| Scalar::random(&mut *rng)).collect());
+
+    let v = g * status_index + h * gamma.0;
+    let t = g * m_tilde.0 + h * gamma_tilde.0;
+    let commitments: Vec<G1> = revoked
+        .iter()
+        .zip(a_tilde.iter().zip(b_tilde.iter()))
+        .map(|(j, (a_tilde, b_tilde))| (v - g * j) * a_tilde + h * b_tilde)
+        .collect();
+
+    let proof = Proof::generate_with_blindings::<X, _>(
+        signature,
+        pk,
+        header,
+        Some(&linked_ph(ph, &v, &t, &commitments)),
+        messages,
+        disclosed_indexes,
+        &BTreeMap::from([(index, m_tilde.0)]),
+        rng,
+    )?;
+
+    let c = proof.challenge();
+    let status = StatusProof {
+        v,
+        t,
+        gamma_hat: c * gamma.0 + gamma_tilde.0,
+        commitments,
+        a_hat: inverses.iter().zip(a_tilde.iter()).map(|(a, a_tilde)| c * a + a_tilde).collect(),
+        b_hat: inverses
+            .iter()
+            .zip(b_tilde.iter())
+            .map(|(a, b_tilde)| c * (-gamma.0 * a) + b_tilde)
+            .collect(),
+    };
+    Ok((proof, status))
+}
+
+// Verifies the BBS proof and that its hidden message at index, a status list index, is not revoked in
+// status_list, the list as the verifier fetched it
+#[allow(clippy::too_many_arguments)]
+pub fn verify_status<'a, X>(
+    proof: &Proof,
+    status: &StatusProof,
+    pk: &PublicKey,
+    header: Option<&[u8]>,
+    ph: Option<&[u8]>,
+    disclosed_messages: &BTreeMap<usize, Scalar>,
+    index: usize,
+    status_list: &StatusList,
+) -> bool
+where
+    X: BbsCiphersuite<'a>
+{
+    let revoked = status_list.revoked_indexes();
+    let k = revoked.len();
+    if status.commitments.len() != k || status.a_hat.len() != k || status.b_hat.len() != k {
+        return false;
+    }
+    if disclosed_messages.contains_key(&index) {
+        return false;
+    }
+    if !proof.verify::<X>(pk, header, Some(&linked_ph(ph, &status.v, &status.t, &status.commitments)), disclosed_messages) {
+        return false;
+    }
+
+    let (g, h) = predicate_generators::<X>();
+    let c = proof.challenge();
+    if !opens_hidden_message(proof, disclosed_messages, index, (g, h), &status.v, &status.t, &status.gamma_hat) {
+        return false;
+    }
+
+    // D_j * a^_j + H * b^_j == R_j + G * c
+    revoked.iter().enumerate().all(|(n, &j)| {
+        let d = status.v - g * Scalar::from(j as u64);
+        d * status.a_hat[n] + h * status.b_hat[n] == status.commitments[n] + g * c
+    })
+}
+
+impl StatusProof {
+    // V || T || gamma^ || R_1 || a^_1 || b^_1 || ... || R_k || a^_k || b^_k
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 * 48 + 32 + self.commitments.len() * (48 + 2 * 32));
+        bytes.extend_from_slice(&Backend::g1_to_bytes(&self.v));
+        bytes.extend_from_slice(&Backend::g1_to_bytes(&self.t));
+        bytes.extend_from_slice(&scalar_to_bytes(&self.gamma_hat));
+        for ((r, a_hat), b_hat) in self.commitments.iter().zip(&self.a_hat).zip(&self.b_hat) {
+            bytes.extend_from_slice(&Backend::g1_to_bytes(r));
+            bytes.extend_from_slice(&scalar_to_bytes(a_hat));
+            bytes.extend_from_slice(&scalar_to_bytes(b_hat));
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        const REVOKED_LENGTH: usize = 48 + 2 * 32;
+        if bytes.len() < 2 * 48 + 32 || (bytes.len() - 2 * 48 - 32) % REVOKED_LENGTH != 0 {
+            return Err(BbsError::InvalidLength("a status proof is 2 points and a scalar, then a point and 2 scalars per revoked index"));
+        }
+        let scalar = |s: &[u8]| scalar_from_bytes(s.try_into().expect("32 octets"));
+
+        let (head, revoked) = bytes.split_at(2 * 48 + 32);
+        let mut status = StatusProof {
+            v: g1_from_octets(&head[..48])?,
+            t: g1_from_octets(&head[48..96])?,
+            gamma_hat: scalar(&head[96..])?,
+            commitments: Vec::new(),
+            a_hat: Vec::new(),
+            b_hat: Vec::new(),
+        };
+        for chunk in revoked.chunks(REVOKED_LENGTH) {
+            status.commitments.push(g1_from_octets(&chunk[..48])?);
+            status.a_hat.push(scalar(&chunk[48..80])?);
+            status.b_hat.push(scalar(&chunk[80..])?);
+        }
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+    use crate::key_pair::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const IKM: &[u8] = b"this-IS-just-an-Test-IKM-to-generate-$e(r@#-key";
+
+    // Two messages followed by the status list index, disclosing the first
+    fn credential<'a, X>(index: usize) -> (KeyPair, Vec<Scalar>, Signature, BTreeMap<usize, Scalar>)
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let key_pair = KeyPair::from_ikm(IKM, None).unwrap();
+        let messages = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(index as u64)];
+        let signature = Signature::sign::<X>(&messages, &key_pair.secret_key, Some(b"header")).unwrap();
+        (key_pair, messages, signature, BTreeMap::from([(0, messages[0])]))
+    }
+
+    fn status<'a, X>()
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (key_pair, messages, signature, disclosed) = credential::<X>(12);
+        let mut status_list = StatusList::new(64);
+        for i in [0, 3, 13, 63] {
+            status_list.revoke(i).unwrap();
+        }
+
+        let (proof, status) =
+            prove_status::<X, _>(&signature, &key_pair.public_key, Some(b"header"), Some(b"ph"), &messages, &[0], 2, &status_list, rng)
+                .unwrap();
+        let verify = |status: &StatusProof, ph: &[u8], status_list: &StatusList| {
+            verify_status::<X>(&proof, status, &key_pair.public_key, Some(b"header"), Some(ph), &disclosed, 2, status_list)
+        };
+        assert!(verify(&status, b"ph", &status_list));
+        assert!(verify(&StatusProof::from_bytes(&status.to_bytes()).unwrap(), b"ph", &status_list));
+        assert!(!verify(&status, b"another ph", &status_list));
+
+        // The list revoking another index, or the credential, since the proof was generated
+        let mut other = status_list.clone();
+        other.revoke(40).unwrap();
+        assert!(!verify(&status, b"ph", &other));
+        status_list.revoke(12).unwrap();
+        assert!(!verify(&status, b"ph", &status_list));
+    }
+
+    #[test]
+    fn status_proof_verifies_until_the_index_is_revoked() {
+        status::<Bls12381Sha256>();
+        status::<Bls12381Shake256>();
+    }
+
+    #[test]
+    fn revoked_credential_can_not_prove_its_status() {
+        let rng = &mut StdRng::seed_from_u64(1);
+        let (key_pair, messages, signature, _) = credential::<Bls12381Sha256>(5);
+        let mut status_list = StatusList::new(16);
+        status_list.revoke(5).unwrap();
+        let prove = |disclosed_indexes: &[usize], rng: &mut StdRng| {
+            let pk = &key_pair.public_key;
+            prove_status::<Bls12381Sha256, _>(&signature, pk, None, None, &messages, disclosed_indexes, 2, &status_list, rng)
+        };
+        assert!(matches!(prove(&[0], rng), Err(BbsError::ProofGenerationFailed)));
+        assert!(matches!(prove(&[2], rng), Err(BbsError::InvalidDisclosedIndexes)));
+    }
+
+    #[test]
+    fn status_list_bits() {
+        let mut status_list = StatusList::new(10);
+        assert_eq!(status_list.len(), 16);
+        assert!(status_list.revoked_indexes().is_empty());
+
+        status_list.revoke(0).unwrap();
+        status_list.revoke(9).unwrap();
+        assert_eq!(status_list.to_bytes(), [0x80, 0x40]);
+        assert_eq!(status_list.revoked_indexes(), [0, 9]);
+        assert!(!status_list.is_revoked(100));
+        assert!(matches!(status_list.revoke(16), Err(BbsError::InvalidLength(_))));
+        assert_eq!(StatusList::from_bytes(status_list.to_bytes().to_vec()), status_list);
+    }
+
+    #[test]
+    fn status_proof_from_bytes_rejects_other_lengths() {
+        for length in [0, 2 * 48, 2 * 48 + 32 + 1, 2 * 48 + 32 + 48 + 32] {
+            assert!(matches!(StatusProof::from_bytes(&vec![0u8; length]), Err(BbsError::InvalidLength(_))));
+        }
+    }
+}
@@ -0,0 +1,661 @@
+// t-of-n threshold signing producing standard BBS signatures, so an issuer key can be split across HSM
+// nodes. The secret key is Shamir shared, any t nodes sign a message and the partial signatures are
+// aggregated into a signature that verifies under the unchanged public key.
+//
+// Signing follows the presignature approach of Doerner et al. (Threshold BBS+ Signatures for Distributed
+// Anonymous Credential Issuance): for a random r shared as r_i and shares u_i of r * SK, each node returns
+// A_i = B * r_i and delta_i = u_i + e * r_i, and A = (sum l_i * A_i) * (sum l_i * delta_i)^-1 = B * 1/(SK + e)
+// with l_i the Lagrange coefficients of the signing nodes. The shares of r * SK are computed without
+// reconstructing SK from a Beaver triple (a, b, c = a * b) handed out by a dealer: r = a, the signing
+// nodes open d = SK - b among themselves and u_i = c_i + d * a_i.
+//
+// The dealer of the triples is trusted with SK. It knows b, so d gives it SK = d + b, and it knows a_i
+// and c_i, so a partial signature gives it d through delta_i = c_i + d * a_i + e * a_i. The masked key
+// shares d_i are sent only to the other signing nodes, over confidential and authenticated channels,
+// and the partial signatures only to the aggregator. Neither may reach the dealer, and the dealer must
+// not collude with a signing node or the aggregator. Run the dealer inside the same trust boundary as
+// the key shares, e.g. in an HSM, or generate the triples with a dealerless multiplication protocol.
+//
+// A presignature must be used for a single message, two signatures with the same r reveal SK. e and s are
+// derived from the public nonce of the presignature rather than from SK.
+//
+// The key shares of long lived keys are refreshed periodically, so that shares leaked before a refresh
+// are useless after it: every node deals a sharing of 0, with Feldman commitments to its polynomial, and
+// each node adds the shares it receives to its key share. SK and the public key do not change
+use alloc::vec::Vec;
+
+use ff::Field;
+use rand::{CryptoRng, RngCore};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::curve::{Backend, PairingCurve, Scalar, G1, G2};
+use crate::encoding::{encode_for_hash, g1_from_octets, i2osp, nonzero_scalar_from_octets, scalar_to_bytes};
+use crate::error::BbsError;
+use crate::generators::{make_generators, Generators};
+use crate::hashing::hash_to_scalar;
+use crate::key_pair::{PublicKey, SecretKey};
+use crate::limits::check_message_count;
+use crate::secret::SecretScalar;
+use crate::signature::{calculate_b, calculate_domain, Signature};
+
+// The share of the secret key held by the node with index 1 to n
+pub struct KeyShare {
+    pub(crate) index: usize,
+    pub(crate) share: SecretScalar,
+}
+
+// A node's share of a Beaver triple (a, b, c = a * b), dealt for one presignature
+pub struct TripleShare {
+    pub(crate) index: usize,
+    pub(crate) nonce: [u8; 32],
+    pub(crate) a: SecretScalar,
+    pub(crate) b: SecretScalar,
+    pub(crate) c: SecretScalar,
+}
+
+// d_i = SK_i - b_i, sent by each node to the other signing nodes, never the dealer, to open d = SK - b
+pub struct MaskedKeyShare {
+    pub(crate) index: usize,
+    pub(crate) nonce: [u8; 32],
+    pub(crate) d: Scalar,
+}
+
+// A node's shares r_i of r and u_i of r * SK, consumed by signing one message
+pub struct Presignature {
+    pub(crate) index: usize,
+    pub(crate) nonce: [u8; 32],
+    pub(crate) r: SecretScalar,
+    pub(crate) u: SecretScalar,
+}
+
+// A_i = B * r_i and delta_i = u_i + e * r_i
+pub struct PartialSignature {
+    pub(crate) index: usize,
+    pub(crate) nonce: [u8; 32],
+    pub(crate) a: G1,
+    pub(crate) delta: Scalar,
+}
+
+// A node's share of the sharing of 0 dealt by node from in a refresh, with the commitments P2 * a_k to
+// the coefficients of the dealer's polynomial, the first of which is Identity_G2
+pub struct RefreshShare {
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    pub(crate) delta: SecretScalar,
+    pub(crate) commitments: Vec<G2>,
+}
+
+// Generates a key pair and splits the secret key into count shares, any threshold of which sign. The
+// secret key is dropped, and zeroized, when this returns
+pub fn generate_key_shares<R>(threshold: usize, count: usize, rng: &mut R) -> Result<(PublicKey, Vec<KeyShare>), BbsError>
+where
+    R: RngCore + CryptoRng
+{
+    let sk = SecretKey(Scalar::random(&mut *rng));
+    let shares = split_secret_key(&sk, threshold, count, rng)?;
+    Ok((sk.public_key(), shares))
+}
+
+// Splits an existing secret key, e.g. when an issuer moves its key to threshold signing
+pub fn split_secret_key<R>(sk: &SecretKey, threshold: usize, count: usize, rng: &mut R) -> Result<Vec<KeyShare>, BbsError>
+where
+    R: RngCore + CryptoRng
+{
+    let shares = shamir_split(&sk.0, threshold, count, rng)?;
+    Ok(shares
+        .into_iter()
+        .enumerate()
+        .map(|(i, share)| KeyShare { index: i + 1, share: SecretScalar(share) })
+        .collect())
+}
+
+// Deals the triple shares of one presignature to count nodes, any threshold of which complete it
+pub fn deal_triples<R>(threshold: usize, count: usize, rng: &mut R) -> Result<Vec<TripleShare>, BbsError>
+where
+    R: RngCore + CryptoRng
+{
+    let mut nonce = [0u8; 32];
+    rng.fill_bytes(&mut nonce);
+
+    let a = SecretScalar(Scalar::random(&mut *rng));
+    let b = SecretScalar(Scalar::random(&mut *rng));
+    let c = SecretScalar(a.0 * b.0);
+    let a_shares = shamir_split(&a.0, threshold, count, rng)?;
+    let b_shares = shamir_split(&b.0, threshold, count, rng)?;
+    let c_shares = shamir_split(&c.0, threshold, count, rng)?;
+
+    Ok((0..count)
+        .map(|i| TripleShare {
+            index: i + 1,
+            nonce,
+            a: SecretScalar(a_shares[i]),
+            b: SecretScalar(b_shares[i]),
+            c: SecretScalar(c_shares[i]),
+        })
+        .collect())
+}
+
+impl KeyShare {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    // I2OSP(index, 4) || share
+    pub fn to_bytes(&self) -> [u8; 36] {
+        let mut bytes = [0u8; 36];
+        bytes[..4].copy_from_slice(&i2osp(self.index as u64, 4));
+        bytes[4..].copy_from_slice(&scalar_to_bytes(&self.share.0));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        if bytes.len() != 36 {
+            return Err(BbsError::InvalidLength("a key share is 36 octets"));
+        }
+        Ok(KeyShare {
+            index: share_index(&bytes[..4])?,
+            share: SecretScalar(nonzero_scalar_from_octets(&bytes[4..])?),
+        })
+    }
+}
+
+impl KeyShare {
+    // The node's contribution to a refresh, one RefreshShare for each of the count nodes
+    pub fn refresh_shares<R>(&self, threshold: usize, count: usize, rng: &mut R) -> Result<Vec<RefreshShare>, BbsError>
+    where
+        R: RngCore + CryptoRng
+    {
+        let coefficients = shamir_polynomial(&Scalar::zero(), threshold, count, rng)?;
+        let commitments: Vec<G2> = coefficients.iter().map(|a| Backend::g2_generator() * a.0).collect();
+
+        Ok((1..=count)
+            .map(|to| RefreshShare {
+                from: self.index,
+                to,
+                delta: SecretScalar(evaluate(&coefficients, to)),
+                commitments: commitments.clone(),
+            })
+            .collect())
+    }
+
+    // The refreshed key share, the sum of this share and the RefreshShares dealt to this node. All nodes
+    // apply the shares of the same dealers, including their own. Fails with InvalidCommitment when a
+    // share does not match its dealer's commitments or the dealt polynomial is not a sharing of 0
+    pub fn refresh(self, received: &[RefreshShare]) -> Result<KeyShare, BbsError> {
+        let mut share = SecretScalar(self.share.0);
+        for (i, refresh) in received.iter().enumerate() {
+            if refresh.to != self.index || received[..i].iter().any(|r| r.from == refresh.from) {
+                return Err(BbsError::InvalidLength("one refresh share of each dealer to this node"));
+            }
+            if !refresh.verify() {
+                return Err(BbsError::InvalidCommitment);
+            }
+            share.0 += refresh.delta.0;
+        }
+        Ok(KeyShare { index: self.index, share })
+    }
+}
+
+impl RefreshShare {
+    // Feldman's check P2 * delta == sum C_k * to^k, with C_0 = Identity_G2 so the shared value is 0
+    fn verify(&self) -> bool {
+        let c_0_is_identity = self.commitments.first().map_or(false, Backend::g2_is_identity);
+        let x = Scalar::from(self.to as u64);
+        let expected = self
+            .commitments
+            .iter()
+            .rev()
+            .fold(Backend::g2_identity(), |sum, commitment| sum * x + commitment);
+        c_0_is_identity && Backend::g2_generator() * self.delta.0 == expected
+    }
+}
+
+impl TripleShare {
+    // The d_i to send to the other signing nodes for this presignature. Together with the triple it gives
+    // SK, so it must not reach the dealer
+    pub fn mask(&self, key_share: &KeyShare) -> Result<MaskedKeyShare, BbsError> {
+        if key_share.index != self.index {
+            return Err(BbsError::SigningFailed);
+        }
+        Ok(MaskedKeyShare {
+            index: self.index,
+            nonce: self.nonce,
+            d: key_share.share.0 - self.b.0,
+        })
+    }
+}
+
+impl Presignature {
+    // Completes the node's presignature from the masked key shares of at least threshold nodes, its own
+    // included. The triple share is consumed
+    pub fn new(triple: TripleShare, masked: &[MaskedKeyShare]) -> Result<Self, BbsError> {
+        if masked.iter().any(|m| m.nonce != triple.nonce) {
+            return Err(BbsError::SigningFailed);
+        }
+        let indexes: Vec<usize> = masked.iter().map(|m| m.index).collect();
+        let lagrange = lagrange_coefficients(&indexes)?;
+        let d = masked.iter().zip(&lagrange).fold(Scalar::zero(), |d, (m, l)| d + m.d * l);
+
+        Ok(Presignature {
+            index: triple.index,
+            nonce: triple.nonce,
+            r: SecretScalar(triple.a.0),
+            u: SecretScalar(triple.c.0 + d * triple.a.0),
+        })
+    }
+
+    pub fn nonce(&self) -> [u8; 32] {
+        self.nonce
+    }
+}
+
+impl PartialSignature {
+    // The node's partial signature of messages, consuming the presignature so it is not used again, also
+    // when it fails with TooManyMessages
+    pub fn new<'a, X>(presignature: Presignature, pk: &PublicKey, messages: &[Scalar], header: Option<&[u8]>) -> Result<Self, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let (generators, domain, e, s) = signing_scalars::<X>(&presignature.nonce, pk, messages, header)?;
+        let b = calculate_b(&generators, s, domain, messages);
+
+        Ok(PartialSignature {
+            index: presignature.index,
+            nonce: presignature.nonce,
+            a: b * presignature.r.0,
+            delta: presignature.u.0 + e * presignature.r.0,
+        })
+    }
+
+    // I2OSP(index, 4) || nonce || point_to_octets(A_i) || delta_i
+    pub fn to_bytes(&self) -> [u8; 116] {
+        let mut bytes = [0u8; 116];
+        bytes[..4].copy_from_slice(&i2osp(self.index as u64, 4));
+        bytes[4..36].copy_from_slice(&self.nonce);
+        bytes[36..84].copy_from_slice(&Backend::g1_to_bytes(&self.a));
+        bytes[84..].copy_from_slice(&scalar_to_bytes(&self.delta));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbsError> {
+        if bytes.len() != 116 {
+            return Err(BbsError::InvalidLength("a partial signature is 116 octets"));
+        }
+        let mut nonce = [0u8; 32];
+        nonce.copy_from_slice(&bytes[4..36]);
+        Ok(PartialSignature {
+            index: share_index(&bytes[..4])?,
+            nonce,
+            a: g1_from_octets(&bytes[36..84])?,
+            delta: nonzero_scalar_from_octets(&bytes[84..])?,
+        })
+    }
+}
+
+// Aggregates the partial signatures of at least threshold nodes into a BBS signature of messages. The
+// signature is verified before it is returned, so too few or inconsistent partial signatures fail
+pub fn aggregate<'a, X>(
+    partials: &[PartialSignature],
+    pk: &PublicKey,
+    messages: &[Scalar],
+    header: Option<&[u8]>,
+) -> Result<Signature, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let nonce = partials.first().ok_or(BbsError::SigningFailed)?.nonce;
+    if partials.iter().any(|p| p.nonce != nonce) {
+        return Err(BbsError::SigningFailed);
+    }
+    let indexes: Vec<usize> = partials.iter().map(|p| p.index).collect();
+    let lagrange = lagrange_coefficients(&indexes)?;
+
+    let (a, delta) = partials
+        .iter()
+        .zip(&lagrange)
+        .fold((Backend::g1_identity(), Scalar::zero()), |(a, delta), (p, l)| (a + p.a * l, delta + p.delta * l));
+    let delta_inv: Scalar = Option::from(delta.invert()).ok_or(BbsError::SigningFailed)?;
+
+    let (_, _, e, s) = signing_scalars::<X>(&nonce, pk, messages, header)?;
+    let signature = Signature { a: a * delta_inv, e, s };
+    if !signature.verify::<X>(pk, messages, header) {
+        return Err(BbsError::SigningFailed);
+    }
+    Ok(signature)
+}
+
+// The generators, domain, e and s of a threshold signature, e and s hashed from the presignature nonce
+// where Sign hashes SK
+fn signing_scalars<'a, X>(
+    nonce: &[u8; 32],
+    pk: &PublicKey,
+    messages: &[Scalar],
+    header: Option<&[u8]>,
+) -> Result<(Generators, Scalar, Scalar, Scalar), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    check_message_count(messages.len())?;
+    let generators = make_generators::<X>(None, messages.len() + 2);
+    let domain = calculate_domain::<X>(pk, &generators.message_generators, header);
+
+    // The nonce takes the place of SK and, as SK, is not length prefixed
+    let mut e_s_for_hash = nonce.to_vec();
+    e_s_for_hash.extend_from_slice(&encode_for_hash(&[&domain, &messages]));
+    let scalars = hash_to_scalar::<X>(&e_s_for_hash, 2, None);
+    Ok((generators, domain, scalars[0], scalars[1]))
+}
+
+// Shares of secret at x = 1, ..., count on a random polynomial of degree threshold - 1
+pub(crate) fn shamir_split<R>(secret: &Scalar, threshold: usize, count: usize, rng: &mut R) -> Result<Vec<Scalar>, BbsError>
+where
+    R: RngCore + CryptoRng
+{
+    let coefficients = shamir_polynomial(secret, threshold, count, rng)?;
+    Ok((1..=count).map(|x| evaluate(&coefficients, x)).collect())
+}
+
+// The coefficients of a random polynomial of degree threshold - 1 with constant term secret
+fn shamir_polynomial<R>(secret: &Scalar, threshold: usize, count: usize, rng: &mut R) -> Result<Vec<SecretScalar>, BbsError>
+where
+    R: RngCore + CryptoRng
+{
+    if threshold == 0 || threshold > count || count > u32::MAX as usize {
+        return Err(BbsError::InvalidLength("the threshold is between 1 and the number of shares"));
+    }
+
+    let mut coefficients = Vec::with_capacity(threshold);
+    coefficients.push(SecretScalar(*secret));
+    coefficients.extend((1..threshold).map(|_| SecretScalar(Scalar::random(&mut *rng))));
+    Ok(coefficients)
+}
+
+fn evaluate(coefficients: &[SecretScalar], x: usize) -> Scalar {
+    let x = Scalar::from(x as u64);
+    coefficients.iter().rev().fold(Scalar::zero(), |y, coefficient| y * x + coefficient.0)
+}
+
+// The Lagrange coefficients at 0 of the given distinct, non-zero share indexes
+pub(crate) fn lagrange_coefficients(indexes: &[usize]) -> Result<Vec<Scalar>, BbsError> {
+    if indexes.is_empty() || indexes.iter().enumerate().any(|(i, x)| *x == 0 || indexes[..i].contains(x)) {
+        return Err(BbsError::InvalidLength("share indexes are distinct and non-zero"));
+    }
+
+    indexes
+        .iter()
+        .map(|&i| {
+            let x_i = Scalar::from(i as u64);
+            let (numerator, denominator) = indexes
+                .iter()
+                .filter(|&&j| j != i)
+                .map(|&j| Scalar::from(j as u64))
+                .fold((Scalar::one(), Scalar::one()), |(n, d), x_j| (n * x_j, d * (x_j - x_i)));
+            Option::from(denominator.invert())
+                .map(|d_inv: Scalar| numerator * d_inv)
+                .ok_or(BbsError::SigningFailed)
+        })
+        .collect()
+}
+
+fn share_index(bytes: &[u8]) -> Result<usize, BbsError> {
+    let mut index = [0u8; 4];
+    index.copy_from_slice(bytes);
+    match u32::from_be_bytes(index) {
+        0 => Err(BbsError::InvalidScalar),
+        index => Ok(index as usize),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphersuites::{Bls12381Sha256, Bls12381Shake256};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const THRESHOLD: usize = 3;
+    const COUNT: usize = 5;
+
+    fn messages() -> Vec<Scalar> {
+        (1..=4u64).map(Scalar::from).collect()
+    }
+
+    // The partial signatures of the nodes at signers, the 1 based indexes of the key shares, for one
+    // dealt presignature
+    fn partial_signatures<'a, X>(
+        key_shares: &[KeyShare],
+        signers: &[usize],
+        pk: &PublicKey,
+        messages: &[Scalar],
+        rng: &mut StdRng,
+    ) -> Vec<PartialSignature>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let triples = deal_triples(THRESHOLD, COUNT, rng).unwrap();
+        let masked: Vec<MaskedKeyShare> = signers.iter().map(|&i| triples[i - 1].mask(&key_shares[i - 1]).unwrap()).collect();
+        triples
+            .into_iter()
+            .filter(|triple| signers.contains(&triple.index))
+            .map(|triple| {
+                let presignature = Presignature::new(triple, &masked).unwrap();
+                PartialSignature::new::<X>(presignature, pk, messages, Some(b"header")).unwrap()
+            })
+            .collect()
+    }
+
+    fn threshold_sign<'a, X>()
+    where
+        X: BbsCiphersuite<'a>
+    {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (pk, key_shares) = generate_key_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        let messages = messages();
+
+        for signers in [[1, 2, 3], [1, 3, 5], [2, 4, 5], [5, 4, 3]] {
+            let partials = partial_signatures::<X>(&key_shares, &signers, &pk, &messages, &mut rng);
+            let signature = aggregate::<X>(&partials, &pk, &messages, Some(b"header")).unwrap();
+            assert!(signature.verify::<X>(&pk, &messages, Some(b"header")), "signers {:?}", signers);
+            assert!(!signature.verify::<X>(&pk, &messages, None));
+        }
+
+        // More than threshold nodes sign as well
+        let partials = partial_signatures::<X>(&key_shares, &[1, 2, 3, 4, 5], &pk, &messages, &mut rng);
+        let signature = aggregate::<X>(&partials, &pk, &messages, Some(b"header")).unwrap();
+        assert!(signature.verify::<X>(&pk, &messages, Some(b"header")));
+    }
+
+    #[test]
+    fn any_threshold_subset_signs() {
+        threshold_sign::<Bls12381Sha256>();
+        threshold_sign::<Bls12381Shake256>();
+    }
+
+    #[test]
+    fn split_key_signs_under_its_public_key() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let sk = SecretKey(Scalar::random(&mut rng));
+        let pk = sk.public_key();
+        let key_shares = split_secret_key(&sk, THRESHOLD, COUNT, &mut rng).unwrap();
+        let messages = messages();
+
+        let partials = partial_signatures::<Bls12381Sha256>(&key_shares, &[2, 3, 4], &pk, &messages, &mut rng);
+        let signature = aggregate::<Bls12381Sha256>(&partials, &pk, &messages, Some(b"header")).unwrap();
+        assert!(signature.verify::<Bls12381Sha256>(&pk, &messages, Some(b"header")));
+    }
+
+    #[test]
+    fn fewer_than_threshold_partials_fail() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let (pk, key_shares) = generate_key_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        let messages = messages();
+
+        let partials = partial_signatures::<Bls12381Sha256>(&key_shares, &[1, 2, 3], &pk, &messages, &mut rng);
+        let result = aggregate::<Bls12381Sha256>(&partials[..THRESHOLD - 1], &pk, &messages, Some(b"header"));
+        assert!(matches!(result, Err(BbsError::SigningFailed)));
+        assert!(matches!(aggregate::<Bls12381Sha256>(&[], &pk, &messages, None), Err(BbsError::SigningFailed)));
+    }
+
+    #[test]
+    fn rejects_mismatched_nonces() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let (pk, key_shares) = generate_key_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        let messages = messages();
+
+        let mut partials = partial_signatures::<Bls12381Sha256>(&key_shares, &[1, 2, 3], &pk, &messages, &mut rng);
+        let other = partial_signatures::<Bls12381Sha256>(&key_shares, &[1, 2, 3], &pk, &messages, &mut rng);
+        partials[2] = PartialSignature::from_bytes(&other[2].to_bytes()).unwrap();
+        let result = aggregate::<Bls12381Sha256>(&partials, &pk, &messages, Some(b"header"));
+        assert!(matches!(result, Err(BbsError::SigningFailed)));
+
+        // Masked key shares of another presignature
+        let triples = deal_triples(THRESHOLD, COUNT, &mut rng).unwrap();
+        let other_triples = deal_triples(THRESHOLD, COUNT, &mut rng).unwrap();
+        let masked = [
+            triples[0].mask(&key_shares[0]).unwrap(),
+            other_triples[1].mask(&key_shares[1]).unwrap(),
+            triples[2].mask(&key_shares[2]).unwrap(),
+        ];
+        let triple = triples.into_iter().next().unwrap();
+        assert!(matches!(Presignature::new(triple, &masked), Err(BbsError::SigningFailed)));
+    }
+
+    #[test]
+    fn mask_rejects_another_nodes_key_share() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let (_, key_shares) = generate_key_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        let triples = deal_triples(THRESHOLD, COUNT, &mut rng).unwrap();
+        assert!(matches!(triples[0].mask(&key_shares[1]), Err(BbsError::SigningFailed)));
+    }
+
+    #[test]
+    fn rejects_invalid_thresholds() {
+        let mut rng = StdRng::seed_from_u64(6);
+        assert!(matches!(generate_key_shares(0, COUNT, &mut rng), Err(BbsError::InvalidLength(_))));
+        assert!(matches!(generate_key_shares(COUNT + 1, COUNT, &mut rng), Err(BbsError::InvalidLength(_))));
+        assert!(matches!(deal_triples(COUNT + 1, COUNT, &mut rng), Err(BbsError::InvalidLength(_))));
+    }
+
+    // Every node deals a sharing of 0 and every node applies the shares dealt to it
+    fn refresh_all(key_shares: Vec<KeyShare>, rng: &mut StdRng) -> Vec<KeyShare> {
+        let dealt: Vec<Vec<RefreshShare>> = key_shares
+            .iter()
+            .map(|key_share| key_share.refresh_shares(THRESHOLD, COUNT, &mut *rng).unwrap())
+            .collect();
+        key_shares
+            .into_iter()
+            .map(|key_share| {
+                let received: Vec<RefreshShare> = dealt
+                    .iter()
+                    .map(|shares| {
+                        let share = &shares[key_share.index - 1];
+                        RefreshShare {
+                            from: share.from,
+                            to: share.to,
+                            delta: SecretScalar(share.delta.0),
+                            commitments: share.commitments.clone(),
+                        }
+                    })
+                    .collect();
+                key_share.refresh(&received).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn refresh_keeps_the_public_key() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (pk, key_shares) = generate_key_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        let old: Vec<[u8; 36]> = key_shares.iter().map(KeyShare::to_bytes).collect();
+        let refreshed = refresh_all(key_shares, &mut rng);
+        let messages = messages();
+
+        for (old, new) in old.iter().zip(&refreshed) {
+            assert_ne!(old, &new.to_bytes());
+        }
+        let partials = partial_signatures::<Bls12381Sha256>(&refreshed, &[1, 4, 5], &pk, &messages, &mut rng);
+        let signature = aggregate::<Bls12381Sha256>(&partials, &pk, &messages, Some(b"header")).unwrap();
+        assert!(signature.verify::<Bls12381Sha256>(&pk, &messages, Some(b"header")));
+
+        // Old and refreshed shares do not combine
+        let mut mixed: Vec<KeyShare> = old.iter().map(|bytes| KeyShare::from_bytes(bytes).unwrap()).collect();
+        mixed[4] = KeyShare::from_bytes(&refreshed[4].to_bytes()).unwrap();
+        let partials = partial_signatures::<Bls12381Sha256>(&mixed, &[1, 4, 5], &pk, &messages, &mut rng);
+        let result = aggregate::<Bls12381Sha256>(&partials, &pk, &messages, Some(b"header"));
+        assert!(matches!(result, Err(BbsError::SigningFailed)));
+    }
+
+    #[test]
+    fn refresh_rejects_tampered_shares() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let (_, key_shares) = generate_key_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        let key_share = || KeyShare::from_bytes(&key_shares[0].to_bytes()).unwrap();
+
+        let mut shares = key_shares[1].refresh_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        shares[0].delta.0 += Scalar::one();
+        assert!(matches!(key_share().refresh(&shares[..1]), Err(BbsError::InvalidCommitment)));
+
+        // A sharing of another value than 0
+        let mut shares = key_shares[1].refresh_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        let offset = Scalar::from(7u64);
+        shares[0].delta.0 += offset;
+        shares[0].commitments[0] = Backend::g2_generator() * offset;
+        assert!(matches!(key_share().refresh(&shares[..1]), Err(BbsError::InvalidCommitment)));
+
+        // Shares dealt to another node, or twice by the same dealer
+        let shares = key_shares[1].refresh_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        assert!(matches!(key_share().refresh(&shares[1..2]), Err(BbsError::InvalidLength(_))));
+        let twice = [
+            RefreshShare { from: 2, to: 1, delta: SecretScalar(shares[0].delta.0), commitments: shares[0].commitments.clone() },
+            RefreshShare { from: 2, to: 1, delta: SecretScalar(shares[0].delta.0), commitments: shares[0].commitments.clone() },
+        ];
+        assert!(matches!(key_share().refresh(&twice), Err(BbsError::InvalidLength(_))));
+    }
+
+    #[test]
+    fn key_share_round_trip() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let (_, key_shares) = generate_key_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        for key_share in &key_shares {
+            let bytes = key_share.to_bytes();
+            let decoded = KeyShare::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.index(), key_share.index());
+            assert_eq!(decoded.to_bytes(), bytes);
+        }
+
+        let bytes = key_shares[0].to_bytes();
+        assert!(matches!(KeyShare::from_bytes(&bytes[..35]), Err(BbsError::InvalidLength(_))));
+        let mut index_0 = bytes;
+        index_0[..4].fill(0);
+        assert!(matches!(KeyShare::from_bytes(&index_0), Err(BbsError::InvalidScalar)));
+        let mut zero_share = bytes;
+        zero_share[4..].fill(0);
+        assert!(matches!(KeyShare::from_bytes(&zero_share), Err(BbsError::InvalidScalar)));
+    }
+
+    #[test]
+    fn partial_signature_round_trip() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let (pk, key_shares) = generate_key_shares(THRESHOLD, COUNT, &mut rng).unwrap();
+        let messages = messages();
+        let partials = partial_signatures::<Bls12381Sha256>(&key_shares, &[1, 2, 3], &pk, &messages, &mut rng);
+
+        let decoded: Vec<PartialSignature> = partials
+            .iter()
+            .map(|p| {
+                let bytes = p.to_bytes();
+                let decoded = PartialSignature::from_bytes(&bytes).unwrap();
+                assert_eq!(decoded.to_bytes(), bytes);
+                decoded
+            })
+            .collect();
+        let signature = aggregate::<Bls12381Sha256>(&decoded, &pk, &messages, Some(b"header")).unwrap();
+        assert!(signature.verify::<Bls12381Sha256>(&pk, &messages, Some(b"header")));
+
+        let bytes = partials[0].to_bytes();
+        assert!(matches!(PartialSignature::from_bytes(&bytes[1..]), Err(BbsError::InvalidLength(_))));
+        let mut identity_a = bytes;
+        identity_a[36..84].copy_from_slice(&Backend::g1_to_bytes(&Backend::g1_identity()));
+        assert!(matches!(PartialSignature::from_bytes(&identity_a), Err(BbsError::InvalidPoint)));
+    }
+}
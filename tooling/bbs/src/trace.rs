@@ -0,0 +1,51 @@
+// Debug events with the intermediate values of the operations, so that the step where a result
+// diverges from another implementation can be found. They are only emitted with the tracing
+// feature, without it trace_value! expands to nothing. Secret values are never traced
+#[cfg(feature = "tracing")]
+use alloc::string::String;
+
+#[cfg(feature = "tracing")]
+use crate::curve::{Backend, PairingCurve, Scalar, G1};
+#[cfg(feature = "tracing")]
+use crate::encoding::scalar_to_bytes;
+
+// The hex encoding of a traced value, as in the spec's test vectors
+#[cfg(feature = "tracing")]
+pub trait TraceHex {
+    fn trace_hex(&self) -> String;
+}
+
+#[cfg(feature = "tracing")]
+impl TraceHex for [u8] {
+    fn trace_hex(&self) -> String {
+        self.iter().map(|b| alloc::format!("{:02x}", b)).collect()
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl TraceHex for Scalar {
+    fn trace_hex(&self) -> String {
+        scalar_to_bytes(self).trace_hex()
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl TraceHex for G1 {
+    fn trace_hex(&self) -> String {
+        Backend::g1_to_bytes(self).trace_hex()
+    }
+}
+
+// trace_value!(debug, "domain", domain) emits the event domain = <hex> at the debug level, with
+// the bbs target
+macro_rules! trace_value {
+    ($level:ident, $name:expr, $value:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!(target: "bbs", "{} = {}", $name, {
+            use $crate::trace::TraceHex;
+            ($value).trace_hex()
+        });
+    };
+}
+
+pub(crate) use trace_value;
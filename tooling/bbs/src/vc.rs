@@ -0,0 +1,220 @@
+// An end to end flow of verifiable credentials over plain JSON claims, built on json_messages and
+// did. The issuer signs every leaf of the claims, as in json_messages, with its did:key as the issuer
+// of the credential and the verification method of its key as the header, so the signature can not
+// be passed off as another issuer's. The holder derives presentations disclosing the claims selected
+// by JSON pointer, the issuer always among them, and the verifier checks that every value of the
+// presented credential is covered by the proof. The proof objects are specific to this crate, for
+// the registered bbs-2023 Data Integrity cryptosuite see data_integrity
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde_json::{json, Map, Value};
+
+use crate::ciphersuites::BbsCiphersuite;
+use crate::did::{base58_decode, base58_encode, resolve, to_did_key, verification_method};
+use crate::error::BbsError;
+use crate::json_messages::{disclosed_scalars, reconstruct, JsonMessages};
+use crate::key_pair::{KeyPair, PublicKey};
+use crate::proof::Proof;
+use crate::signature::Signature;
+
+pub const SIGNATURE_TYPE: &str = "BbsJsonSignature";
+pub const PROOF_TYPE: &str = "BbsJsonProof";
+
+const ISSUER_POINTER: &str = "/issuer";
+
+// The claims, a JSON object, with the issuer set to the did:key of key_pair and a proof holding the
+// signature. An issuer of the claims is replaced
+pub fn issue_credential<'a, X>(key_pair: &KeyPair, claims: &Value) -> Result<Value, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let mut credential = match claims {
+        Value::Object(claims) if !claims.contains_key("proof") => claims.clone(),
+        _ => return Err(BbsError::Serialization("the claims are a JSON object without a proof".to_string())),
+    };
+    credential.insert("issuer".to_string(), to_did_key(&key_pair.public_key).into());
+    let mut credential = Value::Object(credential);
+
+    let method = verification_method(&key_pair.public_key);
+    let messages = JsonMessages::from_json(&credential);
+    let signature = Signature::sign::<X>(&messages.scalars::<X>()?, &key_pair.secret_key, Some(method.as_bytes()))?;
+
+    credential["proof"] = json!({
+        "type": SIGNATURE_TYPE,
+        "cryptosuite": cryptosuite::<X>(),
+        "verificationMethod": method,
+        "proofValue": multibase(&signature.to_bytes()),
+    });
+    Ok(credential)
+}
+
+// Checks the signature of a credential issued by issue_credential, as the holder does on receipt
+pub fn verify_credential<'a, X>(credential: &Value) -> Result<bool, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let (credential, proof) = split_proof(credential)?;
+    let (method, pk, proof_value) = read_proof::<X>(&proof, SIGNATURE_TYPE)?;
+    if !issued_by(&credential, &method) {
+        return Ok(false);
+    }
+    let signature = match Signature::from_bytes(&proof_value) {
+        Ok(signature) => signature,
+        Err(_) => return Ok(false),
+    };
+    let messages = JsonMessages::from_json(&credential);
+    Ok(signature.verify::<X>(&pk, &messages.scalars::<X>()?, Some(method.as_bytes())))
+}
+
+// A presentation of credential disclosing the values at or below disclosed_claims, JSON pointers
+// such as /credentialSubject/age, and the issuer. ph is the presentation header, e.g. a nonce of the
+// verifier
+pub fn derive_presentation<'a, X>(credential: &Value, disclosed_claims: &[String], ph: &[u8]) -> Result<Value, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let (credential, proof) = split_proof(credential)?;
+    let (method, pk, proof_value) = read_proof::<X>(&proof, SIGNATURE_TYPE)?;
+    let signature = Signature::from_bytes(&proof_value)?;
+
+    let messages = JsonMessages::from_json(&credential);
+    let mut pointers = disclosed_claims.to_vec();
+    pointers.push(ISSUER_POINTER.to_string());
+    let indexes = messages.indexes(&pointers)?;
+    let proof = Proof::generate::<X>(
+        &signature,
+        &pk,
+        Some(method.as_bytes()),
+        Some(ph),
+        &messages.scalars::<X>()?,
+        &indexes,
+    )?;
+
+    let disclosed: Map<String, Value> = indexes
+        .iter()
+        .map(|&i| (i.to_string(), messages.pointers()[i].clone().into()))
+        .collect();
+    Ok(json!({
+        "credential": reconstruct(&messages.disclose(&indexes))?,
+        "proof": {
+            "type": PROOF_TYPE,
+            "cryptosuite": cryptosuite::<X>(),
+            "verificationMethod": method,
+            "disclosed": disclosed,
+            "proofValue": multibase(&proof.to_bytes()),
+        },
+    }))
+}
+
+// Checks a presentation made by derive_presentation with the presentation header ph. False when the
+// credential holds a value the proof does not disclose
+pub fn verify_presentation<'a, X>(presentation: &Value, ph: &[u8]) -> Result<bool, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let credential = presentation.get("credential").ok_or_else(|| invalid_proof("no credential"))?;
+    let proof = presentation.get("proof").and_then(Value::as_object).ok_or_else(|| invalid_proof("no proof"))?;
+    let (method, pk, proof_value) = read_proof::<X>(proof, PROOF_TYPE)?;
+    if !issued_by(credential, &method) {
+        return Ok(false);
+    }
+
+    // The disclosed messages are the leaves of the credential at the pointers of the proof, which
+    // must make up the whole credential
+    let pointers = proof.get("disclosed").and_then(Value::as_object).ok_or_else(|| invalid_proof("no disclosed"))?;
+    let mut disclosed = BTreeMap::new();
+    for (i, pointer) in pointers {
+        let i: usize = i.parse().map_err(|_| invalid_proof("a disclosed index is not a number"))?;
+        let pointer = pointer.as_str().ok_or_else(|| invalid_proof("a disclosed pointer is not a string"))?;
+        match leaf_message(credential, pointer) {
+            Some(message) => disclosed.insert(i, message),
+            None => return Ok(false),
+        };
+    }
+    if reconstruct(&disclosed)? != *credential {
+        return Ok(false);
+    }
+
+    let proof = match Proof::from_bytes(&proof_value) {
+        Ok(proof) => proof,
+        Err(_) => return Ok(false),
+    };
+    Ok(proof.verify::<X>(&pk, Some(method.as_bytes()), Some(ph), &disclosed_scalars::<X>(&disclosed)?))
+}
+
+// The credential without its proof, and the proof
+fn split_proof(credential: &Value) -> Result<(Value, Map<String, Value>), BbsError> {
+    let mut credential = credential.as_object().ok_or_else(|| invalid_proof("the credential is not an object"))?.clone();
+    match credential.remove("proof") {
+        Some(Value::Object(proof)) => Ok((Value::Object(credential), proof)),
+        _ => Err(invalid_proof("no proof")),
+    }
+}
+
+// The verification method, the key it resolves to and the decoded proofValue of a proof object of
+// proof_type made with ciphersuite X
+fn read_proof<'a, X>(proof: &Map<String, Value>, proof_type: &str) -> Result<(String, PublicKey, Vec<u8>), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let field = |name: &str| proof.get(name).and_then(Value::as_str).ok_or_else(|| invalid_proof(name));
+    if field("type")? != proof_type {
+        return Err(invalid_proof("type"));
+    }
+    if field("cryptosuite")? != cryptosuite::<X>() {
+        return Err(invalid_proof("cryptosuite"));
+    }
+    let method = field("verificationMethod")?;
+    let proof_value = field("proofValue")?
+        .strip_prefix('z')
+        .and_then(base58_decode)
+        .ok_or_else(|| invalid_proof("proofValue"))?;
+    Ok((method.to_string(), resolve(method)?, proof_value))
+}
+
+// Whether the issuer of the credential is the DID of the verification method
+fn issued_by(credential: &Value, method: &str) -> bool {
+    let did = method.split('#').next().unwrap_or(method);
+    credential.get("issuer").and_then(Value::as_str) == Some(did)
+}
+
+// The json_messages message of the leaf at pointer, the segments being array indexes where the
+// document has arrays
+fn leaf_message(document: &Value, pointer: &str) -> Option<Vec<u8>> {
+    let mut node = document;
+    let mut path = Vec::new();
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        match node {
+            Value::Object(map) => {
+                node = map.get(&segment)?;
+                path.push(Value::String(segment));
+            }
+            Value::Array(values) => {
+                let i: usize = segment.parse().ok()?;
+                node = values.get(i)?;
+                path.push(Value::from(i));
+            }
+            _ => return None,
+        }
+    }
+    Some(json!([path, node]).to_string().into_bytes())
+}
+
+fn cryptosuite<'a, X>() -> String
+where
+    X: BbsCiphersuite<'a>
+{
+    String::from_utf8_lossy(X::ID).into_owned()
+}
+
+fn multibase(octets: &[u8]) -> String {
+    format!("z{}", base58_encode(octets))
+}
+
+fn invalid_proof(field: &str) -> BbsError {
+    BbsError::Serialization(format!("not a {} credential or presentation: {}", SIGNATURE_TYPE, field))
+}
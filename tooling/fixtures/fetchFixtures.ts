@@ -1,5 +1,5 @@
 import * as keyPair from "./keyPair.json";
-import * as generators from "./generators.json";
+import generatorsFile from "./generators.json";
 import * as path from "path";
 
 const isObject = (value: unknown) => value && typeof value === "object";
@@ -62,4 +62,10 @@ export const signatureFixtures = fetchNestedFixtures<SignatureFixture>(
   return map;
 }, {});
 
+// Generator files written by the message-generators tool are an object of
+// the form { ciphersuite, seed, bp, generators }, older files are a plain array
+const generators: string[] = Array.isArray(generatorsFile)
+  ? generatorsFile
+  : (generatorsFile as any).generators;
+
 export { keyPair, generators };
@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use bbs::ciphersuites::BbsCiphersuite;
+use bbs::generators::make_generators;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::{BbsError, KeyPair, Proof, Scalar, Signature};
+
+use crate::output::serialization_error;
+use crate::stdio;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchResult {
+    operation: &'static str,
+    message_count: usize,
+    iterations: u32,
+    pub(crate) mean_ns: u128,
+    // Nearest rank percentiles of the single iterations
+    p50_ns: u128,
+    p90_ns: u128,
+    p99_ns: u128,
+}
+
+// The results with what they were measured on, in the shape of the performance comparison tables
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    ciphersuite: String,
+    platform: Platform,
+    results: Vec<BenchResult>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Platform {
+    os: &'static str,
+    arch: &'static str,
+    // release or debug, debug timings are not comparable
+    profile: &'static str,
+    tool_version: &'static str,
+}
+
+impl Platform {
+    fn current() -> Self {
+        Platform {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            profile: if cfg!(debug_assertions) { "debug" } else { "release" },
+            tool_version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+// A report of bench --format json, as read back for --baseline
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Baseline {
+    ciphersuite: String,
+    platform: BaselinePlatform,
+    results: Vec<BaselineResult>,
+}
+
+#[derive(Deserialize)]
+struct BaselinePlatform {
+    os: String,
+    arch: String,
+    profile: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BaselineResult {
+    operation: String,
+    message_count: usize,
+    mean_ns: u128,
+}
+
+// The mean of every operation against the baseline, regressed when one is slower by more than the
+// threshold
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineComparison {
+    pub regressed: bool,
+    threshold_percent: f64,
+    // Differences of ciphersuite or platform, and operations the baseline does not have
+    warnings: Vec<String>,
+    operations: Vec<OperationComparison>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OperationComparison {
+    operation: &'static str,
+    message_count: usize,
+    baseline_mean_ns: u128,
+    mean_ns: u128,
+    // Positive when slower than the baseline
+    change_percent: f64,
+    regressed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BenchFormat {
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for BenchFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(BenchFormat::Json),
+            "csv" => Ok(BenchFormat::Csv),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+impl BenchReport {
+    // One row per result, the ciphersuite and platform repeated on every row so that the files of
+    // several runs can be concatenated below one header
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("ciphersuite,os,arch,profile,operation,message_count,iterations,mean_ns,p50_ns,p90_ns,p99_ns\n");
+        for r in &self.results {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{}\n",
+                self.ciphersuite,
+                self.platform.os,
+                self.platform.arch,
+                self.platform.profile,
+                r.operation,
+                r.message_count,
+                r.iterations,
+                r.mean_ns,
+                r.p50_ns,
+                r.p90_ns,
+                r.p99_ns
+            ));
+        }
+        csv
+    }
+}
+
+// Compares the means of report with those of the baseline file, a report of an earlier run. An
+// operation regresses when its mean exceeds the baseline mean by more than threshold_percent
+pub fn compare_to_baseline(report: &BenchReport, path: &Path, threshold_percent: f64) -> Result<BaselineComparison, BbsError> {
+    let baseline: Baseline = serde_json::from_str(&stdio::read_to_string(path)?).map_err(serialization_error)?;
+
+    let mut warnings = Vec::new();
+    if baseline.ciphersuite != report.ciphersuite {
+        warnings.push(format!("the baseline ran {}, this run {}", baseline.ciphersuite, report.ciphersuite));
+    }
+    let platform = &report.platform;
+    let (os, arch, profile) = (&baseline.platform.os, &baseline.platform.arch, &baseline.platform.profile);
+    if (os.as_str(), arch.as_str(), profile.as_str()) != (platform.os, platform.arch, platform.profile) {
+        warnings.push(format!(
+            "the baseline ran on {} {} {}, this run on {} {} {}",
+            os, arch, profile, platform.os, platform.arch, platform.profile
+        ));
+    }
+
+    let mut operations = Vec::new();
+    for r in &report.results {
+        let base = baseline
+            .results
+            .iter()
+            .find(|b| b.operation == r.operation && b.message_count == r.message_count);
+        let base = match base {
+            Some(base) => base,
+            None => {
+                warnings.push(format!("{} with {} messages is not in the baseline", r.operation, r.message_count));
+                continue;
+            }
+        };
+        let change_percent = (r.mean_ns as f64 - base.mean_ns as f64) * 100.0 / (base.mean_ns.max(1) as f64);
+        operations.push(OperationComparison {
+            operation: r.operation,
+            message_count: r.message_count,
+            baseline_mean_ns: base.mean_ns,
+            mean_ns: r.mean_ns,
+            change_percent,
+            regressed: change_percent > threshold_percent,
+        });
+    }
+
+    Ok(BaselineComparison {
+        regressed: operations.iter().any(|o| o.regressed),
+        threshold_percent,
+        warnings,
+        operations,
+    })
+}
+
+// On stderr, stdout holding the report so it can be stored as the next baseline
+pub fn print_comparison(comparison: &BaselineComparison) {
+    for warning in &comparison.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    for o in &comparison.operations {
+        eprintln!(
+            "{:<12} {:>5} messages {:>12} ns -> {:>12} ns {:>+8.1}%{}",
+            o.operation,
+            o.message_count,
+            o.baseline_mean_ns,
+            o.mean_ns,
+            o.change_percent,
+            if o.regressed { " REGRESSED" } else { "" }
+        );
+    }
+    if comparison.regressed {
+        eprintln!("slower than the baseline by more than {}%", comparison.threshold_percent);
+    }
+}
+
+// Times each operation of the ciphersuite for every message count, for spotting regressions
+// between runs. The criterion benches in ../bbs/benches give statistically sound numbers
+pub fn run_bench<'a, X>(message_counts: &[usize], iterations: u32) -> Result<BenchReport, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let ikm = [0x42u8; 32];
+    let header: &[u8] = b"benchmark header";
+    let ph: &[u8] = b"benchmark presentation header";
+
+    let mut results = vec![time("keygen", 0, iterations, || {
+        KeyPair::from_ikm(&ikm, None)?;
+        Ok(())
+    })?];
+
+    let key_pair = KeyPair::from_ikm(&ikm, None)?;
+    for &count in message_counts {
+        let messages: Vec<Scalar> = (0..count)
+            .map(|i| map_message_to_scalar_as_hash::<X>(&i.to_be_bytes(), None))
+            .collect::<Result<_, _>>()?;
+        let signature = Signature::sign::<X>(&messages, &key_pair.secret_key, Some(header))?;
+
+        // Every other message disclosed
+        let disclosed_indexes: Vec<usize> = (0..count).step_by(2).collect();
+        let disclosed: BTreeMap<usize, Scalar> = disclosed_indexes.iter().map(|&i| (i, messages[i])).collect();
+        let generate_proof = || {
+            Proof::generate::<X>(&signature, &key_pair.public_key, Some(header), Some(ph), &messages, &disclosed_indexes)
+        };
+        let proof = generate_proof()?;
+
+        results.push(time("generators", count, iterations, || {
+            make_generators::<X>(None, count + 2);
+            Ok(())
+        })?);
+        results.push(time("sign", count, iterations, || {
+            Signature::sign::<X>(&messages, &key_pair.secret_key, Some(header))?;
+            Ok(())
+        })?);
+        results.push(time("verify", count, iterations, || {
+            assert!(signature.verify::<X>(&key_pair.public_key, &messages, Some(header)));
+            Ok(())
+        })?);
+        results.push(time("proofgen", count, iterations, || {
+            generate_proof()?;
+            Ok(())
+        })?);
+        results.push(time("proofverify", count, iterations, || {
+            assert!(proof.verify::<X>(&key_pair.public_key, Some(header), Some(ph), &disclosed));
+            Ok(())
+        })?);
+    }
+
+    Ok(BenchReport {
+        ciphersuite: String::from_utf8_lossy(X::ID).into_owned(),
+        platform: Platform::current(),
+        results,
+    })
+}
+
+pub(crate) fn time<F>(operation: &'static str, message_count: usize, iterations: u32, mut f: F) -> Result<BenchResult, BbsError>
+where
+    F: FnMut() -> Result<(), BbsError>
+{
+    let mut samples = Vec::with_capacity(iterations as usize);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed().as_nanos());
+    }
+    samples.sort_unstable();
+
+    Ok(BenchResult {
+        operation,
+        message_count,
+        iterations,
+        mean_ns: samples.iter().sum::<u128>() / u128::from(iterations.max(1)),
+        p50_ns: percentile(&samples, 50),
+        p90_ns: percentile(&samples, 90),
+        p99_ns: percentile(&samples, 99),
+    })
+}
+
+// The nearest rank percentile of sorted samples, 0 without samples
+fn percentile(samples: &[u128], p: usize) -> u128 {
+    let rank = (p * samples.len() + 99) / 100;
+    samples.get(rank.saturating_sub(1)).copied().unwrap_or(0)
+}
@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use bls12_381_plus::{G1Affine, G1Projective};
+use serde::Serialize;
+
+use bbs::curve::G1;
+use bbs::generators::validate_generators;
+use bbs::BbsError;
+
+use crate::diff_generators::read_generators_file;
+
+// The outcome of check-generators
+#[derive(Serialize)]
+pub struct GeneratorsCheck {
+    pub valid: bool,
+    // The number of message generators in the file
+    pub count: usize,
+    // Why the file is invalid, naming the first invalid point
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// Checks a generators file, of this tool or of another implementation, with validate_generators. The
+// points are decoded without the subgroup check, so that a point outside the subgroup is reported
+// as such rather than as an invalid encoding. Whether they are the generators of a ciphersuite is
+// checked by diff-generators and verify-fixtures
+pub fn check_generators(path: &Path) -> Result<GeneratorsCheck, BbsError> {
+    let file = read_generators_file(path)?;
+    let count = file.generators.len();
+    let invalid = |error: String| GeneratorsCheck { valid: false, count, error: Some(error) };
+
+    let base_point = match file.bp.as_deref().map(|bp| decode_unchecked(bp, "P1")).transpose() {
+        Ok(base_point) => base_point,
+        Err(error) => return Ok(invalid(error)),
+    };
+    let mut generators = Vec::with_capacity(count);
+    for (i, generator) in file.generators.iter().enumerate() {
+        match decode_unchecked(generator, &format!("G_{}", i + 1)) {
+            Ok(generator) => generators.push(generator),
+            Err(error) => return Ok(invalid(error)),
+        }
+    }
+
+    Ok(match validate_generators(base_point.as_ref(), &generators) {
+        Ok(()) => GeneratorsCheck { valid: true, count, error: None },
+        Err(BbsError::InvalidGenerator(error)) => invalid(error),
+        Err(e) => invalid(e.to_string()),
+    })
+}
+
+pub fn print_check(check: &GeneratorsCheck) {
+    match &check.error {
+        Some(error) => println!("invalid: {}", error),
+        None => println!("{} generators are valid", check.count),
+    }
+}
+
+// A hex encoded compressed or uncompressed point with valid flags and on the curve
+fn decode_unchecked(octets: &str, name: &str) -> Result<G1, String> {
+    let octets = hex::decode(octets).map_err(|_| format!("{} is not hex", name))?;
+    let point = match octets.len() {
+        48 => G1Affine::from_compressed_unchecked(octets.as_slice().try_into().unwrap()),
+        96 => G1Affine::from_uncompressed_unchecked(octets.as_slice().try_into().unwrap()),
+        _ => return Err(format!("{} is neither 48 nor 96 octets", name)),
+    };
+    Option::from(point)
+        .map(G1Projective::from)
+        .ok_or_else(|| format!("{} is not the encoding of a point on the curve", name))
+}
@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use bls12_381_plus::G1Affine;
+use serde::{Deserialize, Serialize};
+
+use bbs::BbsError;
+
+use crate::output::serialization_error;
+use crate::stdio;
+
+// A generators file of this tool or of another implementation, either in the GeneratorsFile
+// layout or a bare array of the message generators. Points are hex encoded, compressed or not
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GeneratorsListing {
+    File {
+        ciphersuite: Option<String>,
+        seed: Option<String>,
+        bp: Option<String>,
+        generators: Vec<String>,
+    },
+    Array(Vec<String>),
+}
+
+pub struct ParsedGenerators {
+    pub ciphersuite: Option<String>,
+    pub seed: Option<String>,
+    pub bp: Option<String>,
+    pub generators: Vec<String>,
+}
+
+pub fn read_generators_file(path: &Path) -> Result<ParsedGenerators, BbsError> {
+    let listing = serde_json::from_str(&stdio::read_to_string(path)?).map_err(serialization_error)?;
+    Ok(match listing {
+        GeneratorsListing::File { ciphersuite, seed, bp, generators } => ParsedGenerators { ciphersuite, seed, bp, generators },
+        GeneratorsListing::Array(generators) => ParsedGenerators { ciphersuite: None, seed: None, bp: None, generators },
+    })
+}
+
+// Where two generators files disagree
+#[derive(Serialize)]
+pub struct GeneratorsDiff {
+    pub same: bool,
+    // The number of leading message generators that are equal
+    pub equal_generators: usize,
+    pub differences: Vec<Difference>,
+}
+
+#[derive(Serialize)]
+pub struct Difference {
+    // ciphersuite, seed, bp, generators or count
+    pub field: &'static str,
+    pub description: String,
+    // The values of the two files, points decoded and shown compressed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b: Option<String>,
+}
+
+// Compares two generators files, reporting the differing metadata and the first index where the
+// points diverge. Points are compared decoded, so a compressed and an uncompressed encoding of the
+// same point are equal
+pub fn diff_generators(ours: &Path, theirs: &Path) -> Result<GeneratorsDiff, BbsError> {
+    let (a, b) = (read_generators_file(ours)?, read_generators_file(theirs)?);
+    let mut differences = Vec::new();
+
+    for (field, a, b) in [("ciphersuite", &a.ciphersuite, &b.ciphersuite), ("seed", &a.seed, &b.seed)] {
+        if let (Some(a), Some(b)) = (a, b) {
+            if a != b {
+                differences.push(Difference {
+                    field,
+                    description: format!("{} differs", field),
+                    a: Some(a.clone()),
+                    b: Some(b.clone()),
+                });
+            }
+        }
+    }
+
+    if let (Some(a), Some(b)) = (&a.bp, &b.bp) {
+        if !same_point(a, b) {
+            differences.push(Difference {
+                field: "bp",
+                description: "base point differs".to_string(),
+                a: Some(show_point(a)),
+                b: Some(show_point(b)),
+            });
+        }
+    }
+
+    let divergence = a.generators.iter().zip(&b.generators).position(|(a, b)| !same_point(a, b));
+    let equal_generators = divergence.unwrap_or_else(|| a.generators.len().min(b.generators.len()));
+    match divergence {
+        Some(i) => differences.push(Difference {
+            field: "generators",
+            description: format!("generators diverge at index {} (G_{})", i, i + 1),
+            a: Some(show_point(&a.generators[i])),
+            b: Some(show_point(&b.generators[i])),
+        }),
+        None if a.generators.len() != b.generators.len() => differences.push(Difference {
+            field: "count",
+            description: format!(
+                "the first {} generators are equal, a has {} and b has {}",
+                equal_generators,
+                a.generators.len(),
+                b.generators.len()
+            ),
+            a: None,
+            b: None,
+        }),
+        None => {}
+    }
+
+    Ok(GeneratorsDiff {
+        same: differences.is_empty(),
+        equal_generators,
+        differences,
+    })
+}
+
+pub fn print_diff(diff: &GeneratorsDiff) {
+    for difference in &diff.differences {
+        println!("{}", difference.description);
+        if let (Some(a), Some(b)) = (&difference.a, &difference.b) {
+            println!("  a: {}\n  b: {}", a, b);
+        }
+    }
+    if !diff.differences.iter().any(|d| d.field == "generators" || d.field == "count") {
+        println!("{} generators are equal", diff.equal_generators);
+    }
+}
+
+// Identical encodings, or encodings of the same point
+fn same_point(a: &str, b: &str) -> bool {
+    a == b || matches!((decode_point(a), decode_point(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+// The point of a hex encoded compressed or uncompressed G1 point, or why it does not decode
+fn decode_point(octets: &str) -> Result<G1Affine, &'static str> {
+    let octets = hex::decode(octets).map_err(|_| "invalid hex")?;
+    let point = match octets.len() {
+        48 => G1Affine::from_compressed(octets.as_slice().try_into().unwrap()),
+        96 => G1Affine::from_uncompressed(octets.as_slice().try_into().unwrap()),
+        _ => return Err("neither 48 nor 96 octets"),
+    };
+    Option::from(point).ok_or("not a point of the G1 subgroup")
+}
+
+// Compressed when the point decodes, otherwise as given followed by why it does not decode
+fn show_point(octets: &str) -> String {
+    match decode_point(octets) {
+        Ok(point) => hex::encode(point.to_compressed()),
+        Err(e) => format!("{} ({})", octets, e),
+    }
+}
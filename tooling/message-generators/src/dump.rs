@@ -0,0 +1,90 @@
+use std::fmt;
+use std::iter;
+use std::sync::Mutex;
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+// The end of every ciphersuite's BP_GENERATOR_SEED, the seed of P1
+const BP_SEED_SUFFIX: &[u8] = b"BP_MESSAGE_GENERATOR_SEED";
+
+// --dump-intermediates prints the values traced by the library as name = hex lines to stderr, named
+// as in the spec's procedures: msg_1 .. msg_L, P1, Q_1, Q_2, H_1 .. H_L, domain, e, s, B, A, A',
+// Abar, D, C1, C2, T and challenge. Each operation starts with a # line naming it, e.g. # sign,
+// after which messages and generators are numbered from 1 again
+#[derive(Default)]
+pub struct DumpLayer {
+    names: Mutex<Names>,
+}
+
+#[derive(Default)]
+struct Names {
+    messages: usize,
+    generators: usize,
+    // The generators being created are the base point
+    base_point: bool,
+}
+
+impl<S: Subscriber> Layer<S> for DumpLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        *self.names.lock().unwrap() = Names::default();
+
+        let header: Vec<String> = iter::once(attrs.metadata().name().to_string()).chain(fields.0).collect();
+        eprintln!("# {}", header.join(" "));
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != "bbs" {
+            return;
+        }
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let message = fields.0.join(" ");
+        let (name, value) = match message.split_once(" = ") {
+            Some(name_value) => name_value,
+            None => return,
+        };
+
+        let mut names = self.names.lock().unwrap();
+        let name = match name {
+            // Only used to name the generators that follow
+            "generator_seed" => {
+                names.generators = 0;
+                names.base_point = hex::decode(value).map(|seed| seed.ends_with(BP_SEED_SUFFIX)).unwrap_or(false);
+                return;
+            }
+            "generator" => {
+                names.generators += 1;
+                match (names.base_point, names.generators) {
+                    (true, _) => "P1".to_string(),
+                    (false, n) if n <= 2 => format!("Q_{}", n),
+                    (false, n) => format!("H_{}", n - 2),
+                }
+            }
+            "msg_scalar" => {
+                names.messages += 1;
+                format!("msg_{}", names.messages)
+            }
+            name => name.to_string(),
+        };
+        eprintln!("{} = {}", name, value);
+    }
+}
+
+// The values of the fields of a span or event, in order
+#[derive(Default)]
+struct Fields(Vec<String>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, _field: &Field, value: &str) {
+        self.0.push(value.to_string());
+    }
+
+    fn record_debug(&mut self, _field: &Field, value: &dyn fmt::Debug) {
+        self.0.push(format!("{:?}", value));
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+use bbs::BbsError;
+
+// The errors of a run: those of the library and of the files and encodings it reads and writes, and
+// invocations the flags can not express, e.g. a single ciphersuite command with --suite all
+#[derive(Debug)]
+pub enum CliError {
+    Bbs(BbsError),
+    Usage(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Bbs(e) => e.fmt(f),
+            CliError::Usage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<BbsError> for CliError {
+    fn from(e: BbsError) -> Self {
+        CliError::Bbs(e)
+    }
+}
+
+pub fn usage_error<M: Into<String>>(message: M) -> CliError {
+    CliError::Usage(message.into())
+}
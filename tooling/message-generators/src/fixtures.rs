@@ -0,0 +1,857 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use bbs::ciphersuites::BbsCiphersuite;
+use bbs::curve::{Backend, PairingCurve, G1};
+use bbs::encoding::{scalar_to_bytes, DeserializeMode};
+use bbs::generators::make_generators;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::limits::Limits;
+use bbs::proof::{calculate_challenge, mocked_calculate_random_scalars};
+use bbs::signature::{calculate_domain, SigningMode};
+use bbs::{BbsError, KeyPair, Proof, Scalar, Signature};
+
+use crate::output::{OutputFormat, PointEncoding};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KeyPairFixture {
+    /// Hex encoded SK, 32 octets
+    pub(crate) secret_key: String,
+    /// Hex encoded PK, a compressed G2 point of 96 octets
+    pub(crate) public_key: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KeyPairFile {
+    /// Hex encoded IKM the key pair is derived from
+    pub(crate) seed: String,
+    pub(crate) key_pair: KeyPairFixture,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct FixtureResult {
+    pub(crate) valid: bool,
+    /// Why an invalid vector must be rejected, absent for valid vectors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SignatureFixture {
+    pub(crate) case_name: String,
+    pub(crate) signer_key_pair: KeyPairFixture,
+    /// Hex encoded header
+    pub(crate) header: String,
+    /// Hex encoded messages, in signing order
+    pub(crate) messages: Vec<String>,
+    /// Hex encoded signature_to_octets output
+    pub(crate) signature: String,
+    pub(crate) result: FixtureResult,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProofFixture {
+    pub(crate) case_name: String,
+    pub(crate) signer_public_key: String,
+    pub(crate) signature: String,
+    pub(crate) header: String,
+    /// Hex encoded presentation header
+    pub(crate) presentation_header: String,
+    /// Hex encoded disclosed messages keyed by their zero based index
+    pub(crate) revealed_messages: BTreeMap<String, String>,
+    pub(crate) total_message_count: usize,
+    /// Hex encoded seed of mocked_calculate_random_scalars
+    pub(crate) random_scalars_seed: String,
+    /// Hex encoded proof_to_octets output
+    pub(crate) proof: String,
+    pub(crate) result: FixtureResult,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DomainFixture {
+    pub(crate) case_name: String,
+    pub(crate) signer_public_key: String,
+    pub(crate) header: String,
+    /// L, the number of messages and so of the generators H_1, ..., H_L hashed into the domain
+    pub(crate) total_message_count: usize,
+    /// Hex encoded domain scalar, 32 octets
+    pub(crate) domain: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChallengeFixture {
+    pub(crate) case_name: String,
+    /// Hex encoded A', a compressed G1 point of 48 octets, as are Abar, D, C1 and C2
+    pub(crate) a_prime: String,
+    pub(crate) a_bar: String,
+    pub(crate) d: String,
+    pub(crate) c1: String,
+    pub(crate) c2: String,
+    /// Hex encoded disclosed messages keyed by their zero based index, hashed as their scalars
+    pub(crate) revealed_messages: BTreeMap<String, String>,
+    /// Hex encoded domain scalar, 32 octets
+    pub(crate) domain: String,
+    pub(crate) presentation_header: String,
+    /// Hex encoded challenge scalar c, 32 octets
+    pub(crate) challenge: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KeyGenFixture {
+    pub(crate) case_name: String,
+    /// Hex encoded IKM
+    pub(crate) ikm: String,
+    /// Hex encoded key_info, empty for none
+    pub(crate) key_info: String,
+    pub(crate) key_pair: KeyPairFixture,
+}
+
+impl From<&KeyPair> for KeyPairFixture {
+    fn from(key_pair: &KeyPair) -> Self {
+        KeyPairFixture {
+            secret_key: hex::encode(key_pair.secret_key.to_bytes()),
+            public_key: hex::encode(key_pair.public_key.to_bytes()),
+        }
+    }
+}
+
+// The fixtures written: spec, the vectors of the spec over the given messages, or stress, the vectors
+// of write_stress_fixtures at boundary sizes
+#[derive(Debug, Clone, Copy)]
+pub enum FixtureProfile {
+    Spec,
+    Stress,
+}
+
+impl std::str::FromStr for FixtureProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "spec" => Ok(FixtureProfile::Spec),
+            "stress" => Ok(FixtureProfile::Stress),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+// Writes the key pair, signature, proof, domain and challenge fixtures in the layout of ../fixtures
+#[allow(clippy::too_many_arguments)]
+pub fn write_fixtures<'a, X>(
+    ikm: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    messages: &[Vec<u8>],
+    proof_seed: &[u8],
+    dir: &Path,
+    format: OutputFormat,
+    tamper: bool,
+) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    fs::create_dir_all(dir)?;
+    write_signature_fixtures::<X>(ikm, header, messages, dir, format)?;
+    write_proof_fixtures::<X>(ikm, header, ph, messages, proof_seed, dir, format)?;
+    write_domain_fixtures::<X>(ikm, header, messages.len(), dir, format)?;
+    write_challenge_fixtures::<X>(ikm, header, ph, messages, proof_seed, dir, format)?;
+    if tamper {
+        write_tampered_fixtures::<X>(ikm, header, ph, messages, proof_seed, dir, format)?;
+    }
+    Ok(())
+}
+
+// Writes the stress profile, vectors at the sizes where length encodings change width or are empty:
+// keys/keyXXX.json for an IKM of exactly 32 octets and a key_info of 0, 1, 255, 256 and 65535 octets,
+// and for every message count a signature, the same signature missing its last message, proofs
+// disclosing no and all messages and the domain. keyPair.json and generators.json, with the
+// generators of the largest count, complete the layout of ../fixtures, so verify-fixtures checks the
+// directory. Message i is empty for i = 0, else I2OSP(i, 4). Counts above the max_messages of limits
+// fail with TooManyMessages
+#[allow(clippy::too_many_arguments)]
+pub fn write_stress_fixtures<'a, X>(
+    ikm: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    message_counts: &[usize],
+    proof_seed: &[u8],
+    dir: &Path,
+    format: OutputFormat,
+    limits: &Limits,
+) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    if ikm.len() < 32 {
+        return Err(BbsError::InvalidLength("IKM must be at least 32 octets"));
+    }
+    fs::create_dir_all(dir)?;
+    let key_pair = KeyPair::from_ikm(ikm, None)?;
+
+    let key_info = |len: usize| -> Vec<u8> { (0..len).map(|i| i as u8).collect() };
+    let key_cases = vec![
+        ("IKM of 32 octets, no key_info", ikm[..32].to_vec(), Vec::new()),
+        ("key_info of 1 octet", ikm.to_vec(), key_info(1)),
+        ("key_info of 255 octets", ikm.to_vec(), key_info(255)),
+        ("key_info of 256 octets", ikm.to_vec(), key_info(256)),
+        ("key_info of 65535 octets", ikm.to_vec(), key_info(65535)),
+    ];
+    let key_dir = dir.join("keys");
+    fs::create_dir_all(&key_dir)?;
+    for (i, (case_name, ikm, key_info)) in key_cases.iter().enumerate() {
+        let fixture = KeyGenFixture {
+            case_name: case_name.to_string(),
+            ikm: hex::encode(ikm),
+            key_info: hex::encode(key_info),
+            key_pair: KeyPairFixture::from(&KeyPair::from_ikm(ikm, Some(key_info.as_slice()))?),
+        };
+        write_fixture(&key_dir.join(format!("key{:03}.{}", i + 1, format.extension())), &fixture, format)?;
+    }
+
+    let key_pair_file = KeyPairFile {
+        seed: hex::encode(ikm),
+        key_pair: KeyPairFixture::from(&key_pair),
+    };
+    write_fixture(&dir.join(format!("keyPair.{}", format.extension())), &key_pair_file, format)?;
+
+    let max_count = message_counts.iter().copied().max().unwrap_or(0);
+    let generators = crate::generators_file(&make_generators::<X>(None, max_count + 2), X::ID, PointEncoding::Compressed);
+    write_fixture(&dir.join(format!("generators.{}", format.extension())), &generators, format)?;
+
+    let mut signature_fixtures = Vec::new();
+    let mut proof_fixtures = Vec::new();
+    let mut domain_fixtures = Vec::new();
+    for &count in message_counts {
+        let messages: Vec<Vec<u8>> = (0..count)
+            .map(|i| if i == 0 { Vec::new() } else { (i as u32).to_be_bytes().to_vec() })
+            .collect();
+        let case_name = format!("stress, {} messages signature", count);
+        let signature = sign_with_limits::<X>(&key_pair, header, &messages, limits)?;
+
+        signature_fixtures.push(fixture_with_limits::<X>(&case_name, &signature, &key_pair, header, &messages, None, limits)?);
+        if count > 0 {
+            let missing = &messages[..count - 1];
+            signature_fixtures.push(fixture_with_limits::<X>(&case_name, &signature, &key_pair, header, missing, Some("missing messages"), limits)?);
+        }
+
+        let all_indexes: Vec<usize> = (0..count).collect();
+        proof_fixtures.push(proof_fixture_with_limits::<X>(&format!("{}, no messages revealed proof", case_name), &signature, &key_pair, header, ph, &messages, &[], proof_seed, limits)?);
+        if count > 0 {
+            proof_fixtures.push(proof_fixture_with_limits::<X>(&format!("{}, all messages revealed proof", case_name), &signature, &key_pair, header, ph, &messages, &all_indexes, proof_seed, limits)?);
+        }
+
+        domain_fixtures.push(domain_fixture::<X>(&format!("{} domain", case_name), &key_pair, header, count));
+    }
+
+    write_numbered(dir, "signature", &signature_fixtures, format)?;
+    write_numbered(dir, "proof", &proof_fixtures, format)?;
+    write_numbered(dir, "domain", &domain_fixtures, format)?;
+    Ok(())
+}
+
+// Writes the fixtures to <name>/<name>XXX in dir, numbered from 1
+fn write_numbered<T: Serialize>(dir: &Path, name: &str, fixtures: &[T], format: OutputFormat) -> Result<(), BbsError> {
+    let fixture_dir = dir.join(name);
+    fs::create_dir_all(&fixture_dir)?;
+    for (i, fixture) in fixtures.iter().enumerate() {
+        let file_name = format!("{}{:03}.{}", name, i + 1, format.extension());
+        write_fixture(&fixture_dir.join(file_name), fixture, format)?;
+    }
+    Ok(())
+}
+
+// Writes keyPair.json and signature/signatureXXX.json
+fn write_signature_fixtures<'a, X>(
+    ikm: &[u8],
+    header: &[u8],
+    messages: &[Vec<u8>],
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    if messages.len() < 2 {
+        return Err(BbsError::InvalidLength("at least two messages are required"));
+    }
+
+    let key_pair = KeyPair::from_ikm(ikm, None)?;
+    let wrong_key_pair = KeyPair::from_ikm(ikm, Some(b"wrong public key".as_ref()))?;
+
+    let single_message = &messages[..1];
+    let single_signature = sign::<X>(&key_pair, header, single_message)?;
+    let multi_signature = sign::<X>(&key_pair, header, messages)?;
+
+    let modified_message = &messages[messages.len() - 1..];
+    let extra_message = &messages[..2];
+    let missing_messages = &messages[..messages.len() - 1];
+    let reordered_messages: Vec<Vec<u8>> = messages.iter().rev().cloned().collect();
+
+    let mut shuffled_messages = messages.to_vec();
+    shuffled_messages.shuffle(&mut StdRng::seed_from_u64(0));
+    if shuffled_messages == messages {
+        shuffled_messages.rotate_left(1);
+    }
+
+    let different_header: Vec<u8> = if header.is_empty() {
+        vec![0xff]
+    } else {
+        header.iter().rev().copied().collect()
+    };
+
+    let fixtures = vec![
+        fixture::<X>("single message signature", &single_signature, &key_pair, header, single_message, None)?,
+        fixture::<X>("single message signature", &single_signature, &key_pair, header, modified_message, Some("modified message"))?,
+        fixture::<X>("single message signature", &single_signature, &key_pair, header, extra_message, Some("extra unsigned message"))?,
+        fixture::<X>("multi-message signature", &multi_signature, &key_pair, header, messages, None)?,
+        fixture::<X>("multi-message signature", &multi_signature, &key_pair, header, missing_messages, Some("missing messages"))?,
+        fixture::<X>("multi-message signature", &multi_signature, &key_pair, header, &reordered_messages, Some("re-ordered messages"))?,
+        fixture::<X>("multi-message signature", &multi_signature, &wrong_key_pair, header, messages, Some("wrong public key"))?,
+        fixture::<X>("multi-message signature", &multi_signature, &key_pair, &different_header, messages, Some("different header"))?,
+        fixture::<X>("multi-message signature", &multi_signature, &key_pair, header, &shuffled_messages, Some("re-ordered(randomly shuffled) messages"))?,
+    ];
+
+    let key_pair_file = KeyPairFile {
+        seed: hex::encode(ikm),
+        key_pair: KeyPairFixture::from(&key_pair),
+    };
+    write_fixture(&dir.join(format!("keyPair.{}", format.extension())), &key_pair_file, format)?;
+
+    let signature_dir = dir.join("signature");
+    fs::create_dir_all(&signature_dir)?;
+    for (i, fixture) in fixtures.iter().enumerate() {
+        let file_name = format!("signature{:03}.{}", i + 1, format.extension());
+        write_fixture(&signature_dir.join(file_name), fixture, format)?;
+    }
+    Ok(())
+}
+
+// Writes proof/proofXXX.json, the proofs use mocked_calculate_random_scalars seeded with seed and
+// are bound to the presentation header ph
+fn write_proof_fixtures<'a, X>(
+    ikm: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    messages: &[Vec<u8>],
+    seed: &[u8],
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let key_pair = KeyPair::from_ikm(ikm, None)?;
+
+    let single_message = &messages[..1];
+    let single_signature = sign::<X>(&key_pair, header, single_message)?;
+    let multi_signature = sign::<X>(&key_pair, header, messages)?;
+
+    let all_indexes: Vec<usize> = (0..messages.len()).collect();
+    let half_indexes: Vec<usize> = (0..messages.len()).step_by(2).collect();
+
+    let fixtures = vec![
+        proof_fixture::<X>("single message signature, message revealed proof", &single_signature, &key_pair, header, ph, single_message, &[0], seed)?,
+        proof_fixture::<X>("multi-message signature, all messages revealed proof", &multi_signature, &key_pair, header, ph, messages, &all_indexes, seed)?,
+        proof_fixture::<X>("multi-message signature, multiple messages revealed proof", &multi_signature, &key_pair, header, ph, messages, &half_indexes, seed)?,
+        proof_fixture::<X>("multi-message signature, no messages revealed proof", &multi_signature, &key_pair, header, ph, messages, &[], seed)?,
+    ];
+
+    let proof_dir = dir.join("proof");
+    fs::create_dir_all(&proof_dir)?;
+    for (i, fixture) in fixtures.iter().enumerate() {
+        let file_name = format!("proof{:03}.{}", i + 1, format.extension());
+        write_fixture(&proof_dir.join(file_name), fixture, format)?;
+    }
+    Ok(())
+}
+
+// Writes domain/domainXXX.json, the domain of the single and multi-message signatures and of the
+// multi-message signature without a header, to check calculate_domain on its own
+fn write_domain_fixtures<'a, X>(
+    ikm: &[u8],
+    header: &[u8],
+    message_count: usize,
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let key_pair = KeyPair::from_ikm(ikm, None)?;
+
+    let fixtures = vec![
+        domain_fixture::<X>("single message signature domain", &key_pair, header, 1),
+        domain_fixture::<X>("multi-message signature domain", &key_pair, header, message_count),
+        domain_fixture::<X>("multi-message signature domain, no header", &key_pair, &[], message_count),
+    ];
+
+    let domain_dir = dir.join("domain");
+    fs::create_dir_all(&domain_dir)?;
+    for (i, fixture) in fixtures.iter().enumerate() {
+        let file_name = format!("domain{:03}.{}", i + 1, format.extension());
+        write_fixture(&domain_dir.join(file_name), fixture, format)?;
+    }
+    Ok(())
+}
+
+// Writes challenge/challengeXXX.json, the inputs and output of calculate_challenge for the
+// multi-message proofs of write_proof_fixtures disclosing every other message and no message
+fn write_challenge_fixtures<'a, X>(
+    ikm: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    messages: &[Vec<u8>],
+    seed: &[u8],
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let key_pair = KeyPair::from_ikm(ikm, None)?;
+    let signature = sign::<X>(&key_pair, header, messages)?;
+    let half_indexes: Vec<usize> = (0..messages.len()).step_by(2).collect();
+
+    let fixtures = vec![
+        challenge_fixture::<X>("multi-message signature, multiple messages revealed proof challenge", &signature, &key_pair, header, ph, messages, &half_indexes, seed)?,
+        challenge_fixture::<X>("multi-message signature, no messages revealed proof challenge", &signature, &key_pair, header, ph, messages, &[], seed)?,
+    ];
+
+    let challenge_dir = dir.join("challenge");
+    fs::create_dir_all(&challenge_dir)?;
+    for (i, fixture) in fixtures.iter().enumerate() {
+        let file_name = format!("challenge{:03}.{}", i + 1, format.extension());
+        write_fixture(&challenge_dir.join(file_name), fixture, format)?;
+    }
+    Ok(())
+}
+
+// Writes tamper/signature/signatureXXX.json and tamper/proof/proofXXX.json, a corpus of vectors that
+// must be rejected. Each is derived from a valid signature or proof by changing one input, the
+// reason names the change
+fn write_tampered_fixtures<'a, X>(
+    ikm: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    messages: &[Vec<u8>],
+    seed: &[u8],
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let key_pair = KeyPair::from_ikm(ikm, None)?;
+    let wrong_key_pair = KeyPair::from_ikm(ikm, Some(b"wrong public key".as_ref()))?;
+    let signature = sign::<X>(&key_pair, header, messages)?;
+    let signature_octets = signature.to_bytes();
+
+    let reordered_messages: Vec<Vec<u8>> = messages.iter().rev().cloned().collect();
+    let modified_header = [header, b"tampered"].concat();
+    let modified_ph = [ph, b"tampered"].concat();
+
+    let signature_case = "multi-message signature, tampered";
+    let signature_fixtures = vec![
+        tampered_signature_fixture::<X>(signature_case, &flip_bit(&signature_octets, 8 * 24), &key_pair, header, messages, "flipped bit in A")?,
+        tampered_signature_fixture::<X>(signature_case, &flip_bit(&signature_octets, 8 * 64), &key_pair, header, messages, "flipped bit in e")?,
+        tampered_signature_fixture::<X>(signature_case, &flip_bit(&signature_octets, 8 * 96), &key_pair, header, messages, "flipped bit in s")?,
+        tampered_signature_fixture::<X>(signature_case, &signature_octets, &wrong_key_pair, header, messages, "wrong public key")?,
+        tampered_signature_fixture::<X>(signature_case, &signature_octets, &key_pair, header, &reordered_messages, "re-ordered messages")?,
+        tampered_signature_fixture::<X>(signature_case, &signature_octets, &key_pair, &modified_header, messages, "modified header")?,
+    ];
+
+    // The valid proof discloses every other message, so that there is an undisclosed index to add
+    let scalars = to_scalars::<X>(messages)?;
+    let disclosed_indexes: Vec<usize> = (0..messages.len()).step_by(2).collect();
+    let proof = Proof::generate_with_random_scalars::<X, _>(
+        &signature,
+        &key_pair.public_key,
+        Some(header),
+        Some(ph),
+        &scalars,
+        &disclosed_indexes,
+        |count| mocked_calculate_random_scalars::<X>(seed, count),
+    )?;
+    let proof_octets = proof.to_bytes();
+    let revealed: BTreeMap<usize, Vec<u8>> = disclosed_indexes.iter().map(|&i| (i, messages[i].clone())).collect();
+
+    let mut extra_revealed = revealed.clone();
+    extra_revealed.insert(1, messages[1].clone());
+    let mut removed_revealed = revealed.clone();
+    removed_revealed.remove(&0);
+    let mut modified_revealed = revealed.clone();
+    modified_revealed.insert(0, [messages[0].as_slice(), b"tampered"].concat());
+
+    let proof_case = "multi-message signature, multiple messages revealed proof, tampered";
+    let tampered = |proof_octets: &[u8], key_pair: &KeyPair, header: &[u8], ph: &[u8], revealed: &BTreeMap<usize, Vec<u8>>, reason: &str| {
+        tampered_proof_fixture::<X>(proof_case, proof_octets, key_pair, &signature, header, ph, revealed, messages.len(), seed, reason)
+    };
+    let last_scalar = 8 * (proof_octets.len() - 32);
+    let proof_fixtures = vec![
+        tampered(&flip_bit(&proof_octets, 8 * 24), &key_pair, header, ph, &revealed, "flipped bit in A'")?,
+        tampered(&flip_bit(&proof_octets, 8 * (3 * 48 + 16)), &key_pair, header, ph, &revealed, "flipped bit in c")?,
+        tampered(&flip_bit(&proof_octets, last_scalar + 128), &key_pair, header, ph, &revealed, "flipped bit in m^")?,
+        tampered(&proof_octets, &wrong_key_pair, header, ph, &revealed, "wrong public key")?,
+        tampered(&proof_octets, &key_pair, &modified_header, ph, &revealed, "modified header")?,
+        tampered(&proof_octets, &key_pair, header, &modified_ph, &revealed, "modified presentation header")?,
+        tampered(&proof_octets, &key_pair, header, ph, &extra_revealed, "extra disclosed index")?,
+        tampered(&proof_octets, &key_pair, header, ph, &removed_revealed, "removed disclosed index")?,
+        tampered(&proof_octets, &key_pair, header, ph, &modified_revealed, "modified revealed message")?,
+    ];
+
+    let signature_dir = dir.join("tamper").join("signature");
+    fs::create_dir_all(&signature_dir)?;
+    for (i, fixture) in signature_fixtures.iter().enumerate() {
+        let file_name = format!("signature{:03}.{}", i + 1, format.extension());
+        write_fixture(&signature_dir.join(file_name), fixture, format)?;
+    }
+
+    let proof_dir = dir.join("tamper").join("proof");
+    fs::create_dir_all(&proof_dir)?;
+    for (i, fixture) in proof_fixtures.iter().enumerate() {
+        let file_name = format!("proof{:03}.{}", i + 1, format.extension());
+        write_fixture(&proof_dir.join(file_name), fixture, format)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn sign<'a, X>(key_pair: &KeyPair, header: &[u8], messages: &[Vec<u8>]) -> Result<Signature, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    sign_with_limits::<X>(key_pair, header, messages, &Limits::DEFAULT)
+}
+
+fn sign_with_limits<'a, X>(key_pair: &KeyPair, header: &[u8], messages: &[Vec<u8>], limits: &Limits) -> Result<Signature, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let messages = to_scalars::<X>(messages)?;
+    let mode = SigningMode::Deterministic;
+    Signature::sign_with_limits::<X, _>(&messages, &key_pair.secret_key, Some(header), mode, limits, &mut rand::thread_rng())
+}
+
+// Builds a fixture, checking that the library agrees with the expected result
+pub(crate) fn fixture<'a, X>(
+    case_name: &str,
+    signature: &Signature,
+    key_pair: &KeyPair,
+    header: &[u8],
+    messages: &[Vec<u8>],
+    reason: Option<&str>,
+) -> Result<SignatureFixture, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    fixture_with_limits::<X>(case_name, signature, key_pair, header, messages, reason, &Limits::DEFAULT)
+}
+
+fn fixture_with_limits<'a, X>(
+    case_name: &str,
+    signature: &Signature,
+    key_pair: &KeyPair,
+    header: &[u8],
+    messages: &[Vec<u8>],
+    reason: Option<&str>,
+    limits: &Limits,
+) -> Result<SignatureFixture, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let valid = signature.verify_with_limits::<X>(&key_pair.public_key, &to_scalars::<X>(messages)?, Some(header), limits);
+    assert_eq!(valid, reason.is_none(), "unexpected verification result for {:?}", reason);
+    if valid {
+        assert_round_trip(&signature.to_bytes(), |bytes| Signature::from_bytes(bytes).map(|s| s.to_bytes().to_vec()).ok());
+    }
+
+    Ok(SignatureFixture {
+        case_name: case_name.to_string(),
+        signer_key_pair: KeyPairFixture::from(key_pair),
+        header: hex::encode(header),
+        messages: messages.iter().map(hex::encode).collect(),
+        signature: hex::encode(signature.to_bytes()),
+        result: FixtureResult {
+            valid,
+            reason: reason.map(str::to_string),
+        },
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn proof_fixture<'a, X>(
+    case_name: &str,
+    signature: &Signature,
+    key_pair: &KeyPair,
+    header: &[u8],
+    ph: &[u8],
+    messages: &[Vec<u8>],
+    disclosed_indexes: &[usize],
+    seed: &[u8],
+) -> Result<ProofFixture, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    proof_fixture_with_limits::<X>(case_name, signature, key_pair, header, ph, messages, disclosed_indexes, seed, &Limits::DEFAULT)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn proof_fixture_with_limits<'a, X>(
+    case_name: &str,
+    signature: &Signature,
+    key_pair: &KeyPair,
+    header: &[u8],
+    ph: &[u8],
+    messages: &[Vec<u8>],
+    disclosed_indexes: &[usize],
+    seed: &[u8],
+    limits: &Limits,
+) -> Result<ProofFixture, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let scalars = to_scalars::<X>(messages)?;
+    let proof = Proof::generate_with_random_scalars_and_limits::<X, _>(
+        signature,
+        &key_pair.public_key,
+        Some(header),
+        Some(ph),
+        &scalars,
+        disclosed_indexes,
+        limits,
+        |count| mocked_calculate_random_scalars::<X>(seed, count),
+    )?;
+
+    let disclosed_messages: BTreeMap<usize, Scalar> = disclosed_indexes.iter().map(|&i| (i, scalars[i])).collect();
+    let valid = proof.verify_with_limits::<X>(&key_pair.public_key, Some(header), Some(ph), &disclosed_messages, limits);
+    assert!(valid, "generated proof for {:?} does not verify", case_name);
+    let from_bytes = |bytes: &[u8]| Proof::from_bytes_with_limits(bytes, DeserializeMode::Strict, limits).map(|p| p.to_bytes()).ok();
+    assert_round_trip(&proof.to_bytes(), from_bytes);
+
+    Ok(ProofFixture {
+        case_name: case_name.to_string(),
+        signer_public_key: hex::encode(key_pair.public_key.to_bytes()),
+        signature: hex::encode(signature.to_bytes()),
+        header: hex::encode(header),
+        presentation_header: hex::encode(ph),
+        revealed_messages: disclosed_indexes
+            .iter()
+            .map(|&i| (i.to_string(), hex::encode(&messages[i])))
+            .collect(),
+        total_message_count: messages.len(),
+        random_scalars_seed: hex::encode(seed),
+        proof: hex::encode(proof.to_bytes()),
+        result: FixtureResult {
+            valid,
+            reason: None,
+        },
+    })
+}
+
+fn domain_fixture<'a, X>(case_name: &str, key_pair: &KeyPair, header: &[u8], message_count: usize) -> DomainFixture
+where
+    X: BbsCiphersuite<'a>
+{
+    let generators = make_generators::<X>(None, message_count + 2);
+    let domain = calculate_domain::<X>(&key_pair.public_key, &generators.message_generators, Some(header));
+
+    DomainFixture {
+        case_name: case_name.to_string(),
+        signer_public_key: hex::encode(key_pair.public_key.to_bytes()),
+        header: hex::encode(header),
+        total_message_count: message_count,
+        domain: hex::encode(scalar_to_bytes(&domain)),
+    }
+}
+
+// C1 and C2 are not part of the proof, they are computed again from the random scalars of
+// mocked_calculate_random_scalars as in ProofGen. The challenge must be the one of the proof
+#[allow(clippy::too_many_arguments)]
+fn challenge_fixture<'a, X>(
+    case_name: &str,
+    signature: &Signature,
+    key_pair: &KeyPair,
+    header: &[u8],
+    ph: &[u8],
+    messages: &[Vec<u8>],
+    disclosed_indexes: &[usize],
+    seed: &[u8],
+) -> Result<ChallengeFixture, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let scalars = to_scalars::<X>(messages)?;
+    let proof = Proof::generate_with_random_scalars::<X, _>(
+        signature,
+        &key_pair.public_key,
+        Some(header),
+        Some(ph),
+        &scalars,
+        disclosed_indexes,
+        |count| mocked_calculate_random_scalars::<X>(seed, count),
+    )?;
+    let octets = proof.to_bytes();
+    let point = |i: usize| {
+        let bytes: &[u8; 48] = octets[48 * i..48 * (i + 1)].try_into().expect("48 octets");
+        Backend::g1_from_bytes(bytes).expect("a point of a valid proof")
+    };
+    let (a_prime, a_bar, d) = (point(0), point(1), point(2));
+
+    // C1 = A' * e~ + Q_1 * r2~, C2 = D * (-r3~) + Q_1 * s~ + H_j1 * m~_j1 + ... + H_jU * m~_jU
+    let undisclosed: Vec<usize> = (0..messages.len()).filter(|i| !disclosed_indexes.contains(i)).collect();
+    let random_scalars = mocked_calculate_random_scalars::<X>(seed, 6 + undisclosed.len());
+    let generators = make_generators::<X>(None, messages.len() + 2);
+    let q_1 = generators.message_generators[0];
+    let h = &generators.message_generators[2..];
+    let c1 = a_prime * random_scalars[2] + q_1 * random_scalars[3];
+    let mut c2 = d * (-random_scalars[4]) + q_1 * random_scalars[5];
+    for (&j, m_tilde) in undisclosed.iter().zip(&random_scalars[6..]) {
+        c2 += h[j] * m_tilde;
+    }
+
+    let disclosed: BTreeMap<usize, Scalar> = disclosed_indexes.iter().map(|&i| (i, scalars[i])).collect();
+    let domain = calculate_domain::<X>(&key_pair.public_key, &generators.message_generators, Some(header));
+    let challenge = calculate_challenge::<X>(&a_prime, &a_bar, &d, &c1, &c2, &disclosed, &domain, Some(ph));
+    assert_eq!(challenge, proof.challenge(), "calculate_challenge differs from the challenge of the proof for {:?}", case_name);
+
+    let point_hex = |p: &G1| hex::encode(Backend::g1_to_bytes(p));
+    Ok(ChallengeFixture {
+        case_name: case_name.to_string(),
+        a_prime: point_hex(&a_prime),
+        a_bar: point_hex(&a_bar),
+        d: point_hex(&d),
+        c1: point_hex(&c1),
+        c2: point_hex(&c2),
+        revealed_messages: disclosed_indexes
+            .iter()
+            .map(|&i| (i.to_string(), hex::encode(&messages[i])))
+            .collect(),
+        domain: hex::encode(scalar_to_bytes(&domain)),
+        presentation_header: hex::encode(ph),
+        challenge: hex::encode(scalar_to_bytes(&challenge)),
+    })
+}
+
+// Builds a fixture from signature octets that may not decode, checking that they are rejected
+fn tampered_signature_fixture<'a, X>(
+    case_name: &str,
+    signature: &[u8],
+    key_pair: &KeyPair,
+    header: &[u8],
+    messages: &[Vec<u8>],
+    reason: &str,
+) -> Result<SignatureFixture, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let scalars = to_scalars::<X>(messages)?;
+    let valid = Signature::from_bytes(signature)
+        .map(|signature| signature.verify::<X>(&key_pair.public_key, &scalars, Some(header)))
+        .unwrap_or(false);
+    assert!(!valid, "tampered signature for {:?} verifies", reason);
+
+    Ok(SignatureFixture {
+        case_name: case_name.to_string(),
+        signer_key_pair: KeyPairFixture::from(key_pair),
+        header: hex::encode(header),
+        messages: messages.iter().map(hex::encode).collect(),
+        signature: hex::encode(signature),
+        result: FixtureResult {
+            valid,
+            reason: Some(reason.to_string()),
+        },
+    })
+}
+
+// As tampered_signature_fixture for proofs, revealed maps the disclosed indexes to their messages
+#[allow(clippy::too_many_arguments)]
+fn tampered_proof_fixture<'a, X>(
+    case_name: &str,
+    proof: &[u8],
+    key_pair: &KeyPair,
+    signature: &Signature,
+    header: &[u8],
+    ph: &[u8],
+    revealed: &BTreeMap<usize, Vec<u8>>,
+    total_message_count: usize,
+    seed: &[u8],
+    reason: &str,
+) -> Result<ProofFixture, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let disclosed_messages = revealed
+        .iter()
+        .map(|(&i, msg)| Ok((i, map_message_to_scalar_as_hash::<X>(msg, None)?)))
+        .collect::<Result<BTreeMap<usize, Scalar>, BbsError>>()?;
+    let valid = Proof::from_bytes(proof)
+        .map(|proof| proof.verify::<X>(&key_pair.public_key, Some(header), Some(ph), &disclosed_messages))
+        .unwrap_or(false);
+    assert!(!valid, "tampered proof for {:?} verifies", reason);
+
+    Ok(ProofFixture {
+        case_name: case_name.to_string(),
+        signer_public_key: hex::encode(key_pair.public_key.to_bytes()),
+        signature: hex::encode(signature.to_bytes()),
+        header: hex::encode(header),
+        presentation_header: hex::encode(ph),
+        revealed_messages: revealed.iter().map(|(i, msg)| (i.to_string(), hex::encode(msg))).collect(),
+        total_message_count,
+        random_scalars_seed: hex::encode(seed),
+        proof: hex::encode(proof),
+        result: FixtureResult {
+            valid,
+            reason: Some(reason.to_string()),
+        },
+    })
+}
+
+// Flips the bit at the given position, counted from the most significant bit of the first octet
+fn flip_bit(octets: &[u8], bit: usize) -> Vec<u8> {
+    let mut octets = octets.to_vec();
+    octets[bit / 8] ^= 0x80 >> (bit % 8);
+    octets
+}
+
+// Decoding the octets of a fixture and encoding them again must give back the same octets
+fn assert_round_trip<F>(octets: &[u8], decode_encode: F)
+where
+    F: Fn(&[u8]) -> Option<Vec<u8>>
+{
+    assert_eq!(decode_encode(octets).as_deref(), Some(octets), "octets do not round-trip");
+}
+
+fn to_scalars<'a, X>(messages: &[Vec<u8>]) -> Result<Vec<Scalar>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    messages
+        .iter()
+        .map(|msg| map_message_to_scalar_as_hash::<X>(msg, None))
+        .collect()
+}
+
+fn write_fixture<T: Serialize>(path: &Path, value: &T, format: OutputFormat) -> Result<(), BbsError> {
+    let file = File::create(path)?;
+
+    let mut writer = BufWriter::new(file);
+
+    format.write(&mut writer, value)?;
+
+    writer.flush()?;
+    Ok(())
+}
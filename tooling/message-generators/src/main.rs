@@ -1,27 +1,104 @@
-use bls12_381_plus::{ExpandMsg, ExpandMsgXof, G1Projective, G2Projective, Scalar};
-use ff::Field;
-use group::{Curve};
-use sha3::digest::{ExtendableOutput, Update, XofReader};
-use sha3::Shake256;
+use indicatif::ProgressBar;
+use rand::rngs::StdRng;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use schemars::JsonSchema;
+use serde::Serialize;
 use structopt::StructOpt;
 use std::env;
-use std::fs::File;
-use std::io::{BufWriter, Write};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-mod ciphersuites;
-use ciphersuites::{BbsCiphersuite, Bls12381Shake256, Bls12381Sha256};
+use bbs::ciphersuites::{BbsCiphersuite, Bls12381Shake256, Bls12381Sha256, CiphersuiteParams};
+#[cfg(feature = "non-standard-suites")]
+use bbs::ciphersuites::Bls12381Sha512;
+use bbs::cpu::{cpu_info, CpuInfo};
+use bbs::did::{to_did_key, verification_method};
+use bbs::generator_cache::GeneratorCache;
+use bbs::generators::{make_g1_base_point, make_generators, make_generators_parallel, GeneratorIter, Generators};
+use bbs::hd::ExtendedSecretKey;
+use bbs::limits::Limits;
+use bbs::signature::SigningMode;
+use bbs::signer::LocalSigner;
+use bbs::curve::G1;
+use bbs::{BbsError, KeyPair, PublicKey, SecretKey};
 
-const DST: &[u8] = b"BBS_BLS12381G1_XOF:SHAKE-256_SSWU_RO_";
+mod bench;
+mod check_generators;
+mod diff_generators;
+mod dump;
+mod error;
+mod fixtures;
+mod messages_file;
+mod operations;
+mod output;
+mod progress;
+mod schema;
+mod serve;
+mod shell;
+mod stats;
+mod stdio;
+mod suite_file;
+mod table;
+mod verify_fixtures;
+use bench::{compare_to_baseline, print_comparison, run_bench, BenchFormat};
+use check_generators::{check_generators, print_check};
+use diff_generators::{diff_generators, print_diff};
+use dump::DumpLayer;
+use error::{usage_error, CliError};
+use fixtures::{write_fixtures, write_stress_fixtures, FixtureProfile};
+use fixtures::KeyPairFile;
+use messages_file::read_messages_file;
+use operations::{PresentationFile, SignedMessagesFile};
+use output::{print_json, serialization_error, KeyFormat, OutputFormat, PointEncoding, PrintFormat, ValueEncoding};
+use progress::{generator_progress, make_generators_with_progress};
+use schema::{fixture_schema, FixtureKind};
+use serve::serve;
+use shell::run_shell;
+use stats::{print_stats, stats};
+use stdio::is_stdio;
+use suite_file::load_suite_file;
+use table::{ColorChoice, GeneratorTable};
+use verify_fixtures::{decode, matrix_json, print_matrix, read_json, verify_fixtures};
 
-struct Generators {
-    g1_base_point: G1Projective,
-    message_generators: Vec<G1Projective>
-}
-
-#[derive(StructOpt, Debug)]
+#[derive(StructOpt, Debug, Clone)]
 struct Opt {
+    /// Ciphersuite, or all for matched SHAKE-256 and SHA-256 outputs in suite named directories
     #[structopt(short, long, default_value = "Shake")]
-    suite: Ciphersuite,
+    suite: Suites,
+    /// Format of the files written: generators, fixtures and the outputs of sign and proof-gen
+    #[structopt(short, long, default_value = "Json")]
+    format: OutputFormat,
+    /// Seeds the rng of signer specific generators, random key pairs and proofs, for reproducible output
+    #[structopt(long)]
+    rng_seed: Option<u64>,
+    /// What is printed: text, or json for scripts
+    #[structopt(long, default_value = "text")]
+    output: PrintFormat,
+    /// Encoding of the printed points, scalars, signatures and proofs: hex, base64url or multibase
+    #[structopt(long, default_value = "hex")]
+    encoding: ValueEncoding,
+    /// Hides the progress bar shown while creating many generators
+    #[structopt(short, long)]
+    quiet: bool,
+    /// Logs intermediate values to stderr, -v the domain, B and challenge, -vv also every generator and message scalar
+    #[structopt(short, long, parse(from_occurrences))]
+    verbose: u8,
+    /// Prints every intermediate value of the operations to stderr, named as in the spec
+    #[structopt(long)]
+    dump_intermediates: bool,
+    /// Largest number of messages signed, verified or proven, e.g. for stress fixtures above the default
+    #[structopt(long, default_value = "65536")]
+    max_messages: usize,
+    #[structopt(flatten)]
+    write: WriteOpt,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt, Debug, Clone)]
+struct GeneratorsOpt {
     #[structopt(short, long, default_value = "10")]
     length: usize,
     #[structopt(short, long, default_value = "Global")]
@@ -30,24 +107,302 @@ struct Opt {
     out_type: OutputType,
     #[structopt(required_if("out-type", "file"))]
     file_name: Option<String>,
+    /// Encoding of the printed and written points: compressed, uncompressed or both
+    #[structopt(long, default_value = "compressed")]
+    point_encoding: PointEncoding,
+    /// Outputs each generator as soon as it is created instead of collecting them first
+    #[structopt(long)]
+    stream: bool,
+    /// Hashes the generators to the curve across all cores
+    #[structopt(long)]
+    parallel: bool,
+    /// Generator cache file, read when it exists and updated with the generators created
+    #[structopt(long)]
+    cache: Option<PathBuf>,
+    /// TOML or JSON ciphersuite definition used instead of --suite
+    #[structopt(long)]
+    suite_file: Option<PathBuf>,
+    /// Hex encoded generator seed used instead of the ciphersuite's seed or the signer public key
+    #[structopt(long)]
+    seed_hex: Option<String>,
+    /// Hex encoded public key of the signer of -g Signer, a random key is used when omitted
+    #[structopt(long)]
+    signer_pk: Option<String>,
+    /// Colors the printed table: auto, always or never
+    #[structopt(long, default_value = "auto")]
+    color: ColorChoice,
+    /// Message generators left out at the start of the printed table
+    #[structopt(long, default_value = "0")]
+    skip: usize,
+    /// Message generators printed after those skipped, all by default
+    #[structopt(long)]
+    limit: Option<usize>,
+}
+
+// Where the files of generators -o file, sign --out and proof-gen --out are written
+#[derive(StructOpt, Debug, Clone)]
+struct WriteOpt {
+    /// Directory relative output file names are resolved against, the current directory by default
+    #[structopt(long)]
+    out_dir: Option<PathBuf>,
+    /// Overwrites existing output files
+    #[structopt(long)]
+    force: bool,
+}
+
+// The header and messages of sign, verify and proof-gen, hex encoded or read from a messages file
+#[derive(StructOpt, Debug, Clone)]
+struct MessagesOpt {
+    /// Hex encoded header
+    #[structopt(long = "header-hex", default_value = "")]
+    header: String,
+    /// Hex encoded messages
+    #[structopt(long, conflicts_with = "messages-file")]
+    messages: Vec<String>,
+    /// File with one message per line, or a JSON array of messages when the extension is .json
+    #[structopt(long)]
+    messages_file: Option<PathBuf>,
+    /// The messages of --messages-file are UTF-8 text instead of hex
+    #[structopt(long, requires = "messages-file")]
+    messages_utf8: bool,
+}
+
+#[derive(StructOpt, Debug, Clone)]
+enum Command {
+    /// Creates the base point and message generators of the ciphersuite, printed or written to a file
+    Generators(GeneratorsOpt),
+    /// Derives a key pair from IKM using the KeyGen and SkToPk operations
+    Keygen {
+        /// Hex encoded IKM of at least 32 bytes, random when omitted
+        #[structopt(long)]
+        ikm: Option<String>,
+        /// Hex encoded key_info
+        #[structopt(long)]
+        key_info: Option<String>,
+        /// Hardened derivation path, e.g. m/0'/1', the IKM is then the master seed
+        #[structopt(long, conflicts_with = "key-info")]
+        path: Option<String>,
+        #[structopt(long)]
+        json: bool,
+        /// Prints the key pair as a private JWK, as PKCS#8 and SubjectPublicKeyInfo PEM, as hex DER, or the public key as a did:key
+        #[structopt(long, conflicts_with = "json")]
+        out_format: Option<KeyFormat>,
+    },
+    /// Prints the ciphersuite_id, octet lengths and DSTs of the selected ciphersuite
+    SuiteInfo {
+        #[structopt(long)]
+        json: bool,
+    },
+    /// Prints whether the CPU has ADX and BMI2 and which field arithmetic the curve operations run with
+    #[structopt(name = "cpuinfo")]
+    CpuInfo,
+    /// Times keygen, generator creation, sign, verify, proofgen and proofverify, printing the results as JSON or CSV
+    Bench {
+        /// Message counts to run the per message operations with
+        #[structopt(long, default_value = "1,10,100,1000", use_delimiter = true)]
+        messages: Vec<usize>,
+        #[structopt(long, default_value = "10")]
+        iterations: u32,
+        /// json or csv
+        #[structopt(long, default_value = "json")]
+        format: BenchFormat,
+        /// A json report of an earlier run, exits with status 1 when an operation is slower than in it by more than --threshold
+        #[structopt(long, parse(from_os_str))]
+        baseline: Option<PathBuf>,
+        /// Slowdown of the mean of an operation tolerated against --baseline, in percent
+        #[structopt(long, default_value = "10")]
+        threshold: f64,
+    },
+    /// Reports the octet sizes of a signature and proof for the given message counts and times their verification
+    Stats {
+        /// Number of signed messages
+        #[structopt(long)]
+        messages: usize,
+        /// Number of messages disclosed by the proof
+        #[structopt(long, default_value = "0")]
+        disclosed: usize,
+        /// Verifications timed for the mean
+        #[structopt(long, default_value = "10")]
+        iterations: u32,
+    },
+    /// Prints the JSON Schema of a file written by the tool: generators, keyPair, keyGen, signature, proof, domain, challenge, signedMessages or presentation
+    Schema {
+        kind: FixtureKind,
+    },
+    /// Reports where two generators files, e.g. of this tool and of another implementation, diverge
+    DiffGenerators {
+        a: PathBuf,
+        b: PathBuf,
+    },
+    /// Checks that the points of a generators file are in the G1 subgroup, not the identity and distinct
+    CheckGenerators {
+        file: PathBuf,
+    },
+    /// Runs the vectors of a fixture directory through the library and prints a pass/fail matrix
+    VerifyFixtures {
+        /// Directory in the layout of ../fixtures
+        dir: PathBuf,
+    },
+    /// Starts an interactive session to keygen, sign and generate proofs with the selected ciphersuite
+    Shell,
+    /// Serves keygen, sign, verify, proof-gen, proof-verify and fixtures as JSON over HTTP on localhost
+    Serve {
+        #[structopt(long, default_value = "8080")]
+        port: u16,
+    },
+    /// Signs the messages, printing the signature or writing it with the public key, header and messages
+    Sign {
+        /// Hex encoded secret key
+        #[structopt(long, conflicts_with = "key-file")]
+        sk: Option<String>,
+        /// Key pair file in the layout of keygen --json and ../fixtures/keyPair.json, stdin when neither is given
+        #[structopt(long)]
+        key_file: Option<PathBuf>,
+        #[structopt(flatten)]
+        messages: MessagesOpt,
+        /// Writes the signed messages file, the input of verify and proof-gen, instead of printing the signature
+        #[structopt(long)]
+        out: Option<PathBuf>,
+        /// deterministic, the spec's Sign, or hedged with random octets added to the hash of e and s
+        #[structopt(long, default_value = "deterministic", parse(try_from_str = parse_signing_mode))]
+        mode: SigningMode,
+    },
+    /// Verifies a signature, exiting with status 1 when it is invalid
+    Verify {
+        #[structopt(flatten)]
+        signed: SignedOpt,
+    },
+    /// Generates a proof of a signature disclosing the messages at the given indexes
+    ProofGen {
+        #[structopt(flatten)]
+        signed: SignedOpt,
+        /// Hex encoded presentation header
+        #[structopt(long = "ph-hex", default_value = "")]
+        ph: String,
+        /// Zero based indexes of the disclosed messages
+        #[structopt(long, use_delimiter = true)]
+        disclose: Vec<usize>,
+        /// Writes the presentation file, the input of proof-verify, instead of printing the proof
+        #[structopt(long)]
+        out: Option<PathBuf>,
+    },
+    /// Verifies a proof, exiting with status 1 when it is invalid
+    ProofVerify {
+        /// Hex encoded public key of the signer
+        #[structopt(long, conflicts_with = "presentation-file")]
+        pk: Option<String>,
+        /// Hex encoded proof
+        #[structopt(long, conflicts_with = "presentation-file")]
+        proof: Option<String>,
+        /// Hex encoded header
+        #[structopt(long = "header-hex", default_value = "", conflicts_with = "presentation-file")]
+        header: String,
+        /// Hex encoded presentation header
+        #[structopt(long = "ph-hex", default_value = "", conflicts_with = "presentation-file")]
+        ph: String,
+        /// Disclosed messages as <index>:<hex>
+        #[structopt(long, conflicts_with = "presentation-file")]
+        revealed: Vec<String>,
+        /// Number of signed messages, disclosed or not
+        #[structopt(long, conflicts_with = "presentation-file")]
+        total: Option<usize>,
+        /// Presentation file written by proof-gen --out, instead of the other flags, stdin when none is given
+        #[structopt(long)]
+        presentation_file: Option<PathBuf>,
+    },
+    /// Writes the key pair and signature fixtures used by the spec
+    Fixtures {
+        /// Hex encoded IKM the signer key pair is derived from
+        #[structopt(long)]
+        ikm: String,
+        /// Hex encoded header, bound into every signature and proof
+        #[structopt(long = "header-hex", alias = "header", default_value = "")]
+        header: String,
+        /// Hex encoded presentation header, bound into the proofs
+        #[structopt(long = "ph-hex", default_value = "")]
+        ph: String,
+        /// Hex encoded messages, at least two are required
+        #[structopt(long, required_unless_one = &["messages-file", "profile"], conflicts_with = "messages-file", min_values = 2)]
+        messages: Vec<String>,
+        /// File with one message per line, or a JSON array of messages when the extension is .json
+        #[structopt(long)]
+        messages_file: Option<PathBuf>,
+        /// The messages of --messages-file are UTF-8 text instead of hex
+        #[structopt(long, requires = "messages-file")]
+        messages_utf8: bool,
+        /// Also writes tamper/, vectors derived from valid ones by one change that must be rejected
+        #[structopt(long)]
+        tamper: bool,
+        /// spec, the default, or stress for vectors at boundary message counts and key_info lengths instead of the messages
+        #[structopt(long, conflicts_with_all = &["messages", "messages-file", "tamper"])]
+        profile: Option<FixtureProfile>,
+        /// Message counts of the stress profile
+        #[structopt(long, default_value = "0,1,2,255,256,65535,65536", use_delimiter = true)]
+        message_counts: Vec<usize>,
+        /// Hex encoded seed of the mocked random scalars used by the proof fixtures
+        #[structopt(long, default_value = "332e313431353932363533353839373933323338343632363433333833323739")]
+        proof_seed: String,
+        /// Directory the fixtures are written to
+        #[structopt(default_value = ".")]
+        dir: PathBuf,
+    },
 }
 
-#[derive(Debug)]
+// The signature of verify and proof-gen, given as hex flags or as the file written by sign --out
+#[derive(StructOpt, Debug, Clone)]
+struct SignedOpt {
+    /// Hex encoded public key of the signer
+    #[structopt(long, conflicts_with = "signed-file")]
+    pk: Option<String>,
+    /// Hex encoded signature
+    #[structopt(long, conflicts_with = "signed-file")]
+    signature: Option<String>,
+    #[structopt(flatten)]
+    messages: MessagesOpt,
+    /// Signed messages file written by sign --out, instead of the other flags, stdin when none is given
+    #[structopt(long)]
+    signed_file: Option<PathBuf>,
+}
+
+// The generators file, self describing so it can be validated without the CLI flags
+#[derive(Serialize, JsonSchema)]
+struct GeneratorsFile {
+    ciphersuite: String,
+    seed: String,
+    /// Compressed, or uncompressed with --point-encoding uncompressed
+    bp: String,
+    generators: Vec<String>,
+    /// The uncompressed encodings, with --point-encoding both
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bp_uncompressed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generators_uncompressed: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
 enum OutputType {
     Print,
     File,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum GenType {
     Global,
     SignerSpecific,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum Ciphersuite {
     SHA256,
-    SHAKE256
+    SHAKE256,
+    #[cfg(feature = "non-standard-suites")]
+    SHA512,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Suites {
+    One(Ciphersuite),
+    All,
 }
 
 impl std::str::FromStr for GenType {
@@ -81,121 +436,883 @@ impl std::str::FromStr for Ciphersuite {
         match s.to_lowercase().as_str() {
             "sha" | "sha2" | "sha25" | "sha256" | "xmd" => Ok(Ciphersuite::SHA256),
             "shake" | "shake2" | "shake25" | "shake256" | "xof" => Ok(Ciphersuite::SHAKE256),
+            #[cfg(feature = "non-standard-suites")]
+            "sha512" => Ok(Ciphersuite::SHA512),
             _ => Err("Invalid Value".to_string())
         }
     }
 }
 
+impl std::str::FromStr for Suites {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "all" => Ok(Suites::All),
+            _ => s.parse().map(Suites::One),
+        }
+    }
+}
+
+impl Ciphersuite {
+    // The ciphersuites of the spec, each with a fixture directory
+    const SPEC: [Ciphersuite; 2] = [Ciphersuite::SHAKE256, Ciphersuite::SHA256];
+
+    fn id(&self) -> &'static [u8] {
+        match self {
+            Ciphersuite::SHA256 => Bls12381Sha256::ID,
+            Ciphersuite::SHAKE256 => Bls12381Shake256::ID,
+            #[cfg(feature = "non-standard-suites")]
+            Ciphersuite::SHA512 => Bls12381Sha512::ID,
+        }
+    }
+
+    // Name of the spec's fixture directory of the ciphersuite
+    fn dir_name(&self) -> &'static str {
+        match self {
+            Ciphersuite::SHA256 => "bls12-381-sha-256",
+            Ciphersuite::SHAKE256 => "bls12-381-shake-256",
+            #[cfg(feature = "non-standard-suites")]
+            Ciphersuite::SHA512 => "bls12-381-sha-512",
+        }
+    }
+}
 
 fn main() {
-    let opt: Opt = Opt::from_args();
+    let opt = Opt::from_args();
+    let level = match opt.verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let log = tracing_subscriber::fmt::layer().with_writer(std::io::stderr).with_filter(level);
+    let dump = opt.dump_intermediates.then(DumpLayer::default);
+    tracing_subscriber::registry().with(log).with(dump).init();
+
+    if let Err(e) = run_suites(opt) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+// With --suite all the run is repeated for each spec ciphersuite, the files and fixture directories
+// written are moved into a directory named after the ciphersuite, e.g. bls12-381-sha-256/generators.json
+fn run_suites(opt: Opt) -> Result<(), CliError> {
+    if let Suites::One(suite) = opt.suite {
+        return run(opt, suite);
+    }
+
+    match opt.command {
+        // Independent of the ciphersuite
+        Command::Keygen { .. } | Command::Schema { .. } | Command::DiffGenerators { .. } | Command::CheckGenerators { .. } => {
+            return run(opt, Ciphersuite::SHAKE256);
+        }
+        Command::Shell => return Err(usage_error("the shell runs a single ciphersuite, not all")),
+        Command::Serve { .. } => return Err(usage_error("the server serves a single ciphersuite, not all")),
+        Command::Sign { .. } | Command::Verify { .. } | Command::ProofGen { .. } | Command::ProofVerify { .. } => {
+            return Err(usage_error("signatures and proofs are of a single ciphersuite, not all"));
+        }
+        Command::Generators(ref generators) if generators.suite_file.is_some() => {
+            return Err(usage_error("--suite-file defines a single ciphersuite, it can not be used with --suite all"));
+        }
+        _ => {}
+    }
+
+    for suite in Ciphersuite::SPEC {
+        let mut opt = opt.clone();
+        match opt.command {
+            Command::Fixtures { ref mut dir, .. } | Command::VerifyFixtures { ref mut dir } => {
+                *dir = dir.join(suite.dir_name());
+            }
+            Command::Generators(ref mut generators) => match generators.out_type {
+                // The Json output names the ciphersuite itself
+                OutputType::Print if opt.output == PrintFormat::Json => {}
+                OutputType::Print => println!("ciphersuite = {}", String::from_utf8_lossy(suite.id())),
+                OutputType::File => {
+                    let name = file_name(generators.file_name.take())?;
+                    if is_stdio(Path::new(&name)) {
+                        return Err(usage_error("--suite all writes a file per ciphersuite, not -"));
+                    }
+                    generators.file_name = Some(suite_file_name(&name, suite))
+                }
+            },
+            _ => {}
+        }
+        run(opt, suite)?;
+    }
+    Ok(())
+}
+
+// dir/name becomes dir/<suite dir>/name
+fn suite_file_name(file_name: &str, suite: Ciphersuite) -> String {
+    let path = Path::new(file_name);
+    let name = path.file_name().unwrap_or_else(|| path.as_os_str());
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    parent.join(suite.dir_name()).join(name).to_string_lossy().into_owned()
+}
+
+fn run(opt: Opt, suite: Ciphersuite) -> Result<(), CliError> {
+    let mut rng = match opt.rng_seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let limits = Limits::new(opt.max_messages);
+
+    match opt.command {
+        Command::Keygen { ikm, key_info, path, json, out_format } => {
+            Ok(keygen(ikm, key_info, path, json || opt.output == PrintFormat::Json, out_format, opt.encoding, &mut rng)?)
+        }
+        Command::SuiteInfo { json } => {
+            // Suite specific suite info function
+            let suite_info_fn = match suite {
+                Ciphersuite::SHAKE256 => suite_info::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => suite_info::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => suite_info::<Bls12381Sha512>,
+            };
+            Ok(suite_info_fn(json || opt.output == PrintFormat::Json)?)
+        }
+        Command::CpuInfo => {
+            let info = cpu_info();
+            match opt.output {
+                PrintFormat::Text => print_cpu_info(&info),
+                PrintFormat::Json => print_json(&serde_json::json!({
+                    "arch": info.arch,
+                    "adx": info.adx,
+                    "bmi2": info.bmi2,
+                    "staticAdxBmi2": info.static_adx_bmi2,
+                    "runtimeDispatch": info.runtime_dispatch,
+                    "acceleration": info.acceleration.as_str(),
+                }))?,
+            }
+            Ok(())
+        }
+        Command::Bench { messages, iterations, format, baseline, threshold } => {
+            // Suite specific bench function
+            let run_bench_fn = match suite {
+                Ciphersuite::SHAKE256 => run_bench::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => run_bench::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => run_bench::<Bls12381Sha512>,
+            };
+            let report = run_bench_fn(&messages, iterations)?;
+            match format {
+                BenchFormat::Json => print_json(&report)?,
+                BenchFormat::Csv => print!("{}", report.to_csv()),
+            }
+            if let Some(baseline) = baseline {
+                let comparison = compare_to_baseline(&report, &baseline, threshold)?;
+                print_comparison(&comparison);
+                if comparison.regressed {
+                    std::process::exit(1);
+                }
+            }
+            Ok(())
+        }
+        Command::Stats { messages, disclosed, iterations } => {
+            // Suite specific stats function
+            let stats_fn = match suite {
+                Ciphersuite::SHAKE256 => stats::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => stats::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => stats::<Bls12381Sha512>,
+            };
+            let stats = stats_fn(messages, disclosed, iterations)?;
+            match opt.output {
+                PrintFormat::Text => print_stats(&stats),
+                PrintFormat::Json => print_json(&stats)?,
+            }
+            Ok(())
+        }
+        Command::Schema { kind } => {
+            println!("{}", serde_json::to_string_pretty(&fixture_schema(kind)).map_err(serialization_error)?);
+            Ok(())
+        }
+        Command::DiffGenerators { a, b } => {
+            let diff = diff_generators(&a, &b)?;
+            match opt.output {
+                PrintFormat::Text => print_diff(&diff),
+                PrintFormat::Json => print_json(&diff)?,
+            }
+            if !diff.same {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Command::CheckGenerators { file } => {
+            let check = check_generators(&file)?;
+            match opt.output {
+                PrintFormat::Text => print_check(&check),
+                PrintFormat::Json => print_json(&check)?,
+            }
+            if !check.valid {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Command::VerifyFixtures { dir } => {
+            // Suite specific verify fixtures function
+            let verify_fixtures_fn = match suite {
+                Ciphersuite::SHAKE256 => verify_fixtures::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => verify_fixtures::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => verify_fixtures::<Bls12381Sha512>,
+            };
+            let outcomes = verify_fixtures_fn(&dir, &limits)?;
+            match opt.output {
+                PrintFormat::Text => print_matrix(&outcomes),
+                PrintFormat::Json => print_json(&matrix_json(&outcomes))?,
+            }
+            if outcomes.iter().any(|o| !o.passed) {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Command::Shell => {
+            // Suite specific shell function
+            let run_shell_fn = match suite {
+                Ciphersuite::SHAKE256 => run_shell::<Bls12381Shake256, StdRng>,
+                Ciphersuite::SHA256 => run_shell::<Bls12381Sha256, StdRng>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => run_shell::<Bls12381Sha512, StdRng>,
+            };
+            Ok(run_shell_fn(opt.encoding, &mut rng)?)
+        }
+        Command::Serve { port } => {
+            // Suite specific serve function
+            let serve_fn = match suite {
+                Ciphersuite::SHAKE256 => serve::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => serve::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => serve::<Bls12381Sha512>,
+            };
+            Ok(serve_fn(port, &limits)?)
+        }
+        Command::Fixtures { ikm, header, ph, messages, messages_file, messages_utf8, tamper, profile, message_counts, proof_seed, dir } => {
+            let ikm = hex::decode(ikm).map_err(serialization_error)?;
+            let header = hex::decode(header).map_err(serialization_error)?;
+            let ph = hex::decode(ph).map_err(serialization_error)?;
+            if let Some(FixtureProfile::Stress) = profile {
+                let proof_seed = hex::decode(proof_seed).map_err(serialization_error)?;
+                let write_stress_fixtures_fn = match suite {
+                    Ciphersuite::SHAKE256 => write_stress_fixtures::<Bls12381Shake256>,
+                    Ciphersuite::SHA256 => write_stress_fixtures::<Bls12381Sha256>,
+                    #[cfg(feature = "non-standard-suites")]
+                    Ciphersuite::SHA512 => write_stress_fixtures::<Bls12381Sha512>,
+                };
+                return Ok(write_stress_fixtures_fn(&ikm, &header, &ph, &message_counts, &proof_seed, &dir, opt.format, &limits)?);
+            }
+            let messages: Vec<Vec<u8>> = match messages_file {
+                Some(path) => read_messages_file(&path, messages_utf8)?,
+                None => messages
+                    .iter()
+                    .map(hex::decode)
+                    .collect::<Result<_, _>>()
+                    .map_err(serialization_error)?,
+            };
+            let proof_seed = hex::decode(proof_seed).map_err(serialization_error)?;
+
+            // Suite specific write fixtures function
+            let write_fixtures_fn = match suite {
+                Ciphersuite::SHAKE256 => write_fixtures::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => write_fixtures::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => write_fixtures::<Bls12381Sha512>,
+            };
+            Ok(write_fixtures_fn(&ikm, &header, &ph, &messages, &proof_seed, &dir, opt.format, tamper)?)
+        }
+        Command::Sign { sk, key_file, messages, out, mode } => {
+            let sk = match sk {
+                Some(sk) => sk,
+                None => read_json::<KeyPairFile>(&key_file.unwrap_or_else(|| PathBuf::from("-")))?.key_pair.secret_key,
+            };
+            let sk = decode(&sk)?;
+            let sk = sk.as_slice().try_into().map_err(|_| BbsError::InvalidLength("a secret key is 32 octets"))?;
+            let (header, messages) = messages.read()?;
+
+            // Suite specific sign function
+            let sign_fn = match suite {
+                Ciphersuite::SHAKE256 => operations::sign::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => operations::sign::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => operations::sign::<Bls12381Sha512>,
+            };
+            let signed = sign_fn(&LocalSigner::with_mode(SecretKey::from_bytes(sk)?, mode).with_limits(limits), &header, &messages)?;
+            match out {
+                Some(path) => write_file(&opt.write, &path, &signed, opt.format)?,
+                None if opt.output == PrintFormat::Json => print_json(&signed)?,
+                None => println!("signature = {}", opt.encoding.reencode(&signed.signature)?),
+            }
+            Ok(())
+        }
+        Command::Verify { signed } => {
+            // Suite specific verify function
+            let verify_fn = match suite {
+                Ciphersuite::SHAKE256 => operations::verify::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => operations::verify::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => operations::verify::<Bls12381Sha512>,
+            };
+            Ok(print_validity(verify_fn(&signed.read()?, &limits)?, opt.output)?)
+        }
+        Command::ProofGen { signed, ph, disclose, out } => {
+            let ph = decode(&ph)?;
+
+            // Suite specific proof generation function
+            let proof_gen_fn = match suite {
+                Ciphersuite::SHAKE256 => operations::proof_gen::<Bls12381Shake256, StdRng>,
+                Ciphersuite::SHA256 => operations::proof_gen::<Bls12381Sha256, StdRng>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => operations::proof_gen::<Bls12381Sha512, StdRng>,
+            };
+            let presentation = proof_gen_fn(&signed.read()?, &ph, &disclose, &limits, &mut rng)?;
+            match out {
+                Some(path) => write_file(&opt.write, &path, &presentation, opt.format)?,
+                None if opt.output == PrintFormat::Json => print_json(&presentation)?,
+                None => println!("proof = {}", opt.encoding.reencode(&presentation.proof)?),
+            }
+            Ok(())
+        }
+        Command::ProofVerify { pk, proof, header, ph, revealed, total, presentation_file } => {
+            let presentation = match presentation_file {
+                Some(path) => read_json(&path)?,
+                None if pk.is_none() && proof.is_none() => read_json(Path::new("-"))?,
+                None => PresentationFile {
+                    signer_public_key: pk.ok_or_else(|| usage_error("--pk or --presentation-file is required"))?,
+                    header,
+                    presentation_header: ph,
+                    revealed_messages: revealed
+                        .iter()
+                        .map(|revealed| {
+                            revealed
+                                .split_once(':')
+                                .map(|(i, msg)| (i.to_string(), msg.to_string()))
+                                .ok_or_else(|| usage_error("--revealed takes <index>:<hex>"))
+                        })
+                        .collect::<Result<_, _>>()?,
+                    total_message_count: total.ok_or_else(|| usage_error("--total or --presentation-file is required"))?,
+                    proof: proof.ok_or_else(|| usage_error("--proof or --presentation-file is required"))?,
+                },
+            };
+
+            // Suite specific proof verification function
+            let proof_verify_fn = match suite {
+                Ciphersuite::SHAKE256 => operations::proof_verify::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => operations::proof_verify::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => operations::proof_verify::<Bls12381Sha512>,
+            };
+            Ok(print_validity(proof_verify_fn(&presentation, &limits)?, opt.output)?)
+        }
+        Command::Generators(generators) => {
+            run_generators(generators, suite, opt.format, opt.output, opt.encoding, opt.quiet, &opt.write, &mut rng)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_generators<R>(
+    opt: GeneratorsOpt,
+    suite: Ciphersuite,
+    format: OutputFormat,
+    output: PrintFormat,
+    encoding: ValueEncoding,
+    quiet: bool,
+    write: &WriteOpt,
+    rng: &mut R,
+) -> Result<(), CliError>
+where
+    R: RngCore + CryptoRng
+{
+    let seed = opt.seed_hex.as_deref().map(hex::decode).transpose().map_err(serialization_error)?;
+    let table = GeneratorTable::new(opt.point_encoding, encoding, opt.color, opt.skip, opt.limit);
+
+    // The key signer specific generators are bound to, printed so the output can be tied to the issuer
+    let signer_pk = match (&seed, &opt.generator_type) {
+        (None, GenType::SignerSpecific) => {
+            let pk = signer_public_key(opt.signer_pk.as_deref(), rng)?;
+            // On stderr with --output json, which prints the generators file alone
+            match output {
+                PrintFormat::Text => println!("PK = {}", encoding.encode(pk.to_bytes())),
+                PrintFormat::Json => eprintln!("PK = {}", hex::encode(pk.to_bytes())),
+            }
+            Some(pk)
+        }
+        _ => None,
+    };
+
+    if let Some(ref path) = opt.suite_file {
+        let suite = load_suite_file(path)?;
+        let make_generators_fn = |seed: Option<&[u8]>, len| suite.make_generators(seed, len);
+
+        let generators = match (&seed, &signer_pk) {
+            (Some(seed), _) => seeded_generators(make_generators_fn, seed, opt.length),
+            (None, Some(pk)) => signer_specific_generators(make_generators_fn, opt.length, pk),
+            (None, None) => global_generators(make_generators_fn, opt.length),
+        };
+
+        match opt.out_type {
+            OutputType::Print => match output {
+                PrintFormat::Text => print_generators(&generators, &table)?,
+                PrintFormat::Json => print_json(&generators_file(&generators, &suite.ciphersuite_id, opt.point_encoding))?,
+            },
+            OutputType::File => {
+                let file_name = file_name(opt.file_name)?;
+                write_generators_to_file(write, &generators, &suite.ciphersuite_id, file_name, format, opt.point_encoding)?
+            }
+        }
+        return Ok(());
+    }
+
+    if opt.stream {
+        // Suite specific stream generators function
+        let stream_generators_fn = match suite {
+            Ciphersuite::SHAKE256 => stream_generators::<Bls12381Shake256>,
+            Ciphersuite::SHA256 => stream_generators::<Bls12381Sha256>,
+            #[cfg(feature = "non-standard-suites")]
+            Ciphersuite::SHA512 => stream_generators::<Bls12381Sha512>,
+        };
+
+        let seed = match (seed, &signer_pk) {
+            (Some(seed), _) => Some(seed),
+            (None, Some(pk)) => Some(pk.to_bytes().to_vec()),
+            (None, None) => None,
+        };
+
+        return match opt.out_type {
+            OutputType::Print if output == PrintFormat::Text => {
+                let stdout = &mut std::io::stdout().lock();
+                Ok(stream_generators_fn(seed.as_deref(), opt.length, stdout, Some(&table), opt.point_encoding, &ProgressBar::hidden())?)
+            }
+            OutputType::Print => {
+                if matches!(opt.point_encoding, PointEncoding::Both) {
+                    return Err(usage_error("one point encoding can be streamed as Json, not both"));
+                }
+                let stdout = &mut std::io::stdout().lock();
+                Ok(stream_generators_fn(seed.as_deref(), opt.length, stdout, None, opt.point_encoding, &ProgressBar::hidden())?)
+            }
+            OutputType::File => {
+                if !matches!(format, OutputFormat::Json) {
+                    return Err(usage_error("only Json output can be streamed to a file"));
+                }
+                if matches!(opt.point_encoding, PointEncoding::Both) {
+                    return Err(usage_error("one point encoding can be streamed to a file, not both"));
+                }
+                let file_name = file_name(opt.file_name)?;
+                // The bar would interleave with generators streamed to stdout
+                let progress = if is_stdio(Path::new(&file_name)) {
+                    ProgressBar::hidden()
+                } else {
+                    generator_progress(opt.length, quiet)
+                };
+                let mut writer = write.create(Path::new(&file_name))?;
+                stream_generators_fn(seed.as_deref(), opt.length, &mut writer, None, opt.point_encoding, &progress)?;
+                Ok(writer.commit()?)
+            }
+        };
+    }
 
     // Suite specific create generators function
-    let get_generators_fn = match opt.suite {
-        Ciphersuite::SHAKE256 => make_generators::<Bls12381Shake256>,
-        Ciphersuite::SHA256 => make_generators::<Bls12381Sha256>,
+    let get_generators_fn = match (&suite, opt.parallel) {
+        (Ciphersuite::SHAKE256, false) => make_generators::<Bls12381Shake256>,
+        (Ciphersuite::SHA256, false) => make_generators::<Bls12381Sha256>,
+        #[cfg(feature = "non-standard-suites")]
+        (Ciphersuite::SHA512, false) => make_generators::<Bls12381Sha512>,
+        (Ciphersuite::SHAKE256, true) => make_generators_parallel::<Bls12381Shake256>,
+        (Ciphersuite::SHA256, true) => make_generators_parallel::<Bls12381Sha256>,
+        #[cfg(feature = "non-standard-suites")]
+        (Ciphersuite::SHA512, true) => make_generators_parallel::<Bls12381Sha512>,
     };
+    // Suite specific grow generators function, creating the generators in parts to report progress
+    let ensure_generators_fn = match (&suite, opt.parallel) {
+        (Ciphersuite::SHAKE256, false) => Generators::ensure::<Bls12381Shake256>,
+        (Ciphersuite::SHA256, false) => Generators::ensure::<Bls12381Sha256>,
+        #[cfg(feature = "non-standard-suites")]
+        (Ciphersuite::SHA512, false) => Generators::ensure::<Bls12381Sha512>,
+        (Ciphersuite::SHAKE256, true) => Generators::ensure_parallel::<Bls12381Shake256>,
+        (Ciphersuite::SHA256, true) => Generators::ensure_parallel::<Bls12381Sha256>,
+        #[cfg(feature = "non-standard-suites")]
+        (Ciphersuite::SHA512, true) => Generators::ensure_parallel::<Bls12381Sha512>,
+    };
+
+    let generators = match opt.cache {
+        Some(ref path) => {
+            let mut cache = if path.exists() {
+                GeneratorCache::load(path)?
+            } else {
+                GeneratorCache::new()
+            };
+
+            // Suite specific cached create generators function
+            let cached_generators_fn = match suite {
+                Ciphersuite::SHAKE256 => GeneratorCache::make_generators::<Bls12381Shake256>,
+                Ciphersuite::SHA256 => GeneratorCache::make_generators::<Bls12381Sha256>,
+                #[cfg(feature = "non-standard-suites")]
+                Ciphersuite::SHA512 => GeneratorCache::make_generators::<Bls12381Sha512>,
+            };
+            let make_generators_fn = |seed: Option<&[u8]>, len| cached_generators_fn(&mut cache, seed, len);
+
+            let generators = match (&seed, &signer_pk) {
+                (Some(seed), _) => seeded_generators(make_generators_fn, seed, opt.length),
+                (None, Some(pk)) => signer_specific_generators(make_generators_fn, opt.length, pk),
+                (None, None) => global_generators(make_generators_fn, opt.length),
+            };
+            cache.save(path)?;
+            generators
+        }
+        None => {
+            let progress = generator_progress(opt.length, quiet);
+            let make_generators_fn = |seed: Option<&[u8]>, len| {
+                make_generators_with_progress(get_generators_fn, ensure_generators_fn, seed, len, &progress)
+            };
 
-    let generators = match opt.generator_type {
-        GenType::Global => global_generators(get_generators_fn, opt.length),
-        GenType::SignerSpecific => signer_specific_generators(get_generators_fn, opt.length),
+            match (&seed, &signer_pk) {
+                (Some(seed), _) => seeded_generators(make_generators_fn, seed, opt.length),
+                (None, Some(pk)) => signer_specific_generators(make_generators_fn, opt.length, pk),
+                (None, None) => global_generators(make_generators_fn, opt.length),
+            }
+        }
     };
 
     match opt.out_type {
-        OutputType::Print => print_generators(&generators),
-        OutputType::File => write_generators_to_file(&generators, opt.file_name.unwrap())
+        OutputType::Print => match output {
+            PrintFormat::Text => print_generators(&generators, &table)?,
+            PrintFormat::Json => print_json(&generators_file(&generators, suite.id(), opt.point_encoding))?,
+        },
+        OutputType::File => {
+            let file_name = file_name(opt.file_name)?;
+            write_generators_to_file(write, &generators, suite.id(), file_name, format, opt.point_encoding)?
+        }
+    }
+    Ok(())
+}
+
+impl MessagesOpt {
+    // The decoded header and messages
+    fn read(&self) -> Result<(Vec<u8>, Vec<Vec<u8>>), BbsError> {
+        let messages = match &self.messages_file {
+            Some(path) => read_messages_file(path, self.messages_utf8)?,
+            None => self.messages.iter().map(|msg| decode(msg)).collect::<Result<_, _>>()?,
+        };
+        Ok((decode(&self.header)?, messages))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.header.is_empty() && self.messages.is_empty() && self.messages_file.is_none()
+    }
+}
+
+impl SignedOpt {
+    // The signed messages of --signed-file, or of the hex flags
+    fn read(&self) -> Result<SignedMessagesFile, CliError> {
+        if self.signed_file.is_some() || (self.pk.is_none() && self.signature.is_none()) {
+            if !self.messages.is_empty() {
+                return Err(usage_error("the signed messages file holds the header and messages, they can not be given as well"));
+            }
+            return Ok(read_json(self.signed_file.as_deref().unwrap_or_else(|| Path::new("-")))?);
+        }
+        let (header, messages) = self.messages.read()?;
+        Ok(SignedMessagesFile {
+            signer_public_key: self.pk.clone().ok_or_else(|| usage_error("--pk or --signed-file is required"))?,
+            header: hex::encode(header),
+            messages: messages.iter().map(hex::encode).collect(),
+            signature: self.signature.clone().ok_or_else(|| usage_error("--signature or --signed-file is required"))?,
+        })
+    }
+}
+
+// valid or invalid, exiting with status 1 when invalid so scripts can test the result
+fn print_validity(valid: bool, output: PrintFormat) -> Result<(), BbsError> {
+    match output {
+        PrintFormat::Text => println!("{}", if valid { "valid" } else { "invalid" }),
+        PrintFormat::Json => print_json(&serde_json::json!({ "valid": valid }))?,
+    }
+    if !valid {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+impl WriteOpt {
+    // Relative file names are resolved against --out-dir, - is stdout. Existing files are only
+    // replaced with --force
+    fn create(&self, file_name: &Path) -> Result<stdio::Output, BbsError> {
+        if is_stdio(file_name) {
+            return stdio::create(file_name, true);
+        }
+        let path = match &self.out_dir {
+            Some(dir) => dir.join(file_name),
+            None => env::current_dir()?.join(file_name),
+        };
+        stdio::create(&path, self.force)
+    }
+}
+
+// The outputs of sign and proof-gen, in the --format of fixtures
+fn write_file<T: Serialize>(write: &WriteOpt, path: &Path, value: &T, format: OutputFormat) -> Result<(), BbsError> {
+    let mut writer = write.create(path)?;
+    format.write(&mut writer, value)?;
+    writer.commit()
+}
+
+// structopt requires file_name with -o file, a missing one is still reported rather than unwrapped
+fn file_name(file_name: Option<String>) -> Result<String, CliError> {
+    file_name.ok_or_else(|| usage_error("a file name is required with -o file"))
+}
+
+fn parse_signing_mode(mode: &str) -> Result<SigningMode, String> {
+    match mode.to_lowercase().as_str() {
+        "deterministic" => Ok(SigningMode::Deterministic),
+        "hedged" => Ok(SigningMode::Hedged),
+        _ => Err("Invalid Value".to_string()),
     }
 }
 
-fn global_generators<F>(make_generators_fn: F, len: usize) -> Generators
+fn global_generators<F>(mut make_generators_fn: F, len: usize) -> Generators
 where
-    F: for<'r> Fn(Option<&'r [u8]>, usize) -> Generators
+    F: for<'r> FnMut(Option<&'r [u8]>, usize) -> Generators
 {
     make_generators_fn(None, len)
 }
 
-fn signer_specific_generators<F>(make_generators_fn: F, len: usize) -> Generators
+// Generators of a seed used by another implementation, recorded as seed in files
+fn seeded_generators<F>(mut make_generators_fn: F, seed: &[u8], len: usize) -> Generators
 where
-    F: for<'r> Fn(Option<&'r [u8]>, usize) -> Generators
+    F: for<'r> FnMut(Option<&'r [u8]>, usize) -> Generators
 {
-    let sk = Scalar::random(rand::thread_rng());
-    let pk = G2Projective::generator() * sk;
-    make_generators_fn(Some(&pk.to_affine().to_compressed()), len)
+    make_generators_fn(Some(seed), len)
 }
 
-fn print_generators(generators: &Generators) {
-    println!("G1 BP = {}", hex::encode(
-        generators.g1_base_point.to_affine().to_compressed()
-    ));
-    
-    generators.message_generators.iter().enumerate().for_each(|(i, g)| {
-        println!(
-            "G_{} = {}",
-            i + 1,
-            hex::encode(g.to_affine().to_compressed())
-        );
-    });
+fn signer_specific_generators<F>(mut make_generators_fn: F, len: usize, pk: &PublicKey) -> Generators
+where
+    F: for<'r> FnMut(Option<&'r [u8]>, usize) -> Generators
+{
+    make_generators_fn(Some(&pk.to_bytes()), len)
 }
 
-fn write_generators_to_file(generators: &Generators, file_name: String) {
-    let path = env::current_dir().unwrap();
-
-    let file_path = path.join(file_name);
-
-    let result: Vec<String> = generators.message_generators.iter()
-        .map(|item| hex::encode(item.to_affine().to_compressed())).collect();
+// The --signer-pk key of an existing issuer, or a throwaway random one
+fn signer_public_key<R>(signer_pk: Option<&str>, rng: &mut R) -> Result<PublicKey, BbsError>
+where
+    R: RngCore + CryptoRng
+{
+    match signer_pk {
+        Some(pk) => {
+            let octets = hex::decode(pk).map_err(serialization_error)?;
+            let octets = octets.as_slice().try_into().map_err(|_| BbsError::InvalidLength("a public key is 96 octets"))?;
+            PublicKey::from_bytes(octets)
+        }
+        None => Ok(KeyPair::generate_with_rng(rng).public_key),
+    }
+}
 
-    let file = File::create(file_path).unwrap();
+fn keygen<R>(
+    ikm: Option<String>,
+    key_info: Option<String>,
+    path: Option<String>,
+    json: bool,
+    out_format: Option<KeyFormat>,
+    encoding: ValueEncoding,
+    rng: &mut R,
+) -> Result<(), BbsError>
+where
+    R: RngCore + CryptoRng
+{
+    let ikm = match ikm {
+        Some(ikm) => hex::decode(ikm).map_err(serialization_error)?,
+        None => {
+            let mut ikm = vec![0u8; 32];
+            rng.fill_bytes(&mut ikm);
+            ikm
+        }
+    };
+    let key_info = key_info.map(hex::decode).transpose().map_err(serialization_error)?;
 
-    let mut writer = BufWriter::new(file);
+    let key_pair = match path {
+        Some(ref path) => ExtendedSecretKey::from_path(&ikm, path)?.key_pair(),
+        None => KeyPair::from_ikm(&ikm, key_info.as_deref())?,
+    };
 
-    serde_json::to_writer_pretty(&mut writer, &result).unwrap();
+    if let Some(out_format) = out_format {
+        match out_format {
+            KeyFormat::Jwk => {
+                println!("{}", serde_json::to_string_pretty(&key_pair.to_jwk()).map_err(serialization_error)?)
+            }
+            KeyFormat::Pem => {
+                print!("{}", key_pair.to_pem());
+                print!("{}", key_pair.public_key.to_pem());
+            }
+            KeyFormat::Der => {
+                println!("PKCS8 = {}", encoding.encode(key_pair.to_der()));
+                println!("SPKI = {}", encoding.encode(key_pair.public_key.to_der()));
+            }
+            KeyFormat::Did => {
+                println!("DID = {}", to_did_key(&key_pair.public_key));
+                println!("verificationMethod = {}", verification_method(&key_pair.public_key));
+            }
+        }
+    } else if json {
+        // Same layout as ../fixtures/keyPair.json
+        let mut output = serde_json::json!({
+            "seed": hex::encode(&ikm),
+            "keyPair": {
+                "publicKey": hex::encode(key_pair.public_key.to_bytes()),
+                "secretKey": hex::encode(key_pair.secret_key.to_bytes()),
+            }
+        });
+        if let Some(path) = path {
+            output["path"] = path.into();
+        }
+        println!("{}", serde_json::to_string_pretty(&output).map_err(serialization_error)?);
+    } else {
+        if let Some(path) = path {
+            println!("path = {}", path);
+        }
+        println!("SK = {}", encoding.encode(key_pair.secret_key.to_bytes()));
+        println!("PK = {}", encoding.encode(key_pair.public_key.to_bytes()));
+    }
+    Ok(())
+}
 
-    writer.flush().unwrap();
+fn print_cpu_info(info: &CpuInfo) {
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    println!("arch = {}", info.arch);
+    println!("adx = {}", yes_no(info.adx));
+    println!("bmi2 = {}", yes_no(info.bmi2));
+    println!("built with adx,bmi2 = {}", yes_no(info.static_adx_bmi2));
+    println!("runtime dispatch = {}", yes_no(info.runtime_dispatch));
+    println!("acceleration = {}", info.acceleration.as_str());
 }
 
-fn make_generators<'a, X>(seed: Option<&[u8]>, len: usize) -> Generators
+fn suite_info<'a, X>(json: bool) -> Result<(), BbsError>
 where
     X: BbsCiphersuite<'a>
 {
+    let text = |octets: &[u8]| String::from_utf8_lossy(octets).into_owned();
 
-    let default_seed = &X::generator_seed();
-    let seed = seed.unwrap_or(default_seed);
+    let lengths = [
+        ("octet_scalar_length", X::OCTET_SCALAR_LENGTH),
+        ("octet_point_length", X::OCTET_POINT_LENGTH),
+        ("expand_len", X::EXPAND_LEN),
+    ];
+    let octets = [
+        ("ciphersuite_id", X::ID),
+        ("generator_seed", X::GENERATOR_SEED),
+        ("bp_generator_seed", X::BP_GENERATOR_SEED),
+        ("generator_seed_dst", X::GENERATOR_SEED_DST),
+        ("generator_dst", X::GENERATOR_DST),
+        ("hash_to_scalar_dst", X::HASH_TO_SCALAR_DST),
+        ("map_msg_to_scalar_as_hash_dst", X::MAP_MSG_TO_SCALAR_AS_HASH_DST),
+        ("mock_random_scalars_dst", X::MOCK_RANDOM_SCALARS_DST),
+    ];
 
-    let base_point = make_g1_base_point::<X>();
+    if json {
+        let mut output = serde_json::Map::new();
+        for (name, value) in lengths {
+            output.insert(name.to_string(), value.into());
+        }
+        for (name, value) in octets {
+            output.insert(name.to_string(), text(value).into());
+        }
+        println!("{}", serde_json::to_string_pretty(&output).map_err(serialization_error)?);
+    } else {
+        for &(name, value) in octets.iter().take(1) {
+            println!("{} = {}", name, text(value));
+        }
+        for (name, value) in lengths {
+            println!("{} = {}", name, value);
+        }
+        // DSTs are printed hex encoded as well, as they appear in test vectors
+        for &(name, value) in octets.iter().skip(1) {
+            println!("{} = {} ({})", name, text(value), hex::encode(value));
+        }
+    }
+    Ok(())
+}
 
-    let mut reader = Shake256::default()
-        .chain(seed)
-        .finalize_xof();
+fn print_generators(generators: &Generators, table: &GeneratorTable) -> Result<(), BbsError> {
+    table.print(&mut std::io::stdout().lock(), generators)
+}
 
-    let mut generators = Vec::new();
-    let mut buffer = [0u8; 64];
-    for _ in 0..len {
-        reader.read(&mut buffer);
-        let gi = G1Projective::hash::<ExpandMsgXof<Shake256>>(&buffer, DST);
-        generators.push(gi);
-    }
+fn write_generators_to_file(
+    write: &WriteOpt,
+    generators: &Generators,
+    ciphersuite_id: &[u8],
+    file_name: String,
+    format: OutputFormat,
+    encoding: PointEncoding,
+) -> Result<(), BbsError> {
+    let result = generators_file(generators, ciphersuite_id, encoding);
+
+    let mut writer = write.create(Path::new(&file_name))?;
 
-    Generators {
-        g1_base_point: base_point,
-        message_generators: generators
+    format.write(&mut writer, &result)?;
+
+    writer.commit()
+}
+
+fn generators_file(generators: &Generators, ciphersuite_id: &[u8], encoding: PointEncoding) -> GeneratorsFile {
+    GeneratorsFile {
+        ciphersuite: String::from_utf8_lossy(ciphersuite_id).into_owned(),
+        seed: hex::encode(&generators.seed),
+        bp: encoding.primary(&generators.g1_base_point),
+        generators: generators.message_generators.iter().map(|g| encoding.primary(g)).collect(),
+        bp_uncompressed: encoding.secondary(&generators.g1_base_point),
+        generators_uncompressed: encoding
+            .secondary(&generators.g1_base_point)
+            .map(|_| generators.message_generators.iter().filter_map(|g| encoding.secondary(g)).collect()),
     }
 }
 
-fn make_g1_base_point<'a, X>() -> G1Projective
+// Writes the generators one at a time, either as rows of table or, without one, in the GeneratorsFile
+// json layout, so that memory use does not grow with len. The json layout holds a single encoding
+fn stream_generators<'a, X>(
+    seed: Option<&[u8]>,
+    len: usize,
+    writer: &mut dyn Write,
+    table: Option<&GeneratorTable>,
+    encoding: PointEncoding,
+    progress: &ProgressBar,
+) -> Result<(), BbsError>
 where
     X: BbsCiphersuite<'a>
 {
-    let mut v = [0u8; 48];
-    X::Expander::expand_message(&X::bp_generator_seed(), &X::generator_seed_dst(), &mut v);
+    let default_seed = X::generator_seed();
+    let seed = seed.unwrap_or(&default_seed);
+    let bp = make_g1_base_point::<X>();
 
-    // TODO: implement a proper I2OSP
-    let extra = 0usize.to_be_bytes()[4..].to_vec();
-    let buffer = [v.as_ref(), &extra].concat();
+    if table.is_none() {
+        write!(
+            writer,
+            "{{\n  \"ciphersuite\": {},\n  \"seed\": \"{}\",\n  \"bp\": \"{}\",\n  \"generators\": [",
+            serde_json::to_string(&String::from_utf8_lossy(X::ID)).map_err(serialization_error)?,
+            hex::encode(seed),
+            encoding.primary(&bp)
+        )?;
+    }
+    if let Some(table) = table {
+        table.print_header(writer, len)?;
+        if table.shows_base_point() {
+            table.print_row(writer, "BP", &bp, len)?;
+        }
+    }
 
-    X::Expander::expand_message(&buffer, &X::generator_seed_dst(), &mut v);
+    for (i, g) in GeneratorIter::<X>::streaming(seed).take(len).enumerate() {
+        match table {
+            None => {
+                let separator = if i == 0 { "" } else { "," };
+                write!(writer, "{}\n    \"{}\"", separator, encoding.primary(&g))?;
+            }
+            Some(table) if table.done(i) => break,
+            Some(table) if table.shows(i) => table.print_row(writer, &format!("G_{}", i + 1), &g, len)?,
+            Some(_) => {}
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
 
-    G1Projective::hash::<<X as BbsCiphersuite>::Expander>(
-        &v, &X::generator_dst()
-    )
-}
\ No newline at end of file
+    if table.is_none() {
+        writeln!(writer, "\n  ]\n}}")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use bbs::BbsError;
+
+use crate::output::serialization_error;
+use crate::stdio::{self, is_stdio};
+
+// Reads the messages to sign from a JSON array of strings when the extension is .json, otherwise
+// from one message per line. Messages are hex encoded, or taken as UTF-8 text with utf8. stdin, -,
+// has no extension and is read as a JSON array when it starts with [
+pub fn read_messages_file(path: &Path, utf8: bool) -> Result<Vec<Vec<u8>>, BbsError> {
+    let contents = stdio::read_to_string(path)?;
+    let json = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => true,
+        _ => is_stdio(path) && contents.trim_start().starts_with('['),
+    };
+    let messages: Vec<String> = if json {
+        serde_json::from_str(&contents).map_err(serialization_error)?
+    } else {
+        contents.lines().map(str::to_string).collect()
+    };
+
+    messages
+        .into_iter()
+        .map(|msg| if utf8 { Ok(msg.into_bytes()) } else { hex::decode(msg.trim()).map_err(serialization_error) })
+        .collect()
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use futures_executor::block_on;
+use rand::{CryptoRng, RngCore};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use bbs::ciphersuites::BbsCiphersuite;
+use bbs::encoding::DeserializeMode;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::limits::Limits;
+use bbs::signer::{sign_with, BbsSigner};
+use bbs::{BbsError, Proof, Scalar, Signature};
+
+use crate::output::serialization_error;
+use crate::verify_fixtures::{decode, public_key, scalars};
+
+// The output of sign and the input of verify and proof-gen. Unlike the signature fixtures it holds
+// the signer's public key only, so it can be handed to the holder
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedMessagesFile {
+    /// Hex encoded PK, a compressed G2 point of 96 octets
+    pub signer_public_key: String,
+    /// Hex encoded header
+    pub header: String,
+    /// Hex encoded messages, in signing order
+    pub messages: Vec<String>,
+    /// Hex encoded signature_to_octets output
+    pub signature: String,
+}
+
+// The output of proof-gen and the input of proof-verify, what the holder sends to the verifier
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationFile {
+    pub signer_public_key: String,
+    pub header: String,
+    /// Hex encoded presentation header
+    pub presentation_header: String,
+    /// Hex encoded disclosed messages keyed by their zero based index
+    pub revealed_messages: BTreeMap<String, String>,
+    pub total_message_count: usize,
+    /// Hex encoded proof_to_octets output
+    pub proof: String,
+}
+
+// Signs with signer, which may keep the secret key in an HSM or a remote service
+pub fn sign<'a, X>(signer: &dyn BbsSigner, header: &[u8], messages: &[Vec<u8>]) -> Result<SignedMessagesFile, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let _span = tracing::info_span!("sign").entered();
+    let scalars = messages
+        .iter()
+        .map(|msg| map_message_to_scalar_as_hash::<X>(msg, None))
+        .collect::<Result<Vec<_>, _>>()?;
+    let signature = block_on(sign_with::<X>(signer, &scalars, Some(header)))?;
+
+    Ok(SignedMessagesFile {
+        signer_public_key: hex::encode(signer.public_key().to_bytes()),
+        header: hex::encode(header),
+        messages: messages.iter().map(hex::encode).collect(),
+        signature: hex::encode(signature.to_bytes()),
+    })
+}
+
+// A signature that does not decode is as invalid as one that does not verify, malformed hex or
+// public keys are errors. More messages than limits are invalid
+pub fn verify<'a, X>(signed: &SignedMessagesFile, limits: &Limits) -> Result<bool, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let _span = tracing::info_span!("verify").entered();
+    let pk = public_key(&signed.signer_public_key)?;
+    let messages = scalars::<X>(&signed.messages)?;
+    let header = decode(&signed.header)?;
+    Ok(Signature::from_bytes(&decode(&signed.signature)?)
+        .map(|signature| signature.verify_with_limits::<X>(&pk, &messages, Some(&header), limits))
+        .unwrap_or(false))
+}
+
+pub fn proof_gen<'a, X, R>(
+    signed: &SignedMessagesFile,
+    ph: &[u8],
+    disclosed_indexes: &[usize],
+    limits: &Limits,
+    rng: &mut R,
+) -> Result<PresentationFile, BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    let _span = tracing::info_span!("proof-gen").entered();
+    let header = decode(&signed.header)?;
+    let mut disclosed_indexes = disclosed_indexes.to_vec();
+    disclosed_indexes.sort_unstable();
+    disclosed_indexes.dedup();
+    if disclosed_indexes.iter().any(|&i| i >= signed.messages.len()) {
+        return Err(BbsError::InvalidDisclosedIndexes);
+    }
+
+    let proof = Proof::generate_with_limits::<X, R>(
+        &Signature::from_bytes(&decode(&signed.signature)?)?,
+        &public_key(&signed.signer_public_key)?,
+        Some(&header),
+        Some(ph),
+        &scalars::<X>(&signed.messages)?,
+        &disclosed_indexes,
+        limits,
+        rng,
+    )?;
+
+    Ok(PresentationFile {
+        signer_public_key: signed.signer_public_key.clone(),
+        header: signed.header.clone(),
+        presentation_header: hex::encode(ph),
+        revealed_messages: disclosed_indexes
+            .iter()
+            .map(|&i| (i.to_string(), signed.messages[i].clone()))
+            .collect(),
+        total_message_count: signed.messages.len(),
+        proof: hex::encode(proof.to_bytes()),
+    })
+}
+
+// As verify, a proof that does not decode is invalid
+pub fn proof_verify<'a, X>(presentation: &PresentationFile, limits: &Limits) -> Result<bool, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let _span = tracing::info_span!("proof-verify").entered();
+    let pk = public_key(&presentation.signer_public_key)?;
+    let mut disclosed_messages: BTreeMap<usize, Scalar> = BTreeMap::new();
+    for (i, msg) in &presentation.revealed_messages {
+        let i: usize = i.parse().map_err(serialization_error)?;
+        if i >= presentation.total_message_count {
+            return Ok(false);
+        }
+        disclosed_messages.insert(i, map_message_to_scalar_as_hash::<X>(&decode(msg)?, None)?);
+    }
+    let header = decode(&presentation.header)?;
+    let ph = decode(&presentation.presentation_header)?;
+
+    Ok(Proof::from_bytes_with_limits(&decode(&presentation.proof)?, DeserializeMode::Strict, limits)
+        .map(|proof| {
+            proof.m_hat().len() + disclosed_messages.len() == presentation.total_message_count
+                && proof.verify_with_limits::<X>(&pk, Some(&header), Some(&ph), &disclosed_messages, limits)
+        })
+        .unwrap_or(false))
+}
@@ -0,0 +1,258 @@
+use std::io::Write;
+
+use group::Curve;
+use serde::Serialize;
+
+use bbs::curve::G1;
+use bbs::BbsError;
+
+// A serialization format that generators and fixtures can be written in
+pub trait FixtureWriter {
+    const EXTENSION: &'static str;
+
+    fn write<T: Serialize, W: Write>(writer: W, value: &T) -> Result<(), BbsError>;
+}
+
+// Errors of serde_json, toml, hex and the other encodings the CLI reads and writes
+pub fn serialization_error<E: std::fmt::Display>(e: E) -> BbsError {
+    BbsError::Serialization(e.to_string())
+}
+
+pub struct JsonWriter;
+pub struct CborWriter;
+pub struct YamlWriter;
+pub struct TomlWriter;
+
+impl FixtureWriter for JsonWriter {
+    const EXTENSION: &'static str = "json";
+
+    fn write<T: Serialize, W: Write>(writer: W, value: &T) -> Result<(), BbsError> {
+        serde_json::to_writer_pretty(writer, value).map_err(serialization_error)
+    }
+}
+
+impl FixtureWriter for CborWriter {
+    const EXTENSION: &'static str = "cbor";
+
+    fn write<T: Serialize, W: Write>(writer: W, value: &T) -> Result<(), BbsError> {
+        serde_cbor::to_writer(writer, value).map_err(serialization_error)
+    }
+}
+
+impl FixtureWriter for YamlWriter {
+    const EXTENSION: &'static str = "yaml";
+
+    fn write<T: Serialize, W: Write>(writer: W, value: &T) -> Result<(), BbsError> {
+        serde_yaml::to_writer(writer, value).map_err(serialization_error)
+    }
+}
+
+impl FixtureWriter for TomlWriter {
+    const EXTENSION: &'static str = "toml";
+
+    fn write<T: Serialize, W: Write>(mut writer: W, value: &T) -> Result<(), BbsError> {
+        // TOML documents must be a table, other values are nested under a "value" key
+        let value = match toml::Value::try_from(value).map_err(serialization_error)? {
+            table @ toml::Value::Table(_) => table,
+            value => {
+                let mut table = toml::value::Table::new();
+                table.insert("value".to_string(), value);
+                toml::Value::Table(table)
+            }
+        };
+        writer.write_all(toml::to_string_pretty(&value).map_err(serialization_error)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum OutputFormat {
+    Json,
+    Cbor,
+    Yaml,
+    Toml,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "j" | "js" | "jso" | "json" => Ok(OutputFormat::Json),
+            "c" | "cb" | "cbo" | "cbor" => Ok(OutputFormat::Cbor),
+            "y" | "ya" | "yam" | "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            "t" | "to" | "tom" | "toml" => Ok(OutputFormat::Toml),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => JsonWriter::EXTENSION,
+            OutputFormat::Cbor => CborWriter::EXTENSION,
+            OutputFormat::Yaml => YamlWriter::EXTENSION,
+            OutputFormat::Toml => TomlWriter::EXTENSION,
+        }
+    }
+
+    pub fn write<T: Serialize, W: Write>(&self, writer: W, value: &T) -> Result<(), BbsError> {
+        match self {
+            OutputFormat::Json => JsonWriter::write(writer, value),
+            OutputFormat::Cbor => CborWriter::write(writer, value),
+            OutputFormat::Yaml => YamlWriter::write(writer, value),
+            OutputFormat::Toml => TomlWriter::write(writer, value),
+        }
+    }
+}
+
+// How G1 points are printed and written. Compressed is the point_to_octets_g1 encoding of the spec,
+// some verifiers (HSMs, constrained parsers) only take the 96 octet uncompressed encoding
+#[derive(Debug, Clone, Copy)]
+pub enum PointEncoding {
+    Compressed,
+    Uncompressed,
+    Both,
+}
+
+impl std::str::FromStr for PointEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "c" | "compressed" => Ok(PointEncoding::Compressed),
+            "u" | "uncompressed" => Ok(PointEncoding::Uncompressed),
+            "b" | "both" => Ok(PointEncoding::Both),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+impl PointEncoding {
+    // The hex encoding in the bp and generators fields, uncompressed only with Uncompressed
+    pub fn primary(&self, point: &G1) -> String {
+        hex::encode(self.primary_octets(point))
+    }
+
+    // With Both, the hex encoded uncompressed point written next to the compressed one
+    pub fn secondary(&self, point: &G1) -> Option<String> {
+        self.secondary_octets(point).map(hex::encode)
+    }
+
+    pub fn primary_octets(&self, point: &G1) -> Vec<u8> {
+        match self {
+            PointEncoding::Uncompressed => point.to_affine().to_uncompressed().to_vec(),
+            PointEncoding::Compressed | PointEncoding::Both => point.to_affine().to_compressed().to_vec(),
+        }
+    }
+
+    pub fn secondary_octets(&self, point: &G1) -> Option<Vec<u8>> {
+        match self {
+            PointEncoding::Both => Some(point.to_affine().to_uncompressed().to_vec()),
+            PointEncoding::Compressed | PointEncoding::Uncompressed => None,
+        }
+    }
+}
+
+// How the points, scalars, signatures and proofs of the text output are printed, see --encoding.
+// Multibase is the u prefixed base64url form of DID documents and verifiable credentials. Files and
+// --output json stay hex encoded, as the spec's fixtures and the inputs of verify and proof-verify
+#[derive(Debug, Clone, Copy)]
+pub enum ValueEncoding {
+    Hex,
+    Base64Url,
+    Multibase,
+}
+
+impl Default for ValueEncoding {
+    fn default() -> Self {
+        ValueEncoding::Hex
+    }
+}
+
+impl std::str::FromStr for ValueEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "h" | "hex" => Ok(ValueEncoding::Hex),
+            "b" | "base64url" | "b64u" => Ok(ValueEncoding::Base64Url),
+            "m" | "multibase" => Ok(ValueEncoding::Multibase),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+impl ValueEncoding {
+    pub fn encode<T: AsRef<[u8]>>(&self, octets: T) -> String {
+        match self {
+            ValueEncoding::Hex => hex::encode(octets),
+            ValueEncoding::Base64Url => base64::encode_config(octets, base64::URL_SAFE_NO_PAD),
+            ValueEncoding::Multibase => format!("u{}", base64::encode_config(octets, base64::URL_SAFE_NO_PAD)),
+        }
+    }
+
+    // Converts a hex encoded value of the files and --output json
+    pub fn reencode(&self, value: &str) -> Result<String, BbsError> {
+        Ok(self.encode(hex::decode(value).map_err(serialization_error)?))
+    }
+
+    // The length of the encoding of len octets, for aligned columns
+    pub fn encoded_len(&self, len: usize) -> usize {
+        match self {
+            ValueEncoding::Hex => 2 * len,
+            ValueEncoding::Base64Url => (4 * len + 2) / 3,
+            ValueEncoding::Multibase => 1 + (4 * len + 2) / 3,
+        }
+    }
+}
+
+// Key encodings of keygen --out-format, see bbs::key_formats and bbs::did
+#[derive(Debug, Clone, Copy)]
+pub enum KeyFormat {
+    Jwk,
+    Pem,
+    Der,
+    Did,
+}
+
+impl std::str::FromStr for KeyFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "jwk" => Ok(KeyFormat::Jwk),
+            "pem" => Ok(KeyFormat::Pem),
+            "der" => Ok(KeyFormat::Der),
+            "did" | "did:key" => Ok(KeyFormat::Did),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+// What the subcommands print, text for people or JSON for scripts and the test harnesses of other
+// implementations, see --output. Files written are unaffected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrintFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for PrintFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "t" | "text" => Ok(PrintFormat::Text),
+            "j" | "json" => Ok(PrintFormat::Json),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+// Pretty JSON on stdout
+pub fn print_json<T: Serialize>(value: &T) -> Result<(), BbsError> {
+    println!("{}", serde_json::to_string_pretty(value).map_err(serialization_error)?);
+    Ok(())
+}
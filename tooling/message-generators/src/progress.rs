@@ -0,0 +1,48 @@
+use indicatif::{ProgressBar, ProgressStyle};
+
+use bbs::generators::Generators;
+use bbs::BbsError;
+
+// Creating fewer generators is quick, the bar would only flicker
+const PROGRESS_MIN_LENGTH: usize = 10_000;
+
+// The progress bar of creating len generators, on stderr so that the output is unaffected. Hidden
+// with --quiet and for short runs
+pub fn generator_progress(len: usize, quiet: bool) -> ProgressBar {
+    if quiet || len < PROGRESS_MIN_LENGTH {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new(len as u64);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("{elapsed_precise} [{bar:40}] {pos}/{len} generators, {per_sec}, ETA {eta}")
+            .progress_chars("=> "),
+    );
+    bar
+}
+
+// Creates the generators a hundredth at a time with make_generators_fn and then ensure_fn, which
+// continues where the previous part stopped, advancing the bar after each part. Both must be of the
+// same ciphersuite
+pub fn make_generators_with_progress<F, E>(
+    make_generators_fn: F,
+    ensure_fn: E,
+    seed: Option<&[u8]>,
+    len: usize,
+    bar: &ProgressBar,
+) -> Generators
+where
+    F: FnOnce(Option<&[u8]>, usize) -> Generators,
+    E: Fn(&mut Generators, usize) -> Result<(), BbsError>
+{
+    let step = (len / 100).max(1);
+    let mut generators = make_generators_fn(seed, step.min(len));
+    bar.set_position(generators.message_generators.len() as u64);
+    while generators.message_generators.len() < len {
+        let count = (generators.message_generators.len() + step).min(len);
+        ensure_fn(&mut generators, count).expect("generators of the ciphersuite of ensure_fn");
+        bar.set_position(count as u64);
+    }
+    bar.finish_and_clear();
+    generators
+}
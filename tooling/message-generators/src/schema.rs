@@ -0,0 +1,54 @@
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+use crate::fixtures::{ChallengeFixture, DomainFixture, KeyGenFixture, KeyPairFile, ProofFixture, SignatureFixture};
+use crate::operations::{PresentationFile, SignedMessagesFile};
+use crate::GeneratorsFile;
+
+// The files written by the tool. The schemas are derived from the structs that are serialized, so
+// they cannot drift from the output
+#[derive(Debug, Clone, Copy)]
+pub enum FixtureKind {
+    Generators,
+    KeyPair,
+    KeyGen,
+    Signature,
+    Proof,
+    Domain,
+    Challenge,
+    SignedMessages,
+    Presentation,
+}
+
+impl std::str::FromStr for FixtureKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "generators" => Ok(FixtureKind::Generators),
+            "keypair" | "key-pair" => Ok(FixtureKind::KeyPair),
+            "keygen" | "key-gen" => Ok(FixtureKind::KeyGen),
+            "signature" => Ok(FixtureKind::Signature),
+            "proof" => Ok(FixtureKind::Proof),
+            "domain" => Ok(FixtureKind::Domain),
+            "challenge" => Ok(FixtureKind::Challenge),
+            "signedmessages" | "signed-messages" => Ok(FixtureKind::SignedMessages),
+            "presentation" => Ok(FixtureKind::Presentation),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+pub fn fixture_schema(kind: FixtureKind) -> RootSchema {
+    match kind {
+        FixtureKind::Generators => schema_for!(GeneratorsFile),
+        FixtureKind::KeyPair => schema_for!(KeyPairFile),
+        FixtureKind::KeyGen => schema_for!(KeyGenFixture),
+        FixtureKind::Signature => schema_for!(SignatureFixture),
+        FixtureKind::Proof => schema_for!(ProofFixture),
+        FixtureKind::Domain => schema_for!(DomainFixture),
+        FixtureKind::Challenge => schema_for!(ChallengeFixture),
+        FixtureKind::SignedMessages => schema_for!(SignedMessagesFile),
+        FixtureKind::Presentation => schema_for!(PresentationFile),
+    }
+}
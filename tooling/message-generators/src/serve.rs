@@ -0,0 +1,177 @@
+use std::io::Read;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use bbs::ciphersuites::BbsCiphersuite;
+use bbs::limits::Limits;
+use bbs::signer::LocalSigner;
+use bbs::{BbsError, KeyPair, SecretKey};
+
+use crate::fixtures::{fixture, proof_fixture, sign, KeyPairFile, KeyPairFixture};
+use crate::operations::{self, PresentationFile, SignedMessagesFile};
+use crate::output::serialization_error;
+use crate::verify_fixtures::decode;
+
+// The bodies of the REST endpoints, hex encoded like the files of the tool
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeygenRequest {
+    /// Random when omitted
+    ikm: Option<String>,
+    key_info: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignRequest {
+    secret_key: String,
+    #[serde(default)]
+    header: String,
+    messages: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProofGenRequest {
+    #[serde(flatten)]
+    signed: SignedMessagesFile,
+    #[serde(default)]
+    presentation_header: String,
+    disclosed_indexes: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FixtureRequest {
+    ikm: String,
+    #[serde(default)]
+    header: String,
+    #[serde(default)]
+    presentation_header: String,
+    messages: Vec<String>,
+    // Proof fixtures only
+    #[serde(default)]
+    disclosed_indexes: Vec<usize>,
+    #[serde(default)]
+    proof_seed: String,
+}
+
+// Serves the operations of the ciphersuite X as JSON over HTTP, one request at a time, for browser
+// based interop demos and test dashboards. Every endpoint takes a POST with a JSON body, errors are
+// 400 with {"error": ...}. CORS is open so that pages of any origin can call it. Sign, verify and
+// the proof endpoints accept as many messages as limits
+pub fn serve<'a, X>(port: u16, limits: &Limits) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| serialization_error(e.to_string()))?;
+    eprintln!("serving {} on http://127.0.0.1:{}", String::from_utf8_lossy(X::ID), port);
+
+    for mut request in server.incoming_requests() {
+        let (method, url) = (request.method().clone(), request.url().to_string());
+        let response = match method {
+            Method::Options => Response::from_string(""),
+            Method::Post => {
+                match body(&mut request).and_then(|body| route::<X>(&url, &body, limits)) {
+                    Ok(Some(value)) => json_response(&value, 200),
+                    Ok(None) => json_response(&json!({ "error": "not found" }), 404),
+                    Err(e) => json_response(&json!({ "error": e.to_string() }), 400),
+                }
+            }
+            _ => json_response(&json!({ "error": "only POST is served" }), 405),
+        };
+        // The client may have gone away, the next request is served regardless
+        let _ = request.respond(cors(response));
+    }
+    Ok(())
+}
+
+fn route<'a, X>(url: &str, body: &str, limits: &Limits) -> Result<Option<Value>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let value = match url {
+        "/keygen" => {
+            let request: KeygenRequest = parse(body)?;
+            let ikm = match request.ikm {
+                Some(ikm) => decode(&ikm)?,
+                None => rand::random::<[u8; 32]>().to_vec(),
+            };
+            let key_info = request.key_info.map(|key_info| decode(&key_info)).transpose()?;
+            let key_pair = KeyPair::from_ikm(&ikm, key_info.as_deref())?;
+            to_value(&KeyPairFile {
+                seed: hex::encode(&ikm),
+                key_pair: KeyPairFixture::from(&key_pair),
+            })?
+        }
+        "/sign" => {
+            let request: SignRequest = parse(body)?;
+            let sk = decode(&request.secret_key)?;
+            let sk = sk.as_slice().try_into().map_err(|_| BbsError::InvalidLength("a secret key is 32 octets"))?;
+            let messages = request.messages.iter().map(|msg| decode(msg)).collect::<Result<Vec<_>, _>>()?;
+            let signer = LocalSigner::new(SecretKey::from_bytes(sk)?).with_limits(*limits);
+            to_value(&operations::sign::<X>(&signer, &decode(&request.header)?, &messages)?)?
+        }
+        "/verify" => json!({ "valid": operations::verify::<X>(&parse(body)?, limits)? }),
+        "/proof-gen" => {
+            let request: ProofGenRequest = parse(body)?;
+            let ph = decode(&request.presentation_header)?;
+            let presentation = operations::proof_gen::<X, _>(&request.signed, &ph, &request.disclosed_indexes, limits, &mut rand::thread_rng())?;
+            to_value(&presentation)?
+        }
+        "/proof-verify" => json!({ "valid": operations::proof_verify::<X>(&parse::<PresentationFile>(body)?, limits)? }),
+        "/fixtures/signature" => {
+            let request: FixtureRequest = parse(body)?;
+            let (key_pair, header, messages) = fixture_inputs(&request)?;
+            let signature = sign::<X>(&key_pair, &header, &messages)?;
+            to_value(&fixture::<X>("served", &signature, &key_pair, &header, &messages, None)?)?
+        }
+        "/fixtures/proof" => {
+            let request: FixtureRequest = parse(body)?;
+            let (key_pair, header, messages) = fixture_inputs(&request)?;
+            let mut disclosed_indexes = request.disclosed_indexes.clone();
+            disclosed_indexes.sort_unstable();
+            disclosed_indexes.dedup();
+            let signature = sign::<X>(&key_pair, &header, &messages)?;
+            let ph = decode(&request.presentation_header)?;
+            let seed = decode(&request.proof_seed)?;
+            to_value(&proof_fixture::<X>("served", &signature, &key_pair, &header, &ph, &messages, &disclosed_indexes, &seed)?)?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+fn fixture_inputs(request: &FixtureRequest) -> Result<(KeyPair, Vec<u8>, Vec<Vec<u8>>), BbsError> {
+    let key_pair = KeyPair::from_ikm(&decode(&request.ikm)?, None)?;
+    let messages = request.messages.iter().map(|msg| decode(msg)).collect::<Result<Vec<_>, _>>()?;
+    Ok((key_pair, decode(&request.header)?, messages))
+}
+
+fn body(request: &mut Request) -> Result<String, BbsError> {
+    let mut body = String::new();
+    request.as_reader().read_to_string(&mut body)?;
+    Ok(body)
+}
+
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, BbsError> {
+    serde_json::from_str(body).map_err(serialization_error)
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, BbsError> {
+    serde_json::to_value(value).map_err(serialization_error)
+}
+
+fn json_response(value: &Value, status: u16) -> Response<std::io::Cursor<Vec<u8>>> {
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    Response::from_string(value.to_string()).with_status_code(status).with_header(content_type)
+}
+
+fn cors<R: Read>(response: Response<R>) -> Response<R> {
+    ["Access-Control-Allow-Origin: *", "Access-Control-Allow-Headers: Content-Type", "Access-Control-Allow-Methods: POST, OPTIONS"]
+        .iter()
+        .fold(response, |response, header| response.with_header(header.parse::<Header>().expect("valid header")))
+}
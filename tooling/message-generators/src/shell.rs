@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+use rand::{CryptoRng, RngCore};
+
+use bbs::ciphersuites::BbsCiphersuite;
+use bbs::generators::make_generators;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::{BbsError, KeyPair, Proof, Scalar, Signature};
+
+use crate::output::{PointEncoding, ValueEncoding};
+use crate::print_generators;
+use crate::table::GeneratorTable;
+
+const HELP: &str = "\
+keygen [ikm]            derive a key pair from hex IKM, random when omitted
+generators <count>      print the base point and count message generators
+header [hex]            set the header, empty when omitted
+ph [hex]                set the presentation header, empty when omitted
+message <hex>           append a hex encoded message
+text <text>             append a UTF-8 message
+messages                list the messages
+clear                   remove the messages, signature and proof
+sign                    sign the messages with the key pair
+verify                  verify the signature
+proof [indexes]         generate a proof disclosing the zero based, comma separated indexes
+verify-proof            verify the proof
+show                    print the key pair, header, ph, signature and proof
+help                    print this help
+exit                    leave the shell";
+
+// The state of a shell session, each command works on the results of the previous ones
+#[derive(Default)]
+struct Session {
+    key_pair: Option<KeyPair>,
+    header: Vec<u8>,
+    ph: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    signature: Option<Signature>,
+    // The proof and the indexes it discloses
+    proof: Option<(Proof, Vec<usize>)>,
+    // How keys, signatures and proofs are printed, messages and headers are always hex as entered
+    encoding: ValueEncoding,
+}
+
+// Reads commands from stdin until exit or end of input. A failing command prints its error and
+// leaves the session as it was
+pub fn run_shell<'a, X, R>(encoding: ValueEncoding, rng: &mut R) -> Result<(), BbsError>
+where
+    X: BbsCiphersuite<'a>,
+    R: RngCore + CryptoRng
+{
+    let mut session = Session { encoding, ..Session::default() };
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    println!("{} shell, type help for the commands", String::from_utf8_lossy(X::ID));
+    loop {
+        print!("bbs> ");
+        io::stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        let line = line.trim();
+        let (command, argument) = match line.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            "" => {}
+            "exit" | "quit" => return Ok(()),
+            "help" => println!("{}", HELP),
+            _ => {
+                if let Err(e) = session.execute::<X, R>(command, argument, rng) {
+                    println!("error: {}", e);
+                }
+            }
+        }
+    }
+}
+
+impl Session {
+    fn execute<'a, X, R>(&mut self, command: &str, argument: &str, rng: &mut R) -> Result<(), Box<dyn Error>>
+    where
+        X: BbsCiphersuite<'a>,
+        R: RngCore + CryptoRng
+    {
+        match command {
+            "keygen" => {
+                let ikm = if argument.is_empty() {
+                    let mut ikm = vec![0u8; 32];
+                    rng.fill_bytes(&mut ikm);
+                    ikm
+                } else {
+                    hex::decode(argument)?
+                };
+                let key_pair = KeyPair::from_ikm(&ikm, None)?;
+                println!("PK = {}", self.encoding.encode(key_pair.public_key.to_bytes()));
+                self.key_pair = Some(key_pair);
+                self.signature = None;
+                self.proof = None;
+            }
+            "generators" => {
+                let count = argument.parse()?;
+                print_generators(&make_generators::<X>(None, count), &GeneratorTable::plain(PointEncoding::Compressed, self.encoding))?;
+            }
+            "header" => {
+                self.header = hex::decode(argument)?;
+                self.signature = None;
+                self.proof = None;
+            }
+            "ph" => {
+                self.ph = hex::decode(argument)?;
+                self.proof = None;
+            }
+            "message" => self.add_message(hex::decode(argument)?),
+            "text" => self.add_message(argument.as_bytes().to_vec()),
+            "messages" => {
+                for (i, msg) in self.messages.iter().enumerate() {
+                    println!("{}: {}", i, hex::encode(msg));
+                }
+            }
+            "clear" => {
+                self.messages.clear();
+                self.signature = None;
+                self.proof = None;
+            }
+            "sign" => {
+                let key_pair = self.key_pair()?;
+                let signature = Signature::sign::<X>(&self.scalars::<X>()?, &key_pair.secret_key, Some(&self.header))?;
+                println!("signature = {}", self.encoding.encode(signature.to_bytes()));
+                self.signature = Some(signature);
+                self.proof = None;
+            }
+            "verify" => {
+                let signature = self.signature.as_ref().ok_or("no signature, run sign first")?;
+                let valid = signature.verify::<X>(&self.key_pair()?.public_key, &self.scalars::<X>()?, Some(&self.header));
+                println!("{}", if valid { "valid" } else { "invalid" });
+            }
+            "proof" => {
+                let disclosed_indexes = argument
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|i| !i.is_empty())
+                    .map(str::parse)
+                    .collect::<Result<Vec<usize>, _>>()?;
+                let signature = self.signature.as_ref().ok_or("no signature, run sign first")?;
+                let proof = Proof::generate_with_rng::<X, _>(
+                    signature,
+                    &self.key_pair()?.public_key,
+                    Some(&self.header),
+                    Some(&self.ph),
+                    &self.scalars::<X>()?,
+                    &disclosed_indexes,
+                    rng,
+                )?;
+                println!("proof = {}", self.encoding.encode(proof.to_bytes()));
+                self.proof = Some((proof, disclosed_indexes));
+            }
+            "verify-proof" => {
+                let (proof, disclosed_indexes) = self.proof.as_ref().ok_or("no proof, run proof first")?;
+                let scalars = self.scalars::<X>()?;
+                let disclosed: BTreeMap<usize, Scalar> = disclosed_indexes.iter().map(|&i| (i, scalars[i])).collect();
+                let valid = proof.verify::<X>(&self.key_pair()?.public_key, Some(&self.header), Some(&self.ph), &disclosed);
+                println!("{}", if valid { "valid" } else { "invalid" });
+            }
+            "show" => {
+                if let Some(key_pair) = &self.key_pair {
+                    println!("SK = {}", self.encoding.encode(key_pair.secret_key.to_bytes()));
+                    println!("PK = {}", self.encoding.encode(key_pair.public_key.to_bytes()));
+                }
+                println!("header = {}", hex::encode(&self.header));
+                println!("ph = {}", hex::encode(&self.ph));
+                if let Some(signature) = &self.signature {
+                    println!("signature = {}", self.encoding.encode(signature.to_bytes()));
+                }
+                if let Some((proof, disclosed_indexes)) = &self.proof {
+                    println!("proof = {}", self.encoding.encode(proof.to_bytes()));
+                    println!("disclosed = {:?}", disclosed_indexes);
+                }
+            }
+            _ => return Err(format!("unknown command {:?}, type help for the commands", command).into()),
+        }
+        Ok(())
+    }
+
+    // A new message invalidates the signature and proof over the previous messages
+    fn add_message(&mut self, msg: Vec<u8>) {
+        println!("{}: {}", self.messages.len(), hex::encode(&msg));
+        self.messages.push(msg);
+        self.signature = None;
+        self.proof = None;
+    }
+
+    fn key_pair(&self) -> Result<&KeyPair, &'static str> {
+        self.key_pair.as_ref().ok_or("no key pair, run keygen first")
+    }
+
+    fn scalars<'a, X>(&self) -> Result<Vec<Scalar>, BbsError>
+    where
+        X: BbsCiphersuite<'a>
+    {
+        self.messages
+            .iter()
+            .map(|msg| map_message_to_scalar_as_hash::<X>(msg, None))
+            .collect()
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use bbs::ciphersuites::BbsCiphersuite;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::{BbsError, KeyPair, Proof, Scalar, Signature};
+
+use crate::bench::time;
+
+const POINT_OCTETS: usize = 48;
+const SCALAR_OCTETS: usize = 32;
+
+// The sizes of a signature over messages messages and of a proof disclosing disclosed of them, with
+// Verify and ProofVerify timed on this machine, for choosing how to lay out the messages
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    messages: usize,
+    disclosed: usize,
+    public_key_octets: usize,
+    signature_octets: usize,
+    signature_points: usize,
+    signature_scalars: usize,
+    proof_octets: usize,
+    proof_points: usize,
+    proof_scalars: usize,
+    verify_mean_ns: u128,
+    proof_verify_mean_ns: u128,
+}
+
+pub fn stats<'a, X>(messages: usize, disclosed: usize, iterations: u32) -> Result<Stats, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    if disclosed > messages {
+        return Err(BbsError::InvalidLength("--disclosed must not exceed --messages"));
+    }
+
+    let key_pair = KeyPair::from_ikm(&[0x42u8; 32], None)?;
+    let scalars: Vec<Scalar> = (0..messages)
+        .map(|i| map_message_to_scalar_as_hash::<X>(&i.to_be_bytes(), None))
+        .collect::<Result<_, _>>()?;
+    let signature = Signature::sign::<X>(&scalars, &key_pair.secret_key, None)?;
+
+    let disclosed_indexes: Vec<usize> = (0..disclosed).collect();
+    let revealed: BTreeMap<usize, Scalar> = disclosed_indexes.iter().map(|&i| (i, scalars[i])).collect();
+    let proof = Proof::generate::<X>(&signature, &key_pair.public_key, None, None, &scalars, &disclosed_indexes)?;
+
+    let verify = time("verify", messages, iterations, || {
+        assert!(signature.verify::<X>(&key_pair.public_key, &scalars, None));
+        Ok(())
+    })?;
+    let proof_verify = time("proofverify", messages, iterations, || {
+        assert!(proof.verify::<X>(&key_pair.public_key, None, None, &revealed));
+        Ok(())
+    })?;
+
+    // A', Abar and D, then c, e^, r2^, r3^, s^ and one m^ per undisclosed message
+    let proof_points = 3;
+    let proof_scalars = 5 + messages - disclosed;
+    let proof_octets = proof_points * POINT_OCTETS + proof_scalars * SCALAR_OCTETS;
+    assert_eq!(proof_octets, proof.to_bytes().len());
+
+    Ok(Stats {
+        messages,
+        disclosed,
+        public_key_octets: key_pair.public_key.to_bytes().len(),
+        signature_octets: signature.to_bytes().len(),
+        signature_points: 1,
+        signature_scalars: 2,
+        proof_octets,
+        proof_points,
+        proof_scalars,
+        verify_mean_ns: verify.mean_ns,
+        proof_verify_mean_ns: proof_verify.mean_ns,
+    })
+}
+
+pub fn print_stats(stats: &Stats) {
+    println!("{} messages, {} disclosed", stats.messages, stats.disclosed);
+    println!("public key    {:>6} octets", stats.public_key_octets);
+    println!(
+        "signature     {:>6} octets  {} point, {} scalars",
+        stats.signature_octets, stats.signature_points, stats.signature_scalars
+    );
+    println!(
+        "proof         {:>6} octets  {} points, {} scalars",
+        stats.proof_octets, stats.proof_points, stats.proof_scalars
+    );
+    println!("verify        {:>10.3} ms", stats.verify_mean_ns as f64 / 1e6);
+    println!("proof-verify  {:>10.3} ms", stats.proof_verify_mean_ns as f64 / 1e6);
+}
@@ -0,0 +1,93 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bbs::BbsError;
+
+use crate::output::serialization_error;
+
+// Set once stdin has been read, a second input named - would find it empty
+static STDIN_READ: AtomicBool = AtomicBool::new(false);
+
+// - names stdin for the files read and stdout for the files written, so that subcommands compose in
+// pipelines, e.g. keygen --json | sign --messages-file msgs.json --out - | verify
+pub fn is_stdio(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+pub fn read_to_string(path: &Path) -> Result<String, BbsError> {
+    if !is_stdio(path) {
+        return Ok(fs::read_to_string(path)?);
+    }
+    if STDIN_READ.swap(true, Ordering::SeqCst) {
+        return Err(serialization_error("stdin can only be read once, a single input can be -"));
+    }
+    let mut contents = String::new();
+    io::stdin().read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+// A file being written. The contents go to a temporary file next to it that replaces the file on
+// commit, so that an interrupted run leaves either the previous file or the complete new one
+pub struct Output {
+    writer: Box<dyn Write>,
+    // The temporary file and the file it is renamed to, None for stdout
+    rename: Option<(PathBuf, PathBuf)>,
+}
+
+// Creates the file, or the directories it is in when they are missing. An existing file is only
+// replaced with overwrite
+pub fn create(path: &Path, overwrite: bool) -> Result<Output, BbsError> {
+    if is_stdio(path) {
+        return Ok(Output { writer: Box::new(BufWriter::new(io::stdout())), rename: None });
+    }
+    if !overwrite && path.exists() {
+        return Err(serialization_error(format!("{} exists, --force overwrites it", path.display())));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", process::id()));
+    let tmp = path.with_file_name(tmp_name);
+    let writer = Box::new(BufWriter::new(File::create(&tmp)?));
+    Ok(Output { writer, rename: Some((tmp, path.to_path_buf())) })
+}
+
+impl Output {
+    pub fn commit(mut self) -> Result<(), BbsError> {
+        self.writer.flush()?;
+        if let Some((tmp, path)) = self.rename.take() {
+            // Closes the temporary file before it is renamed
+            self.writer = Box::new(io::sink());
+            if let Err(e) = fs::rename(&tmp, &path) {
+                let _ = fs::remove_file(&tmp);
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+// An output that is not committed, after an error, leaves no temporary file behind
+impl Drop for Output {
+    fn drop(&mut self) {
+        if let Some((tmp, _)) = self.rename.take() {
+            self.writer = Box::new(io::sink());
+            let _ = fs::remove_file(tmp);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use bbs::dyn_ciphersuite::{DynCiphersuite, DynExpander};
+use bbs::BbsError;
+
+use crate::output::serialization_error;
+
+// octet_scalar_length and octet_point_length of BLS12-381 G1
+const OCTET_SCALAR_LENGTH: usize = 32;
+const OCTET_POINT_LENGTH: usize = 48;
+
+// A ciphersuite definition, the seeds and DSTs default to the values derived from ciphersuite_id
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SuiteFile {
+    ciphersuite_id: String,
+    // "xmd:sha-256" or "xof:shake-256"
+    expander: String,
+    generator_seed: Option<String>,
+    bp_generator_seed: Option<String>,
+    generator_seed_dst: Option<String>,
+    generator_dst: Option<String>,
+    hash_to_scalar_dst: Option<String>,
+    map_message_to_scalar_dst: Option<String>,
+    expand_len: Option<usize>,
+    octet_scalar_length: Option<usize>,
+    octet_point_length: Option<usize>,
+}
+
+// Reads a TOML or JSON ciphersuite definition, the format is chosen by the file extension
+pub fn load_suite_file(path: &Path) -> Result<DynCiphersuite, BbsError> {
+    let contents = fs::read_to_string(path)?;
+    let file: SuiteFile = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(&contents).map_err(serialization_error)?,
+        _ => toml::from_str(&contents).map_err(serialization_error)?,
+    };
+
+    let expander = match file.expander.to_lowercase().as_str() {
+        "xmd:sha-256" | "xmd" => DynExpander::XmdSha256,
+        "xof:shake-256" | "xof" => DynExpander::XofShake256,
+        other => return Err(serialization_error(format!("unsupported expander {:?}", other))),
+    };
+
+    // Only BLS12-381 G1 is available, the lengths are accepted to keep definitions self describing
+    if file.octet_scalar_length.unwrap_or(OCTET_SCALAR_LENGTH) != OCTET_SCALAR_LENGTH {
+        return Err(BbsError::InvalidLength("octet_scalar_length must be 32"));
+    }
+    if file.octet_point_length.unwrap_or(OCTET_POINT_LENGTH) != OCTET_POINT_LENGTH {
+        return Err(BbsError::InvalidLength("octet_point_length must be 48"));
+    }
+
+    let mut suite = DynCiphersuite::new(file.ciphersuite_id.as_bytes(), expander);
+    let overrides = [
+        (file.generator_seed, &mut suite.generator_seed),
+        (file.bp_generator_seed, &mut suite.bp_generator_seed),
+        (file.generator_seed_dst, &mut suite.generator_seed_dst),
+        (file.generator_dst, &mut suite.generator_dst),
+        (file.hash_to_scalar_dst, &mut suite.hash_to_scalar_dst),
+        (file.map_message_to_scalar_dst, &mut suite.map_message_to_scalar_dst),
+    ];
+    for (value, field) in overrides {
+        if let Some(value) = value {
+            *field = value.into_bytes();
+        }
+    }
+
+    if let Some(expand_len) = file.expand_len {
+        // os2ip reduces at most 64 octets, fewer than 48 would bias the scalars
+        if !(48..=64).contains(&expand_len) {
+            return Err(BbsError::InvalidLength("expand_len must be between 48 and 64"));
+        }
+        suite.expand_len = expand_len;
+    }
+
+    Ok(suite)
+}
@@ -0,0 +1,133 @@
+use std::io::Write;
+
+use bbs::curve::G1;
+use bbs::generators::Generators;
+use bbs::BbsError;
+
+use crate::output::{PointEncoding, ValueEncoding};
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+// When the generators table is colored: auto colors a terminal unless NO_COLOR is set
+#[derive(Debug, Clone, Copy)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl std::str::FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err("Invalid Value".to_string()),
+        }
+    }
+}
+
+impl ColorChoice {
+    fn enabled(&self) -> bool {
+        match self {
+            ColorChoice::Auto => atty::is(atty::Stream::Stdout) && std::env::var_os("NO_COLOR").is_none(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+// The printed generators: one row per point with its name, BP for the base point and G_i for the
+// message generators, and its encodings in aligned columns. skip and limit select the message
+// generators shown, the base point is shown with the first page only
+pub struct GeneratorTable {
+    encoding: PointEncoding,
+    values: ValueEncoding,
+    color: bool,
+    skip: usize,
+    limit: Option<usize>,
+}
+
+impl GeneratorTable {
+    pub fn new(encoding: PointEncoding, values: ValueEncoding, color: ColorChoice, skip: usize, limit: Option<usize>) -> Self {
+        GeneratorTable { encoding, values, color: color.enabled(), skip, limit }
+    }
+
+    // Every generator, uncolored, as the shell prints them
+    pub fn plain(encoding: PointEncoding, values: ValueEncoding) -> Self {
+        GeneratorTable { encoding, values, color: false, skip: 0, limit: None }
+    }
+
+    pub fn print(&self, writer: &mut dyn Write, generators: &Generators) -> Result<(), BbsError> {
+        let count = generators.message_generators.len();
+        self.print_header(writer, count)?;
+        if self.shows_base_point() {
+            self.print_row(writer, "BP", &generators.g1_base_point, count)?;
+        }
+        for (i, g) in generators.message_generators.iter().enumerate() {
+            if self.shows(i) {
+                self.print_row(writer, &format!("G_{}", i + 1), g, count)?;
+            }
+        }
+        Ok(())
+    }
+
+    // The column titles, count is the number of message generators and sets the width of the names
+    pub fn print_header(&self, writer: &mut dyn Write, count: usize) -> Result<(), BbsError> {
+        let titles = match self.encoding {
+            PointEncoding::Compressed => vec![("compressed", 48)],
+            PointEncoding::Uncompressed => vec![("uncompressed", 96)],
+            PointEncoding::Both => vec![("compressed", 48), ("uncompressed", 96)],
+        };
+        let mut line = format!("{:width$}", "name", width = name_width(count));
+        for (title, len) in titles {
+            line.push_str(&format!("  {:width$}", title, width = self.values.encoded_len(len)));
+        }
+        let line = line.trim_end();
+        if self.color {
+            writeln!(writer, "{}{}{}", BOLD, line, RESET)?;
+        } else {
+            writeln!(writer, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    pub fn print_row(&self, writer: &mut dyn Write, name: &str, point: &G1, count: usize) -> Result<(), BbsError> {
+        let name = format!("{:width$}", name, width = name_width(count));
+        let primary = self.values.encode(self.encoding.primary_octets(point));
+        let secondary = self.encoding
+            .secondary_octets(point)
+            .map(|s| format!("  {}", self.values.encode(s)))
+            .unwrap_or_default();
+        if self.color {
+            writeln!(writer, "{}{}{}  {}{}{}{}", CYAN, name, RESET, primary, DIM, secondary, RESET)?;
+        } else {
+            writeln!(writer, "{}  {}{}", name, primary, secondary)?;
+        }
+        Ok(())
+    }
+
+    pub fn shows_base_point(&self) -> bool {
+        self.skip == 0
+    }
+
+    // Whether message generator i, zero based, is on the page
+    pub fn shows(&self, i: usize) -> bool {
+        i >= self.skip && self.limit.map_or(true, |limit| i - self.skip < limit)
+    }
+
+    // Whether no message generator from i on is on the page, so streaming can stop
+    pub fn done(&self, i: usize) -> bool {
+        self.limit.map_or(false, |limit| i >= self.skip + limit)
+    }
+}
+
+// Wide enough for the name of the last message generator
+fn name_width(count: usize) -> usize {
+    format!("G_{}", count.max(1)).len().max("name".len())
+}
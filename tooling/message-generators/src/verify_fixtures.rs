@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use group::Curve;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use bbs::ciphersuites::BbsCiphersuite;
+use bbs::curve::{Backend, PairingCurve, G1};
+use bbs::encoding::{scalar_from_bytes, scalar_to_bytes, DeserializeMode};
+use bbs::generators::make_generators;
+use bbs::hashing::map_message_to_scalar_as_hash;
+use bbs::limits::Limits;
+use bbs::proof::calculate_challenge;
+use bbs::signature::{calculate_domain, SigningMode};
+use bbs::{BbsError, KeyPair, Proof, PublicKey, Scalar, SecretKey, Signature};
+
+use crate::diff_generators::read_generators_file;
+use crate::fixtures::{ChallengeFixture, DomainFixture, KeyGenFixture, KeyPairFile, ProofFixture, SignatureFixture};
+use crate::output::serialization_error;
+use crate::stdio;
+
+// The result of one check of one vector
+#[derive(Serialize)]
+pub struct Outcome {
+    pub file: String,
+    pub check: &'static str,
+    pub passed: bool,
+    // Why the check failed, or the error reading the vector
+    pub detail: Option<String>,
+}
+
+// Runs every Json vector of a fixture directory in the layout of ../fixtures through the library:
+// keyPair.json and keys/ through KeyGen, generators.json through create_generators, signature/ through Sign
+// and Verify, proof/ through ProofVerify, domain/ through calculate_domain and challenge/ through
+// calculate_challenge, accepting as many messages as limits. A directory that is missing is skipped
+pub fn verify_fixtures<'a, X>(dir: &Path, limits: &Limits) -> Result<Vec<Outcome>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let mut outcomes = Vec::new();
+
+    let key_pair_path = dir.join("keyPair.json");
+    if key_pair_path.exists() {
+        outcomes.push(outcome(&key_pair_path, "keygen", || check_key_pair(&key_pair_path)));
+    }
+    let generators_path = dir.join("generators.json");
+    if generators_path.exists() {
+        outcomes.push(outcome(&generators_path, "generators", || check_generators::<X>(&generators_path)));
+    }
+
+    for path in json_files(&dir.join("keys"))? {
+        let check = || read_json(&path).and_then(|fixture: KeyGenFixture| check_key_gen(&fixture));
+        outcomes.push(outcome(&path, "keygen", check));
+    }
+
+    for path in json_files(&dir.join("signature"))? {
+        let fixture: Result<SignatureFixture, _> = read_json(&path);
+        match fixture {
+            Ok(fixture) => {
+                outcomes.push(outcome(&path, "verify", || check_signature_verify::<X>(&fixture, limits)));
+                if fixture.result.valid {
+                    outcomes.push(outcome(&path, "sign", || check_signature_sign::<X>(&fixture, limits)));
+                }
+            }
+            Err(e) => outcomes.push(outcome(&path, "verify", || Err(e))),
+        }
+    }
+
+    for path in json_files(&dir.join("proof"))? {
+        let check = || read_json(&path).and_then(|fixture: ProofFixture| check_proof_verify::<X>(&fixture, limits));
+        outcomes.push(outcome(&path, "proofverify", check));
+    }
+
+    for path in json_files(&dir.join("domain"))? {
+        let check = || read_json(&path).and_then(|fixture: DomainFixture| check_domain::<X>(&fixture));
+        outcomes.push(outcome(&path, "domain", check));
+    }
+
+    for path in json_files(&dir.join("challenge"))? {
+        let check = || read_json(&path).and_then(|fixture: ChallengeFixture| check_challenge::<X>(&fixture));
+        outcomes.push(outcome(&path, "challenge", check));
+    }
+
+    Ok(outcomes)
+}
+
+// One line per check followed by the totals
+pub fn print_matrix(outcomes: &[Outcome]) {
+    let width = outcomes.iter().map(|o| o.file.len()).max().unwrap_or(0);
+    for o in outcomes {
+        let status = if o.passed { "PASS" } else { "FAIL" };
+        match &o.detail {
+            Some(detail) => println!("{:width$}  {:12} {}  {}", o.file, o.check, status, detail, width = width),
+            None => println!("{:width$}  {:12} {}", o.file, o.check, status, width = width),
+        }
+    }
+
+    let failed = outcomes.iter().filter(|o| !o.passed).count();
+    println!("{} passed, {} failed", outcomes.len() - failed, failed);
+}
+
+// The outcomes and the totals of --output json
+pub fn matrix_json(outcomes: &[Outcome]) -> serde_json::Value {
+    let failed = outcomes.iter().filter(|o| !o.passed).count();
+    serde_json::json!({
+        "outcomes": outcomes,
+        "passed": outcomes.len() - failed,
+        "failed": failed,
+    })
+}
+
+// A check passes with Ok(None), Ok(Some(detail)) is a mismatch and Err an unreadable vector. The
+// check runs in a span, which heads its values in the output of -v and --dump-intermediates
+fn outcome<F>(path: &Path, check: &'static str, run_check: F) -> Outcome
+where
+    F: FnOnce() -> Result<Option<String>, BbsError>
+{
+    let file = path.display().to_string();
+    let result = tracing::info_span!("fixture", check, file = %file).in_scope(run_check);
+    match result {
+        Ok(mismatch) => Outcome { file, check, passed: mismatch.is_none(), detail: mismatch },
+        Err(e) => Outcome { file, check, passed: false, detail: Some(e.to_string()) },
+    }
+}
+
+fn check_key_pair(path: &Path) -> Result<Option<String>, BbsError> {
+    let file: KeyPairFile = read_json(path)?;
+    let key_pair = KeyPair::from_ikm(&decode(&file.seed)?, None)?;
+
+    if hex::encode(key_pair.secret_key.to_bytes()) != file.key_pair.secret_key {
+        return Ok(Some("secret key differs".to_string()));
+    }
+    if hex::encode(key_pair.public_key.to_bytes()) != file.key_pair.public_key {
+        return Ok(Some("public key differs".to_string()));
+    }
+    Ok(None)
+}
+
+fn check_key_gen(fixture: &KeyGenFixture) -> Result<Option<String>, BbsError> {
+    let key_pair = KeyPair::from_ikm(&decode(&fixture.ikm)?, Some(decode(&fixture.key_info)?.as_slice()))?;
+
+    if hex::encode(key_pair.secret_key.to_bytes()) != fixture.key_pair.secret_key {
+        return Ok(Some("secret key differs".to_string()));
+    }
+    if hex::encode(key_pair.public_key.to_bytes()) != fixture.key_pair.public_key {
+        return Ok(Some("public key differs".to_string()));
+    }
+    Ok(None)
+}
+
+// generators.json is either a GeneratorsFile or a bare array of the message generators
+fn check_generators<'a, X>(path: &Path) -> Result<Option<String>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let file = read_generators_file(path)?;
+    let expected = file.generators;
+
+    let generators = make_generators::<X>(None, expected.len());
+    if let Some(bp) = file.bp {
+        if hex::encode(generators.g1_base_point.to_affine().to_compressed()) != bp {
+            return Ok(Some("base point differs".to_string()));
+        }
+    }
+    for (i, (g, expected)) in generators.message_generators.iter().zip(&expected).enumerate() {
+        if &hex::encode(g.to_affine().to_compressed()) != expected {
+            return Ok(Some(format!("generator {} differs", i + 1)));
+        }
+    }
+    Ok(None)
+}
+
+fn check_signature_verify<'a, X>(fixture: &SignatureFixture, limits: &Limits) -> Result<Option<String>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key(&fixture.signer_key_pair.public_key)?;
+    let messages = scalars::<X>(&fixture.messages)?;
+    let header = decode(&fixture.header)?;
+    // A signature that does not decode is as invalid as one that does not verify
+    let valid = Signature::from_bytes(&decode(&fixture.signature)?)
+        .map(|signature| signature.verify_with_limits::<X>(&pk, &messages, Some(&header), limits))
+        .unwrap_or(false);
+    Ok(expected_result(valid, fixture.result.valid))
+}
+
+// Sign is deterministic, signing the messages again must give the signature of the vector
+fn check_signature_sign<'a, X>(fixture: &SignatureFixture, limits: &Limits) -> Result<Option<String>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let sk_octets = decode(&fixture.signer_key_pair.secret_key)?;
+    let sk_octets = sk_octets.as_slice().try_into().map_err(|_| BbsError::InvalidLength("a secret key is 32 octets"))?;
+    let sk = SecretKey::from_bytes(sk_octets)?;
+    let messages = scalars::<X>(&fixture.messages)?;
+    let header = decode(&fixture.header)?;
+    let signature = Signature::sign_with_limits::<X, _>(&messages, &sk, Some(&header), SigningMode::Deterministic, limits, &mut rand::thread_rng())?;
+
+    if hex::encode(signature.to_bytes()) != fixture.signature {
+        return Ok(Some("signature differs".to_string()));
+    }
+    Ok(None)
+}
+
+fn check_proof_verify<'a, X>(fixture: &ProofFixture, limits: &Limits) -> Result<Option<String>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key(&fixture.signer_public_key)?;
+    let mut disclosed_messages = BTreeMap::new();
+    for (i, msg) in &fixture.revealed_messages {
+        let i: usize = i.parse().map_err(serialization_error)?;
+        disclosed_messages.insert(i, map_message_to_scalar_as_hash::<X>(&decode(msg)?, None)?);
+    }
+    let header = decode(&fixture.header)?;
+    let ph = decode(&fixture.presentation_header)?;
+
+    let valid = Proof::from_bytes_with_limits(&decode(&fixture.proof)?, DeserializeMode::Strict, limits)
+        .map(|proof| proof.verify_with_limits::<X>(&pk, Some(&header), Some(&ph), &disclosed_messages, limits))
+        .unwrap_or(false);
+    Ok(expected_result(valid, fixture.result.valid))
+}
+
+fn check_domain<'a, X>(fixture: &DomainFixture) -> Result<Option<String>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let pk = public_key(&fixture.signer_public_key)?;
+    let generators = make_generators::<X>(None, fixture.total_message_count + 2);
+    let domain = calculate_domain::<X>(&pk, &generators.message_generators, Some(&decode(&fixture.header)?));
+
+    if hex::encode(scalar_to_bytes(&domain)) != fixture.domain {
+        return Ok(Some("domain differs".to_string()));
+    }
+    Ok(None)
+}
+
+fn check_challenge<'a, X>(fixture: &ChallengeFixture) -> Result<Option<String>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    let mut disclosed = BTreeMap::new();
+    for (i, msg) in &fixture.revealed_messages {
+        let i: usize = i.parse().map_err(serialization_error)?;
+        disclosed.insert(i, map_message_to_scalar_as_hash::<X>(&decode(msg)?, None)?);
+    }
+    let challenge = calculate_challenge::<X>(
+        &point(&fixture.a_prime)?,
+        &point(&fixture.a_bar)?,
+        &point(&fixture.d)?,
+        &point(&fixture.c1)?,
+        &point(&fixture.c2)?,
+        &disclosed,
+        &scalar(&fixture.domain)?,
+        Some(&decode(&fixture.presentation_header)?),
+    );
+
+    if hex::encode(scalar_to_bytes(&challenge)) != fixture.challenge {
+        return Ok(Some("challenge differs".to_string()));
+    }
+    Ok(None)
+}
+
+fn expected_result(valid: bool, expected: bool) -> Option<String> {
+    if valid == expected {
+        None
+    } else {
+        Some(format!("expected valid = {}, got {}", expected, valid))
+    }
+}
+
+// The .json files of dir in name order, none when dir does not exist
+fn json_files(dir: &Path) -> Result<Vec<std::path::PathBuf>, BbsError> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, BbsError> {
+    serde_json::from_str(&stdio::read_to_string(path)?).map_err(serialization_error)
+}
+
+pub fn decode(octets: &str) -> Result<Vec<u8>, BbsError> {
+    hex::decode(octets).map_err(serialization_error)
+}
+
+pub fn public_key(octets: &str) -> Result<PublicKey, BbsError> {
+    let octets = decode(octets)?;
+    let octets = octets.as_slice().try_into().map_err(|_| BbsError::InvalidLength("a public key is 96 octets"))?;
+    PublicKey::from_bytes(octets)
+}
+
+fn point(octets: &str) -> Result<G1, BbsError> {
+    let octets = decode(octets)?;
+    let octets = octets.as_slice().try_into().map_err(|_| BbsError::InvalidLength("a G1 point is 48 octets"))?;
+    Backend::g1_from_bytes(octets).ok_or_else(|| serialization_error("not a point of the G1 subgroup"))
+}
+
+fn scalar(octets: &str) -> Result<Scalar, BbsError> {
+    let octets = decode(octets)?;
+    let octets = octets.as_slice().try_into().map_err(|_| BbsError::InvalidLength("a scalar is 32 octets"))?;
+    scalar_from_bytes(octets)
+}
+
+pub fn scalars<'a, X>(messages: &[String]) -> Result<Vec<Scalar>, BbsError>
+where
+    X: BbsCiphersuite<'a>
+{
+    messages
+        .iter()
+        .map(|msg| map_message_to_scalar_as_hash::<X>(&decode(msg)?, None))
+        .collect()
+}